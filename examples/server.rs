@@ -0,0 +1,215 @@
+//  SERVER.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:19:02
+//  Last edited:
+//    16 Oct 2026, 18:19:49
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Example that embeds the crate in a minimal HTTP service.
+//!
+//!   Run it with:
+//!   ```bash
+//!   cargo run --example server --features server -- tests/cities.json 127.0.0.1:8000
+//!   ```
+//!   and then query it with, e.g.:
+//!   ```bash
+//!   curl 'http://127.0.0.1:8000/route?src=Berlin&dst=Chicago&k=3&alg=yen<dijkstra>'
+//!   ```
+//
+
+// We keep tests at the top of every module
+#![allow(clippy::items_after_test_module)]
+
+use std::str::FromStr;
+
+use error_trace::trace;
+use ksp::{OwnedPath, Pipeline};
+use ksp_graph::Graph;
+use tiny_http::{Header, Response, Server};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loads the graph served in the tests.
+    fn cities() -> Graph { ksp_graph::json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cities.json")).unwrap() }
+
+    #[test]
+    fn test_handle_route() {
+        let mut g: Graph = cities();
+        let (status, body): (u16, String) = handle(&mut g, "/route?src=Berlin&dst=Chicago&k=2&alg=yen%3Cdijkstra%3E");
+        assert_eq!(status, 200, "{body}");
+        let paths: Vec<OwnedPath> = serde_json::from_str(&body).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.iter().all(|path| path.validate_against(&g).is_ok()));
+        assert_eq!(paths[0].hops.iter().map(|(n, _)| n.as_str()).collect::<Vec<&str>>(), vec!["Berlin", "Amsterdam", "Dorchester", "Chicago"]);
+
+        // The algorithm is optional, and no paths is not an error
+        assert_eq!(handle(&mut g, "/route?dst=Chicago&src=Berlin&k=1"), handle(&mut g, "/route?src=Berlin&dst=Chicago&k=1&alg=yen<dijkstra>"));
+        assert_eq!(handle(&mut g, "/route?src=Berlin&dst=Chicago&k=0"), (200, "[]".into()));
+    }
+
+    #[test]
+    fn test_handle_errors() {
+        let mut g: Graph = cities();
+        assert_eq!(handle(&mut g, "/paths?src=Berlin&dst=Chicago&k=1").0, 404);
+        assert_eq!(handle(&mut g, "/route?src=Berlin&k=1"), (400, "Missing query parameter 'dst'".into()));
+        assert_eq!(handle(&mut g, "/route?src=Berlin&dst=Chicago&k=many").0, 400);
+        assert_eq!(handle(&mut g, "/route?src=Berlin&dst=Chicago&k=1&alg=foo").0, 400);
+        assert_eq!(handle(&mut g, "/route?src=Paris&dst=Chicago&k=1"), (400, "Unknown source node 'Paris'".into()));
+        // NOTE: Nothing is left pruned by failed queries
+        assert_eq!(g.node_count(), cities().node_count());
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Decodes a percent-encoded query string component.
+///
+/// # Arguments
+/// - `raw`: The component to decode. `+` is decoded as a space.
+///
+/// # Returns
+/// The decoded component, or [`None`] if it isn't valid (UTF-8).
+fn decode(raw: &str) -> Option<String> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(raw.len());
+    let mut iter = raw.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'%' => {
+                let hex: [u8; 2] = [iter.next()?, iter.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            },
+            b'+' => bytes.push(b' '),
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Handles a single request to the service.
+///
+/// The only endpoint is `GET /route?src=<SRC>&dst=<DST>&k=<K>[&alg=<ALG>]`, which runs a [`Pipeline`] with just the given algorithm (which
+/// defaults to `yen<dijkstra>`) on the `graph` and returns the paths found as JSON.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to find paths in. It is the same as it was after this function returns.
+/// - `url`: The URL requested, i.e., its path and query string.
+///
+/// # Returns
+/// A pair of the HTTP status code and the body of the response. The body is a JSON list of [`OwnedPath`]s if the status is 200, or else
+/// an error message.
+fn handle(graph: &mut Graph, url: &str) -> (u16, String) {
+    let (path, query): (&str, &str) = url.split_once('?').unwrap_or((url, ""));
+    if path != "/route" {
+        return (404, format!("Unknown endpoint '{path}'"));
+    }
+
+    // Parse the query string
+    let (mut src, mut dst, mut k, mut alg): (Option<String>, Option<String>, Option<String>, Option<String>) = (None, None, None, None);
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value): (&str, &str) = pair.split_once('=').unwrap_or((pair, ""));
+        let value: String = match decode(value) {
+            Some(value) => value,
+            None => return (400, format!("Illegal encoding of query parameter '{key}'")),
+        };
+        match key {
+            "src" => src = Some(value),
+            "dst" => dst = Some(value),
+            "k" => k = Some(value),
+            "alg" => alg = Some(value),
+            _ => return (400, format!("Unknown query parameter '{key}'")),
+        }
+    }
+    let (src, dst, k): (String, String, String) = match (src, dst, k) {
+        (Some(src), Some(dst), Some(k)) => (src, dst, k),
+        (None, _, _) => return (400, "Missing query parameter 'src'".into()),
+        (_, None, _) => return (400, "Missing query parameter 'dst'".into()),
+        (_, _, None) => return (400, "Missing query parameter 'k'".into()),
+    };
+    let k: usize = match usize::from_str(&k) {
+        Ok(k) => k,
+        Err(err) => return (400, trace!(("Illegal query parameter 'k'"), err).to_string()),
+    };
+    let pipeline: Pipeline = match Pipeline::from_str(alg.as_deref().unwrap_or("yen<dijkstra>")) {
+        Ok(pipeline) if !pipeline.has_prep() => pipeline,
+        Ok(_) => return (400, "Query parameter 'alg' must be a single algorithm, not a pipeline with preprocessing steps".into()),
+        Err(err) => return (400, trace!(("Illegal query parameter 'alg'"), err).to_string()),
+    };
+
+    // Run it, using the fallible API such that bad queries don't take the server down
+    match pipeline.k_shortest_paths_batch(graph, &[(&src, &dst, k)]).pop() {
+        Some(Ok(paths)) => {
+            let paths: Vec<OwnedPath> = paths.unwrap_or_default();
+            match serde_json::to_string(&paths) {
+                Ok(body) => (200, body),
+                Err(err) => (500, trace!(("Failed to serialize paths"), err).to_string()),
+            }
+        },
+        Some(Err(err)) => (400, err.to_string()),
+        None => unreachable!(),
+    }
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let (path, addr): (String, String) = match (args.next(), args.next()) {
+        (Some(path), addr) => (path, addr.unwrap_or_else(|| "127.0.0.1:8000".into())),
+        (None, _) => {
+            eprintln!("Usage: server <GRAPH> [<ADDRESS>]");
+            std::process::exit(1);
+        },
+    };
+
+    // Load the graph once
+    let mut graph: Graph = match ksp_graph::json::parse(&path) {
+        Ok(graph) => graph,
+        Err(err) => {
+            eprintln!("{}", trace!(("Failed to load graph '{path}'"), err));
+            std::process::exit(1);
+        },
+    };
+    let server: Server = match Server::http(&addr) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("Failed to listen on '{addr}': {err}");
+            std::process::exit(1);
+        },
+    };
+    println!("Serving {} node(s) and {} edge(s) from '{path}' on 'http://{addr}'", graph.node_count(), graph.edge_count());
+
+    // Serve requests one at a time, as every query borrows the graph mutably
+    for request in server.incoming_requests() {
+        let (status, body): (u16, String) = if *request.method() == tiny_http::Method::Get {
+            handle(&mut graph, request.url())
+        } else {
+            (405, format!("Method '{}' not allowed", request.method()))
+        };
+        let content_type: &str = if status == 200 { "application/json" } else { "text/plain" };
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(Header::from_bytes("Content-Type", content_type).unwrap_or_else(|()| unreachable!()));
+        if let Err(err) = request.respond(response) {
+            eprintln!("Failed to respond: {err}");
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//  ALLOC.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:48:31
+//  Last edited:
+//    16 Oct 2026, 14:18:21
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a global allocator that counts allocations, used to profile
+//!   the memory usage of pipelines.
+//
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::runner::MemoryProfile;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counting_allocator() {
+        reset();
+        let v: Vec<u64> = (0..1024).collect();
+        let profile: MemoryProfile = measure();
+        assert!(profile.allocations >= 1);
+        assert!(profile.peak_bytes >= 1024 * std::mem::size_of::<u64>());
+        drop(v);
+    }
+}
+
+
+
+
+
+/***** GLOBALS *****/
+/// The global allocator, which counts.
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// The number of bytes currently allocated.
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+/// The number of bytes allocated at the last [`reset()`].
+static BASELINE: AtomicUsize = AtomicUsize::new(0);
+/// The most bytes allocated at any time since the last [`reset()`].
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+/// The number of allocations since the last [`reset()`].
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+
+
+
+
+/***** LIBRARY *****/
+/// Resets the counters of the global allocator, starting a new measurement.
+#[inline]
+pub fn reset() {
+    let current: usize = CURRENT.load(Ordering::SeqCst);
+    BASELINE.store(current, Ordering::SeqCst);
+    PEAK.store(current, Ordering::SeqCst);
+    COUNT.store(0, Ordering::SeqCst);
+}
+
+/// Reads the counters of the global allocator since the last [`reset()`].
+///
+/// Note that the counters are process-wide, so this also counts allocations done by other threads.
+///
+/// # Returns
+/// A [`MemoryProfile`] with the peak memory usage and number of allocations since the last reset.
+#[inline]
+pub fn measure() -> MemoryProfile {
+    MemoryProfile {
+        peak_bytes:  PEAK.load(Ordering::SeqCst).saturating_sub(BASELINE.load(Ordering::SeqCst)),
+        allocations: COUNT.load(Ordering::SeqCst),
+    }
+}
+
+
+
+/// A wrapper around the [`System`] allocator that keeps track of how much it allocates.
+pub struct CountingAllocator;
+unsafe impl GlobalAlloc for CountingAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr: *mut u8 = System.alloc(layout);
+        if !ptr.is_null() {
+            COUNT.fetch_add(1, Ordering::Relaxed);
+            let current: usize = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,116 @@
+//  VERIFY PATHS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:39:00
+//  Last edited:
+//    16 Oct 2026, 17:40:59
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Entrypoint for the `verify-paths`-binary.
+//
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use error_trace::trace;
+use humanlog::{DebugMode, HumanLogger};
+use ksp::OwnedPath;
+use ksp_bench::check::{check_path, parse_paths, PathCheck};
+use ksp_bench::parser;
+use ksp_graph::{Graph, GraphFormat};
+use log::{debug, error, info};
+
+
+/***** ARGUMENTS *****/
+/// Defines the arguments to the `verify-paths`-binary.
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Whether to run with additional log statements.
+    #[clap(long, global = true, help = "If given, shows DEBUG- and INFO-level log statements.")]
+    debug: bool,
+    /// Whether to run with maximum log statements.
+    #[clap(long, global = true, help = "If given, shows TRACE-level log statements. Implies '--debug'.")]
+    trace: bool,
+
+    /// The graph to check the paths against.
+    #[clap(name = "GRAPH", help = "The graph file to check the paths against. Its format is deduced from its extension.")]
+    graph:  PathBuf,
+    /// The paths to check.
+    #[clap(
+        name = "PATHS",
+        help = "A JSON file with a list of the paths to check, each given as '{ \"hops\": [[<node>, <cost so far>], ...] }'."
+    )]
+    paths:  PathBuf,
+    /// The node all paths should start at.
+    #[clap(short, long, help = "If given, paths that don't start at this node are invalid.")]
+    source: Option<String>,
+    /// The node all paths should end at.
+    #[clap(short, long, help = "If given, paths that don't end at this node are invalid.")]
+    target: Option<String>,
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+fn main() {
+    // Parse the arguments
+    let args = Arguments::parse();
+
+    // Setup the logger
+    if let Err(err) = HumanLogger::terminal(DebugMode::from_flags(args.trace, args.debug)).init() {
+        eprintln!("WARNING: Failed to setup logger: {err} (no logging for this session)");
+    }
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+
+    // Load the graph and the paths
+    let fmt: GraphFormat = match GraphFormat::from_path(&args.graph) {
+        Some(fmt) => fmt,
+        None => {
+            error!("Unknown graph format extension{}", if let Some(ext) = args.graph.extension() { format!(" {ext:?}") } else { String::new() });
+            std::process::exit(1);
+        },
+    };
+    debug!("Loading graph file '{}' as {}...", args.graph.display(), fmt);
+    let graph: Graph = match fmt {
+        GraphFormat::Json => match ksp_graph::json::parse(&args.graph) {
+            Ok(g) => g,
+            Err(err) => {
+                error!("{}", trace!(("Failed to load graph file '{}' as a JSON graph", args.graph.display()), err));
+                std::process::exit(1);
+            },
+        },
+        GraphFormat::SNDLibXml => match parser::parse_graph(&args.graph, true) {
+            Ok(g) => g,
+            Err(err) => {
+                error!("{}", trace!(("Failed to load graph file '{}' as an SNDLib XML graph", args.graph.display()), err));
+                std::process::exit(1);
+            },
+        },
+    };
+    debug!("Loading paths file '{}'...", args.paths.display());
+    let paths: Vec<OwnedPath> = match parse_paths(&args.paths) {
+        Ok(paths) => paths,
+        Err(err) => {
+            error!("{}", trace!(("Failed to load paths to check"), err));
+            std::process::exit(1);
+        },
+    };
+
+    // Check them all
+    let mut invalid: usize = 0;
+    for (i, path) in paths.iter().enumerate() {
+        let check: PathCheck = check_path(&graph, path, args.source.as_deref(), args.target.as_deref());
+        println!("Path {i} ({path}): {check}");
+        invalid += !check.is_valid() as usize;
+    }
+    if invalid > 0 {
+        error!("{invalid}/{} path(s) are invalid", paths.len());
+        std::process::exit(1);
+    }
+    info!("All {} path(s) are valid", paths.len());
+}
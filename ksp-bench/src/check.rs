@@ -0,0 +1,216 @@
+//  CHECK.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:39:00
+//  Last edited:
+//    16 Oct 2026, 17:40:59
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the `verify-paths`-binary, which checks paths found by
+//!   other tools against a graph.
+//
+
+use std::collections::HashSet;
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use ksp::{OwnedPath, PathError};
+use ksp_graph::Graph;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use arrayvec::ArrayString;
+
+    use super::*;
+    use crate::utils::load_graph;
+
+    /// Creates an [`OwnedPath`] from hops.
+    fn owned(hops: &[(&str, f64)]) -> OwnedPath { OwnedPath { hops: hops.iter().map(|(n, c)| (ArrayString::from(n).unwrap(), *c)).collect() } }
+
+    #[test]
+    fn test_check_paths() {
+        let g: Graph = load_graph("cities");
+        let dir: PathBuf = std::env::temp_dir().join(format!("ksp-bench-test-check-paths-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file: PathBuf = dir.join("paths.json");
+        std::fs::write(
+            &file,
+            r#"[
+                { "hops": [["Amsterdam", 0.0], ["Dorchester", 540.86], ["Chicago", 6790.01]] },
+                { "hops": [["Amsterdam", 0.0], ["Chicago", 6249.15]] }
+            ]"#,
+        )
+        .unwrap();
+        let paths: Vec<OwnedPath> = parse_paths(&file).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(paths.len(), 2);
+
+        // The first one is fine...
+        let check: PathCheck = check_path(&g, &paths[0], Some("Amsterdam"), Some("Chicago"));
+        assert!(check.is_valid() && check.connected() && check.simple && check.endpoints);
+        assert!((check.cost.unwrap() - 6790.01).abs() < 1e-9);
+        // ...unless it was supposed to go elsewhere
+        let check: PathCheck = check_path(&g, &paths[0], Some("Berlin"), None);
+        assert!(!check.is_valid() && check.connected() && !check.endpoints);
+
+        // The second one skips Dorchester
+        let check: PathCheck = check_path(&g, &paths[1], None, None);
+        assert!(!check.is_valid() && !check.connected() && check.endpoints);
+        assert!(matches!(check.error, Some(PathError::NotConnected { index: 1, .. })));
+        assert_eq!(check.cost, None);
+
+        // Wrong costs are reported, but the real cost is still computed
+        let check: PathCheck = check_path(&g, &owned(&[("Berlin", 0.0), ("Amsterdam", 1.0), ("Berlin", 2.0)]), None, None);
+        assert!(!check.is_valid() && check.connected() && !check.simple);
+        assert!(matches!(check.error, Some(PathError::CostMismatch { index: 1, .. })));
+        assert_eq!(check.cost, Some(2.0 * 577.34));
+    }
+}
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating when reading paths to check.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to open the paths file.
+    FileOpen { path: PathBuf, err: std::io::Error },
+    /// Failed to read/parse the paths file.
+    FileReadParse { path: PathBuf, err: serde_json::Error },
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use Error::*;
+        match self {
+            FileOpen { path, .. } => write!(f, "Failed to open paths file '{}'", path.display()),
+            FileReadParse { path, .. } => write!(f, "Failed to read & parse paths file '{}' as a JSON list of paths", path.display()),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            FileOpen { err, .. } => Some(err),
+            FileReadParse { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Describes how a path holds up against a graph.
+#[derive(Debug)]
+pub struct PathCheck {
+    /// Why the path (with the costs it records) doesn't exist in the graph, if it doesn't.
+    pub error:     Option<PathError>,
+    /// Whether the path visits every node at most once.
+    pub simple:    bool,
+    /// Whether the path starts and ends at the expected nodes (if any).
+    pub endpoints: bool,
+    /// The cost of the path when taking the cheapest edge for every hop, or [`None`] if it isn't connected.
+    pub cost:      Option<f64>,
+}
+impl PathCheck {
+    /// Whether all nodes in the path exist and every hop is an edge in the graph, regardless of its cost.
+    ///
+    /// # Returns
+    /// False if the path has an unknown node or a hop without an edge.
+    #[inline]
+    pub fn connected(&self) -> bool { matches!(self.error, None | Some(PathError::CostMismatch { .. })) }
+
+    /// Whether the path is valid, i.e., exists in the graph with the costs it records and has the expected endpoints.
+    ///
+    /// Note that paths don't need to be [simple](PathCheck::simple) to be valid.
+    ///
+    /// # Returns
+    /// True if it's valid, or false otherwise.
+    #[inline]
+    pub fn is_valid(&self) -> bool { self.error.is_none() && self.endpoints }
+}
+impl Display for PathCheck {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        match self.cost {
+            Some(cost) => write!(f, "{} (cost {cost}, ", if self.is_valid() { "valid" } else { "INVALID" })?,
+            None => write!(f, "INVALID (")?,
+        }
+        write!(f, "{})", if self.simple { "simple" } else { "not simple" })?;
+        if !self.endpoints {
+            write!(f, ": wrong endpoints")?;
+        }
+        if let Some(err) = &self.error {
+            write!(f, ": {err}")?;
+        }
+        Ok(())
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Reads paths to check from a JSON file.
+///
+/// # Arguments
+/// - `path`: The [`Path`]-like of the file, which contains a list of serialized [`OwnedPath`]s.
+///
+/// # Returns
+/// The paths in the file, in order.
+///
+/// # Errors
+/// This function errors if we failed to open, read or parse the given file.
+pub fn parse_paths(path: impl AsRef<Path>) -> Result<Vec<OwnedPath>, Error> {
+    let path: &Path = path.as_ref();
+    match File::open(path) {
+        Ok(handle) => serde_json::from_reader(BufReader::new(handle)).map_err(|err| Error::FileReadParse { path: path.into(), err }),
+        Err(err) => Err(Error::FileOpen { path: path.into(), err }),
+    }
+}
+
+/// Checks a path against a graph.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to check the path against.
+/// - `path`: The [`OwnedPath`] to check.
+/// - `src`: The node the path should start at, if any.
+/// - `dst`: The node the path should end at, if any.
+///
+/// # Returns
+/// A [`PathCheck`] describing whether the path is valid, and what it would cost in the graph.
+pub fn check_path(graph: &Graph, path: &OwnedPath, src: Option<&str>, dst: Option<&str>) -> PathCheck {
+    let error: Option<PathError> = path.validate_against(graph).err();
+    let first: Option<&str> = path.hops.first().map(|(n, _)| n.as_str());
+    let last: Option<&str> = path.hops.last().map(|(n, _)| n.as_str());
+    let endpoints: bool = src.is_none_or(|src| first == Some(src)) && dst.is_none_or(|dst| last == Some(dst));
+
+    // Recompute the cost from the edges, if there are any
+    let mut cost: Option<f64> = Some(0.0);
+    for hop in path.hops.windows(2) {
+        let edge: Option<f64> =
+            graph.successors(hop[0].0.as_str()).filter(|(n, _)| *n == hop[1].0.as_str()).map(|(_, e)| e.cost).min_by(f64::total_cmp);
+        cost = cost.zip(edge).map(|(cost, edge)| cost + edge);
+    }
+    if path.hops.iter().any(|(n, _)| !graph.nodes.contains_key(n)) {
+        cost = None;
+    }
+
+    let mut seen: HashSet<&str> = HashSet::with_capacity(path.hops.len());
+    let simple: bool = path.hops.iter().all(|(n, _)| seen.insert(n.as_str()));
+    PathCheck { error, simple, endpoints, cost }
+}
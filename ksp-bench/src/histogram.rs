@@ -0,0 +1,182 @@
+//  HISTOGRAM.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:57:42
+//  Last edited:
+//    16 Oct 2026, 19:00:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements summarizing the costs of the paths found by a KSP
+//!   algorithm in a histogram, e.g., to analyse how diverse they are.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+
+use ksp::Path;
+use serde::{Deserialize, Serialize};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use ksp::ksp::yen::YenKSP;
+    use ksp::ksp::KShortestPath as _;
+    use ksp::sssp::dijkstra::DijkstraSSSP;
+    use ksp_graph::Graph;
+
+    use super::*;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_cost_histogram() {
+        let hist: CostHistogram = CostHistogram::from_costs([7.0, 2.0, 1.0, 2.5], 2.0);
+        assert_eq!(hist.start, 0.0);
+        assert_eq!(hist.counts, vec![1, 2, 0, 1]);
+        assert_eq!(hist.summary, Some(CostSummary { min: 1.0, median: 2.25, max: 7.0, spread: 6.0 }));
+        assert_eq!(hist.buckets().collect::<Vec<(f64, f64, usize)>>(), vec![(0.0, 2.0, 1), (2.0, 4.0, 2), (4.0, 6.0, 0), (6.0, 8.0, 1)]);
+        assert_eq!(hist.to_string(), "[0, 2): 1, [2, 4): 2, [4, 6): 0, [6, 8): 1");
+
+        // Buckets start at the one containing the cheapest path, and costs on a boundary go in the next one
+        let hist: CostHistogram = CostHistogram::from_costs([10.0, 15.0, 20.0], 5.0);
+        assert_eq!((hist.start, hist.counts.clone()), (10.0, vec![1, 1, 1]));
+        assert_eq!(hist.summary.unwrap().median, 15.0);
+
+        // Nothing found means nothing to summarize
+        let hist: CostHistogram = CostHistogram::from_costs([], 2.0);
+        assert!(hist.counts.is_empty());
+        assert_eq!(hist.summary, None);
+        assert_eq!(hist.to_string(), "-");
+
+        // It can be made from paths directly
+        let g: Graph = load_graph("cities");
+        let paths: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Chicago", 2);
+        let hist: CostHistogram = CostHistogram::new(&paths, 1000.0);
+        assert_eq!((hist.start, hist.counts), (6000.0, vec![1, 1]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cost_histogram_zero_width() { CostHistogram::from_costs([1.0], 0.0); }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Summarizes the costs of a set of paths.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CostSummary {
+    /// The cost of the cheapest path.
+    pub min:    f64,
+    /// The median cost. If there is an even number of paths, this is the mean of the middle two.
+    pub median: f64,
+    /// The cost of the most expensive path.
+    pub max:    f64,
+    /// The difference between the most expensive and the cheapest path.
+    pub spread: f64,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A histogram of the costs of a set of paths, e.g., those found by a KSP algorithm.
+///
+/// The buckets are `width` wide and aligned to multiples of it, starting at the one containing the cheapest path and ending at the one
+/// containing the most expensive one. Every bucket includes its lower bound but not its upper one.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CostHistogram {
+    /// The width of every bucket.
+    pub width:   f64,
+    /// The lower bound of the first bucket.
+    pub start:   f64,
+    /// The number of paths in every bucket. Empty if there were no paths.
+    pub counts:  Vec<usize>,
+    /// The summary of the costs, or [`None`] if there were no paths.
+    pub summary: Option<CostSummary>,
+}
+impl CostHistogram {
+    /// Constructor for the CostHistogram of the given paths.
+    ///
+    /// # Arguments
+    /// - `paths`: The [`Path`]s to summarize the costs of.
+    /// - `width`: The width of every bucket.
+    ///
+    /// # Returns
+    /// A new CostHistogram of the costs of `paths`.
+    ///
+    /// # Panics
+    /// This function panics if `width` is not positive and finite.
+    #[inline]
+    #[track_caller]
+    pub fn new(paths: &[Path], width: f64) -> Self { Self::from_costs(paths.iter().map(Path::cost), width) }
+
+    /// Constructor for the CostHistogram of the given costs.
+    ///
+    /// # Arguments
+    /// - `costs`: The costs to summarize. They must be finite.
+    /// - `width`: The width of every bucket.
+    ///
+    /// # Returns
+    /// A new CostHistogram of `costs`.
+    ///
+    /// # Panics
+    /// This function panics if `width` is not positive and finite.
+    #[track_caller]
+    pub fn from_costs(costs: impl IntoIterator<Item = f64>, width: f64) -> Self {
+        if !(width > 0.0 && width < f64::INFINITY) {
+            panic!("Cannot make a histogram with buckets of width {width} (must be positive and finite)");
+        }
+        let mut costs: Vec<f64> = costs.into_iter().collect();
+        costs.sort_by(f64::total_cmp);
+        let (min, max): (f64, f64) = match (costs.first(), costs.last()) {
+            (Some(min), Some(max)) => (*min, *max),
+            _ => return Self { width, start: 0.0, counts: Vec::new(), summary: None },
+        };
+
+        // Count the costs in their buckets
+        let start: f64 = (min / width).floor() * width;
+        let bucket = |cost: f64| -> usize { ((cost - start) / width).floor() as usize };
+        let mut counts: Vec<usize> = vec![0; bucket(max) + 1];
+        for cost in &costs {
+            counts[bucket(*cost)] += 1;
+        }
+
+        let median: f64 = match costs.len() {
+            n if n % 2 == 0 => (costs[n / 2 - 1] + costs[n / 2]) / 2.0,
+            n => costs[n / 2],
+        };
+        Self { width, start, counts, summary: Some(CostSummary { min, median, max, spread: max - min }) }
+    }
+
+    /// Iterates over the buckets of this histogram.
+    ///
+    /// # Returns
+    /// An iterator over the lower bound, upper bound and number of paths of every bucket, in order.
+    #[inline]
+    pub fn buckets(&self) -> impl '_ + Iterator<Item = (f64, f64, usize)> {
+        self.counts.iter().enumerate().map(|(i, count)| {
+            let lower: f64 = self.start + i as f64 * self.width;
+            (lower, lower + self.width, *count)
+        })
+    }
+}
+impl Display for CostHistogram {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        if self.counts.is_empty() {
+            return write!(f, "-");
+        }
+        for (i, (lower, upper, count)) in self.buckets().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "[{lower}, {upper}): {count}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+//  INTERRUPT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:49:38
+//  Last edited:
+//    16 Oct 2026, 15:52:00
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Catches Ctrl-C such that long benchmark runs can be stopped
+//!   gracefully, i.e., while still reporting what they have done so far.
+//
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interrupt() {
+        assert!(!is_interrupted());
+        interrupt();
+        assert!(is_interrupted());
+        reset();
+        assert!(!is_interrupted());
+    }
+}
+
+
+
+
+
+/***** GLOBALS *****/
+/// Whether the user has asked us to stop.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Handles `SIGINT` by setting the [`INTERRUPTED`] flag the first time, and exiting immediately the second time.
+///
+/// # Arguments
+/// - `_sig`: The signal received. Always `SIGINT`.
+#[cfg(unix)]
+extern "C" fn handle_sigint(_sig: libc::c_int) {
+    // NOTE: Only async-signal-safe stuff in here, which atomics and `_exit()` are
+    if INTERRUPTED.swap(true, Ordering::SeqCst) {
+        unsafe { libc::_exit(130) };
+    }
+}
+
+/// Clears the flag set by [`interrupt()`].
+#[cfg(test)]
+#[inline]
+pub(crate) fn reset() { INTERRUPTED.store(false, Ordering::SeqCst); }
+
+
+
+
+
+/***** LIBRARY *****/
+/// Installs a handler for Ctrl-C (`SIGINT`) that calls [`interrupt()`] instead of killing the process.
+///
+/// Pressing Ctrl-C a second time still kills the process immediately, in case it doesn't stop quickly enough.
+///
+/// # Returns
+/// Whether the handler was installed. This isn't supported on non-Unix platforms, where Ctrl-C keeps killing the process.
+pub fn install() -> bool {
+    #[cfg(unix)]
+    {
+        // SAFETY: The handler only touches an atomic and calls `_exit()`, which are both async-signal-safe
+        let handler: extern "C" fn(libc::c_int) = handle_sigint;
+        unsafe { libc::signal(libc::SIGINT, handler as libc::sighandler_t) != libc::SIG_ERR }
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
+
+/// Asks the benchmark to stop after the run that is currently going.
+///
+/// This is what Ctrl-C does after [`install()`]ing the handler.
+#[inline]
+pub fn interrupt() { INTERRUPTED.store(true, Ordering::SeqCst); }
+
+/// Returns whether the benchmark has been asked to stop.
+///
+/// # Returns
+/// True if [`interrupt()`] has been called (e.g., because the user pressed Ctrl-C).
+#[inline]
+pub fn is_interrupted() -> bool { INTERRUPTED.load(Ordering::SeqCst) }
@@ -0,0 +1,223 @@
+//  JSONL.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:42:30
+//  Last edited:
+//    16 Oct 2026, 19:00:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements streaming the paths found for every query to a JSON Lines
+//!   file, i.e., one JSON object per line.
+//
+
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::io::Write;
+
+use arrayvec::ArrayString;
+use ksp::{OwnedPath, Pipeline};
+use serde::{Deserialize, Serialize};
+
+use crate::histogram::CostHistogram;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use ksp::ksp::yen::YenKSP;
+    use ksp::ksp::KShortestPath as _;
+    use ksp::sssp::dijkstra::DijkstraSSSP;
+    use ksp::Path;
+    use ksp_graph::Graph;
+
+    use super::*;
+    use crate::parser::parse_tests;
+    use crate::tests::TestCase;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_jsonl_writer() {
+        let g: Graph = load_graph("cities");
+        let tests: Vec<TestCase> = parse_tests(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.xml")).unwrap();
+        let pip: Pipeline = "yen<dijkstra>".parse().unwrap();
+
+        // Write a record for every query
+        let mut writer: JsonlWriter<Vec<u8>> = JsonlWriter::new(Vec::new());
+        let mut expected: Vec<QueryRecord> = Vec::with_capacity(tests.len());
+        for (i, test) in tests.iter().enumerate() {
+            let paths: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, test.source.as_str(), test.target.as_str(), test.k);
+            let mut record: QueryRecord = QueryRecord::new("cities", test, &pip, &paths);
+            // Histograms are optional
+            if i % 2 == 0 {
+                record.histogram = Some(CostHistogram::new(&paths, 100.0));
+            }
+            writer.write(&record).unwrap();
+            expected.push(record);
+        }
+        assert_eq!(writer.count(), tests.len());
+
+        // Every line is one complete object
+        let raw: String = String::from_utf8(writer.into_inner()).unwrap();
+        let lines: Vec<&str> = raw.lines().collect();
+        assert_eq!(lines.len(), tests.len());
+        assert!(raw.ends_with('\n'));
+        for (line, expected) in lines.iter().zip(&expected) {
+            assert!(serde_json::from_str::<serde_json::Value>(line).unwrap().is_object());
+            let record: QueryRecord = serde_json::from_str(line).unwrap();
+            assert_eq!(record, *expected);
+        }
+    }
+}
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating when writing JSON Lines.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to serialize a record as JSON.
+    Serialize { err: serde_json::Error },
+    /// Failed to write a record to the output.
+    Write { err: std::io::Error },
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use Error::*;
+        match self {
+            Serialize { .. } => write!(f, "Failed to serialize record as JSON"),
+            Write { .. } => write!(f, "Failed to write record"),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            Serialize { err } => Some(err),
+            Write { err } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// The paths found by a pipeline for a single query, as written on one line.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct QueryRecord {
+    /// The benchmark the query is from.
+    pub benchmark: String,
+    /// The test case the query is from.
+    pub test:      ArrayString<64>,
+    /// The pipeline that found the paths.
+    pub pipeline:  Pipeline,
+    /// The node the paths start at.
+    pub source:    ArrayString<64>,
+    /// The node the paths end at.
+    pub target:    ArrayString<64>,
+    /// The number of paths that were asked for.
+    pub k:         usize,
+    /// The paths found, ordered by cost.
+    pub paths:     Vec<OwnedPath>,
+    /// The histogram of the costs of the paths, if asked for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<CostHistogram>,
+}
+impl QueryRecord {
+    /// Constructor for the QueryRecord.
+    ///
+    /// # Arguments
+    /// - `benchmark`: The name of the benchmark the query is from.
+    /// - `test`: The [`TestCase`](crate::tests::TestCase) that was run.
+    /// - `pipeline`: The [`Pipeline`] that ran it.
+    /// - `paths`: The paths it found, which are copied.
+    ///
+    /// # Returns
+    /// A new QueryRecord for the query, without a [histogram](QueryRecord::histogram).
+    #[inline]
+    pub fn new(benchmark: impl Into<String>, test: &crate::tests::TestCase, pipeline: &Pipeline, paths: &[ksp::Path]) -> Self {
+        Self {
+            benchmark: benchmark.into(),
+            test:      test.id,
+            pipeline:  pipeline.clone(),
+            source:    test.source,
+            target:    test.target,
+            k:         test.k,
+            paths:     paths.iter().map(ksp::Path::to_owned_path).collect(),
+            histogram: None,
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Writes records as JSON Lines, i.e., as one JSON object per line.
+///
+/// Every record is written as soon as it's given, so results can be streamed to a file without keeping them in memory.
+#[derive(Debug)]
+pub struct JsonlWriter<W> {
+    /// The output to write to.
+    writer: W,
+    /// The number of records written so far.
+    count:  usize,
+}
+impl<W: Write> JsonlWriter<W> {
+    /// Constructor for the JsonlWriter.
+    ///
+    /// # Arguments
+    /// - `writer`: The [`Write`]r to write to. Consider buffering it.
+    ///
+    /// # Returns
+    /// A new JsonlWriter that hasn't written anything yet.
+    #[inline]
+    pub const fn new(writer: W) -> Self { Self { writer, count: 0 } }
+
+    /// Writes a record on its own line.
+    ///
+    /// # Arguments
+    /// - `record`: The record to write, e.g., a [`QueryRecord`].
+    ///
+    /// # Errors
+    /// This function errors if we failed to serialize the record or to write it. In the former case, nothing is written.
+    pub fn write(&mut self, record: &impl Serialize) -> Result<(), Error> {
+        // NOTE: Serialize first, such that a failure doesn't leave half a line
+        let mut line: Vec<u8> = serde_json::to_vec(record).map_err(|err| Error::Serialize { err })?;
+        line.push(b'\n');
+        self.writer.write_all(&line).map_err(|err| Error::Write { err })?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    ///
+    /// # Errors
+    /// This function errors if we failed to flush it.
+    #[inline]
+    pub fn flush(&mut self) -> Result<(), Error> { self.writer.flush().map_err(|err| Error::Write { err }) }
+
+    /// Returns the number of records written so far.
+    ///
+    /// # Returns
+    /// The number of lines written.
+    #[inline]
+    pub const fn count(&self) -> usize { self.count }
+
+    /// Returns the underlying writer.
+    ///
+    /// # Returns
+    /// The [`Write`]r given to [`JsonlWriter::new()`]. Note that it's not flushed.
+    #[inline]
+    pub fn into_inner(self) -> W { self.writer }
+}
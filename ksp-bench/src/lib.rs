@@ -4,7 +4,7 @@
 //  Created:
 //    16 Jul 2024, 00:53:52
 //  Last edited:
-//    19 Jul 2024, 23:47:27
+//    16 Oct 2026, 17:40:59
 //  Auto updated?
 //    Yes
 //
@@ -12,6 +12,22 @@
 //!   A test suite for comparing different K-Shortest Path (KSP) algorithms.
 //
 
+// We keep tests at the top of every module
+#![allow(clippy::items_after_test_module)]
+
 // Declare modules
+#[cfg(feature = "profile_mem")]
+pub mod alloc;
+pub mod check;
+pub mod histogram;
+pub mod interrupt;
+pub mod jsonl;
 pub mod parser;
+pub mod progress;
+pub mod random;
+pub mod report;
+pub mod runner;
 pub mod tests;
+#[cfg(test)]
+pub mod utils;
+pub mod verify;
@@ -4,7 +4,7 @@
 //  Created:
 //    16 Jul 2024, 00:09:40
 //  Last edited:
-//    25 Jul 2024, 00:16:34
+//    16 Oct 2026, 18:53:39
 //  Auto updated?
 //    Yes
 //
@@ -12,20 +12,31 @@
 //!   Entrypoint for the `benchmark`-binary.
 //
 
-use std::borrow::Cow;
-use std::collections::HashMap;
-use std::fs::{self, DirEntry, ReadDir};
+use std::fs::{self, DirEntry, File, ReadDir};
+use std::io::{BufWriter, Write};
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 use clap::Parser;
-use comfy_table::Table;
 use error_trace::trace;
 use humanlog::{DebugMode, HumanLogger};
-use ksp::{Path, Pipeline, PipelineProfile};
+use ksp::cache::{QueryCache, QueryKey};
+use ksp::ksp::brute_force::BruteForceKSP;
+use ksp::ksp::KShortestPath as _;
+use ksp::{OwnedPath, Path, Pipeline};
+use ksp_bench::histogram::CostHistogram;
+use ksp_bench::interrupt;
+use ksp_bench::jsonl::{JsonlWriter, QueryRecord};
 use ksp_bench::parser::{self};
-use ksp_bench::tests::TestCase;
-use ksp_graph::{Graph, GraphFormat};
+use ksp_bench::progress::Progress;
+use ksp_bench::random::random_tests;
+use ksp_bench::report::{BenchmarkReport, ReportEntry, ReportOptions, Timing};
+use ksp_bench::runner::{run_jobs, run_repeated, RunOptions, Samples};
+use ksp_bench::tests::{split_connected, TestCase};
+use ksp_bench::verify::{compare_paths, expected_paths, Mismatch};
+use ksp_graph::{Graph, GraphDiff, GraphFormat, GraphGuard};
 use log::{debug, error, info, warn};
+use rayon::{ThreadPool, ThreadPoolBuilder};
 
 
 /***** ARGUMENTS *****/
@@ -42,8 +53,8 @@ struct Arguments {
     /// Any algorithms to run.
     #[clap(
         name = "ALGORITHMS",
-        help = "A list of KSP algorithms to benchmark. They are given as pipelines: [PREP->]* ALG [(SSSP)], where 'PREP' is one of 'peek'; 'ALG' is \
-                one of 'wikipedia', 'yen'; and SSSP is one of 'dijkstra'."
+        help = "A list of KSP algorithms to benchmark. They are given as pipelines: [PREP->]* ALG [(SSSP)], where 'PREP' is one of 'peek' or 'contract-chains'; 'ALG' is \
+                one of 'hoffman-pavley', 'wikipedia', 'yen'; and SSSP is one of 'dijkstra'."
     )]
     algs: Vec<Pipeline>,
     /// Any specific benchmarks to run.
@@ -54,10 +65,156 @@ struct Arguments {
                 optional."
     )]
     benchmark: Vec<String>,
+    /// Any specific tests to run.
+    #[clap(
+        short,
+        long,
+        help = "If given, does not run all tests (i.e., demands) in the benchmarks, but instead only the ones with the given identifier."
+    )]
+    test: Vec<String>,
+    /// The number of random tests to run instead.
+    #[clap(
+        long,
+        help = "If given, does not run the tests in the benchmarks, but instead the given number of random tests between distinct, connected pairs \
+                of nodes in every benchmark. See '--seed', '--random-min-k' and '--random-max-k' to configure them."
+    )]
+    random_tests: Option<usize>,
+    /// The seed for the random tests.
+    #[clap(long, default_value = "42", help = "The seed used to generate '--random-tests'. The same seed always generates the same tests.")]
+    seed: u64,
+    /// The minimum K for the random tests.
+    #[clap(long, default_value = "1", help = "The minimum number of paths to find in '--random-tests' (inclusive).")]
+    random_min_k: usize,
+    /// The maximum K for the random tests.
+    #[clap(long, default_value = "8", help = "The maximum number of paths to find in '--random-tests' (inclusive).")]
+    random_max_k: usize,
     /// Where to find the benchmarks.
     #[clap(short = 'd', long, default_value = "./benchmarks", help = "The directory where the benchmark XML files are read from.")]
     benchmark_dir: PathBuf,
 
+    /// The number of timed runs per test.
+    #[clap(
+        short,
+        long,
+        default_value = "1",
+        help = "The number of times to run every pipeline on every test. If more than once, the median duration is reported together with the \
+                standard deviation."
+    )]
+    repeat: usize,
+    /// The number of untimed runs per test.
+    #[clap(short, long, default_value = "0", help = "The number of times to run every pipeline on every test before starting to time it.")]
+    warmup: usize,
+
+    /// The number of threads to run tests on.
+    #[clap(
+        short,
+        long,
+        default_value = "1",
+        help = "The number of threads on which to run the tests of a benchmark, where every pipeline on every test is a separate job. If 0, \
+                uses one thread per CPU. The output is the same regardless, but note that concurrent runs may influence each other's timings. \
+                Must be 1 if '--profile-mem' is given, as memory usage is counted for the whole process."
+    )]
+    jobs: usize,
+
+    /// Whether to profile memory usage too.
+    #[cfg(feature = "profile_mem")]
+    #[clap(long, help = "If given, also reports the peak memory usage and number of allocations of every pipeline.")]
+    profile_mem: bool,
+
+    /// Whether to check all pipelines against a brute-force reference.
+    #[clap(
+        long,
+        help = "If given, checks the paths found by every pipeline against a brute-force reference implementation, or against the admissible \
+                paths of a demand if the benchmark file has any (these should then be the shortest paths). Mismatches are collected and \
+                summarized at the end instead of aborting the benchmark."
+    )]
+    verify: bool,
+    /// The maximum graph size for which to run the reference.
+    #[clap(
+        long,
+        default_value = "16",
+        help = "The maximum number of nodes a benchmark may have to be verified with '--verify'. Larger benchmarks are skipped (except for \
+                demands with admissible paths), as the reference is exponential in the size of the graph."
+    )]
+    verify_max_nodes: usize,
+
+    /// Whether to show what preprocessing does to the graph.
+    #[clap(
+        long,
+        help = "If given, prints which nodes and edges the preprocessing steps of every pipeline remove or change for every test (to stderr)."
+    )]
+    diff: bool,
+    /// Where to write the graphs after preprocessing.
+    #[clap(
+        long,
+        help = "If given, writes the graph left after the preprocessing steps of every pipeline as a sorted edge list ('<left> <right> <cost>' \
+                per line) for every test to the given file, or to stdout if it's '-'."
+    )]
+    dump_graph: Option<PathBuf>,
+    /// Where to stream the paths found for every query.
+    #[clap(
+        long,
+        help = "If given, writes the paths found by every pipeline for every test to the given file as JSON Lines, i.e., one JSON object per \
+                line. They are written as soon as they're found."
+    )]
+    jsonl: Option<PathBuf>,
+    /// Where to save the report of all benchmarks.
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "If given, saves the results of all benchmarks to the given file as a single JSON object once they are done, such that they can \
+                be post-processed. This includes the histograms if '--histogram' is given."
+    )]
+    report: Option<PathBuf>,
+    /// The width of the buckets of the cost histograms.
+    #[clap(
+        long,
+        value_name = "WIDTH",
+        help = "If given, also prints a table summarizing the costs of the paths found by every pipeline for every test (min, median, max and \
+                spread), with a histogram of them in buckets of the given width. If '--jsonl' is given, the histograms are written there too."
+    )]
+    histogram: Option<f64>,
+    /// Where to cache the paths found for every query.
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "If given, caches the paths found by every pipeline for every test in the given directory, by the contents of the graph, the \
+                test's query and the pipeline. Queries already in there are not run again: their paths are still checked, written and \
+                summarized, but they have no timings."
+    )]
+    cache_dir: Option<PathBuf>,
+
+    /// Whether to show a progress bar.
+    #[clap(
+        long,
+        help = "If given, shows how many benchmarks, tests and pipelines have been completed in a progress bar (on stderr). Ignored if '--debug', \
+                '--trace', '--diff' or '--dump-graph -' is given, as their output would garble it."
+    )]
+    progress: bool,
+
+    /// Whether to refuse benchmarks without costs instead of assuming one per hop.
+    #[clap(
+        long,
+        help = "If given, fails on SNDLib XML benchmarks that have no cost information at all instead of assuming a cost of 1.0 per link. Use \
+                this if the experiment must use real costs."
+    )]
+    no_unit_fallback: bool,
+
+    /// Whether to skip tests between disconnected nodes.
+    #[clap(
+        long,
+        help = "If given, skips tests (i.e., demands) whose source and target aren't connected in the graph instead of running them, and \
+                reports how many were skipped at the end."
+    )]
+    skip_disconnected: bool,
+
+    /// Whether to only show what would be run.
+    #[clap(
+        long,
+        help = "If given, lists the benchmarks that would be run and the steps of every pipeline, but doesn't load or run anything."
+    )]
+    dry_run: bool,
+
     /// If given, prints the results as CSV.
     #[clap(short, long, help = "If given, prints the results as Comma-Separated Values (CSV) instead of in a table.")]
     csv: bool,
@@ -67,6 +224,57 @@ struct Arguments {
 
 
 
+/***** HELPERS *****/
+/// The outcome of running a single pipeline on a single test.
+#[derive(Debug)]
+struct Outcome {
+    /// The samples of the timed runs, or [`None`] if the paths came from the cache.
+    samples:   Option<Samples>,
+    /// What preprocessing did to the graph, if it should be shown.
+    diff:      Option<String>,
+    /// The graph after preprocessing, if it should be dumped.
+    dump:      Option<String>,
+    /// The paths found, if they should be written as JSON Lines.
+    record:    Option<QueryRecord>,
+    /// How the paths disagreed with the reference, if they did.
+    mismatch:  Option<Mismatch>,
+    /// The histogram of the costs of the paths found, if it should be made.
+    histogram: Option<CostHistogram>,
+    /// The paths found (pretty-printed) and their costs. Empty if they were verified against the reference instead.
+    shortest:  Vec<(String, f64)>,
+}
+
+
+
+/// Checks whether paths are as short as those found before, remembering them if they are the first.
+///
+/// # Arguments
+/// - `min_cost`: The paths found before (pretty-printed) and their costs, by index.
+/// - `found`: The paths to check (pretty-printed) and their costs.
+/// - `test`: The [`TestCase`] for which they were found.
+/// - `pip`: The [`Pipeline`] that found them.
+///
+/// # Panics
+/// This function panics if any of the paths has a different cost than the one found before at the same index.
+fn check_shortest(min_cost: &mut [Option<(String, f64)>], found: &[(String, f64)], test: &TestCase, pip: &Pipeline) {
+    for (prev, (pretty, cost)) in min_cost.iter_mut().zip(found) {
+        if let Some(prev) = prev {
+            if *cost != prev.1 {
+                panic!(
+                    "Benchmark '{}' failed for {}: path not shortest (got {}, previous alg got {})\n\nPath:\n{}\nPrev path:\n{}",
+                    test.id, pip, cost, prev.1, pretty, prev.0,
+                );
+            }
+        } else {
+            *prev = Some((pretty.clone(), *cost));
+        }
+    }
+}
+
+
+
+
+
 /***** ENTRYPOINT *****/
 fn main() {
     // Parse arguments
@@ -77,6 +285,48 @@ fn main() {
         eprintln!("WARNING: Failed to setup logger: {err} (no logging for this session)");
     }
     info!("{} -  v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+    if args.random_min_k > args.random_max_k {
+        error!("'--random-min-k' ({}) cannot be larger than '--random-max-k' ({})", args.random_min_k, args.random_max_k);
+        std::process::exit(1);
+    }
+    if let Some(width) = args.histogram {
+        if !(width > 0.0 && width < f64::INFINITY) {
+            error!("'--histogram' ({width}) must be a positive, finite bucket width");
+            std::process::exit(1);
+        }
+    }
+    #[cfg(feature = "profile_mem")]
+    if args.profile_mem && args.jobs != 1 {
+        error!("'--profile-mem' cannot be combined with '--jobs' ({}), as concurrent runs would count each other's allocations", args.jobs);
+        std::process::exit(1);
+    }
+    if !interrupt::install() {
+        debug!("Failed to install Ctrl-C handler; interrupting will not report partial results");
+    }
+    let pool: ThreadPool = match ThreadPoolBuilder::new().num_threads(args.jobs).build() {
+        Ok(pool) => pool,
+        Err(err) => {
+            error!("{}", trace!(("Failed to start {} thread(s)", args.jobs), err));
+            std::process::exit(1);
+        },
+    };
+    let cache: Option<QueryCache> = match &args.cache_dir {
+        Some(dir) => match QueryCache::new(dir) {
+            Ok(cache) => Some(cache),
+            Err(err) => {
+                error!("{}", trace!(("Failed to open query cache"), err));
+                std::process::exit(1);
+            },
+        },
+        None => None,
+    };
+    for pip in &args.algs {
+        if let Err(warns) = pip.validate() {
+            for warn in warns {
+                warn!("Pipeline {pip}: {warn}");
+            }
+        }
+    }
 
 
 
@@ -88,7 +338,7 @@ fn main() {
             let entry_path: PathBuf = PathBuf::from(&entry);
             if entry_path.exists() {
                 let entry_name: String = entry_path.file_name().map(|n| n.to_string_lossy().into()).unwrap_or(entry);
-                let fmt: GraphFormat = if entry_name.ends_with(".json") { GraphFormat::Json } else { GraphFormat::SNDLibXml };
+                let fmt: GraphFormat = GraphFormat::from_path(&entry_path).unwrap_or(GraphFormat::SNDLibXml);
                 files.push((entry_name, entry_path, fmt));
             } else {
                 let mut path: PathBuf = args.benchmark_dir.join(&entry);
@@ -96,7 +346,7 @@ fn main() {
                     // Re-try with XML
                     if let Some(old) = path.file_name() {
                         let mut old = old.to_os_string();
-                        old.push(".xml");
+                        old.push(format!(".{}", GraphFormat::SNDLibXml.extensions()[0]));
                         path.set_file_name(old);
                     } else {
                         panic!("Should never have no filename for generated path '{}' from entry '{}'", path.display(), entry);
@@ -132,11 +382,10 @@ fn main() {
                 },
             };
 
-            // See if it ends in .xml
+            // See if it's SNDLib XML
             let entry_path: PathBuf = entry.path();
-            let entry_name: Cow<str> = entry_path.file_name().unwrap_or_else(|| entry_path.as_os_str()).to_string_lossy();
-            if !entry_name.ends_with(".xml") {
-                debug!("Skipping entry '{}' as it does not end in '.xml'", entry_path.display());
+            if GraphFormat::from_path(&entry_path) != Some(GraphFormat::SNDLibXml) {
+                debug!("Skipping entry '{}' as it is not an SNDLib XML file", entry_path.display());
                 continue;
             }
 
@@ -145,15 +394,58 @@ fn main() {
         }
     }
 
+    // Stop here if we're only showing what would be run
+    if args.dry_run {
+        for (name, file, fmt) in &files {
+            println!("Benchmark {} ({} @ '{}')", name, fmt, file.display());
+        }
+        for pip in &args.algs {
+            println!("Pipeline {pip}:");
+            for (i, step) in pip.plan().into_iter().enumerate() {
+                println!("  {}. {}", i + 1, step);
+            }
+        }
+        return;
+    }
+
+    // Open where to dump the graphs, if anywhere
+    let dump_to_stdout: bool = args.dump_graph.as_ref().is_some_and(|path| path.as_os_str() == "-");
+    let mut dump: Option<Box<dyn Write>> = match &args.dump_graph {
+        Some(_) if dump_to_stdout => Some(Box::new(std::io::stdout())),
+        Some(path) => match File::create(path) {
+            Ok(handle) => Some(Box::new(BufWriter::new(handle))),
+            Err(err) => {
+                error!("{}", trace!(("Failed to create graph dump file '{}'", path.display()), err));
+                std::process::exit(1);
+            },
+        },
+        None => None,
+    };
+    let mut jsonl: Option<JsonlWriter<BufWriter<File>>> = match &args.jsonl {
+        Some(path) => match File::create(path) {
+            Ok(handle) => Some(JsonlWriter::new(BufWriter::new(handle))),
+            Err(err) => {
+                error!("{}", trace!(("Failed to create JSON Lines file '{}'", path.display()), err));
+                std::process::exit(1);
+            },
+        },
+        None => None,
+    };
+
     // Run them
     debug!("Running {} benchmark(s)", files.len());
     let mut first: bool = true;
+    let mut report: BenchmarkReport = BenchmarkReport::new(args.algs.clone());
+    let mut mismatches: Vec<Mismatch> = Vec::new();
+    let mut n_skipped: usize = 0;
+    let mut progress: Progress<_> =
+        Progress::stderr(files.len(), args.progress && !args.debug && !args.trace && !args.diff && !dump_to_stdout);
     for (name, file, fmt) in files {
-        debug!("Loading benchmark {:?} @ '{}' as {:?}...", name, file.display(), fmt);
+        debug!("Loading benchmark {:?} @ '{}' as {}...", name, file.display(), fmt);
 
         // Open the file and parse the graph & test case
-        let mut graph: Graph = match fmt {
-            GraphFormat::SNDLibXml => match ksp_graph::sndlib_xml::parse(&file) {
+        let graph: Graph = match fmt {
+            GraphFormat::SNDLibXml => match parser::parse_graph(&file, !args.no_unit_fallback) {
                 Ok(res) => res,
                 Err(err) => {
                     error!("{}", trace!(("Failed to load benchmark '{name}'"), err));
@@ -168,111 +460,308 @@ fn main() {
                 },
             },
         };
-        let tests: Vec<TestCase> = match crate::parser::parse_tests(&file) {
-            Ok(res) => res,
-            Err(err) => {
-                error!("{}", trace!(("Failed to load benchmark '{name}'"), err));
-                std::process::exit(1);
-            },
+        let tests: Vec<TestCase> = if let Some(count) = args.random_tests {
+            random_tests(&graph, count, args.random_min_k..=args.random_max_k, args.seed)
+        } else {
+            match parser::parse_tests_iter(&file)
+                .filter(|t| args.test.is_empty() || t.as_ref().map(|t| args.test.iter().any(|id| id == t.id.as_str())).unwrap_or(true))
+                .collect::<Result<Vec<TestCase>, parser::Error>>()
+            {
+                Ok(res) => res,
+                Err(err) => {
+                    error!("{}", trace!(("Failed to load benchmark '{name}'"), err));
+                    std::process::exit(1);
+                },
+            }
         };
-        if !graph.edges.values().any(|e| e.cost > 0.0) {
-            warn!("Benchmark '{name}' does not have any cost associated with the links (will assume '1.0' per hop)");
-            debug!("Re-assigning link costs...");
-            for edge in graph.edges.values_mut() {
-                edge.cost = 1.0;
+        let tests: Vec<TestCase> = if args.skip_disconnected {
+            let (tests, skipped): (Vec<TestCase>, Vec<TestCase>) = split_connected(&graph, tests);
+            for test in &skipped {
+                warn!("Skipping test '{}' in benchmark '{}' as '{}' and '{}' aren't connected", test.id, name, test.source, test.target);
             }
-        }
-        info!("Benchmark {} ({} nodes, {} edges, '{}')", name, graph.nodes.len(), graph.edges.len(), file.display());
+            n_skipped += skipped.len();
+            tests
+        } else {
+            tests
+        };
+        info!("Benchmark {} ({} nodes, {} edges, '{}')", name, graph.node_count(), graph.edge_count(), file.display());
+        progress.start_file(&name, tests.len(), args.algs.len());
 
 
 
+        // See if we can verify this benchmark with the reference (tests with expected paths are always verified)
+        let verify: bool = if args.verify && graph.node_count() > args.verify_max_nodes {
+            if tests.iter().any(|t| t.expected_paths.is_empty()) {
+                warn!(
+                    "Not verifying tests without admissible paths in benchmark '{}' as it has too many nodes ({} > {})",
+                    name,
+                    graph.node_count(),
+                    args.verify_max_nodes
+                );
+            }
+            false
+        } else {
+            args.verify
+        };
+
         // Now run some routing algorithm on all tests
-        let mut results: HashMap<&str, HashMap<Pipeline, PipelineProfile>> = HashMap::new();
-        for (i, test) in tests.iter().enumerate() {
-            // Benchmark the test
-            let mut min_cost: Vec<Option<(String, f64)>> = vec![None; test.k];
-            for pip in &args.algs {
-                debug!("Benchmarking {} for test '{}' ({}/{})...", pip, test.id, i + 1, tests.len());
-                let mut g: Graph = graph.clone();
-                let (paths, profile): (Vec<Path>, PipelineProfile) =
-                    pip.k_shortest_paths_profiled(&mut g, test.source.as_str(), test.target.as_str(), test.k);
-                results.entry(test.id.as_str()).or_default().insert(pip.clone(), profile);
+        let opts: RunOptions = RunOptions {
+            warmup: args.warmup,
+            repeat: args.repeat,
+            #[cfg(feature = "profile_mem")]
+            profile_mem: args.profile_mem,
+            #[cfg(not(feature = "profile_mem"))]
+            profile_mem: false,
+        };
+        // Run every pipeline on every test as a separate job, for as far as we aren't interrupted
+        let references: Vec<Option<Vec<Path>>> = tests
+            .iter()
+            .map(|test| {
+                if args.verify && !test.expected_paths.is_empty() {
+                    Some(expected_paths(&graph, &test.expected_paths, test.k))
+                } else if verify {
+                    Some(BruteForceKSP::simple().k_shortest_paths(&graph, test.source.as_str(), test.target.as_str(), test.k))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let graph_hash: Option<u64> = cache.as_ref().map(|_| graph.content_hash());
+        let jobs: Vec<(usize, &Pipeline)> = (0..tests.len()).flat_map(|i| args.algs.iter().map(move |pip| (i, pip))).collect();
+        let dumping: bool = dump.is_some();
+        let jsonl_given: bool = jsonl.is_some();
+        let shared: Mutex<&mut Progress<_>> = Mutex::new(&mut progress);
+        let outcomes: Vec<Option<Outcome>> = run_jobs(&pool, &graph, &jobs, |scratch: &mut Graph, (i, pip): &(usize, &Pipeline)| {
+            if interrupt::is_interrupted() {
+                return None;
+            }
+            let (test, reference): (&TestCase, &Option<Vec<Path>>) = (&tests[*i], &references[*i]);
+            debug!("Benchmarking {} for test '{}' ({}/{})...", pip, test.id, i + 1, tests.len());
 
-                // Verify correctness of the paths
-                for (i, path) in paths.into_iter().enumerate() {
-                    // Ensure all entries are connected
-                    'hops: for i in 1..path.hops.len() {
-                        let n1: &str = path.hops[i - 1].0;
-                        let n2: &str = path.hops[i].0;
-                        for edge in graph.edges.values() {
-                            if (edge.left.as_str() == n1 && edge.right.as_str() == n2) || (edge.left.as_str() == n2 && edge.right.as_str() == n1) {
-                                continue 'hops;
-                            }
+            // Show what preprocessing does, if asked
+            let mut diff: Option<String> = None;
+            let mut dumped: Option<String> = None;
+            if (args.diff && pip.has_prep()) || dumping {
+                let mut prepped: GraphGuard = GraphGuard::new(scratch);
+                pip.preprocess(&mut prepped, test.source.as_str(), test.target.as_str(), test.k);
+                if args.diff && pip.has_prep() {
+                    let changes: GraphDiff = graph.diff(&prepped);
+                    diff = Some(if changes.is_empty() {
+                        format!("Preprocessing of {} for test '{}' in benchmark '{}' did not change the graph\n", pip, test.id, name)
+                    } else {
+                        format!("Preprocessing of {} for test '{}' in benchmark '{}' changed the graph:\n{}", pip, test.id, name, changes)
+                    });
+                }
+                if dumping {
+                    dumped = Some(format!(
+                        "# Graph after preprocessing of {} for test '{}' in benchmark '{}'\n{}",
+                        pip,
+                        test.id,
+                        name,
+                        prepped.edge_list()
+                    ));
+                }
+            }
+
+            let mut record: Option<QueryRecord> = None;
+            let mut histogram: Option<CostHistogram> = None;
+            let mut mismatch: Option<Mismatch> = None;
+            let mut verified: bool = false;
+            let mut shortest: Vec<Option<(String, f64)>> = vec![None; test.k];
+            let mut inspect = |prepped: &Graph, paths: &[Path]| {
+                // Remember what was found to write it (once is enough)
+                if jsonl_given && record.is_none() {
+                    record = Some(QueryRecord::new(&name, test, pip, paths));
+                }
+                if let (Some(width), None) = (args.histogram, &histogram) {
+                    histogram = Some(CostHistogram::new(paths, width));
+                }
+
+                // Check against the reference if we're verifying (once is enough)
+                if let Some(reference) = reference {
+                    if !verified {
+                        if let Some((index, expected, got)) = compare_paths(reference, paths) {
+                            mismatch = Some(Mismatch { benchmark: name.clone(), test: test.id, pipeline: (*pip).clone(), index, expected, got });
                         }
-                        panic!("Benchmark '{}' failed for {}: not all paths are connected\n\nPath: {:?}", test.id, pip, path);
+                        verified = true;
+                    }
+                }
+
+                // Verify correctness of the paths
+                for path in paths {
+                    // Ensure all entries exist in the graph as recorded
+                    if let Err(err) = path.validate_against(prepped) {
+                        panic!("Benchmark '{}' failed for {}: {}\n\nPath:\n{}", test.id, pip, err, path.pretty(prepped));
                     }
 
                     // Ensure the path connects the test's endpoints
                     if path.hops.first().unwrap().0 != test.source.as_str() {
-                        panic!("Benchmark '{}' failed for {}: path doesn't start at test source ({})\n\nPath: {:?}", test.id, pip, test.source, path);
+                        panic!(
+                            "Benchmark '{}' failed for {}: path doesn't start at test source ({})\n\nPath:\n{}",
+                            test.id,
+                            pip,
+                            test.source,
+                            path.pretty(prepped)
+                        );
                     }
                     if path.hops.last().unwrap().0 != test.target.as_str() {
-                        panic!("Benchmark '{}' failed for {}: path doesn't start at test target ({})\n\nPath: {:?}", test.id, pip, test.target, path);
+                        panic!(
+                            "Benchmark '{}' failed for {}: path doesn't end at test target ({})\n\nPath:\n{}",
+                            test.id,
+                            pip,
+                            test.target,
+                            path.pretty(prepped)
+                        );
                     }
+                }
 
-                    // Check whether the test agrees with the minimum
-                    if let Some(prev) = &min_cost[i] {
-                        if path.cost() != prev.1 {
-                            panic!(
-                                "Benchmark '{}' failed for {}: path not shortest (got {}, previous alg got {})\n\nPath:\n{}\n\nPrev path:\n{}\n",
-                                test.id,
-                                pip,
-                                path.cost(),
-                                prev.1,
-                                path,
-                                prev.0,
-                            );
-                        }
-                    } else {
-                        min_cost[i] = Some((path.to_string(), path.cost()));
-                    }
+                // Check whether every run agrees on the minimum (if not verifying against the reference instead)
+                if reference.is_none() {
+                    let found: Vec<(String, f64)> = paths.iter().map(|path| (path.pretty(prepped), path.cost())).collect();
+                    check_shortest(&mut shortest, &found, test, pip);
+                }
+            };
+
+            // Take the paths from the cache if they're in there, or else run the pipeline
+            let key: Option<QueryKey> = graph_hash.map(|hash| QueryKey::with_hash(hash, test.source.as_str(), test.target.as_str(), test.k, pip));
+            let cached: Option<Vec<OwnedPath>> = match (&cache, &key) {
+                (Some(cache), Some(key)) => cache.get(key).unwrap_or_else(|err| {
+                    warn!("{}", trace!(("Failed to read cached paths of {} for test '{}'; running it again", pip, test.id), err));
+                    None
+                }),
+                _ => None,
+            };
+            let mut hit: bool = false;
+            if let Some(cached) = cached {
+                // NOTE: The paths were found in the preprocessed graph, so check them there
+                let mut prepped: GraphGuard = GraphGuard::new(scratch);
+                pip.preprocess(&mut prepped, test.source.as_str(), test.target.as_str(), test.k);
+                match cached.iter().map(|path| path.to_path(&prepped)).collect::<Option<Vec<Path>>>() {
+                    Some(paths) => {
+                        inspect(&prepped, &paths);
+                        hit = true;
+                    },
+                    None => warn!("Cached paths of {} for test '{}' are not in the graph; running it again", pip, test.id),
                 }
             }
-        }
+            let samples: Option<Samples> = if hit {
+                None
+            } else {
+                let mut found: Option<Vec<OwnedPath>> = None;
+                let samples: Samples = run_repeated(pip, scratch, test, opts, |prepped: &Graph, paths: &[Path]| {
+                    if key.is_some() && found.is_none() {
+                        found = Some(paths.iter().map(Path::to_owned_path).collect());
+                    }
+                    inspect(prepped, paths);
+                });
+                if let (Some(cache), Some(key), Some(found)) = (&cache, &key, found) {
+                    if let Err(err) = cache.insert(key, &found) {
+                        warn!("{}", trace!(("Failed to cache paths of {} for test '{}'", pip, test.id), err));
+                    }
+                }
+                Some(samples)
+            };
+            shared.lock().unwrap().finish_pipeline();
+            Some(Outcome { samples, diff, dump: dumped, record, mismatch, histogram, shortest: shortest.into_iter().flatten().collect() })
+        });
 
-        // Format the results in some nice table
-        if !args.csv {
-            let mut table = Table::new();
-            table.set_header(["Benchmark".to_string(), "Executed test".to_string()].into_iter().chain(args.algs.iter().map(|p| p.to_string())));
-            for (test, times) in results {
-                table.add_row(
-                    [name.to_string(), test.to_string()]
-                        .into_iter()
-                        .chain(args.algs.iter().map(|p| ((times.get(p).unwrap().alg.as_nanos() as f64) / 1000000.0).to_string())),
-                );
+        // Collect the outcomes in order, which is also when the pipelines are checked against each other
+        progress.clear();
+        let mut results: BenchmarkReport = BenchmarkReport::new(args.algs.clone());
+        let mut min_cost: Vec<Vec<Option<(String, f64)>>> = tests.iter().map(|test| vec![None; test.k]).collect();
+        for ((i, pip), outcome) in jobs.into_iter().zip(outcomes) {
+            let test: &TestCase = &tests[i];
+            let outcome: Outcome = match outcome {
+                Some(outcome) => outcome,
+                None => continue,
+            };
+            if let Some(diff) = outcome.diff {
+                eprint!("{diff}");
             }
-            println!("{table}");
-        } else {
-            // Print the header
-            if first {
-                print!("Benchmark,Executed test");
-                for pip in args.algs.iter() {
-                    print!(",{pip} duration (ms)");
+            if let (Some(dump), Some(dumped)) = (&mut dump, outcome.dump) {
+                if let Err(err) = write!(dump, "{dumped}") {
+                    error!("{}", trace!(("Failed to write graph dump"), err));
+                    std::process::exit(1);
                 }
-                println!();
             }
-
-            // Print the rows
-            for (test, times) in results {
-                print!("{name},{test}");
-                for time in args.algs.iter().map(|p| ((times.get(p).unwrap().alg.as_nanos() as f64) / 1000000.0)) {
-                    print!(",{time}");
+            if let (Some(jsonl), Some(mut record)) = (&mut jsonl, outcome.record) {
+                record.histogram = outcome.histogram.clone();
+                if let Err(err) = jsonl.write(&record) {
+                    error!("{}", trace!(("Failed to write paths for test '{}' to JSON Lines file", test.id), err));
+                    std::process::exit(1);
                 }
-                println!();
             }
+            mismatches.extend(outcome.mismatch);
+            check_shortest(&mut min_cost[i], &outcome.shortest, test, pip);
+            // NOTE: Cached queries still get a row, if without timings
+            results.entries.push(ReportEntry {
+                benchmark: name.to_string(),
+                test:      test.id,
+                pipeline:  pip.clone(),
+                timing:    outcome.samples.as_ref().map(Timing::from),
+                histogram: outcome.histogram,
+            });
+        }
+
+        // Format the results in some nice table
+        let ropts: ReportOptions = ReportOptions { csv: args.csv, repeat: args.repeat, profile_mem: opts.profile_mem };
+        print!("{}", results.format_results(ropts, first));
+        if args.histogram.is_some() {
+            print!("{}", results.format_histograms(args.csv, first));
         }
+        report.entries.extend(results.entries);
 
         // OK, did the first one
         first = false;
+        if interrupt::is_interrupted() {
+            break;
+        }
+    }
+
+    progress.finish();
+    if let Some(mut dump) = dump {
+        if let Err(err) = dump.flush() {
+            error!("{}", trace!(("Failed to write graph dump"), err));
+            std::process::exit(1);
+        }
+    }
+    if let Some(mut jsonl) = jsonl {
+        if let Err(err) = jsonl.flush() {
+            error!("{}", trace!(("Failed to write JSON Lines file"), err));
+            std::process::exit(1);
+        }
+        debug!("Wrote {} query result(s) as JSON Lines", jsonl.count());
+    }
+    if let Some(path) = &args.report {
+        if let Err(err) = fs::write(path, report.to_json()) {
+            error!("{}", trace!(("Failed to write report file '{}'", path.display()), err));
+            std::process::exit(1);
+        }
+        debug!("Wrote report of {} run(s) to '{}'", report.entries.len(), path.display());
+    }
+    if args.skip_disconnected {
+        info!("Skipped {n_skipped} test(s) between disconnected nodes");
+    }
+    if let Some(cache) = &cache {
+        info!("Answered {}/{} query(ies) from the cache", cache.hits(), cache.hits() + cache.misses());
+    }
+    if interrupt::is_interrupted() {
+        warn!("Interrupted; the results above are partial");
+    }
+
+    // Summarize the verification
+    if args.verify {
+        if mismatches.is_empty() {
+            info!("All pipelines agree with the reference");
+        } else {
+            error!("{} pipeline run(s) disagree with the reference:", mismatches.len());
+            for mismatch in &mismatches {
+                error!(" - {mismatch}");
+            }
+            std::process::exit(1);
+        }
+    }
+    if interrupt::is_interrupted() {
+        std::process::exit(130);
     }
 }
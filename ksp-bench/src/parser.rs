@@ -4,27 +4,429 @@
 //  Created:
 //    19 Jul 2024, 23:47:38
 //  Last edited:
-//    19 Jul 2024, 23:53:05
+//    16 Oct 2026, 16:16:03
 //  Auto updated?
 //    Yes
 //
 //  Description:
-//!   Parses test cases from an SNDLib XML file.
+//!   Parses test cases from an SNDLib XML file, or writes them to one.
 //
 
+use std::collections::HashMap;
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
 use std::fs::File;
 use std::io::BufReader;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub use ksp_graph::sndlib_xml::Error;
-use ksp_graph::sndlib_xml::XmlNetwork;
+use arrayvec::ArrayString;
+use ksp_graph::sndlib_xml::{XmlAdmissiblePath, XmlAdmissiblePaths, XmlDemand, XmlLink, XmlNetwork};
+use ksp_graph::Graph;
+use log::warn;
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
 
 use crate::tests::TestCase;
 
 
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_graph_costs() {
+        // Explicit zero costs are kept as-is...
+        let g: Graph = parse_graph(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/zero_costs.xml"), true).unwrap();
+        assert_eq!(g.edges.len(), 2);
+        assert!(g.edges.values().all(|e| e.cost == 0.0));
+
+        // ...but if there's no cost information at all, they're hops
+        let g: Graph = parse_graph(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/no_costs.xml"), true).unwrap();
+        assert_eq!(g.edges.len(), 2);
+        assert!(g.edges.values().all(|e| e.cost == 1.0));
+
+        // Costs are otherwise untouched
+        let g: Graph = parse_graph(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.xml"), true).unwrap();
+        assert_eq!(g.edges["Chicago-Dorchester"].cost, 6249.15);
+    }
+
+    #[test]
+    fn test_parse_graph_no_unit_fallback() {
+        // Without the fallback, no cost information at all is an error...
+        let path: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/no_costs.xml");
+        assert!(matches!(parse_graph(path, false), Err(GraphError::NoCosts { .. })));
+        assert_eq!(parse_graph(path, false).unwrap_err().to_string(), format!("Benchmark file '{path}' does not have any link costs"));
+
+        // ...but graphs that do have them are the same as with
+        for file in ["zero_costs.xml", "cities.xml"] {
+            let path: String = format!("{}/../tests/{file}", env!("CARGO_MANIFEST_DIR"));
+            assert!(parse_graph(&path, true).unwrap().diff(&parse_graph(&path, false).unwrap()).is_empty());
+        }
+        assert!(matches!(parse_graph("/does/not/exist.xml", false), Err(GraphError::Parse { .. })));
+    }
+
+    #[test]
+    fn test_parse_tests_admissible_paths() {
+        let tests: Vec<TestCase> = parse_tests(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.xml")).unwrap();
+        assert_eq!(tests.len(), 3);
+        assert_eq!(tests[0].id.as_str(), "Amsterdam-Chicago");
+        assert_eq!(tests[0].expected_paths, vec![
+            vec![
+                ArrayString::from("Amsterdam").unwrap(),
+                ArrayString::from("Edinburgh").unwrap(),
+                ArrayString::from("Dorchester").unwrap(),
+                ArrayString::from("Chicago").unwrap()
+            ],
+            vec![ArrayString::from("Amsterdam").unwrap(), ArrayString::from("Dorchester").unwrap(), ArrayString::from("Chicago").unwrap()],
+        ]);
+        assert_eq!(tests[1].expected_paths.len(), 2);
+        assert!(tests[2].expected_paths.is_empty());
+    }
+
+    #[test]
+    fn test_parse_tests_iter() {
+        for file in ["cities.xml", "zero_costs.xml", "no_costs.xml"] {
+            let path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join("tests").join(file);
+            let tests: Vec<TestCase> = parse_tests(&path).unwrap();
+            let streamed: Vec<TestCase> = parse_tests_iter(&path).collect::<Result<Vec<TestCase>, Error>>().unwrap();
+            assert_eq!(streamed, tests, "{file}");
+        }
+
+        // Errors are yielded once, after which the iterator stops
+        let mut iter = parse_tests_iter(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/does_not_exist.xml"));
+        assert!(matches!(iter.next(), Some(Err(Error::FileOpen { .. }))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_write_tests() {
+        let path: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.xml");
+        let g: Graph = parse_graph(path, true).unwrap();
+        let tests: Vec<TestCase> = parse_tests(path).unwrap();
+
+        let dir: PathBuf = std::env::temp_dir().join(format!("ksp-bench-test-write-tests-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let out: PathBuf = dir.join("cities.xml");
+        write_tests(&out, &g, &tests).unwrap();
+        assert!(g.diff(&parse_graph(&out, true).unwrap()).is_empty());
+        assert_eq!(parse_tests(&out).unwrap(), tests);
+
+        // Expected paths must be walkable
+        let mut test: TestCase = tests[0].clone();
+        test.expected_paths = vec![vec![ArrayString::from("Berlin").unwrap(), ArrayString::from("Chicago").unwrap()]];
+        assert!(matches!(write_tests(&out, &g, &[test]), Err(Error::NoLink { .. })));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating when parsing the graph of a benchmark.
+#[derive(Debug)]
+pub enum GraphError {
+    /// Failed to parse the benchmark file.
+    Parse { err: ksp_graph::sndlib_xml::Error },
+    /// The benchmark file has no cost information, and we weren't allowed to assume one per hop.
+    NoCosts { path: PathBuf },
+}
+impl Display for GraphError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use GraphError::*;
+        match self {
+            Parse { .. } => write!(f, "Failed to parse benchmark graph"),
+            NoCosts { path } => write!(f, "Benchmark file '{}' does not have any link costs", path.display()),
+        }
+    }
+}
+impl error::Error for GraphError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use GraphError::*;
+        match self {
+            Parse { err } => Some(err),
+            NoCosts { .. } => None,
+        }
+    }
+}
+
+/// Defines errors originating when parsing test cases.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to open the benchmark file.
+    FileOpen { path: PathBuf, err: std::io::Error },
+    /// Failed to parse the benchmark file as XML.
+    FileReadParse { path: PathBuf, err: quick_xml::de::DeError },
+    /// Failed to read the next XML event from the benchmark file.
+    FileRead { path: PathBuf, err: quick_xml::Error },
+    /// An admissible path referred to a link that doesn't exist.
+    UnknownLink { path: PathBuf, demand: String, admissible: String, link: String },
+    /// An admissible path has a link that isn't connected to the previous one.
+    DisconnectedLink { path: PathBuf, demand: String, admissible: String, link: String },
+    /// An expected path to write has two consecutive nodes that aren't connected by a link.
+    NoLink { path: PathBuf, demand: String, from: String, to: String },
+    /// Failed to write the benchmark file.
+    FileWrite { err: ksp_graph::sndlib_xml::Error },
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use Error::*;
+        match self {
+            FileOpen { path, .. } => write!(f, "Failed to open benchmark file '{}'", path.display()),
+            FileReadParse { path, .. } => write!(f, "Failed to read/parse benchmark file '{}' as SNDLib XML", path.display()),
+            FileRead { path, .. } => write!(f, "Failed to read benchmark file '{}' as XML", path.display()),
+            UnknownLink { path, demand, admissible, link } => {
+                write!(f, "Admissible path '{admissible}' of demand '{demand}' in benchmark file '{}' refers to unknown link '{link}'", path.display())
+            },
+            DisconnectedLink { path, demand, admissible, link } => write!(
+                f,
+                "Admissible path '{admissible}' of demand '{demand}' in benchmark file '{}' has link '{link}' that is not connected to the rest of the path",
+                path.display()
+            ),
+            NoLink { path, demand, from, to } => write!(
+                f,
+                "Expected path of test '{demand}' to write to benchmark file '{}' goes from '{from}' to '{to}', but there is no link between them",
+                path.display()
+            ),
+            FileWrite { .. } => write!(f, "Failed to write benchmark file"),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            FileOpen { err, .. } => Some(err),
+            FileReadParse { err, .. } => Some(err),
+            FileRead { err, .. } => Some(err),
+            FileWrite { err } => Some(err),
+            UnknownLink { .. } | DisconnectedLink { .. } | NoLink { .. } => None,
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Converts an admissible path, given as a sequence of links, into the sequence of nodes it visits.
+///
+/// # Arguments
+/// - `path`: The path of the file we're parsing (for debugging purposes).
+/// - `demand`: The identifier of the demand the path belongs to (for debugging purposes).
+/// - `source`: The node the path starts in.
+/// - `admissible`: The [`XmlAdmissiblePath`] to convert.
+/// - `links`: A map of all links in the network, by identifier.
+///
+/// # Returns
+/// The nodes visited by the path, including `source`.
+///
+/// # Errors
+/// This function errors if the path refers to unknown links or isn't connected.
+fn admissible_to_nodes(
+    path: &Path,
+    demand: ArrayString<64>,
+    source: ArrayString<64>,
+    admissible: &XmlAdmissiblePath,
+    links: &HashMap<ArrayString<64>, XmlLink>,
+) -> Result<Vec<ArrayString<64>>, Error> {
+    let mut nodes: Vec<ArrayString<64>> = Vec::with_capacity(1 + admissible.links.len());
+    nodes.push(source);
+    for link in &admissible.links {
+        let link: &XmlLink = match links.get(link) {
+            Some(link) => link,
+            None => return Err(Error::UnknownLink { path: path.into(), demand: demand.to_string(), admissible: admissible.id.to_string(), link: link.to_string() }),
+        };
+
+        // Links are undirected, so walk it in whichever direction continues the path
+        let end: ArrayString<64> = *nodes.last().unwrap();
+        if link.source == end {
+            nodes.push(link.target);
+        } else if link.target == end {
+            nodes.push(link.source);
+        } else {
+            return Err(Error::DisconnectedLink { path: path.into(), demand: demand.to_string(), admissible: admissible.id.to_string(), link: link.id.to_string() });
+        }
+    }
+    Ok(nodes)
+}
+
+
+/// Reads the element of which the start tag has just been read from a streaming XML reader.
+///
+/// The element is written back to a buffer as a standalone document, such that it can be deserialized as usual.
+///
+/// # Arguments
+/// - `path`: The path of the file we're parsing (for debugging purposes).
+/// - `reader`: The [`Reader`] that has just read the start tag of the element.
+/// - `start`: The start [`Event`] that was read.
+/// - `buf`: A buffer to read events into.
+///
+/// # Returns
+/// The raw XML of the element.
+///
+/// # Errors
+/// This function errors if we failed to read the rest of the element.
+fn read_element(path: &Path, reader: &mut Reader<BufReader<File>>, start: Event, buf: &mut Vec<u8>) -> Result<Vec<u8>, Error> {
+    let mut writer: Writer<Vec<u8>> = Writer::new(Vec::new());
+    // NOTE: Writing to a Vec can't fail
+    writer.write_event(start).unwrap();
+    let mut depth: usize = 1;
+    while depth > 0 {
+        buf.clear();
+        let event: Event = match reader.read_event_into(buf) {
+            Ok(Event::Eof) => return Err(Error::FileRead { path: path.into(), err: quick_xml::Error::Syntax(quick_xml::errors::SyntaxError::UnclosedTag) }),
+            Ok(event) => event,
+            Err(err) => return Err(Error::FileRead { path: path.into(), err }),
+        };
+        match &event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            _ => {},
+        }
+        writer.write_event(event).unwrap();
+    }
+    Ok(writer.into_inner())
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Iterates over the demands in an SNDLib XML file as [`TestCase`]s, reading them one at a time.
+///
+/// Created by [`parse_tests_iter()`].
+pub struct TestCaseIter {
+    /// The path of the file we're reading (for debugging purposes).
+    path:   PathBuf,
+    /// The reader reading the file. [`None`] if we're done.
+    reader: Option<Reader<BufReader<File>>>,
+    /// An error we got while opening the file, to be yielded first.
+    opened: Option<Error>,
+    /// A buffer to read events into.
+    buf:    Vec<u8>,
+    /// The links read so far, by identifier. In SNDLib, these come before the demands.
+    links:  HashMap<ArrayString<64>, XmlLink>,
+}
+impl TestCaseIter {
+    /// Reads until the next demand in the file, remembering any links encountered on the way.
+    ///
+    /// # Returns
+    /// The next demand, or [`None`] if there are none left.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read the file or failed to parse an element in it.
+    fn next_demand(&mut self) -> Result<Option<TestCase>, Error> {
+        if let Some(err) = self.opened.take() {
+            return Err(err);
+        }
+        let reader: &mut Reader<BufReader<File>> = match &mut self.reader {
+            Some(reader) => reader,
+            None => return Ok(None),
+        };
+        loop {
+            self.buf.clear();
+            let event: Event = match reader.read_event_into(&mut self.buf) {
+                Ok(event) => event.into_owned(),
+                Err(err) => return Err(Error::FileRead { path: self.path.clone(), err }),
+            };
+            match &event {
+                Event::Start(start) if start.local_name().as_ref() == b"link" => {
+                    let raw: Vec<u8> = read_element(&self.path, reader, event, &mut self.buf)?;
+                    let link: XmlLink =
+                        quick_xml::de::from_reader(raw.as_slice()).map_err(|err| Error::FileReadParse { path: self.path.clone(), err })?;
+                    self.links.insert(link.id, link);
+                },
+                Event::Start(start) if start.local_name().as_ref() == b"demand" => {
+                    let raw: Vec<u8> = read_element(&self.path, reader, event, &mut self.buf)?;
+                    let d: XmlDemand =
+                        quick_xml::de::from_reader(raw.as_slice()).map_err(|err| Error::FileReadParse { path: self.path.clone(), err })?;
+                    let mut expected_paths: Vec<Vec<ArrayString<64>>> = Vec::new();
+                    for admissible in d.admissible_paths.iter().flat_map(|a| &a.paths) {
+                        expected_paths.push(admissible_to_nodes(&self.path, d.id, d.source, admissible, &self.links)?);
+                    }
+                    return Ok(Some(TestCase { id: d.id, source: d.source, target: d.target, k: 1, expected_paths }));
+                },
+                Event::Eof => return Ok(None),
+                _ => {},
+            }
+        }
+    }
+}
+impl Iterator for TestCaseIter {
+    type Item = Result<TestCase, Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_demand() {
+            Ok(Some(test)) => Some(Ok(test)),
+            Ok(None) => {
+                self.reader = None;
+                None
+            },
+            Err(err) => {
+                // Stop after the first error
+                self.reader = None;
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+
+
+
+
 /***** LIBRARY FUNCTIONS *****/
+/// Parses the graph in an SNDLib XML file for benchmarking.
+///
+/// This is like [`ksp_graph::sndlib_xml::parse()`], except that if the file has no cost information at all (i.e., no routing costs and no
+/// distances between the nodes' coordinates), every link is assumed to cost `1.0` (i.e., paths are measured in hops). Links with an explicit cost
+/// of `0.0` are left alone.
+///
+/// # Arguments
+/// - `path`: The path where the XML file is located.
+/// - `unit_fallback`: Whether to assume a cost of `1.0` per link if the file has no cost information. If not, that's an error instead.
+///
+/// # Returns
+/// A new [`Graph`], encoding the parsed graph.
+///
+/// # Errors
+/// This function may error if we failed to read the target file or failed to parse it as (the right kind of) XML, or if it has no cost
+/// information and `unit_fallback` is false.
+pub fn parse_graph(path: impl AsRef<Path>, unit_fallback: bool) -> Result<Graph, GraphError> {
+    let path: &Path = path.as_ref();
+
+    // Parse the network, noting whether it has costs
+    let network: XmlNetwork = ksp_graph::sndlib_xml::parse_network(path).map_err(|err| GraphError::Parse { err })?;
+    let has_costs: bool = network.has_routing_costs();
+    let mut graph: Graph = network.into_graph();
+
+    // If there aren't any (not even distances), use hops instead
+    if !has_costs && graph.edges_iter().all(|e| e.cost == 0.0) {
+        if !unit_fallback {
+            return Err(GraphError::NoCosts { path: path.into() });
+        }
+        warn!("Benchmark '{}' does not have any cost associated with the links (will assume '1.0' per hop)", path.display());
+        for edge in graph.edges.values_mut() {
+            edge.cost = 1.0;
+        }
+    }
+    Ok(graph)
+}
+
 /// Parses any demands in the SNDLib XML file as [`TestCase`]s.
 ///
+/// If demands have admissible paths, then these are parsed as the test case's [expected paths](TestCase::expected_paths).
+///
 /// # Arguments
 /// - `path`: The path where the XML file is located.
 ///
@@ -32,12 +434,12 @@ use crate::tests::TestCase;
 /// A list of [`TestCase`]s, encoding the desired "test cases".
 ///
 /// # Errors
-/// This function may error if we failed to read the target file or failed to parse it as (the right kind of) XML.
+/// This function may error if we failed to read the target file, failed to parse it as (the right kind of) XML or if any admissible paths are invalid.
 pub fn parse_tests(path: impl AsRef<Path>) -> Result<Vec<TestCase>, Error> {
     let path: &Path = path.as_ref();
 
     // Open & parse the file
-    let bench: XmlNetwork = match File::open(&path) {
+    let bench: XmlNetwork = match File::open(path) {
         Ok(handle) => match quick_xml::de::from_reader(BufReader::new(handle)) {
             Ok(bench) => bench,
             Err(err) => return Err(Error::FileReadParse { path: path.into(), err }),
@@ -46,5 +448,81 @@ pub fn parse_tests(path: impl AsRef<Path>) -> Result<Vec<TestCase>, Error> {
     };
 
     // Convert it to the standardized Graph.
-    Ok(bench.demands.demands.into_iter().map(|d| TestCase { id: d.id, source: d.source, target: d.target, k: 1 }).collect())
+    let links: HashMap<ArrayString<64>, XmlLink> = bench.network_structure.links.links.iter().map(|l| (l.id, l.clone())).collect();
+    let mut tests: Vec<TestCase> = Vec::with_capacity(bench.demands.demands.len());
+    for d in &bench.demands.demands {
+        let mut expected_paths: Vec<Vec<ArrayString<64>>> = Vec::new();
+        for admissible in d.admissible_paths.iter().flat_map(|a| &a.paths) {
+            expected_paths.push(admissible_to_nodes(path, d.id, d.source, admissible, &links)?);
+        }
+        tests.push(TestCase { id: d.id, source: d.source, target: d.target, k: 1, expected_paths });
+    }
+    Ok(tests)
+}
+
+/// Parses any demands in the SNDLib XML file as [`TestCase`]s, one at a time.
+///
+/// Unlike [`parse_tests()`], this only keeps the file's links and a single demand in memory at a time. This is useful to efficiently filter a
+/// few tests from files with many demands.
+///
+/// # Arguments
+/// - `path`: The path where the XML file is located.
+///
+/// # Returns
+/// A [`TestCaseIter`] yielding the [`TestCase`]s in the file.
+///
+/// It yields an error if we failed to open or read the target file, failed to parse it as (the right kind of) XML or if any admissible paths
+/// are invalid. No further test cases are yielded after that.
+pub fn parse_tests_iter(path: impl AsRef<Path>) -> TestCaseIter {
+    let path: &Path = path.as_ref();
+    let (reader, opened): (Option<Reader<BufReader<File>>>, Option<Error>) = match File::open(path) {
+        Ok(handle) => (Some(Reader::from_reader(BufReader::new(handle))), None),
+        Err(err) => (None, Some(Error::FileOpen { path: path.into(), err })),
+    };
+    TestCaseIter { path: path.into(), reader, opened, buf: Vec::new(), links: HashMap::new() }
+}
+
+/// Writes a graph and [`TestCase`]s on it to an SNDLib XML file.
+///
+/// Every test becomes a demand (with a demand value of `1.0`), and its [expected paths](TestCase::expected_paths) become admissible paths
+/// over the cheapest link between every pair of consecutive nodes. Note that SNDLib has no notion of [`TestCase::k`], so it isn't written.
+///
+/// # Arguments
+/// - `path`: The path of the XML file to write to. Overwritten if it already exists.
+/// - `graph`: The [`Graph`] to write.
+/// - `tests`: The [`TestCase`]s to write.
+///
+/// # Errors
+/// This function errors if an expected path isn't connected in `graph` or if we failed to write the file.
+pub fn write_tests(path: impl AsRef<Path>, graph: &Graph, tests: &[TestCase]) -> Result<(), Error> {
+    let path: &Path = path.as_ref();
+
+    let mut demands: Vec<XmlDemand> = Vec::with_capacity(tests.len());
+    for test in tests {
+        let mut paths: Vec<XmlAdmissiblePath> = Vec::with_capacity(test.expected_paths.len());
+        for (i, nodes) in test.expected_paths.iter().enumerate() {
+            let mut links: Vec<ArrayString<64>> = Vec::with_capacity(nodes.len().saturating_sub(1));
+            for hop in nodes.windows(2) {
+                let link: ArrayString<64> = match graph
+                    .successors(&hop[0])
+                    .filter(|(n, _)| *n == hop[1].as_str())
+                    .min_by(|(_, e1), (_, e2)| e1.cost.total_cmp(&e2.cost))
+                {
+                    Some((_, e)) => e.id,
+                    None => return Err(Error::NoLink { path: path.into(), demand: test.id.to_string(), from: hop[0].to_string(), to: hop[1].to_string() }),
+                };
+                links.push(link);
+            }
+            // NOTE: Cannot fail, as a `usize` is always shorter than 64 bytes
+            paths.push(XmlAdmissiblePath { id: ArrayString::from(&format!("P_{i}")).unwrap(), links });
+        }
+        demands.push(XmlDemand {
+            id: test.id,
+            source: test.source,
+            target: test.target,
+            demand_value: 1.0,
+            admissible_paths: if paths.is_empty() { None } else { Some(XmlAdmissiblePaths { paths }) },
+        });
+    }
+    ksp_graph::sndlib_xml::write_network(path, &XmlNetwork::from_graph(graph, demands)).map_err(|err| Error::FileWrite { err })
 }
@@ -0,0 +1,191 @@
+//  PROGRESS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:47:00
+//  Last edited:
+//    16 Oct 2026, 15:48:57
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a simple progress bar for long benchmark runs.
+//
+
+use std::io::{Stderr, Write};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        let mut progress: Progress<Vec<u8>> = Progress::new(Vec::new(), 2);
+        progress.start_file("cities", 2, 3);
+        for _ in 0..6 {
+            progress.finish_pipeline();
+        }
+        progress.start_file("india35", 1, 3);
+        for _ in 0..3 {
+            progress.finish_pipeline();
+        }
+        let out: String = String::from_utf8(progress.out.clone().unwrap()).unwrap();
+        assert!(out.contains("[file 1/2] [test 1/2] [pipeline 0/3]"));
+        assert!(out.contains("[file 1/2] [test 2/2] [pipeline 3/3]"));
+        assert!(out.contains("[file 2/2] [test 1/1] [pipeline 1/3]"));
+        assert!(out.contains("cities") && out.contains("india35"));
+        assert!(out.contains(" 100%"));
+
+        // It can be cleared temporarily, or for good
+        progress.clear();
+        assert!(progress.out.as_ref().unwrap().ends_with(CLEAR.as_bytes()));
+        progress.finish();
+        progress.finish_pipeline();
+        assert!(progress.out.is_none());
+
+        // Hidden progress writes nothing at all
+        let mut progress: Progress<Vec<u8>> = Progress::hidden();
+        progress.start_file("cities", 2, 3);
+        progress.finish_pipeline();
+        progress.finish();
+        assert!(progress.out.is_none());
+    }
+}
+
+
+
+
+
+/***** CONSTANTS *****/
+/// The ANSI sequence that moves the cursor to the start of the line and clears it.
+const CLEAR: &str = "\r\x1b[2K";
+
+/// The width of the bar itself, in characters.
+const WIDTH: usize = 30;
+
+
+
+
+
+/***** LIBRARY *****/
+/// Shows how many benchmark files, tests and pipelines have been completed on a single line.
+///
+/// The line is redrawn in-place on every update, so it should be written somewhere other than the results (i.e., stderr). Use
+/// [`Progress::clear()`] before writing anything else to the same stream.
+#[derive(Debug)]
+pub struct Progress<W> {
+    /// Where to draw the bar, or [`None`] if it's hidden (or done).
+    out:       Option<W>,
+    /// The name of the current file.
+    name:      String,
+    /// The number of the current file (starting at 1), and the total number of files.
+    files:     (usize, usize),
+    /// The number of the current test (starting at 1), and the number of tests in the current file.
+    tests:     (usize, usize),
+    /// The number of pipelines completed for the current test, and the number of pipelines per test.
+    pipelines: (usize, usize),
+}
+impl Progress<Stderr> {
+    /// Constructor for a Progress that draws to stderr.
+    ///
+    /// # Arguments
+    /// - `files`: The total number of benchmark files that will be run.
+    /// - `show`: Whether to actually show the bar. If not, this is the same as [`Progress::hidden()`].
+    ///
+    /// # Returns
+    /// A new Progress that hasn't drawn anything yet.
+    #[inline]
+    pub fn stderr(files: usize, show: bool) -> Self { if show { Self::new(std::io::stderr(), files) } else { Self::hidden() } }
+}
+impl<W: Write> Progress<W> {
+    /// Constructor for the Progress.
+    ///
+    /// # Arguments
+    /// - `out`: The [`Write`]r to draw the bar to.
+    /// - `files`: The total number of benchmark files that will be run.
+    ///
+    /// # Returns
+    /// A new Progress that hasn't drawn anything yet.
+    #[inline]
+    pub fn new(out: W, files: usize) -> Self {
+        Self { out: Some(out), name: String::new(), files: (0, files), tests: (0, 0), pipelines: (0, 0) }
+    }
+
+    /// Constructor for a Progress that doesn't draw anything.
+    ///
+    /// # Returns
+    /// A new Progress for which all methods are no-ops.
+    #[inline]
+    pub fn hidden() -> Self { Self { out: None, name: String::new(), files: (0, 0), tests: (0, 0), pipelines: (0, 0) } }
+
+    /// Draws the bar with the current progress.
+    fn draw(&mut self) {
+        let out: &mut W = match &mut self.out {
+            Some(out) => out,
+            None => return,
+        };
+
+        // Compute the overall fraction, assuming all tests take equally long
+        let (file, files): (usize, usize) = self.files;
+        let (test, tests): (usize, usize) = self.tests;
+        let (pipeline, pipelines): (usize, usize) = self.pipelines;
+        let in_test: f64 = if pipelines > 0 { pipeline as f64 / pipelines as f64 } else { 1.0 };
+        let in_file: f64 = if tests > 0 { (test.saturating_sub(1) as f64 + in_test) / tests as f64 } else { 1.0 };
+        let total: f64 = if files > 0 { ((file.saturating_sub(1) as f64 + in_file) / files as f64).min(1.0) } else { 1.0 };
+
+        // Draw it
+        let filled: usize = (total * WIDTH as f64) as usize;
+        // NOTE: Failing to draw the progress is not worth failing the benchmark over
+        let _ = write!(
+            out,
+            "{CLEAR}[file {file}/{files}] [test {test}/{tests}] [pipeline {pipeline}/{pipelines}] [{}{}] {:3.0}% {}",
+            "=".repeat(filled),
+            " ".repeat(WIDTH - filled),
+            100.0 * total,
+            self.name
+        );
+        let _ = out.flush();
+    }
+
+    /// Moves to the next benchmark file.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the benchmark, shown next to the bar.
+    /// - `tests`: The number of tests in it.
+    /// - `pipelines`: The number of pipelines that will be run per test.
+    pub fn start_file(&mut self, name: &str, tests: usize, pipelines: usize) {
+        self.name = name.into();
+        self.files.0 += 1;
+        self.tests = (tests.min(1), tests);
+        self.pipelines = (0, pipelines);
+        self.draw();
+    }
+
+    /// Records that a pipeline has been run on the current test, moving to the next test if all of them have.
+    pub fn finish_pipeline(&mut self) {
+        self.pipelines.0 += 1;
+        if self.pipelines.0 > self.pipelines.1 && self.tests.0 < self.tests.1 {
+            self.tests.0 += 1;
+            self.pipelines.0 = 1;
+        }
+        self.draw();
+    }
+
+    /// Removes the bar from the line, e.g., to write something else. It is drawn again on the next update.
+    #[inline]
+    pub fn clear(&mut self) {
+        if let Some(out) = &mut self.out {
+            let _ = write!(out, "{CLEAR}");
+            let _ = out.flush();
+        }
+    }
+
+    /// Removes the bar for good. Further updates draw nothing.
+    #[inline]
+    pub fn finish(&mut self) {
+        self.clear();
+        self.out = None;
+    }
+}
@@ -0,0 +1,198 @@
+//  RANDOM.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:55:25
+//  Last edited:
+//    16 Oct 2026, 14:57:00
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Generates random [`TestCase`]s over a graph for load testing.
+//
+
+use std::ops::RangeInclusive;
+
+use arrayvec::ArrayString;
+use ksp_graph::Graph;
+
+use crate::tests::TestCase;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_rng_seeded() {
+        let mut a: SeededRng = SeededRng::new(42);
+        let mut b: SeededRng = SeededRng::new(42);
+        let mut c: SeededRng = SeededRng::new(43);
+        let a: Vec<usize> = (0..16).map(|_| a.range(0..=100)).collect();
+        assert_eq!(a, (0..16).map(|_| b.range(0..=100)).collect::<Vec<usize>>());
+        assert_ne!(a, (0..16).map(|_| c.range(0..=100)).collect::<Vec<usize>>());
+        assert!(a.iter().all(|n| *n <= 100));
+    }
+
+    #[test]
+    fn test_random_tests_connected() {
+        // Isolate Chicago, so that pairs with it should be rejected
+        let mut g: Graph = load_graph("cities");
+        g.edges.get_mut("Chicago-Dorchester").unwrap().cost = f64::INFINITY;
+
+        for seed in 0..16 {
+            let tests: Vec<TestCase> = random_tests(&g, 6, 1..=3, seed);
+            // There are exactly 6 connected pairs in the remaining 4 nodes
+            assert_eq!(tests.len(), 6);
+            let mut pairs: HashSet<(&str, &str)> = HashSet::new();
+            for test in &tests {
+                assert_ne!(test.source, test.target);
+                assert!(g.are_connected(test.source.as_str(), test.target.as_str()), "{} -> {}", test.source, test.target);
+                assert!((1..=3).contains(&test.k));
+                assert!(pairs.insert(if test.source < test.target {
+                    (test.source.as_str(), test.target.as_str())
+                } else {
+                    (test.target.as_str(), test.source.as_str())
+                }));
+            }
+
+            // Asking for more gives up gracefully
+            assert_eq!(random_tests(&g, 7, 1..=3, seed).len(), 6);
+        }
+
+        // The same seed gives the same tests
+        assert_eq!(random_tests(&g, 4, 1..=8, 7), random_tests(&g, 4, 1..=8, 7));
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// A small, seedable pseudo-random number generator (SplitMix64).
+///
+/// Not cryptographically secure in the slightest, but fast and reproducible across platforms, which is all we need to generate tests.
+#[derive(Clone, Copy, Debug)]
+pub struct SeededRng {
+    /// The current state of the generator.
+    state: u64,
+}
+impl SeededRng {
+    /// Constructor for the SeededRng.
+    ///
+    /// # Arguments
+    /// - `seed`: The seed that determines the sequence of numbers generated.
+    ///
+    /// # Returns
+    /// A new SeededRng.
+    #[inline]
+    pub const fn new(seed: u64) -> Self { Self { state: seed } }
+
+    /// Generates the next random number.
+    ///
+    /// # Returns
+    /// A uniformly distributed [`u64`].
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z: u64 = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Generates a random number in the given range.
+    ///
+    /// # Arguments
+    /// - `range`: The (inclusive) range to generate in.
+    ///
+    /// # Returns
+    /// A number in `range`. Its distribution is very close to uniform for small ranges.
+    ///
+    /// # Panics
+    /// This function panics if `range` is empty.
+    #[inline]
+    #[track_caller]
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end): (usize, usize) = range.into_inner();
+        if start > end {
+            panic!("Cannot generate a number in empty range {start}..={end}");
+        }
+        match (end - start).checked_add(1) {
+            Some(n) => start + (self.next_u64() % (n as u64)) as usize,
+            None => self.next_u64() as usize,
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Generates random test cases between connected nodes in a graph.
+///
+/// Every pair of nodes is used at most once (in either direction), and pairs that are not connected are rejected.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to generate tests for.
+/// - `count`: The number of tests to generate.
+/// - `k`: The range from which to randomly pick the number of paths to find for every test.
+/// - `seed`: The seed for the random generator. The same seed and graph always result in the same tests.
+///
+/// # Returns
+/// A list of at most `count` [`TestCase`]s, named `random-<i>`. It is shorter if there aren't `count` distinct connected pairs in the graph.
+///
+/// # Panics
+/// This function panics if `k` is empty.
+#[track_caller]
+pub fn random_tests(graph: &Graph, count: usize, k: RangeInclusive<usize>, seed: u64) -> Vec<TestCase> {
+    if k.is_empty() {
+        panic!("Cannot generate tests with an empty range of K ({}..={})", k.start(), k.end());
+    }
+    let mut rng: SeededRng = SeededRng::new(seed);
+
+    // Sort the nodes to not depend on the order of the map
+    let mut nodes: Vec<&str> = graph.nodes.keys().map(|id| id.as_str()).collect();
+    nodes.sort();
+
+    // Find all pairs, but in random order to pick the first connected ones
+    let mut pairs: Vec<(&str, &str)> = Vec::with_capacity(nodes.len() * nodes.len().saturating_sub(1) / 2);
+    for (i, src) in nodes.iter().enumerate() {
+        for dst in &nodes[i + 1..] {
+            pairs.push((src, dst));
+        }
+    }
+    for i in (1..pairs.len()).rev() {
+        pairs.swap(i, rng.range(0..=i));
+    }
+
+    // Then take the connected ones
+    let mut tests: Vec<TestCase> = Vec::with_capacity(count);
+    for (src, dst) in pairs {
+        if tests.len() >= count {
+            break;
+        }
+        if !graph.are_connected(src, dst) {
+            continue;
+        }
+
+        // Randomly swap the direction, too
+        let (src, dst): (&str, &str) = if rng.range(0..=1) == 0 { (src, dst) } else { (dst, src) };
+        tests.push(TestCase {
+            // NOTE: Always fits, as `usize` has at most 20 digits
+            id: ArrayString::from(&format!("random-{}", tests.len())).unwrap(),
+            source: ArrayString::from(src).unwrap(),
+            target: ArrayString::from(dst).unwrap(),
+            k: rng.range(k.clone()),
+            expected_paths: vec![],
+        });
+    }
+    tests
+}
@@ -0,0 +1,386 @@
+//  REPORT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:49:38
+//  Last edited:
+//    16 Oct 2026, 17:33:13
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Collects the results of benchmarking in a [`BenchmarkReport`], and
+//!   formats it as a table, as CSV or as JSON.
+//
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use arrayvec::ArrayString;
+use comfy_table::Table;
+use ksp::Pipeline;
+use serde::{Deserialize, Serialize};
+
+use crate::histogram::{CostHistogram, CostSummary};
+use crate::runner::{MemoryProfile, Samples};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use ksp::PipelineProfile;
+
+    use super::*;
+
+    /// Creates the entry of a single run that took the given number of milliseconds.
+    fn entry(benchmark: &str, test: &str, pipeline: &Pipeline, ms: u64) -> ReportEntry {
+        let samples: Samples =
+            Samples { profiles: vec![PipelineProfile { prep: vec![], alg: Duration::from_millis(ms), sssp: vec![] }], memory: vec![] };
+        ReportEntry {
+            benchmark: benchmark.into(),
+            test:      ArrayString::from(test).unwrap(),
+            pipeline:  pipeline.clone(),
+            timing:    Some(Timing::from(&samples)),
+            histogram: None,
+        }
+    }
+
+    #[test]
+    fn test_format_results_partial() {
+        let algs: Vec<Pipeline> = vec!["yen<dijkstra>".parse().unwrap(), "peek->yen<dijkstra>".parse().unwrap()];
+
+        // As if interrupted halfway through the second test
+        let mut report: BenchmarkReport = BenchmarkReport::new(algs.clone());
+        report.entries.push(entry("cities", "t2", &algs[0], 3));
+        report.entries.push(entry("cities", "t1", &algs[0], 1));
+        report.entries.push(entry("cities", "t1", &algs[1], 2));
+
+        let opts: ReportOptions = ReportOptions { csv: true, repeat: 1, profile_mem: false };
+        assert_eq!(
+            report.format_results(opts, true),
+            "Benchmark,Executed test,Yen<Dijkstra> duration (ms),Peek->Yen<Dijkstra> duration (ms)\ncities,t1,1,2\ncities,t2,3,\n"
+        );
+        assert_eq!(report.format_results(opts, false), "cities,t1,1,2\ncities,t2,3,\n");
+
+        let table: String = report.format_results(ReportOptions { csv: false, ..opts }, true);
+        let rows: Vec<&str> = table.lines().filter(|l| l.contains("cities")).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].contains("t1") && rows[0].contains(" 1 ") && rows[0].contains(" 2 "));
+        assert!(rows[1].contains("t2") && rows[1].contains(" 3 ") && rows[1].contains(" - "));
+    }
+
+    #[test]
+    fn test_format_results_csv() {
+        let algs: Vec<Pipeline> = vec!["yen<dijkstra>".parse().unwrap(), "wikipedia".parse().unwrap()];
+
+        // Benchmarks keep their order, tests are sorted, and cached queries have a row without timings
+        let mut report: BenchmarkReport = BenchmarkReport::new(algs.clone());
+        report.entries.push(entry("india35", "t1", &algs[1], 4));
+        report.entries.push(ReportEntry {
+            benchmark: "cities".into(),
+            test:      ArrayString::from("t1").unwrap(),
+            pipeline:  algs[0].clone(),
+            timing:    Some(Timing { runs: 3, median_ms: 1.5, stdev_ms: 0.25, memory: Some(MemoryProfile { peak_bytes: 1024, allocations: 7 }) }),
+            histogram: None,
+        });
+        report.entries.push(ReportEntry { timing: None, ..entry("cities", "t0", &algs[1], 0) });
+
+        let opts: ReportOptions = ReportOptions { csv: true, repeat: 3, profile_mem: true };
+        assert_eq!(
+            report.format_results(opts, true),
+            "Benchmark,Executed test,Yen<Dijkstra> duration (ms),Yen<Dijkstra> stdev (ms),Yen<Dijkstra> peak memory (B),Yen<Dijkstra> \
+             allocations,Wikipedia duration (ms),Wikipedia stdev (ms),Wikipedia peak memory (B),Wikipedia allocations\n\
+             india35,t1,,,,,4,0,0,0\n\
+             cities,t0,,,,,,,,\n\
+             cities,t1,1.5,0.25,1024,7,,,,\n"
+        );
+    }
+
+    #[test]
+    fn test_format_histograms() {
+        let algs: Vec<Pipeline> = vec!["yen<dijkstra>".parse().unwrap(), "wikipedia".parse().unwrap()];
+        let mut report: BenchmarkReport = BenchmarkReport::new(algs.clone());
+        report.entries.push(ReportEntry { histogram: Some(CostHistogram::from_costs([], 2.0)), ..entry("cities", "t1", &algs[1], 1) });
+        report.entries.push(ReportEntry { histogram: Some(CostHistogram::from_costs([1.0, 2.0, 5.0], 2.0)), ..entry("cities", "t1", &algs[0], 1) });
+
+        assert_eq!(
+            report.format_histograms(true, true),
+            "Benchmark,Executed test,Pipeline,Paths,Min cost,Median cost,Max cost,Spread,Bucket width,First bucket,Counts\n\
+             cities,t1,Yen<Dijkstra>,3,1,2,5,4,2,0,1;1;1\ncities,t1,Wikipedia,0,,,,,2,0,\n"
+        );
+        let table: String = report.format_histograms(false, true);
+        assert!(table.contains("[0, 2): 1, [2, 4): 1, [4, 6): 1"));
+        assert_eq!(table.lines().filter(|l| l.contains("cities")).count(), 2);
+    }
+
+    #[test]
+    fn test_report_json() {
+        let algs: Vec<Pipeline> = vec!["peek->yen<dijkstra>".parse().unwrap()];
+        let mut report: BenchmarkReport = BenchmarkReport::new(algs.clone());
+        report.entries.push(ReportEntry { histogram: Some(CostHistogram::from_costs([1.0, 2.0], 2.0)), ..entry("cities", "t1", &algs[0], 2) });
+        report.entries.push(ReportEntry { timing: None, ..entry("cities", "t2", &algs[0], 0) });
+
+        let json: String = report.to_json();
+        assert!(json.contains("\"pipeline\":\"peek->yen<dijkstra>\""));
+        assert_eq!(serde_json::from_str::<BenchmarkReport>(&json).unwrap(), report);
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Converts a [`Duration`] to fractional milliseconds.
+///
+/// # Arguments
+/// - `time`: The [`Duration`] to convert.
+///
+/// # Returns
+/// The number of milliseconds in `time`, as a float.
+#[inline]
+fn as_millis(time: Duration) -> f64 { (time.as_nanos() as f64) / 1000000.0 }
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Configures how [`BenchmarkReport::format_results()`] formats results.
+#[derive(Clone, Copy, Debug)]
+pub struct ReportOptions {
+    /// Whether to format as Comma-Separated Values (CSV) instead of a table.
+    pub csv: bool,
+    /// The number of timed runs per test. If more than one, the standard deviation is reported too.
+    pub repeat: usize,
+    /// Whether memory was profiled. Only used for CSV, where it adds columns; tables show memory usage whenever it's there.
+    pub profile_mem: bool,
+}
+
+
+
+/// Summarizes the timed runs of a pipeline on a single test.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Timing {
+    /// The number of timed runs.
+    pub runs:      usize,
+    /// The median time the main algorithm took, in milliseconds.
+    pub median_ms: f64,
+    /// The (population) standard deviation of the time the main algorithm took, in milliseconds.
+    pub stdev_ms:  f64,
+    /// The worst memory usage of any run, if memory was profiled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory:    Option<MemoryProfile>,
+}
+impl From<&Samples> for Timing {
+    #[inline]
+    fn from(value: &Samples) -> Self {
+        Self {
+            runs:      value.profiles.len(),
+            median_ms: as_millis(value.median_alg()),
+            stdev_ms:  as_millis(value.stdev_alg()),
+            memory:    value.peak_memory(),
+        }
+    }
+}
+
+/// The results of running a single pipeline on a single test.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReportEntry {
+    /// The benchmark the test is from.
+    pub benchmark: String,
+    /// The test case that was run.
+    pub test:      ArrayString<64>,
+    /// The pipeline that ran it.
+    pub pipeline:  Pipeline,
+    /// How long it took, or [`None`] if the paths came from the cache.
+    pub timing:    Option<Timing>,
+    /// The histogram of the costs of the paths found, if asked for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<CostHistogram>,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// The results of running pipelines on the tests of one or more benchmarks.
+///
+/// Rows are formatted per test, in order of the benchmark's first entry and then of the test identifiers. Pipelines that haven't been run
+/// for a test (e.g., because the benchmark was [interrupted](crate::interrupt)) are reported as `-` in tables and as empty cells in CSV.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BenchmarkReport {
+    /// The pipelines that were run, in order of the columns.
+    pub pipelines: Vec<Pipeline>,
+    /// The results of every pipeline on every test, in any order.
+    pub entries:   Vec<ReportEntry>,
+}
+impl BenchmarkReport {
+    /// Constructor for the BenchmarkReport.
+    ///
+    /// # Arguments
+    /// - `pipelines`: The [`Pipeline`]s that are run, in order of the columns.
+    ///
+    /// # Returns
+    /// A new BenchmarkReport without any entries.
+    #[inline]
+    pub fn new(pipelines: Vec<Pipeline>) -> Self { Self { pipelines, entries: Vec::new() } }
+
+    /// Groups the entries into rows.
+    ///
+    /// # Returns
+    /// The benchmark, test and entries of every row, in the order in which they are formatted.
+    fn rows(&self) -> Vec<(&str, &str, Vec<&ReportEntry>)> {
+        let mut benchmarks: Vec<&str> = Vec::new();
+        let mut rows: HashMap<(&str, &str), Vec<&ReportEntry>> = HashMap::new();
+        for entry in &self.entries {
+            if !benchmarks.contains(&entry.benchmark.as_str()) {
+                benchmarks.push(&entry.benchmark);
+            }
+            rows.entry((entry.benchmark.as_str(), entry.test.as_str())).or_default().push(entry);
+        }
+        let mut rows: Vec<(&str, &str, Vec<&ReportEntry>)> = rows.into_iter().map(|((bench, test), entries)| (bench, test, entries)).collect();
+        rows.sort_by_key(|(bench, test, _)| (benchmarks.iter().position(|b| b == bench), *test));
+        rows
+    }
+
+    /// Formats the timings of the report.
+    ///
+    /// # Arguments
+    /// - `opts`: The [`ReportOptions`] determining the format.
+    /// - `header`: Whether to include the CSV header. Tables always have one.
+    ///
+    /// # Returns
+    /// The formatted results, ending in a newline.
+    pub fn format_results(&self, opts: ReportOptions, header: bool) -> String {
+        let rows: Vec<(&str, &str, Vec<&ReportEntry>)> = self.rows();
+        let timing = |entries: &[&ReportEntry], pip: &Pipeline| -> Option<Timing> { entries.iter().find(|e| e.pipeline == *pip)?.timing };
+
+        // NOTE: Writing to a `String` can't fail
+        let mut out: String = String::new();
+        if !opts.csv {
+            let mut table = Table::new();
+            table.set_header(["Benchmark".to_string(), "Executed test".to_string()].into_iter().chain(self.pipelines.iter().map(|p| p.to_string())));
+            for (bench, test, entries) in rows {
+                table.add_row([bench.to_string(), test.to_string()].into_iter().chain(self.pipelines.iter().map(|p| {
+                    let timing: Timing = match timing(&entries, p) {
+                        Some(timing) => timing,
+                        None => return "-".to_string(),
+                    };
+                    let mut cell: String =
+                        if opts.repeat > 1 { format!("{} ± {}", timing.median_ms, timing.stdev_ms) } else { timing.median_ms.to_string() };
+                    if let Some(mem) = timing.memory {
+                        cell.push_str(&format!(" ({} B peak, {} allocs)", mem.peak_bytes, mem.allocations));
+                    }
+                    cell
+                })));
+            }
+            writeln!(out, "{table}").unwrap();
+        } else {
+            // Write the header
+            if header {
+                write!(out, "Benchmark,Executed test").unwrap();
+                for pip in &self.pipelines {
+                    write!(out, ",{pip} duration (ms)").unwrap();
+                    if opts.repeat > 1 {
+                        write!(out, ",{pip} stdev (ms)").unwrap();
+                    }
+                    if opts.profile_mem {
+                        write!(out, ",{pip} peak memory (B),{pip} allocations").unwrap();
+                    }
+                }
+                writeln!(out).unwrap();
+            }
+
+            // Write the rows
+            for (bench, test, entries) in rows {
+                write!(out, "{bench},{test}").unwrap();
+                for pip in &self.pipelines {
+                    let timing: Timing = match timing(&entries, pip) {
+                        Some(timing) => timing,
+                        None => {
+                            let cols: usize = 1 + (opts.repeat > 1) as usize + 2 * (opts.profile_mem as usize);
+                            write!(out, "{}", ",".repeat(cols)).unwrap();
+                            continue;
+                        },
+                    };
+                    write!(out, ",{}", timing.median_ms).unwrap();
+                    if opts.repeat > 1 {
+                        write!(out, ",{}", timing.stdev_ms).unwrap();
+                    }
+                    if opts.profile_mem {
+                        let mem: MemoryProfile = timing.memory.unwrap_or_default();
+                        write!(out, ",{},{}", mem.peak_bytes, mem.allocations).unwrap();
+                    }
+                }
+                writeln!(out).unwrap();
+            }
+        }
+        out
+    }
+
+    /// Formats the cost histograms of the paths found.
+    ///
+    /// Rows are in the same order as for [`BenchmarkReport::format_results()`], and then pipelines in order of the columns. Entries without
+    /// a histogram are omitted.
+    ///
+    /// # Arguments
+    /// - `csv`: Whether to format as Comma-Separated Values (CSV) instead of a table. The counts are then separated by semicolons.
+    /// - `header`: Whether to include the CSV header. Tables always have one.
+    ///
+    /// # Returns
+    /// The formatted histograms, ending in a newline.
+    pub fn format_histograms(&self, csv: bool, header: bool) -> String {
+        let rows = self.rows().into_iter().flat_map(|(bench, test, entries)| {
+            self.pipelines
+                .iter()
+                .filter_map(move |pip| entries.iter().find(|e| e.pipeline == *pip)?.histogram.as_ref().map(|hist| (bench, test, pip, hist)))
+        });
+
+        // NOTE: Writing to a `String` can't fail
+        let mut out: String = String::new();
+        if !csv {
+            let mut table = Table::new();
+            table.set_header(["Benchmark", "Executed test", "Pipeline", "Paths", "Min cost", "Median cost", "Max cost", "Spread", "Histogram"]);
+            for (bench, test, pip, hist) in rows {
+                let summary: [String; 4] = match hist.summary {
+                    Some(CostSummary { min, median, max, spread }) => [min, median, max, spread].map(|cost| cost.to_string()),
+                    None => ["-", "-", "-", "-"].map(String::from),
+                };
+                table.add_row(
+                    [bench.to_string(), test.to_string(), pip.to_string(), hist.counts.iter().sum::<usize>().to_string()]
+                        .into_iter()
+                        .chain(summary)
+                        .chain([hist.to_string()]),
+                );
+            }
+            writeln!(out, "{table}").unwrap();
+        } else {
+            if header {
+                writeln!(out, "Benchmark,Executed test,Pipeline,Paths,Min cost,Median cost,Max cost,Spread,Bucket width,First bucket,Counts").unwrap();
+            }
+            for (bench, test, pip, hist) in rows {
+                write!(out, "{bench},{test},{pip},{}", hist.counts.iter().sum::<usize>()).unwrap();
+                match hist.summary {
+                    Some(CostSummary { min, median, max, spread }) => write!(out, ",{min},{median},{max},{spread}").unwrap(),
+                    None => write!(out, ",,,,").unwrap(),
+                }
+                let counts: Vec<String> = hist.counts.iter().map(usize::to_string).collect();
+                writeln!(out, ",{},{},{}", hist.width, hist.start, counts.join(";")).unwrap();
+            }
+        }
+        out
+    }
+
+    /// Formats the report as JSON, such that it can be saved and post-processed.
+    ///
+    /// # Returns
+    /// The report as a single JSON object, which deserializes back to it.
+    #[inline]
+    pub fn to_json(&self) -> String {
+        // NOTE: Serializing can't fail, as all keys are strings
+        serde_json::to_string(self).unwrap()
+    }
+}
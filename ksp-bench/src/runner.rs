@@ -0,0 +1,346 @@
+//  RUNNER.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:30:12
+//  Last edited:
+//    16 Oct 2026, 19:30:05
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines how to (repeatedly) run a [`Pipeline`] on a [`TestCase`] to
+//!   collect stable timings.
+//
+
+use std::time::Duration;
+
+use ksp::{OwnedPath, Path, Pipeline, PipelineProfile};
+use ksp_graph::{Graph, GraphGuard};
+use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator as _};
+use rayon::ThreadPool;
+use serde::{Deserialize, Serialize};
+
+use crate::tests::TestCase;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use arrayvec::ArrayString;
+
+    use super::*;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_run_repeated() {
+        let mut g: Graph = load_graph("cities");
+        let pip: Pipeline = "yen<dijkstra>".parse().unwrap();
+        let test: TestCase = TestCase {
+            id:     ArrayString::from("test").unwrap(),
+            source: ArrayString::from("Amsterdam").unwrap(),
+            target: ArrayString::from("Chicago").unwrap(),
+            k:      2,
+            expected_paths: vec![],
+        };
+
+        // Every run is inspected, but only the timed ones are sampled
+        let mut runs: usize = 0;
+        let samples: Samples = run_repeated(&pip, &mut g, &test, RunOptions { warmup: 2, repeat: 3, ..Default::default() }, |_, paths| {
+            assert_eq!(paths.len(), 2);
+            runs += 1;
+        });
+        assert_eq!(runs, 5);
+        assert_eq!(samples.profiles.len(), 3);
+        assert!(samples.memory.is_empty());
+
+        // The default only samples once
+        let samples: Samples = run_repeated(&pip, &mut g, &test, RunOptions::default(), |_, _| {});
+        assert_eq!(samples.profiles.len(), 1);
+        assert_eq!(samples.stdev_alg(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_run_repeated_fallback() {
+        let mut g: Graph = load_graph("cities");
+        let pip: Pipeline = "yen<dijkstra>".parse().unwrap();
+        let test: TestCase = TestCase {
+            id:     ArrayString::from("test").unwrap(),
+            source: ArrayString::from("Amsterdam").unwrap(),
+            target: ArrayString::from("Chicago").unwrap(),
+            k:      2,
+            expected_paths: vec![],
+        };
+        let mut expected: Vec<OwnedPath> = Vec::new();
+        run_repeated(&pip, &mut g, &test, RunOptions::default(), |_, paths| expected = paths.iter().map(Path::to_owned_path).collect());
+        assert_eq!(expected.len(), 2);
+
+        // A prune that is too tight removes the second path, so every run falls back to the original graph
+        let prune = |g: &mut GraphGuard| {
+            g.remove_node("Edinburgh");
+            vec![Duration::ZERO]
+        };
+        let mut runs: usize = 0;
+        let opts: RunOptions = RunOptions { warmup: 1, repeat: 2, ..Default::default() };
+        let samples: Samples = run_repeated_with(&pip, &mut g, &test, opts, prune, |graph, paths| {
+            assert!(graph.nodes.contains_key("Edinburgh"));
+            assert_eq!(paths.iter().map(Path::to_owned_path).collect::<Vec<OwnedPath>>(), expected);
+            runs += 1;
+        });
+        assert_eq!(runs, 3);
+        assert_eq!(samples.profiles.len(), 2);
+        assert!(samples.profiles.iter().all(|profile| profile.prep.len() == 1));
+        assert_eq!(g.node_count(), load_graph("cities").node_count());
+    }
+
+    #[test]
+    fn test_run_jobs() {
+        let g: Graph = load_graph("cities");
+        let tests: Vec<TestCase> = crate::parser::parse_tests(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.xml")).unwrap();
+        let pips: Vec<Pipeline> = ["yen<dijkstra>", "wikipedia", "peek->yen<dijkstra>"].into_iter().map(|p| p.parse().unwrap()).collect();
+        let jobs: Vec<(&TestCase, &Pipeline)> = tests.iter().flat_map(|t| pips.iter().map(move |p| (t, p))).collect();
+
+        // Run every job on pools of different sizes
+        let run = |threads: usize| -> Vec<(ArrayString<64>, Pipeline, Vec<OwnedPath>)> {
+            let pool: ThreadPool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            run_jobs(&pool, &g, &jobs, |scratch: &mut Graph, (test, pip): &(&TestCase, &Pipeline)| {
+                let mut found: Vec<OwnedPath> = Vec::new();
+                run_repeated(pip, scratch, test, RunOptions { repeat: 2, ..Default::default() }, |_, paths| {
+                    found = paths.iter().map(Path::to_owned_path).collect();
+                });
+                (test.id, (*pip).clone(), found)
+            })
+        };
+        let sequential = run(1);
+        assert_eq!(sequential.len(), tests.len() * pips.len());
+        for ((id, pip, paths), (test, expected)) in sequential.iter().zip(&jobs) {
+            assert_eq!((id, pip), (&test.id, *expected));
+            assert!(!paths.is_empty());
+        }
+
+        // The results are the same, and in the same order, regardless of the number of threads
+        for _ in 0..5 {
+            assert_eq!(run(4), sequential);
+        }
+    }
+
+    #[cfg(feature = "profile_mem")]
+    #[test]
+    fn test_run_repeated_profile_mem() {
+        let mut g: Graph = load_graph("cities");
+        let pip: Pipeline = "yen<dijkstra>".parse().unwrap();
+        let test: TestCase = TestCase {
+            id:     ArrayString::from("test").unwrap(),
+            source: ArrayString::from("Amsterdam").unwrap(),
+            target: ArrayString::from("Chicago").unwrap(),
+            k:      2,
+            expected_paths: vec![],
+        };
+
+        let samples: Samples = run_repeated(&pip, &mut g, &test, RunOptions { repeat: 2, profile_mem: true, ..Default::default() }, |_, _| {});
+        assert_eq!(samples.memory.len(), 2);
+        let peak: MemoryProfile = samples.peak_memory().unwrap();
+        assert!(peak.allocations > 0);
+        assert!(peak.peak_bytes > 0);
+    }
+
+    #[test]
+    fn test_samples_stats() {
+        let profile = |ms: u64| PipelineProfile { prep: vec![], alg: Duration::from_millis(ms), sssp: vec![] };
+        let samples: Samples = Samples { profiles: vec![profile(3), profile(1), profile(2)], memory: vec![] };
+        assert_eq!(samples.median_alg(), Duration::from_millis(2));
+        assert_eq!(samples.stdev_alg(), Duration::from_secs_f64((2.0f64 / 3.0).sqrt() / 1000.0));
+        let samples: Samples = Samples { profiles: vec![profile(4), profile(1), profile(2), profile(3)], memory: vec![] };
+        assert_eq!(samples.median_alg(), Duration::from_micros(2500));
+        assert_eq!(Samples { profiles: vec![], memory: vec![] }.median_alg(), Duration::ZERO);
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Implements [`run_repeated()`] for a custom preprocessing function.
+///
+/// # Arguments
+/// - `pip`: The [`Pipeline`] to run.
+/// - `graph`: The [`Graph`] to run it on. It is restored to its original state when this function returns.
+/// - `test`: The [`TestCase`] describing what to search for.
+/// - `opts`: The [`RunOptions`] that determine how often to run and what to sample.
+/// - `prep`: A function that preprocesses the graph and returns the time each step took.
+/// - `inspect`: A closure called with the graph the paths were found on and the paths found in every run.
+///
+/// # Returns
+/// The [`Samples`] of the timed runs.
+fn run_repeated_with(
+    pip: &Pipeline,
+    graph: &mut Graph,
+    test: &TestCase,
+    opts: RunOptions,
+    prep: impl Fn(&mut GraphGuard) -> Vec<Duration>,
+    mut inspect: impl FnMut(&Graph, &[Path]),
+) -> Samples {
+    let mut profiles: Vec<PipelineProfile> = Vec::with_capacity(opts.repeat);
+    #[cfg_attr(not(feature = "profile_mem"), allow(unused_mut))]
+    let mut memory: Vec<MemoryProfile> = Vec::new();
+    for i in 0..opts.warmup + opts.repeat {
+        let mut g: GraphGuard = GraphGuard::new(graph);
+
+        // Run the pipeline, measuring memory if told to do so
+        #[cfg(feature = "profile_mem")]
+        if opts.profile_mem {
+            crate::alloc::reset();
+        }
+        let (paths, profile): (Vec<Path>, PipelineProfile) =
+            pip.k_shortest_paths_with_fallback(&mut g, test.source.as_str(), test.target.as_str(), test.k, &prep);
+        #[cfg(feature = "profile_mem")]
+        if opts.profile_mem && i >= opts.warmup {
+            memory.push(crate::alloc::measure());
+        }
+
+        // NOTE: The paths borrow the guard mutably, so we re-borrow them from the graph they were found on (which is the original one if
+        //       the pipeline fell back to it)
+        let paths: Vec<OwnedPath> = paths.iter().map(Path::to_owned_path).collect();
+        let paths: Vec<Path> = paths.iter().map(|path| path.to_path(&g).expect("Found path with nodes not in the graph")).collect();
+        inspect(&g, &paths);
+        if i >= opts.warmup {
+            profiles.push(profile);
+        }
+    }
+    Samples { profiles, memory }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Configures how [`run_repeated()`] runs a [`Pipeline`].
+#[derive(Clone, Copy, Debug)]
+pub struct RunOptions {
+    /// The number of runs to do before sampling. These are not timed.
+    pub warmup: usize,
+    /// The number of timed runs to sample.
+    pub repeat: usize,
+    /// Whether to also sample memory usage of the timed runs. Only has an effect if the `profile_mem`-feature is enabled.
+    ///
+    /// The allocation counters are process-wide, so runs that sample memory must not be done concurrently, e.g., by [`run_jobs()`] on
+    /// more than one thread.
+    pub profile_mem: bool,
+}
+impl Default for RunOptions {
+    #[inline]
+    fn default() -> Self { Self { warmup: 0, repeat: 1, profile_mem: false } }
+}
+
+
+
+/// Describes the memory usage of a single run.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MemoryProfile {
+    /// The most bytes that were allocated at any one time during the run.
+    pub peak_bytes:  usize,
+    /// The number of allocations done during the run.
+    pub allocations: usize,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Collects the profiles of multiple runs of the same [`Pipeline`].
+#[derive(Clone, Debug)]
+pub struct Samples {
+    /// The profiles of every timed run.
+    pub profiles: Vec<PipelineProfile>,
+    /// The memory usage of every timed run. Empty if memory wasn't profiled.
+    pub memory:   Vec<MemoryProfile>,
+}
+impl Samples {
+    /// Computes the worst memory usage over all samples.
+    ///
+    /// # Returns
+    /// A [`MemoryProfile`] with the highest peak and the highest number of allocations of any sample, or [`None`] if memory wasn't profiled.
+    pub fn peak_memory(&self) -> Option<MemoryProfile> {
+        self.memory.iter().copied().reduce(|lhs, rhs| MemoryProfile {
+            peak_bytes:  lhs.peak_bytes.max(rhs.peak_bytes),
+            allocations: lhs.allocations.max(rhs.allocations),
+        })
+    }
+
+    /// Computes the median time the main algorithm took over all samples.
+    ///
+    /// # Returns
+    /// The median [`Duration`]. If there are no samples, returns [`Duration::ZERO`].
+    pub fn median_alg(&self) -> Duration {
+        let mut times: Vec<Duration> = self.profiles.iter().map(|p| p.alg).collect();
+        times.sort();
+        match times.len() {
+            0 => Duration::ZERO,
+            n if n % 2 == 0 => (times[n / 2 - 1] + times[n / 2]) / 2,
+            n => times[n / 2],
+        }
+    }
+
+    /// Computes the (population) standard deviation of the time the main algorithm took over all samples.
+    ///
+    /// # Returns
+    /// The standard deviation as a [`Duration`]. If there are less than two samples, returns [`Duration::ZERO`].
+    pub fn stdev_alg(&self) -> Duration {
+        if self.profiles.len() < 2 {
+            return Duration::ZERO;
+        }
+        let n: f64 = self.profiles.len() as f64;
+        let mean: f64 = self.profiles.iter().map(|p| p.alg.as_secs_f64()).sum::<f64>() / n;
+        let var: f64 = self.profiles.iter().map(|p| (p.alg.as_secs_f64() - mean).powi(2)).sum::<f64>() / n;
+        Duration::from_secs_f64(var.sqrt())
+    }
+}
+
+
+
+/// Runs a [`Pipeline`] on a [`TestCase`] a number of times.
+///
+/// Pipelines may alter the graph while preprocessing it, but these changes are undone after every run. Like
+/// [`Pipeline::k_shortest_paths_profiled()`], they fall back to the original graph if the preprocessing lost paths.
+///
+/// # Arguments
+/// - `pip`: The [`Pipeline`] to run.
+/// - `graph`: The [`Graph`] to run it on. It is restored to its original state when this function returns.
+/// - `test`: The [`TestCase`] describing what to search for.
+/// - `opts`: The [`RunOptions`] that determine how often to run and what to sample.
+/// - `inspect`: A closure called with the graph the paths were found on (i.e., the preprocessed one, unless the pipeline fell back) and
+///   the paths found in every run (including warm-up ones), e.g., to verify them.
+///
+/// # Returns
+/// The [`Samples`] of the timed runs.
+pub fn run_repeated(
+    pip: &Pipeline,
+    graph: &mut Graph,
+    test: &TestCase,
+    opts: RunOptions,
+    inspect: impl FnMut(&Graph, &[Path]),
+) -> Samples {
+    run_repeated_with(pip, graph, test, opts, |g: &mut GraphGuard| pip.preprocess(g, test.source.as_str(), test.target.as_str(), test.k), inspect)
+}
+
+
+
+/// Runs a number of jobs (e.g., a [`Pipeline`] on a [`TestCase`]) concurrently on a thread pool.
+///
+/// Every thread gets its own copy of the graph to run the jobs on, such that pipelines can preprocess it without affecting other ones.
+/// Thus, `run` is free to use [`run_repeated()`] on it.
+///
+/// # Arguments
+/// - `pool`: The [`ThreadPool`] to run the jobs on. If it has a single thread, the jobs are run one after another.
+/// - `graph`: The [`Graph`] to run the jobs on. It is cloned once per thread.
+/// - `jobs`: The jobs to run.
+/// - `run`: A closure that runs a single job on a copy of `graph`, and returns its result. The copy must be restored before returning.
+///
+/// # Returns
+/// The result of every job, in the same order as `jobs` (regardless of the order in which they were run).
+pub fn run_jobs<J: Sync, T: Send>(pool: &ThreadPool, graph: &Graph, jobs: &[J], run: impl Fn(&mut Graph, &J) -> T + Sync) -> Vec<T> {
+    pool.install(|| jobs.par_iter().map_init(|| graph.clone(), |scratch: &mut Graph, job: &J| run(scratch, job)).collect())
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    16 Jul 2024, 02:09:04
 //  Last edited:
-//    16 Jul 2024, 02:41:41
+//    16 Oct 2026, 18:05:51
 //  Auto updated?
 //    Yes
 //
@@ -13,11 +13,49 @@
 //
 
 use arrayvec::ArrayString;
+use ksp_graph::Graph;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod tests {
+    use super::*;
+    use crate::utils::load_graph;
+
+    /// Creates a [`TestCase`] finding one path between two nodes.
+    fn test(id: &str, source: &str, target: &str) -> TestCase {
+        TestCase {
+            id: ArrayString::from(id).unwrap(),
+            source: ArrayString::from(source).unwrap(),
+            target: ArrayString::from(target).unwrap(),
+            k: 1,
+            expected_paths: vec![],
+        }
+    }
+
+    #[test]
+    fn test_split_connected() {
+        let mut g: Graph = load_graph("cities");
+        g.remove_edge("Chicago-Dorchester");
+        let (connected, skipped): (Vec<TestCase>, Vec<TestCase>) = split_connected(&g, vec![
+            test("1", "Amsterdam", "Berlin"),
+            test("2", "Amsterdam", "Chicago"),
+            test("3", "Chicago", "Chicago"),
+            test("4", "Berlin", "Rome"),
+        ]);
+        assert_eq!(connected.iter().map(|t| t.id.as_str()).collect::<Vec<&str>>(), vec!["1", "3"]);
+        assert_eq!(skipped.iter().map(|t| t.id.as_str()).collect::<Vec<&str>>(), vec!["2", "4"]);
+    }
+}
+
+
+
 
 
 /***** LIBRARY *****/
 /// Defines a testcase.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct TestCase {
     /// Some name for the case.
     pub id:     ArrayString<64>,
@@ -27,4 +65,21 @@ pub struct TestCase {
     pub target: ArrayString<64>,
     /// The number of paths to find.
     pub k:      usize,
+    /// Any paths known to be the shortest ones for this test, given as the nodes they visit. Empty if there are none.
+    pub expected_paths: Vec<Vec<ArrayString<64>>>,
+}
+
+
+
+/// Separates the tests that can be run on a graph from those whose endpoints aren't connected in it.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] the tests are run on.
+/// - `tests`: The [`TestCase`]s to separate.
+///
+/// # Returns
+/// A pair of the tests with a path from their source to their target, and the others (including those with endpoints not in the graph).
+/// Both keep the order of `tests`.
+pub fn split_connected(graph: &Graph, tests: Vec<TestCase>) -> (Vec<TestCase>, Vec<TestCase>) {
+    tests.into_iter().partition(|test| graph.are_connected(test.source.as_str(), test.target.as_str()))
 }
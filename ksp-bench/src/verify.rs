@@ -0,0 +1,182 @@
+//  VERIFY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:05:18
+//  Last edited:
+//    16 Oct 2026, 19:00:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the `--verify`-mode of the benchmark, which checks
+//!   pipelines against the brute-force reference in `ksp::ksp::brute_force`.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+
+use arrayvec::ArrayString;
+use ksp::{Path, Pipeline};
+use ksp_graph::Graph;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use ksp::ksp::brute_force::BruteForceKSP;
+    use ksp::ksp::KShortestPath as _;
+    use ksp::ksp::yen::YenKSP;
+    use ksp::path;
+    use ksp::sssp::dijkstra::DijkstraSSSP;
+
+    use super::*;
+    use crate::parser::parse_tests;
+    use crate::tests::TestCase;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_expected_paths() {
+        let g: Graph = load_graph("cities");
+        let tests: Vec<TestCase> = parse_tests(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.xml")).unwrap();
+
+        // The expected paths are ordered by cost
+        assert_eq!(expected_paths(&g, &tests[0].expected_paths, 5), vec![
+            path!(g, "Amsterdam" -> "Dorchester" -| "Chicago"),
+            path!(g, "Amsterdam" -> "Edinburgh" -> "Dorchester" -| "Chicago"),
+        ]);
+        assert_eq!(expected_paths(&g, &tests[0].expected_paths, 1), vec![path!(g, "Amsterdam" -> "Dorchester" -| "Chicago")]);
+
+        // A correct algorithm agrees with them
+        for test in tests.iter().filter(|t| !t.expected_paths.is_empty()) {
+            for k in 1..=2 {
+                let paths: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, test.source.as_str(), test.target.as_str(), k);
+                assert_eq!(compare_paths(&expected_paths(&g, &test.expected_paths, k), &paths), None, "Test '{}' (k = {k})", test.id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compare_paths() {
+        let g: Graph = load_graph("cities");
+        let reference: Vec<Path> = BruteForceKSP::simple().k_shortest_paths(&g, "Amsterdam", "Chicago", 3);
+        assert_eq!(compare_paths(&reference, &reference), None);
+
+        // A Yen that pads its result with repeats is caught...
+        let padded: Vec<Path> = vec![path!(g, "Amsterdam" -> "Dorchester" -| "Chicago"), path!(g, "Amsterdam" -> "Dorchester" -| "Chicago")];
+        assert_eq!(compare_paths(&reference, &padded), Some((1, Some(reference[1].cost()), Some(reference[0].cost()))));
+        // ...as is one that doesn't find enough paths...
+        assert_eq!(compare_paths(&reference, &padded[..1]), Some((1, Some(reference[1].cost()), None)));
+        // ...or too many
+        let mut many: Vec<Path> = reference.clone();
+        many.push(path!(g, "Amsterdam" -> "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago"));
+        assert_eq!(compare_paths(&reference, &many), Some((2, None, Some(many[2].cost()))));
+    }
+}
+
+
+
+
+
+/***** CONSTANTS *****/
+/// The tolerance used when comparing path costs.
+const EPSILON: f64 = 1e-6;
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Describes a pipeline that did not agree with the reference.
+#[derive(Clone, Debug)]
+pub struct Mismatch {
+    /// The benchmark in which it occurred.
+    pub benchmark: String,
+    /// The test for which it occurred.
+    pub test:      ArrayString<64>,
+    /// The pipeline that did not match.
+    pub pipeline:  Pipeline,
+    /// The index of the first path that did not match.
+    pub index:     usize,
+    /// The cost of the reference's path at `index`, if it found one.
+    pub expected:  Option<f64>,
+    /// The cost of the pipeline's path at `index`, if it found one.
+    pub got:       Option<f64>,
+}
+impl Display for Mismatch {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        write!(f, "Benchmark '{}', test '{}': {} disagrees on path {}: ", self.benchmark, self.test, self.pipeline, self.index)?;
+        match (self.expected, self.got) {
+            (Some(expected), Some(got)) => write!(f, "expected cost {expected}, got {got}"),
+            (Some(expected), None) => write!(f, "expected cost {expected}, got no path"),
+            (None, Some(got)) => write!(f, "expected no path, got cost {got}"),
+            (None, None) => write!(f, "<no difference>"),
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Converts known shortest paths, given as the nodes they visit, into [`Path`]s through a graph.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] that the paths are in.
+/// - `expected`: The paths to convert, e.g., a [`TestCase`](crate::tests::TestCase)'s `expected_paths`.
+/// - `k`: The number of paths to return.
+///
+/// # Returns
+/// The `k` cheapest of the given paths, ordered by cost. If there are parallel edges, the cheapest one is assumed to be taken.
+///
+/// # Panics
+/// This function panics if any of the paths visits unknown nodes or takes a hop between nodes that aren't connected.
+pub fn expected_paths<'g>(graph: &'g Graph, expected: &[Vec<ArrayString<64>>], k: usize) -> Vec<Path<'g>> {
+    let mut paths: Vec<Path<'g>> = Vec::with_capacity(expected.len());
+    for nodes in expected {
+        let mut path: Path<'g> = Path { hops: Vec::with_capacity(nodes.len()) };
+        for node in nodes {
+            let (id, _) = graph.nodes.get_key_value(node).unwrap_or_else(|| panic!("Expected path visits unknown node '{node}'"));
+            let cost: f64 = match path.hops.last() {
+                Some((prev, cost)) => {
+                    cost + graph
+                        .edges
+                        .values()
+                        .filter(|e| (e.left.as_str() == *prev && e.right == *id) || (e.left == *id && e.right.as_str() == *prev))
+                        .map(|e| e.cost)
+                        .min_by(f64::total_cmp)
+                        .unwrap_or_else(|| panic!("Expected path hops from '{prev}' to '{id}', but they are not connected"))
+                },
+                None => 0.0,
+            };
+            path.hops.push((id.as_str(), cost));
+        }
+        paths.push(path);
+    }
+    paths.sort_by(|p1, p2| p1.cost().total_cmp(&p2.cost()));
+    paths.truncate(k);
+    paths
+}
+
+/// Compares the paths found by some algorithm with those of a reference.
+///
+/// Because multiple paths may have the same cost, only the costs of the paths are compared.
+///
+/// # Arguments
+/// - `reference`: The paths found by the reference implementation.
+/// - `paths`: The paths found by the algorithm under test.
+///
+/// # Returns
+/// [`None`] if both lists agree, or else the index of the first path they disagree on, together with the cost of that path in the `reference` and in `paths` (if either has it).
+pub fn compare_paths(reference: &[Path], paths: &[Path]) -> Option<(usize, Option<f64>, Option<f64>)> {
+    for i in 0..reference.len().max(paths.len()) {
+        let expected: Option<f64> = reference.get(i).map(Path::cost);
+        let got: Option<f64> = paths.get(i).map(Path::cost);
+        match (expected, got) {
+            (Some(expected), Some(got)) if (expected - got).abs() <= EPSILON => continue,
+            _ => return Some((i, expected, got)),
+        }
+    }
+    None
+}
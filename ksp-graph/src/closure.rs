@@ -0,0 +1,138 @@
+//  CLOSURE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:33:53
+//  Last edited:
+//    16 Oct 2026, 18:44:30
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements computing the metric closure of a [`Graph`].
+//
+
+use std::collections::HashMap;
+
+use arrayvec::ArrayString;
+
+use crate::{Edge, Graph, GraphHasher};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[test]
+    fn test_graph_metric_closure() {
+        // A path A - B - C, plus a disconnected D
+        let mut g: Graph = Graph::from_edges([("1", "A", "B", 1.0), ("2", "C", "B", 2.0), ("3", "A", "C", 5.0), ("4", "A", "B", 4.0)]);
+        let id: ArrayString<64> = ArrayString::from("D").unwrap();
+        g.nodes.insert(id, Node { id, pos: (0.0, 0.0) });
+        for (i, node) in ["A", "B", "C", "D"].into_iter().enumerate() {
+            g.nodes.get_mut(node).unwrap().pos = (i as f64, 0.0);
+        }
+
+        let closure: Graph = g.metric_closure();
+        assert!(closure.validate().is_ok());
+        assert_eq!(closure.node_count(), 4);
+        for node in g.nodes.values() {
+            assert_eq!(closure.nodes[&node.id].pos, node.pos);
+        }
+        // Every connected pair has exactly one edge, with the cost of the shortest path
+        let mut edges: Vec<(&str, &str, &str, f64)> =
+            closure.edges.values().map(|e| (e.id.as_str(), e.left.as_str(), e.right.as_str(), e.cost)).collect();
+        edges.sort_by(|e1, e2| e1.0.cmp(e2.0));
+        assert_eq!(edges, vec![("A-B", "A", "B", 1.0), ("A-C", "A", "C", 3.0), ("B-C", "B", "C", 2.0)]);
+
+        // If directed, there's one in either direction
+        g.directed = true;
+        let closure: Graph = g.metric_closure();
+        assert!(closure.directed);
+        let mut edges: Vec<(&str, f64)> = closure.edges.values().map(|e| (e.id.as_str(), e.cost)).collect();
+        edges.sort_by(|e1, e2| e1.0.cmp(e2.0));
+        assert_eq!(edges, vec![("A-B", 1.0), ("A-C", 5.0), ("C-B", 2.0)]);
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+impl Graph {
+    /// Computes the metric closure of this graph.
+    ///
+    /// This is the complete graph over the same nodes, where the edge between every pair of nodes costs as much as the shortest path between
+    /// them in this graph. If this graph is [directed](Graph::directed), so is the closure, and it has an edge in either direction.
+    ///
+    /// Uses the Floyd-Warshall algorithm, so it takes O(n³) time and O(n²) memory for n nodes, regardless of the number of edges. Only use
+    /// this on small graphs.
+    ///
+    /// # Returns
+    /// A new [`Graph`] with the same nodes (and positions), and for every pair of nodes that are connected, one edge with the cost of the
+    /// shortest path between them. The edges are named `<left>-<right>`, where `left` is the node with the smaller identifier (or the one the
+    /// edge leaves from if directed). Pairs that are not connected have no edge.
+    ///
+    /// # Panics
+    /// This function panics if the identifiers of two connected nodes joined with a dash do not fit in 64 bytes.
+    #[track_caller]
+    pub fn metric_closure(&self) -> Graph {
+        // Sort the nodes to not depend on the order of the map
+        let mut ids: Vec<ArrayString<64>> = self.nodes.keys().copied().collect();
+        ids.sort();
+        let index: HashMap<ArrayString<64>, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        // Initialize the distances with the direct edges
+        let n: usize = ids.len();
+        let mut dist: Vec<f64> = vec![f64::INFINITY; n * n];
+        for i in 0..n {
+            dist[i * n + i] = 0.0;
+        }
+        for edge in self.edges.values() {
+            if let (Some(left), Some(right)) = (index.get(&edge.left), index.get(&edge.right)) {
+                for (i, j) in [(*left, *right), (*right, *left)].into_iter().take(if self.directed { 1 } else { 2 }) {
+                    if edge.cost < dist[i * n + j] {
+                        dist[i * n + j] = edge.cost;
+                    }
+                }
+            }
+        }
+
+        // Then allow every node as intermediate step
+        for via in 0..n {
+            for i in 0..n {
+                let to_via: f64 = dist[i * n + via];
+                if to_via == f64::INFINITY {
+                    continue;
+                }
+                for j in 0..n {
+                    let cost: f64 = to_via + dist[via * n + j];
+                    if cost < dist[i * n + j] {
+                        dist[i * n + j] = cost;
+                    }
+                }
+            }
+        }
+
+        // Build the complete graph
+        let mut edges: HashMap<ArrayString<64>, Edge, GraphHasher> =
+            HashMap::with_capacity_and_hasher(n * n.saturating_sub(1) / 2, GraphHasher::default());
+        for i in 0..n {
+            for j in if self.directed { 0 } else { i + 1 }..n {
+                let cost: f64 = dist[i * n + j];
+                if i == j || cost == f64::INFINITY {
+                    continue;
+                }
+                let id: ArrayString<64> = match ArrayString::from(&format!("{}-{}", ids[i], ids[j])) {
+                    Ok(id) => id,
+                    Err(_) => panic!("Identifier for closure edge between '{}' and '{}' is longer than 64 bytes", ids[i], ids[j]),
+                };
+                edges.insert(id, Edge { id, left: ids[i], right: ids[j], cost });
+            }
+        }
+        Graph { nodes: self.nodes.clone(), edges, directed: self.directed, labels: self.labels.clone() }
+    }
+}
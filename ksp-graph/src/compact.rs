@@ -0,0 +1,143 @@
+//  COMPACT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:35:23
+//  Last edited:
+//    16 Oct 2026, 16:10:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a compact, index-based representation of a [`Graph`] for
+//!   algorithms that need to iterate over it quickly.
+//
+
+use std::collections::HashMap;
+
+use arrayvec::ArrayString;
+
+use crate::Graph;
+
+
+/***** TESTS *****/
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_compact() {
+        let g: Graph = crate::json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        let (compact, ids): (CompactGraph, Vec<&str>) = g.compact();
+        assert_eq!(ids, vec!["Amsterdam", "Berlin", "Chicago", "Dorchester", "Edinburgh"]);
+        assert_eq!(compact.node_count(), 5);
+        assert_eq!(compact.edge_count(), 10);
+
+        // The neighbours are the same as those in the graph
+        for (i, id) in ids.iter().enumerate() {
+            let mut expected: Vec<(&str, f64)> = g.neighbours(id).map(|(n, e)| (n, e.cost)).collect();
+            expected.sort_by(|(n1, c1), (n2, c2)| n1.cmp(n2).then(c1.total_cmp(c2)));
+            let neighbours: Vec<(&str, f64)> = compact.neighbours(i).map(|(n, c)| (ids[n], c)).collect();
+            assert_eq!(neighbours, expected);
+        }
+        assert_eq!(ids.binary_search(&"Dorchester"), Ok(3));
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// A read-only [`Graph`] where nodes are identified by contiguous indices and the edges are stored in flat arrays.
+///
+/// Specifically, the edges are stored in Compressed Sparse Row (CSR) format: the neighbours of node `i` are at positions
+/// `offsets[i]..offsets[i + 1]` in the `targets` and `costs` arrays. This makes iterating over the neighbours of a node a matter of reading
+/// a contiguous bit of memory, instead of scanning all edges in a hash map.
+///
+/// Created by [`Graph::compact()`].
+#[derive(Clone, Debug)]
+pub struct CompactGraph {
+    /// Where the neighbours of every node start. Has one more element than there are nodes, which is the total number of neighbours.
+    offsets: Vec<usize>,
+    /// The index of every neighbour.
+    targets: Vec<usize>,
+    /// The cost of the edge to every neighbour.
+    costs:   Vec<f64>,
+}
+impl CompactGraph {
+    /// Returns the number of nodes in the graph.
+    ///
+    /// # Returns
+    /// The number of nodes, which are identified by `0..n`.
+    #[inline]
+    pub fn node_count(&self) -> usize { self.offsets.len() - 1 }
+
+    /// Returns the number of directed edges in the graph.
+    ///
+    /// # Returns
+    /// The number of neighbours over all nodes, i.e., twice the number of (non-self-loop) edges in the original [`Graph`] if it's undirected.
+    #[inline]
+    pub fn edge_count(&self) -> usize { self.targets.len() }
+
+    /// Iterates over the neighbours of a node.
+    ///
+    /// # Arguments
+    /// - `node`: The index of the node to find the neighbours of.
+    ///
+    /// # Returns
+    /// An iterator over pairs of the index of a neighbour and the cost of the edge leading to it, ordered by index. A neighbour is yielded
+    /// multiple times if there are parallel edges leading to it.
+    ///
+    /// # Panics
+    /// This function panics if `node` is out-of-bounds.
+    #[inline]
+    #[track_caller]
+    pub fn neighbours(&self, node: usize) -> impl '_ + Iterator<Item = (usize, f64)> {
+        let range = self.offsets[node]..self.offsets[node + 1];
+        self.targets[range.clone()].iter().copied().zip(self.costs[range].iter().copied())
+    }
+}
+
+
+
+impl Graph {
+    /// Converts this graph to a [`CompactGraph`].
+    ///
+    /// The neighbours of every node are its [successors](Graph::successors()), so edges are only traversable from `left` to `right` if the
+    /// graph is [directed](Graph::directed). Self-loops are skipped. Edges that refer to nodes that aren't in the graph are skipped, too.
+    ///
+    /// # Returns
+    /// A tuple of the [`CompactGraph`] and the identifier of every node in it, i.e., the `i`th identifier is that of node `i`. These are
+    /// sorted, so the index of a particular node can be found with [`slice::binary_search()`].
+    pub fn compact(&self) -> (CompactGraph, Vec<&str>) {
+        let mut ids: Vec<&str> = self.nodes.keys().map(|id| id.as_str()).collect();
+        ids.sort();
+        let index: HashMap<ArrayString<64>, usize> = ids.iter().enumerate().map(|(i, id)| (ArrayString::from(id).unwrap(), i)).collect();
+
+        // Collect all directed edges, sorted by source (and then target to be deterministic)
+        let mut edges: Vec<(usize, usize, f64)> = Vec::with_capacity(2 * self.edges.len());
+        for edge in self.edges.values() {
+            if let (Some(left), Some(right)) = (index.get(&edge.left), index.get(&edge.right)) {
+                if left != right {
+                    edges.push((*left, *right, edge.cost));
+                    if !self.directed {
+                        edges.push((*right, *left, edge.cost));
+                    }
+                }
+            }
+        }
+        edges.sort_by(|(s1, t1, c1), (s2, t2, c2)| s1.cmp(s2).then(t1.cmp(t2)).then(c1.total_cmp(c2)));
+
+        // Then write them as rows
+        let mut offsets: Vec<usize> = vec![0; ids.len() + 1];
+        for (src, _, _) in &edges {
+            offsets[src + 1] += 1;
+        }
+        for i in 0..ids.len() {
+            offsets[i + 1] += offsets[i];
+        }
+        let (targets, costs): (Vec<usize>, Vec<f64>) = edges.into_iter().map(|(_, dst, cost)| (dst, cost)).unzip();
+        (CompactGraph { offsets, targets, costs }, ids)
+    }
+}
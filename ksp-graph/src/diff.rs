@@ -0,0 +1,228 @@
+//  DIFF.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:44:07
+//  Last edited:
+//    16 Oct 2026, 18:44:30
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements structurally comparing two [`Graph`]s.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+
+use arrayvec::ArrayString;
+
+use crate::Graph;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Edge;
+
+    #[test]
+    fn test_diff_same() {
+        let g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 2.0)]);
+        let diff: GraphDiff = g.diff(&g.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff, GraphDiff::default());
+    }
+
+    #[test]
+    fn test_diff_node_removal() {
+        let g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 2.0)]);
+        let mut h: Graph = g.clone();
+        h.nodes.remove("C");
+        h.edges.remove("B-C");
+        let diff: GraphDiff = g.diff(&h);
+        assert_eq!(diff, GraphDiff {
+            removed_nodes: vec![ArrayString::from("C").unwrap()],
+            removed_edges: vec![ArrayString::from("B-C").unwrap()],
+            ..Default::default()
+        });
+
+        // The other way around, it's added
+        let diff: GraphDiff = h.diff(&g);
+        assert_eq!(diff, GraphDiff {
+            added_nodes: vec![ArrayString::from("C").unwrap()],
+            added_edges: vec![ArrayString::from("B-C").unwrap()],
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn test_diff_edge_removal() {
+        let g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 2.0), ("A-C", "A", "C", 5.0)]);
+        let mut h: Graph = g.clone();
+        h.edges.remove("A-C");
+        h.edges.remove("A-B");
+        let diff: GraphDiff = g.diff(&h);
+        assert!(!diff.is_empty());
+        assert_eq!(diff, GraphDiff { removed_edges: vec![ArrayString::from("A-B").unwrap(), ArrayString::from("A-C").unwrap()], ..Default::default() });
+    }
+
+    #[test]
+    fn test_eq_topology_exact() {
+        let g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 0.1 + 0.2)]);
+
+        // Positions and (undirected) endpoint order don't matter
+        let mut h: Graph = g.clone();
+        h.nodes.get_mut("A").unwrap().pos = (4.0, 2.0);
+        let edge: &mut Edge = h.edges.get_mut("A-B").unwrap();
+        std::mem::swap(&mut edge.left, &mut edge.right);
+        assert!(g.eq_topology_exact(&h));
+        h.directed = true;
+        assert!(!g.eq_topology_exact(&h));
+
+        // Costs are compared bit-for-bit, even if the difference is way below any sensible epsilon
+        let mut h: Graph = g.clone();
+        h.edges.get_mut("B-C").unwrap().cost = 0.3;
+        assert!((g.edges["B-C"].cost - h.edges["B-C"].cost).abs() < 1e-12);
+        assert!(!g.eq_topology_exact(&h));
+
+        // And so are edge identifiers
+        let h: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("C-B", "B", "C", 0.1 + 0.2)]);
+        assert!(!g.eq_topology_exact(&h));
+        let h: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 0.1 + 0.2), ("C-D", "C", "D", 1.0)]);
+        assert!(!g.eq_topology_exact(&h));
+    }
+
+    #[test]
+    fn test_diff_cost_change() {
+        let g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 2.0)]);
+        let mut h: Graph = g.clone();
+        h.edges.get_mut("B-C").unwrap().cost = f64::INFINITY;
+        h.edges.get_mut("A-B").unwrap().cost = 1.0;
+        let diff: GraphDiff = g.diff(&h);
+        assert_eq!(diff, GraphDiff { changed_costs: vec![(ArrayString::from("B-C").unwrap(), 2.0, f64::INFINITY)], ..Default::default() });
+        assert_eq!(diff.to_string(), "~ edge 'B-C' (cost 2 -> inf)\n");
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Describes the structural differences between two [`Graph`]s.
+///
+/// Created by [`Graph::diff()`]. All lists are sorted by identifier.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphDiff {
+    /// The nodes that are in the new graph but not in the old one.
+    pub added_nodes:   Vec<ArrayString<64>>,
+    /// The nodes that are in the old graph but not in the new one.
+    pub removed_nodes: Vec<ArrayString<64>>,
+    /// The edges that are in the new graph but not in the old one.
+    pub added_edges:   Vec<ArrayString<64>>,
+    /// The edges that are in the old graph but not in the new one.
+    pub removed_edges: Vec<ArrayString<64>>,
+    /// The edges that are in both graphs but with a different cost, as `(id, old cost, new cost)`.
+    pub changed_costs: Vec<(ArrayString<64>, f64, f64)>,
+}
+impl GraphDiff {
+    /// Returns whether there are any differences.
+    ///
+    /// # Returns
+    /// True if both graphs have the same nodes and edges, with the same costs.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_costs.is_empty()
+    }
+}
+impl Display for GraphDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        for id in &self.added_nodes {
+            writeln!(f, "+ node '{id}'")?;
+        }
+        for id in &self.removed_nodes {
+            writeln!(f, "- node '{id}'")?;
+        }
+        for id in &self.added_edges {
+            writeln!(f, "+ edge '{id}'")?;
+        }
+        for id in &self.removed_edges {
+            writeln!(f, "- edge '{id}'")?;
+        }
+        for (id, old, new) in &self.changed_costs {
+            writeln!(f, "~ edge '{id}' (cost {old} -> {new})")?;
+        }
+        Ok(())
+    }
+}
+
+
+
+impl Graph {
+    /// Compares this graph to another one.
+    ///
+    /// Nodes and edges are matched by identifier. Only edge costs are compared; changed node positions or edge endpoints are not reported.
+    ///
+    /// # Arguments
+    /// - `other`: The (new) [`Graph`] to compare this (old) one to.
+    ///
+    /// # Returns
+    /// A [`GraphDiff`] describing what has been added to, removed from or changed in `other` with respect to this graph.
+    pub fn diff(&self, other: &Graph) -> GraphDiff {
+        // Compare the nodes
+        let mut added_nodes: Vec<ArrayString<64>> = other.nodes.keys().filter(|id| !self.nodes.contains_key(*id)).copied().collect();
+        let mut removed_nodes: Vec<ArrayString<64>> = self.nodes.keys().filter(|id| !other.nodes.contains_key(*id)).copied().collect();
+
+        // Compare the edges
+        let mut added_edges: Vec<ArrayString<64>> = other.edges.keys().filter(|id| !self.edges.contains_key(*id)).copied().collect();
+        let mut removed_edges: Vec<ArrayString<64>> = Vec::new();
+        let mut changed_costs: Vec<(ArrayString<64>, f64, f64)> = Vec::new();
+        for (id, edge) in &self.edges {
+            match other.edges.get(id) {
+                Some(new) if new.cost != edge.cost => changed_costs.push((*id, edge.cost, new.cost)),
+                Some(_) => {},
+                None => removed_edges.push(*id),
+            }
+        }
+
+        // Sort for a deterministic result
+        added_nodes.sort();
+        removed_nodes.sort();
+        added_edges.sort();
+        removed_edges.sort();
+        changed_costs.sort_by_key(|(id, _, _)| *id);
+        GraphDiff { added_nodes, removed_nodes, added_edges, removed_edges, changed_costs }
+    }
+
+    /// Checks whether this graph has exactly the same topology as another one.
+    ///
+    /// Both graphs must have the same node identifiers and the same edge identifiers, and every edge must connect the same nodes (in the same
+    /// direction if the graphs are [directed](Graph::directed)) with a bit-identical cost. Node positions and the order in which things were
+    /// inserted are ignored. Useful for checking that, e.g., serializing and deserializing a graph didn't change it.
+    ///
+    /// # Arguments
+    /// - `other`: The other [`Graph`] to compare to.
+    ///
+    /// # Returns
+    /// True if both graphs are the same up to node positions, or false otherwise.
+    pub fn eq_topology_exact(&self, other: &Graph) -> bool {
+        if self.directed != other.directed || self.nodes.len() != other.nodes.len() || self.edges.len() != other.edges.len() {
+            return false;
+        }
+        if self.nodes.keys().any(|id| !other.nodes.contains_key(id)) {
+            return false;
+        }
+        self.edges.iter().all(|(id, edge)| match other.edges.get(id) {
+            Some(new) => {
+                let same_ends: bool = (edge.left == new.left && edge.right == new.right)
+                    || (!self.directed && edge.left == new.right && edge.right == new.left);
+                same_ends && edge.cost.to_bits() == new.cost.to_bits()
+            },
+            None => false,
+        })
+    }
+}
@@ -0,0 +1,89 @@
+//  EDGE LIST.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:16:37
+//  Last edited:
+//    16 Oct 2026, 18:44:30
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements writing a [`Graph`] as a plain-text, sorted edge list for
+//!   debugging.
+//
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter, Result as FResult};
+
+use crate::{Edge, Graph, Node};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use arrayvec::ArrayString;
+
+    use super::*;
+
+    #[test]
+    fn test_graph_edge_list() {
+        let mut g: Graph = Graph::from_edges([("1", "B", "C", 2.5), ("2", "A", "B", 1.0), ("3", "A", "B", 0.5)]);
+        let id: ArrayString<64> = ArrayString::from("D").unwrap();
+        g.nodes.insert(id, Node { id, pos: (0.0, 0.0) });
+        assert_eq!(g.edge_list().to_string(), "A B 0.5\nA B 1\nB C 2.5\nD\n");
+
+        // Empty graphs are empty lists
+        let g: Graph = Graph { nodes: Default::default(), edges: Default::default(), directed: true, labels: Default::default() };
+        assert_eq!(g.edge_list().to_string(), "");
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Formats a [`Graph`] as a sorted edge list.
+///
+/// Every edge is written on its own line as `<left> <right> <cost>`, sorted by endpoints and then cost, such that two dumps of the same graph
+/// can be compared line-by-line. Nodes without any edges are written after them as a line with only their identifier, so that they don't
+/// silently disappear from the dump. Edge identifiers and node positions are not written.
+///
+/// Created by [`Graph::edge_list()`].
+#[derive(Clone, Copy, Debug)]
+pub struct EdgeList<'g> {
+    /// The graph to format.
+    graph: &'g Graph,
+}
+impl<'g> Display for EdgeList<'g> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        let mut edges: Vec<&Edge> = self.graph.edges_iter().collect();
+        edges.sort_by(|e1, e2| e1.left.cmp(&e2.left).then(e1.right.cmp(&e2.right)).then(e1.cost.total_cmp(&e2.cost)));
+        for edge in &edges {
+            writeln!(f, "{} {} {}", edge.left, edge.right, edge.cost)?;
+        }
+
+        // Write any nodes not mentioned yet
+        let connected: HashSet<&str> = edges.iter().flat_map(|e| [e.left.as_str(), e.right.as_str()]).collect();
+        let mut isolated: Vec<&Node> = self.graph.nodes_iter().filter(|n| !connected.contains(n.id.as_str())).collect();
+        isolated.sort_by_key(|n| n.id);
+        for node in isolated {
+            writeln!(f, "{}", node.id)?;
+        }
+        Ok(())
+    }
+}
+
+
+
+impl Graph {
+    /// Returns a formatter that writes this graph as a sorted, plain-text edge list.
+    ///
+    /// Useful for inspecting what a transformation left of a graph. See [`EdgeList`] for the format.
+    ///
+    /// # Returns
+    /// An [`EdgeList`] that implements [`Display`].
+    #[inline]
+    pub fn edge_list(&self) -> EdgeList<'_> { EdgeList { graph: self } }
+}
@@ -0,0 +1,358 @@
+//  GUARD.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:46:15
+//  Last edited:
+//    16 Oct 2026, 17:10:00
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a guard that records changes made to a [`Graph`] such
+//!   that they can be undone cheaply, without cloning it.
+//
+
+use std::collections::HashSet;
+use std::ops::Deref;
+
+use arrayvec::ArrayString;
+
+use crate::{Edge, Graph, Node};
+
+
+/***** TESTS *****/
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    /// Does some arbitrary changes to the given graph.
+    fn transform(graph: &mut GraphGuard) {
+        assert_eq!(graph.remove_node("Chicago").map(|n| n.id.to_string()), Some("Chicago".into()));
+        assert!(graph.remove_node("Chicago").is_none());
+        assert_eq!(graph.remove_edge("Amsterdam-Berlin").map(|e| e.cost), Some(577.34));
+        assert!(graph.remove_edge("Chicago-Dorchester").is_none());
+        assert_eq!(graph.set_cost("Amsterdam-Edinburgh", f64::INFINITY), Some(660.68));
+        assert_eq!(graph.set_cost("Amsterdam-Edinburgh", 1.0), Some(f64::INFINITY));
+        assert!(graph.set_cost("Amsterdam-Berlin", 1.0).is_none());
+    }
+
+    #[test]
+    fn test_guard_restore() {
+        let original: Graph = crate::json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        let mut g: Graph = original.clone();
+
+        // Transform the graph and see the changes are visible through the guard
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        transform(&mut guard);
+        assert!(!guard.is_pristine());
+        assert_eq!(guard.node_count(), 4);
+        assert_eq!(guard.edge_count(), 3);
+        let diff: crate::GraphDiff = original.diff(&guard);
+        assert_eq!(diff.removed_nodes.len(), 1);
+        assert_eq!(diff.removed_edges.len(), 2);
+        assert_eq!(diff.changed_costs.len(), 1);
+
+        // Dropping the guard undoes it
+        drop(guard);
+        assert!(original.diff(&g).is_empty());
+
+        // Restoring it explicitly too
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        transform(&mut guard);
+        guard.restore();
+        assert!(guard.is_pristine());
+        assert!(original.diff(&guard).is_empty());
+        drop(guard);
+        assert!(original.diff(&g).is_empty());
+    }
+
+    #[test]
+    fn test_guard_restore_to() {
+        let original: Graph = crate::json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        let mut g: Graph = original.clone();
+
+        // Only the changes after the checkpoint are undone
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        guard.set_cost("Dorchester-Edinburgh", 2.0);
+        let checkpoint: usize = guard.checkpoint();
+        transform(&mut guard);
+        assert!(guard.checkpoint() > checkpoint);
+        guard.restore_to(checkpoint);
+        assert_eq!(guard.checkpoint(), checkpoint);
+        let diff: crate::GraphDiff = original.diff(&guard);
+        assert_eq!(diff.changed_costs, vec![(ArrayString::from("Dorchester-Edinburgh").unwrap(), 589.23, 2.0)]);
+        assert!(diff.removed_nodes.is_empty());
+        assert!(diff.removed_edges.is_empty());
+
+        // Restoring to a later checkpoint does nothing
+        guard.restore_to(checkpoint + 10);
+        assert_eq!(guard.checkpoint(), checkpoint);
+        guard.restore_to(0);
+        assert!(guard.is_pristine());
+    }
+
+    #[test]
+    fn test_guard_retain_nodes() {
+        let original: Graph = crate::json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        let mut g: Graph = original.clone();
+
+        // Removing nodes through the predicate is the same as one-by-one
+        let mut expected: Graph = original.clone();
+        let mut guard: GraphGuard = GraphGuard::new(&mut expected);
+        guard.remove_node("Chicago");
+        guard.remove_node("Amsterdam");
+        guard.commit();
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        assert_eq!(guard.retain_nodes(|n| n.id.as_str() != "Chicago" && n.id.as_str() != "Amsterdam"), 2);
+        assert!(expected.diff(&guard).is_empty());
+        assert_eq!(guard.node_count(), 3);
+        assert_eq!(guard.edge_count(), 1);
+
+        // And it's undone the same way
+        drop(guard);
+        assert!(original.diff(&g).is_empty());
+    }
+
+    #[test]
+    fn test_guard_add_edge() {
+        let original: Graph = crate::json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        let mut g: Graph = original.clone();
+
+        // Edges can replace removed ones with the same identifier, but not existing ones
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        let edge: Edge = *guard.edges.get("Amsterdam-Berlin").unwrap();
+        assert!(!guard.add_edge(Edge { cost: 1.0, ..edge }));
+        guard.remove_edge("Amsterdam-Berlin");
+        assert!(guard.add_edge(Edge { cost: 1.0, ..edge }));
+        let id: ArrayString<64> = ArrayString::from("Berlin-Chicago").unwrap();
+        assert!(guard.add_edge(Edge { id, left: edge.right, right: ArrayString::from("Chicago").unwrap(), cost: 2.0 }));
+        let diff: crate::GraphDiff = original.diff(&guard);
+        assert_eq!(diff.added_edges, vec![id]);
+        assert_eq!(diff.changed_costs, vec![(edge.id, 577.34, 1.0)]);
+
+        // Undoing removes them again
+        drop(guard);
+        assert!(original.diff(&g).is_empty());
+    }
+
+    #[test]
+    fn test_guard_commit() {
+        let original: Graph = crate::json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        let mut g: Graph = original.clone();
+
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        transform(&mut guard);
+        guard.commit();
+        assert_eq!(g.node_count(), 4);
+        assert_eq!(g.edge_count(), 3);
+        assert_eq!(g.edges.get("Amsterdam-Edinburgh").unwrap().cost, 1.0);
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Defines a single change made to a [`Graph`] through a [`GraphGuard`].
+#[derive(Clone, Copy, Debug)]
+enum Change {
+    /// A node was removed.
+    RemovedNode(Node),
+    /// An edge was removed.
+    RemovedEdge(Edge),
+    /// The cost of an edge was changed from the given one.
+    ChangedCost(ArrayString<64>, f64),
+    /// An edge with the given identifier was added.
+    AddedEdge(ArrayString<64>),
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Wraps a mutable [`Graph`] such that any changes made through it are undone when it goes out of scope.
+///
+/// This allows a graph to be transformed (e.g., pruned) for one experiment and then be restored for the next without cloning it. The changes
+/// are only recorded, so restoring is linear in the number of changes made instead of in the size of the graph.
+///
+/// Read-only access to the graph is given by dereferencing the guard. Use [`GraphGuard::commit()`] to keep the changes instead.
+#[derive(Debug)]
+pub struct GraphGuard<'g> {
+    /// The graph we're guarding.
+    graph: &'g mut Graph,
+    /// The changes made to the graph so far, in order.
+    log:   Vec<Change>,
+}
+impl<'g> GraphGuard<'g> {
+    /// Constructor for the GraphGuard.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to guard.
+    ///
+    /// # Returns
+    /// A new GraphGuard that hasn't recorded any changes yet.
+    #[inline]
+    pub fn new(graph: &'g mut Graph) -> Self { Self { graph, log: Vec::new() } }
+
+    /// Removes a node from the graph, together with all edges connected to it.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the node to remove.
+    ///
+    /// # Returns
+    /// The removed [`Node`], or [`None`] if there was no node with that identifier.
+    pub fn remove_node(&mut self, id: &str) -> Option<Node> {
+        let node: Node = self.graph.nodes.remove(id)?;
+        self.log.push(Change::RemovedNode(node));
+
+        // Remove the edges it's a part of
+        let edges: Vec<ArrayString<64>> =
+            self.graph.edges.values().filter(|e| e.left.as_str() == id || e.right.as_str() == id).map(|e| e.id).collect();
+        for edge in edges {
+            self.remove_edge(edge.as_str());
+        }
+        Some(node)
+    }
+
+    /// Removes all nodes from the graph that don't match a predicate, together with all edges connected to them.
+    ///
+    /// This is equivalent to calling [`GraphGuard::remove_node()`] for every node to remove, but linear in the size of the graph instead of
+    /// quadratic.
+    ///
+    /// # Arguments
+    /// - `keep`: A function that returns whether to keep a particular [`Node`].
+    ///
+    /// # Returns
+    /// The number of nodes removed.
+    pub fn retain_nodes(&mut self, mut keep: impl FnMut(&Node) -> bool) -> usize {
+        let removed: HashSet<ArrayString<64>> = self.graph.nodes.values().filter(|n| !keep(n)).map(|n| n.id).collect();
+        for id in &removed {
+            if let Some(node) = self.graph.nodes.remove(id) {
+                self.log.push(Change::RemovedNode(node));
+            }
+        }
+
+        // Remove the edges they're a part of
+        let edges: Vec<ArrayString<64>> =
+            self.graph.edges.values().filter(|e| removed.contains(&e.left) || removed.contains(&e.right)).map(|e| e.id).collect();
+        for edge in edges {
+            self.remove_edge(edge.as_str());
+        }
+        removed.len()
+    }
+
+    /// Removes an edge from the graph.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the edge to remove.
+    ///
+    /// # Returns
+    /// The removed [`Edge`], or [`None`] if there was no edge with that identifier.
+    pub fn remove_edge(&mut self, id: &str) -> Option<Edge> {
+        let edge: Edge = self.graph.edges.remove(id)?;
+        self.log.push(Change::RemovedEdge(edge));
+        Some(edge)
+    }
+
+    /// Adds an edge to the graph.
+    ///
+    /// Note that its endpoints are not checked, so it's up to the caller to keep the graph consistent.
+    ///
+    /// # Arguments
+    /// - `edge`: The [`Edge`] to add.
+    ///
+    /// # Returns
+    /// Whether the edge was added, i.e., false if there already is an edge with the same identifier (which is then kept).
+    pub fn add_edge(&mut self, edge: Edge) -> bool {
+        if self.graph.edges.contains_key(&edge.id) {
+            return false;
+        }
+        self.graph.edges.insert(edge.id, edge);
+        self.log.push(Change::AddedEdge(edge.id));
+        true
+    }
+
+    /// Changes the cost of an edge in the graph.
+    ///
+    /// Note that setting it to [`f64::INFINITY`] effectively removes the edge, but without changing the graph's structure.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the edge to change.
+    /// - `cost`: The new cost of the edge.
+    ///
+    /// # Returns
+    /// The previous cost of the edge, or [`None`] if there was no edge with that identifier.
+    pub fn set_cost(&mut self, id: &str, cost: f64) -> Option<f64> {
+        let edge: &mut Edge = self.graph.edges.get_mut(id)?;
+        let old: f64 = std::mem::replace(&mut edge.cost, cost);
+        self.log.push(Change::ChangedCost(edge.id, old));
+        Some(old)
+    }
+
+    /// Returns whether any changes have been made through this guard (that haven't been restored yet).
+    ///
+    /// # Returns
+    /// True if the guarded graph is still the same as when the guard was created.
+    #[inline]
+    pub fn is_pristine(&self) -> bool { self.log.is_empty() }
+
+    /// Returns a checkpoint that [`GraphGuard::restore_to()`] can undo changes up to.
+    ///
+    /// # Returns
+    /// An opaque number identifying the current state of the guarded graph. Later checkpoints are larger.
+    #[inline]
+    pub fn checkpoint(&self) -> usize { self.log.len() }
+
+    /// Undoes all changes made through this guard so far.
+    ///
+    /// This is done automatically when the guard is dropped, but this allows the guard to be re-used.
+    #[inline]
+    pub fn restore(&mut self) { self.restore_to(0) }
+
+    /// Undoes all changes made through this guard since the given checkpoint.
+    ///
+    /// # Arguments
+    /// - `checkpoint`: A checkpoint returned by [`GraphGuard::checkpoint()`]. If it is later than the current state, nothing happens.
+    pub fn restore_to(&mut self, checkpoint: usize) {
+        // Undo in reverse order such that nodes are back before their edges are
+        while self.log.len() > checkpoint {
+            let change: Change = match self.log.pop() {
+                Some(change) => change,
+                None => break,
+            };
+            match change {
+                Change::RemovedNode(node) => {
+                    self.graph.nodes.insert(node.id, node);
+                },
+                Change::RemovedEdge(edge) => {
+                    self.graph.edges.insert(edge.id, edge);
+                },
+                Change::ChangedCost(id, cost) => {
+                    if let Some(edge) = self.graph.edges.get_mut(&id) {
+                        edge.cost = cost;
+                    }
+                },
+                Change::AddedEdge(id) => {
+                    self.graph.edges.remove(&id);
+                },
+            }
+        }
+    }
+
+    /// Keeps all changes made through this guard, i.e., drops the guard without restoring the graph.
+    #[inline]
+    pub fn commit(mut self) { self.log.clear(); }
+}
+impl Deref for GraphGuard<'_> {
+    type Target = Graph;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target { self.graph }
+}
+impl Drop for GraphGuard<'_> {
+    #[inline]
+    fn drop(&mut self) { self.restore(); }
+}
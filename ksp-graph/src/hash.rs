@@ -0,0 +1,177 @@
+//  HASH.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:10:59
+//  Last edited:
+//    16 Oct 2026, 18:44:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements computing a hash of the contents of a [`Graph`] that is
+//!   stable across runs.
+//
+
+use crate::Graph;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphGuard;
+
+    /// Moves the nodes of a graph to the given positions.
+    fn place(mut g: Graph, nodes: &[(&str, (f64, f64))]) -> Graph {
+        for (id, pos) in nodes {
+            g.nodes.get_mut(*id).unwrap().pos = *pos;
+        }
+        g
+    }
+
+    #[test]
+    fn test_graph_content_hash() {
+        let nodes: [(&str, (f64, f64)); 4] = [("A", (0.0, 0.0)), ("B", (1.0, 0.0)), ("C", (1.0, 1.0)), ("D", (0.0, 1.0))];
+        let edges: [(&str, &str, &str, f64); 4] = [("A-B", "A", "B", 1.0), ("B-C", "B", "C", 2.0), ("C-D", "C", "D", 3.0), ("A-D", "A", "D", 4.0)];
+        let g: Graph = place(Graph::from_edges(edges), &nodes);
+        let hash: u64 = g.content_hash();
+
+        // The order in which things are inserted doesn't matter, nor do the edges' identifiers or (if undirected) directions
+        let mut rev_edges: Vec<(&str, &str, &str, f64)> = edges.iter().map(|(id, left, right, cost)| (*id, *right, *left, *cost)).collect();
+        rev_edges.reverse();
+        rev_edges[0].0 = "foo";
+        assert_eq!(place(Graph::from_edges(rev_edges), &nodes).content_hash(), hash);
+        assert_eq!(g.clone().content_hash(), hash);
+
+        // But changing a cost does, as does moving a node or making it directed
+        let mut h: Graph = g.clone();
+        h.edges.get_mut("B-C").unwrap().cost = 2.5;
+        assert_ne!(h.content_hash(), hash);
+        let mut h: Graph = g.clone();
+        h.nodes.get_mut("C").unwrap().pos = (1.0, 2.0);
+        assert_ne!(h.content_hash(), hash);
+        let mut h: Graph = g.clone();
+        h.directed = true;
+        assert_ne!(h.content_hash(), hash);
+
+        // A restored transformation is no change at all
+        let mut h: Graph = g.clone();
+        let mut guard: GraphGuard = GraphGuard::new(&mut h);
+        guard.remove_node("A");
+        assert_ne!(guard.content_hash(), hash);
+        drop(guard);
+        assert_eq!(h.content_hash(), hash);
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Computes the 64-bit FNV-1a hash of a stream of bytes.
+///
+/// We don't use [`std::hash::DefaultHasher`], as its algorithm may change between Rust versions. This makes it suitable for hashes that
+/// are stored, e.g., to name files by.
+#[derive(Clone, Copy, Debug)]
+pub struct Fnv1a(u64);
+impl Fnv1a {
+    /// Constructor for the Fnv1a that initializes it with the offset basis.
+    ///
+    /// # Returns
+    /// A new Fnv1a that hasn't hashed anything yet.
+    #[inline]
+    pub const fn new() -> Self { Self(0xcbf2_9ce4_8422_2325) }
+
+    /// Adds some bytes to the hash.
+    ///
+    /// # Arguments
+    /// - `bytes`: The bytes to add.
+    #[inline]
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    /// Adds a string to the hash, prefixed with its length such that consecutive strings can't be confused.
+    ///
+    /// # Arguments
+    /// - `s`: The string to add.
+    #[inline]
+    pub fn write_str(&mut self, s: &str) {
+        self.write(&(s.len() as u64).to_le_bytes());
+        self.write(s.as_bytes());
+    }
+
+    /// Adds a number to the hash.
+    ///
+    /// # Arguments
+    /// - `f`: The number to add. `-0.0` is treated as `0.0`.
+    #[inline]
+    pub fn write_f64(&mut self, f: f64) { self.write(&(if f == 0.0 { 0.0f64 } else { f }).to_bits().to_le_bytes()); }
+
+    /// Returns the hash of everything added so far.
+    ///
+    /// # Returns
+    /// The 64-bit hash.
+    #[inline]
+    pub const fn finish(&self) -> u64 { self.0 }
+}
+impl Default for Fnv1a {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+impl Graph {
+    /// Computes a hash of the contents of this graph.
+    ///
+    /// Unlike the hash of the maps, this does not depend on the order in which nodes and edges were inserted, and is the same between runs
+    /// (and versions of Rust). This makes it suitable to, e.g., cache results by graph, or to check that a transformation didn't alter one.
+    ///
+    /// Only the structure is hashed: the identifiers and positions of the nodes, the endpoints and costs of the edges, and whether the graph
+    /// is [directed](Graph::directed). The edges' identifiers are not, and neither is the order of their endpoints if the graph is undirected.
+    ///
+    /// # Returns
+    /// A 64-bit hash that is equal for graphs with equal contents. Different contents only give the same hash by (unlikely) accident.
+    pub fn content_hash(&self) -> u64 {
+        let mut nodes: Vec<(&str, (f64, f64))> = self.nodes.values().map(|n| (n.id.as_str(), n.pos)).collect();
+        nodes.sort_by_key(|(id, _)| *id);
+        let mut edges: Vec<(&str, &str, f64)> = self
+            .edges
+            .values()
+            .map(|e| {
+                if self.directed || e.left <= e.right {
+                    (e.left.as_str(), e.right.as_str(), e.cost)
+                } else {
+                    (e.right.as_str(), e.left.as_str(), e.cost)
+                }
+            })
+            .collect();
+        edges.sort_by(|(l1, r1, c1), (l2, r2, c2)| l1.cmp(l2).then(r1.cmp(r2)).then(c1.total_cmp(c2)));
+
+        // Hash it all in that order
+        let mut hasher: Fnv1a = Fnv1a::new();
+        hasher.write(&[self.directed as u8]);
+        hasher.write(&(nodes.len() as u64).to_le_bytes());
+        for (id, (x, y)) in nodes {
+            hasher.write_str(id);
+            hasher.write_f64(x);
+            hasher.write_f64(y);
+        }
+        hasher.write(&(edges.len() as u64).to_le_bytes());
+        for (left, right, cost) in edges {
+            hasher.write_str(left);
+            hasher.write_str(right);
+            hasher.write_f64(cost);
+        }
+        hasher.finish()
+    }
+}
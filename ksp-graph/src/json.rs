@@ -4,31 +4,192 @@
 //  Created:
 //    19 Jul 2024, 23:35:55
 //  Last edited:
-//    19 Jul 2024, 23:41:46
+//    16 Oct 2026, 19:24:07
 //  Auto updated?
 //    Yes
 //
 //  Description:
-//!   Defines a parser for [`Graph`]s from a `json`-serialization for
-//!   them.
+//!   Defines a parser and writer for [`Graph`]s from and to a
+//!   `json`-serialization for them.
+//!
+//!   Edges may be given either as a map of identifiers to edges, or as a
+//!   list of edges where the identifiers are optional.
+//!
+//!   Individual edges may override the graph's `directed`-flag with their
+//!   own. If any edge is one-way, the parsed graph is directed, and every
+//!   two-way edge gets a reverse edge with the identifier `<id>-rev`.
 //
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error;
 use std::fmt::{Display, Formatter, Result as FResult};
 use std::fs::File;
+use std::io::{BufWriter, Write as _};
 use std::path::{Path, PathBuf};
 
-use crate::Graph;
+use arrayvec::ArrayString;
+use serde::de::{Error as _, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{reverse_id, Edge, Graph, GraphHasher, Label, Labels, Node};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the sorted IDs and costs of the edges in the given graph.
+    fn edges(graph: &Graph) -> Vec<(&str, f64)> {
+        let mut edges: Vec<(&str, f64)> = graph.edges_iter().map(|e| (e.id.as_str(), e.cost)).collect();
+        edges.sort_by_key(|(id, _)| *id);
+        edges
+    }
+
+    #[test]
+    fn test_parse_duplicates() {
+        let path: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/asymmetric.json");
+
+        // By default, nothing is collapsed
+        let g: Graph = parse(path).unwrap();
+        assert_eq!(edges(&g), vec![("A-B", 1.0), ("A-C", 5.0), ("B-A", 3.0), ("B-C", 2.0), ("C-B", 4.0)]);
+        let g: Graph = parse_with(path, ParseOptions { on_duplicate: DuplicatePolicy::KeepBoth }).unwrap();
+        assert_eq!(edges(&g), vec![("A-B", 1.0), ("A-C", 5.0), ("B-A", 3.0), ("B-C", 2.0), ("C-B", 4.0)]);
+
+        // Keeping the first is in order of the file
+        let g: Graph = parse_with(path, ParseOptions { on_duplicate: DuplicatePolicy::KeepFirst }).unwrap();
+        assert_eq!(edges(&g), vec![("A-B", 1.0), ("A-C", 5.0), ("C-B", 4.0)]);
+
+        // Keeping the cheapest ignores the order
+        let g: Graph = parse_with(path, ParseOptions { on_duplicate: DuplicatePolicy::KeepCheapest }).unwrap();
+        assert_eq!(edges(&g), vec![("A-B", 1.0), ("A-C", 5.0), ("B-C", 2.0)]);
+        assert_eq!(g.node_count(), 3);
+        assert!(!g.directed);
+
+        // Unless the graph is directed, in which case only edges in the same direction are duplicates
+        let g: Graph = parse_with(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/directed.json"), ParseOptions {
+            on_duplicate: DuplicatePolicy::KeepFirst,
+        })
+        .unwrap();
+        assert!(g.directed);
+        assert_eq!(edges(&g), vec![("A-B", 1.0), ("B-C", 1.0), ("C-A", 1.0), ("C-B", 1.0)]);
+    }
+
+    #[test]
+    fn test_parse_edge_list() {
+        let map: Graph = parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        let list: Graph = parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities_list.json")).unwrap();
+        assert_eq!(edges(&list), edges(&map));
+        assert!(map.diff(&list).is_empty());
+        for edge in list.edges_iter() {
+            assert_eq!((edge.left, edge.right), (map.edges[&edge.id].left, map.edges[&edge.id].right));
+        }
+
+        // Parallel edges without identifiers still get unique ones
+        let json: &str = r#"{ "nodes": {}, "edges": [{ "left": "A", "right": "B", "cost": 1 }, { "left": "A", "right": "B", "cost": 2 }] }"#;
+        let list: OrderedGraph = serde_json::from_str(json).unwrap();
+        let ids: Vec<&str> = list.edges.0.iter().map(|(e, _)| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["A-B", "A-B-2"]);
+    }
+
+    #[test]
+    fn test_parse_mixed_direction() {
+        // One-way edges make the graph directed, so the two-way ones get a reverse
+        let g: Graph = parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/mixed.json")).unwrap();
+        assert!(g.directed);
+        let mut pairs: Vec<(&str, &str, &str)> = g.edges_iter().map(|e| (e.id.as_str(), e.left.as_str(), e.right.as_str())).collect();
+        pairs.sort();
+        assert_eq!(pairs, vec![
+            ("A-B", "A", "B"),
+            ("A-B-rev", "B", "A"),
+            ("A-D", "A", "D"),
+            ("A-D-rev", "D", "A"),
+            ("B-C", "B", "C"),
+            ("C-A", "C", "A"),
+            ("D-A", "D", "A"),
+        ]);
+        assert_eq!(g.edges["A-B-rev"].cost, g.edges["A-B"].cost);
+        assert!(g.validate().is_ok());
+
+        // Duplicates of two-way edges are in either direction
+        let opts: ParseOptions = ParseOptions { on_duplicate: DuplicatePolicy::KeepFirst };
+        let g: Graph = parse_with(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/mixed.json"), opts).unwrap();
+        assert!(!g.edges.contains_key("D-A"));
+        assert_eq!(g.edge_count(), 6);
+
+        // Directed graphs can have two-way edges too, and reverse identifiers don't clash
+        let path: PathBuf = std::env::temp_dir().join(format!("ksp-graph-test-json-mixed-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{ "directed": true, "nodes": {}, "edges": {
+                "A-B": { "id": "A-B", "left": "A", "right": "B", "cost": 1, "directed": false },
+                "A-B-rev": { "id": "A-B-rev", "left": "B", "right": "C", "cost": 2 }
+            } }"#,
+        )
+        .unwrap();
+        let g: Graph = parse(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(g.directed);
+        assert_eq!(edges(&g), vec![("A-B", 1.0), ("A-B-rev", 2.0), ("A-B-rev-2", 1.0)]);
+        assert_eq!((g.edges["A-B-rev-2"].left.as_str(), g.edges["A-B-rev-2"].right.as_str()), ("B", "A"));
+
+        // Without any overrides, nothing changes
+        assert!(!parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities_list.json")).unwrap().directed);
+    }
+
+    #[test]
+    fn test_write_roundtrip() {
+        let dir: PathBuf = std::env::temp_dir().join(format!("ksp-graph-test-json-write-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in ["cities.json", "cities_list.json", "asymmetric.json", "directed.json"] {
+            let g: Graph = parse(Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests").join(file)).unwrap();
+            let path: PathBuf = dir.join(file);
+            write(&path, &g).unwrap();
+            let h: Graph = parse(&path).unwrap();
+            assert!(g.diff(&h).is_empty(), "Round-trip of '{file}' changed the graph:\n{}", g.diff(&h));
+            assert_eq!(h.directed, g.directed);
+            for node in g.nodes.values() {
+                assert_eq!(h.nodes[&node.id].pos, node.pos);
+            }
+            for edge in g.edges_iter() {
+                assert_eq!((h.edges[&edge.id].left, h.edges[&edge.id].right), (edge.left, edge.right));
+            }
+
+            // Serializing is deterministic
+            assert_eq!(to_string(&g), to_string(&h));
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), to_string(&g));
+        }
+
+        // Labels are kept, too
+        let mut g: Graph = parse(Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/cities.json")).unwrap();
+        let edge: ArrayString<64> = *g.edges.keys().next().unwrap();
+        g.labels.nodes.insert(ArrayString::from("Amsterdam").unwrap(), Label::from("home"));
+        g.labels.edges.insert(edge, Label::Tuple(vec![Label::from(1.5), Label::from("a")]));
+        let path: PathBuf = dir.join("labelled.json");
+        write(&path, &g).unwrap();
+        let h: Graph = parse(&path).unwrap();
+        assert_eq!(h.labels.nodes.len(), 1);
+        assert_eq!(h.labels.nodes["Amsterdam"], Label::from("home"));
+        assert_eq!(h.labels.edges[&edge], Label::Tuple(vec![Label::from(1.5), Label::from("a")]));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
 
 
 /***** ERRORS *****/
-/// Defines errors originating when [`parse()`]ing JSON-serializations of [`Graph`]s.
+/// Defines errors originating when [`parse()`]ing or [`write()`]ing JSON-serializations of [`Graph`]s.
 #[derive(Debug)]
 pub enum Error {
     /// Failed to open the JSON file.
     FileOpen { path: PathBuf, err: std::io::Error },
     /// Failed to read/parse the JSON file.
     FileReadParse { path: PathBuf, err: serde_json::Error },
+    /// The identifier generated for the reverse of a two-way edge is too long.
+    ReverseIdTooLong { path: PathBuf, id: String },
+    /// Failed to create the JSON file.
+    FileCreate { path: PathBuf, err: std::io::Error },
+    /// Failed to write to the JSON file.
+    FileWrite { path: PathBuf, err: std::io::Error },
 }
 impl Display for Error {
     #[inline]
@@ -37,6 +198,11 @@ impl Display for Error {
         match self {
             FileOpen { path, .. } => write!(f, "Failed to open graph file '{}'", path.display()),
             FileReadParse { path, .. } => write!(f, "Failed to read & parse graph file '{}' as valid Graph JSON", path.display()),
+            ReverseIdTooLong { path, id } => {
+                write!(f, "Identifier '{id}' generated for the reverse of a two-way edge in graph file '{}' is longer than 64 bytes", path.display())
+            },
+            FileCreate { path, .. } => write!(f, "Failed to create graph file '{}'", path.display()),
+            FileWrite { path, .. } => write!(f, "Failed to write to graph file '{}'", path.display()),
         }
     }
 }
@@ -47,6 +213,9 @@ impl error::Error for Error {
         match self {
             FileOpen { err, .. } => Some(err),
             FileReadParse { err, .. } => Some(err),
+            ReverseIdTooLong { .. } => None,
+            FileCreate { err, .. } => Some(err),
+            FileWrite { err, .. } => Some(err),
         }
     }
 }
@@ -55,6 +224,152 @@ impl error::Error for Error {
 
 
 
+/***** HELPERS *****/
+/// Mirrors a [`Graph`], but remembers the order in which edges appear in the file.
+#[derive(Deserialize)]
+struct OrderedGraph {
+    /// The nodes in the graph.
+    nodes:    HashMap<ArrayString<64>, Node, GraphHasher>,
+    /// The edges in the graph, in the order they are given.
+    edges:    OrderedEdges,
+    /// Whether the edges are directed.
+    #[serde(default)]
+    directed: bool,
+    /// Any labels of the nodes and edges.
+    #[serde(default)]
+    labels:   Labels,
+}
+
+/// Mirrors a [`Graph`] for serialization, but sorts its nodes and edges by identifier.
+#[derive(Serialize)]
+struct SortedGraph<'g> {
+    /// The nodes in the graph.
+    nodes:    BTreeMap<&'g str, &'g Node>,
+    /// The edges in the graph.
+    edges:    BTreeMap<&'g str, &'g Edge>,
+    /// Whether the edges are directed.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    directed: bool,
+    /// Any labels of the nodes and edges.
+    #[serde(skip_serializing_if = "SortedLabels::is_empty")]
+    labels:   SortedLabels<'g>,
+}
+
+/// Mirrors [`Labels`] for serialization, but sorts them by identifier.
+#[derive(Serialize)]
+struct SortedLabels<'g> {
+    /// The labels of nodes.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    nodes: BTreeMap<&'g str, &'g Label>,
+    /// The labels of edges.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    edges: BTreeMap<&'g str, &'g Label>,
+}
+impl SortedLabels<'_> {
+    /// Checks whether there are no labels at all.
+    ///
+    /// # Returns
+    /// True if both maps are empty, or false otherwise.
+    #[inline]
+    fn is_empty(&self) -> bool { self.nodes.is_empty() && self.edges.is_empty() }
+}
+
+/// An [`Edge`] as given in the file, where its identifier may be omitted if it's in a list.
+#[derive(Deserialize)]
+struct JsonEdge {
+    /// The identifier of the edge, if any.
+    id:       Option<ArrayString<64>>,
+    /// The first node the edge connects.
+    left:     ArrayString<64>,
+    /// The second node the edge connects.
+    right:    ArrayString<64>,
+    /// The cost of the edge.
+    cost:     f64,
+    /// Whether the edge is one-way, if it overrides the graph's default.
+    #[serde(default)]
+    directed: Option<bool>,
+}
+
+/// A map or list of edges that is deserialized as a list, such that their order is kept.
+///
+/// Every edge is paired with its own direction, if it has one.
+struct OrderedEdges(Vec<(Edge, Option<bool>)>);
+impl<'de> Deserialize<'de> for OrderedEdges {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Visits the map of edges.
+        struct OrderedEdgesVisitor;
+        impl<'de> Visitor<'de> for OrderedEdgesVisitor {
+            type Value = OrderedEdges;
+
+            #[inline]
+            fn expecting(&self, f: &mut Formatter) -> FResult { write!(f, "a map or list of edges") }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut edges: Vec<(Edge, Option<bool>)> = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                let mut ids: HashSet<ArrayString<64>> = HashSet::with_capacity(edges.capacity());
+                while let Some(JsonEdge { id, left, right, cost, directed }) = seq.next_element::<JsonEdge>()? {
+                    // Generate an identifier if there's none, making sure that parallel edges don't overwrite each other
+                    let id: ArrayString<64> = match id {
+                        Some(id) => id,
+                        None => {
+                            let mut id: String = format!("{left}-{right}");
+                            let mut i: usize = 2;
+                            while ids.contains(id.as_str()) {
+                                id = format!("{left}-{right}-{i}");
+                                i += 1;
+                            }
+                            ArrayString::from(&id).map_err(|_| A::Error::custom(format!("generated edge identifier '{id}' is longer than 64 bytes")))?
+                        },
+                    };
+                    ids.insert(id);
+                    edges.push((Edge { id, left, right, cost }, directed));
+                }
+                Ok(OrderedEdges(edges))
+            }
+
+            #[inline]
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut edges: Vec<(Edge, Option<bool>)> = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some((key, JsonEdge { id, left, right, cost, directed })) = map.next_entry::<ArrayString<64>, JsonEdge>()? {
+                    edges.push((Edge { id: id.unwrap_or(key), left, right, cost }, directed));
+                }
+                Ok(OrderedEdges(edges))
+            }
+        }
+
+        deserializer.deserialize_any(OrderedEdgesVisitor)
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Determines what happens when multiple edges connect the same pair of nodes (in either direction, unless the graph is directed).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Only the edge given first in the file is kept.
+    KeepFirst,
+    /// Only the cheapest edge is kept. If multiple are equally cheap, the first one in the file is kept.
+    KeepCheapest,
+    /// All edges are kept as parallel edges.
+    #[default]
+    KeepBoth,
+}
+
+/// Configures how [`parse_with()`] parses a file.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParseOptions {
+    /// What to do with multiple edges between the same nodes.
+    pub on_duplicate: DuplicatePolicy,
+}
+
+
+
+
+
 /***** LIBRARY *****/
 /// Parses a graph from a JSON file.
 ///
@@ -67,13 +382,116 @@ impl error::Error for Error {
 /// # Errors
 /// This function errors if we failed to open, read or parse the given file.
 #[inline]
-pub fn parse(path: impl AsRef<Path>) -> Result<Graph, Error> {
+pub fn parse(path: impl AsRef<Path>) -> Result<Graph, Error> { parse_with(path, ParseOptions::default()) }
+
+/// Parses a graph from a JSON file with additional options.
+///
+/// Edges with their own `directed`-flag are one- or two-way regardless of the graph's. If any of them is one-way, the result is a directed
+/// graph in which every two-way edge `<id>` from `A` to `B` is accompanied by a reverse edge `<id>-rev` from `B` to `A` (or `<id>-rev-2`, etc.,
+/// if that is taken). Duplicates are resolved before this, where a two-way edge duplicates edges in both directions.
+///
+/// # Arguments
+/// - `path`: The [`Path`]-like of the file to parse from.
+/// - `opts`: The [`ParseOptions`] that determine how to deal with, e.g., duplicate edges.
+///
+/// # Returns
+/// A new [`Graph`] parsed from the given file.
+///
+/// # Errors
+/// This function errors if we failed to open, read or parse the given file, or if the identifier of a reverse edge would be too long.
+pub fn parse_with(path: impl AsRef<Path>, opts: ParseOptions) -> Result<Graph, Error> {
     let path: &Path = path.as_ref();
-    match File::open(path) {
+    let graph: OrderedGraph = match File::open(path) {
         Ok(handle) => match serde_json::from_reader(handle) {
-            Ok(graph) => Ok(graph),
-            Err(err) => Err(Error::FileReadParse { path: path.into(), err }),
+            Ok(graph) => graph,
+            Err(err) => return Err(Error::FileReadParse { path: path.into(), err }),
+        },
+        Err(err) => return Err(Error::FileOpen { path: path.into(), err }),
+    };
+
+    // Resolve any duplicates
+    let mut edges: Vec<(Edge, bool)> = graph.edges.0.into_iter().map(|(e, directed)| (e, directed.unwrap_or(graph.directed))).collect();
+    if opts.on_duplicate != DuplicatePolicy::KeepBoth {
+        // NOTE: A stable sort, so equally cheap edges remain in file order
+        if opts.on_duplicate == DuplicatePolicy::KeepCheapest {
+            edges.sort_by(|(lhs, _), (rhs, _)| lhs.cost.total_cmp(&rhs.cost));
+        }
+        let mut seen: HashSet<(ArrayString<64>, ArrayString<64>)> = HashSet::with_capacity(edges.len());
+        edges.retain(|(e, directed)| {
+            if *directed {
+                return seen.insert((e.left, e.right));
+            }
+            // NOTE: Both directions are checked before either is claimed
+            let fresh: bool = !seen.contains(&(e.left, e.right)) && !seen.contains(&(e.right, e.left));
+            if fresh {
+                seen.insert((e.left, e.right));
+                seen.insert((e.right, e.left));
+            }
+            fresh
+        });
+    }
+
+    // If any edge is one-way, the graph is directed, so the two-way ones become a pair of edges (with the same label)
+    let mut labels: Labels = graph.labels;
+    let directed: bool = graph.directed || edges.iter().any(|(_, directed)| *directed);
+    let mut res: HashMap<ArrayString<64>, Edge, GraphHasher> = edges.iter().map(|(e, _)| (e.id, *e)).collect();
+    for (edge, _) in edges.iter().filter(|(e, edge_directed)| directed && !edge_directed && e.left != e.right) {
+        let id: ArrayString<64> =
+            reverse_id(&edge.id, |id| res.contains_key(id)).map_err(|id| Error::ReverseIdTooLong { path: path.into(), id })?;
+        res.insert(id, Edge { id, left: edge.right, right: edge.left, cost: edge.cost });
+        if let Some(label) = labels.edges.get(&edge.id).cloned() {
+            labels.edges.insert(id, label);
+        }
+    }
+    Ok(Graph { nodes: graph.nodes, edges: res, directed, labels })
+}
+
+
+
+/// Serializes a graph to JSON.
+///
+/// The result uses the map form for edges and sorts nodes and edges by identifier, so it is deterministic and can be read back with
+/// [`parse()`].
+///
+/// Note that JSON has no representation for infinite or NaN costs, which are written as `null` and cannot be read back.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to serialize.
+///
+/// # Returns
+/// A (pretty-printed) JSON string encoding the `graph`.
+pub fn to_string(graph: &Graph) -> String {
+    let graph: SortedGraph = SortedGraph {
+        nodes: graph.nodes.iter().map(|(id, n)| (id.as_str(), n)).collect(),
+        edges: graph.edges.iter().map(|(id, e)| (id.as_str(), e)).collect(),
+        directed: graph.directed,
+        labels: SortedLabels {
+            nodes: graph.labels.nodes.iter().map(|(id, l)| (id.as_str(), l)).collect(),
+            edges: graph.labels.edges.iter().map(|(id, l)| (id.as_str(), l)).collect(),
         },
-        Err(err) => Err(Error::FileOpen { path: path.into(), err }),
+    };
+    match serde_json::to_string_pretty(&graph) {
+        Ok(json) => json,
+        // NOTE: Only happens for non-string map keys or failing writers, neither of which occurs here
+        Err(err) => unreachable!("Failed to serialize graph to JSON: {err}"),
     }
 }
+
+/// Writes a graph to a JSON file.
+///
+/// See [`to_string()`] for the format.
+///
+/// # Arguments
+/// - `path`: The [`Path`]-like of the file to write to. Overwritten if it already exists.
+/// - `graph`: The [`Graph`] to write.
+///
+/// # Errors
+/// This function errors if we failed to create or write to the given file.
+pub fn write(path: impl AsRef<Path>, graph: &Graph) -> Result<(), Error> {
+    let path: &Path = path.as_ref();
+    let mut handle: BufWriter<File> = match File::create(path) {
+        Ok(handle) => BufWriter::new(handle),
+        Err(err) => return Err(Error::FileCreate { path: path.into(), err }),
+    };
+    handle.write_all(to_string(graph).as_bytes()).and_then(|_| handle.flush()).map_err(|err| Error::FileWrite { path: path.into(), err })
+}
@@ -0,0 +1,182 @@
+//  LAYOUT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:26:37
+//  Last edited:
+//    16 Oct 2026, 18:44:57
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements assigning positions to the nodes of a [`Graph`] that
+//!   doesn't have any meaningful ones.
+//
+
+use std::collections::HashMap;
+
+use arrayvec::ArrayString;
+
+use crate::Graph;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_auto_layout() {
+        let mut g: Graph =
+            Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 1.0), ("C-D", "C", "D", 1.0), ("D-A", "D", "A", 1.0), ("A-E", "A", "E", 1.0)]);
+        assert!(g.has_degenerate_layout());
+        g.auto_layout(100);
+        assert!(!g.has_degenerate_layout());
+        for node in g.nodes.values() {
+            assert!(node.pos.0.is_finite() && node.pos.1.is_finite(), "Node {} has non-finite position {:?}", node.id, node.pos);
+            assert!(g.nodes.values().filter(|other| other.pos == node.pos).count() == 1, "Node {} shares its position", node.id);
+        }
+
+        // It's deterministic
+        let mut h: Graph = g.clone();
+        h.auto_layout(100);
+        for (id, node) in &g.nodes {
+            assert_eq!(node.pos, h.nodes[id].pos);
+        }
+
+        // Trivial graphs are fine, too
+        let mut g: Graph = Graph { nodes: Default::default(), edges: Default::default(), directed: false, labels: Default::default() };
+        assert!(g.has_degenerate_layout());
+        g.auto_layout(100);
+        let mut g: Graph = Graph::from_edges([("A-A", "A", "A", 1.0)]);
+        g.auto_layout(0);
+        assert!(g.nodes["A"].pos.0.is_finite());
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Computes the difference between two positions.
+///
+/// # Arguments
+/// - `p1`: The first position.
+/// - `p2`: The second position.
+/// - `i`: Some index used to push coinciding positions apart in a deterministic direction.
+///
+/// # Returns
+/// A tuple of the difference in X, the difference in Y and the (strictly positive) distance between `p1` and `p2`.
+#[inline]
+fn delta(p1: (f64, f64), p2: (f64, f64), i: usize) -> (f64, f64, f64) {
+    let (dx, dy): (f64, f64) = (p1.0 - p2.0, p1.1 - p2.1);
+    let dist: f64 = (dx * dx + dy * dy).sqrt();
+    if dist > 1e-9 {
+        (dx, dy, dist)
+    } else {
+        let angle: f64 = i as f64;
+        (1e-9 * angle.cos(), 1e-9 * angle.sin(), 1e-9)
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+impl Graph {
+    /// Returns whether the nodes in this graph have meaningless positions, i.e., all of them are at the same one.
+    ///
+    /// # Returns
+    /// True if all nodes have the same position (or there are fewer than two nodes), or false otherwise.
+    #[inline]
+    pub fn has_degenerate_layout(&self) -> bool {
+        let mut nodes = self.nodes.values();
+        match nodes.next() {
+            Some(first) => nodes.all(|n| n.pos == first.pos),
+            None => true,
+        }
+    }
+
+    /// Assigns new positions to all nodes in this graph using the force-directed algorithm by Fruchterman and Reingold \[1\].
+    ///
+    /// This is meant for graphs that don't come with positions (see [`Graph::has_degenerate_layout()`]), and overwrites any existing ones.
+    /// Nodes start on a circle (ordered by identifier) and are then moved for the given number of iterations, where nodes push each other
+    /// away and edges pull their nodes together. The result is deterministic.
+    ///
+    /// # Arguments
+    /// - `iterations`: The number of iterations to run. More iterations give a nicer layout, but each takes quadratic time in the number of
+    ///   nodes. About a hundred is usually enough.
+    ///
+    /// # References
+    /// \[1\] Fruchterman, T. M. J., Reingold, E. M. Graph drawing by force-directed placement.
+    /// _Software: Practice and Experience_ 21, 11, 1129–1164 (1991). https://doi.org/10.1002/spe.4380211102.
+    pub fn auto_layout(&mut self, iterations: usize) {
+        // Sort the nodes to not depend on the order of the map
+        let mut ids: Vec<ArrayString<64>> = self.nodes.keys().copied().collect();
+        ids.sort();
+        let index: HashMap<ArrayString<64>, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut edges: Vec<(usize, usize)> = self
+            .edges
+            .values()
+            .filter_map(|e| Some((*index.get(&e.left)?, *index.get(&e.right)?)))
+            .filter(|(left, right)| left != right)
+            .collect();
+        edges.sort();
+
+        // Start on a circle in the unit square
+        let n: usize = ids.len();
+        let mut pos: Vec<(f64, f64)> = (0..n)
+            .map(|i| {
+                let angle: f64 = 2.0 * std::f64::consts::PI * (i as f64) / (n as f64);
+                (0.5 + 0.5 * angle.cos(), 0.5 + 0.5 * angle.sin())
+            })
+            .collect();
+
+        // Then let the forces do their work, cooling down linearly
+        let k: f64 = (1.0 / (n.max(1) as f64)).sqrt();
+        let mut disp: Vec<(f64, f64)> = vec![(0.0, 0.0); n];
+        for it in 0..iterations {
+            disp.iter_mut().for_each(|d| *d = (0.0, 0.0));
+
+            // Every pair of nodes repulses
+            for i in 0..n {
+                for j in i + 1..n {
+                    let (dx, dy, dist): (f64, f64, f64) = delta(pos[i], pos[j], i);
+                    let force: f64 = k * k / dist;
+                    disp[i].0 += dx / dist * force;
+                    disp[i].1 += dy / dist * force;
+                    disp[j].0 -= dx / dist * force;
+                    disp[j].1 -= dy / dist * force;
+                }
+            }
+            // Every edge attracts
+            for (left, right) in &edges {
+                let (dx, dy, dist): (f64, f64, f64) = delta(pos[*left], pos[*right], *left);
+                let force: f64 = dist * dist / k;
+                disp[*left].0 -= dx / dist * force;
+                disp[*left].1 -= dy / dist * force;
+                disp[*right].0 += dx / dist * force;
+                disp[*right].1 += dy / dist * force;
+            }
+
+            // Move the nodes, but not further than the temperature
+            let temp: f64 = 0.1 * (1.0 - (it as f64) / (iterations as f64));
+            for (p, d) in pos.iter_mut().zip(&disp) {
+                let len: f64 = (d.0 * d.0 + d.1 * d.1).sqrt();
+                if len > 0.0 {
+                    p.0 += d.0 / len * len.min(temp);
+                    p.1 += d.1 / len * len.min(temp);
+                }
+            }
+        }
+
+        // Write them back
+        for (id, p) in ids.iter().zip(pos) {
+            if let Some(node) = self.nodes.get_mut(id) {
+                node.pos = p;
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    19 Jul 2024, 23:35:02
 //  Last edited:
-//    19 Jul 2024, 23:59:21
+//    16 Oct 2026, 19:25:31
 //  Auto updated?
 //    Yes
 //
@@ -12,24 +12,327 @@
 //!   <Todo>
 //
 
+// We keep tests at the top of every module
+#![allow(clippy::items_after_test_module)]
+
 // Declare sub-modules
+pub mod closure;
+pub mod compact;
+pub mod diff;
+pub mod edge_list;
+pub mod guard;
+pub mod hash;
 #[cfg(feature = "json")]
 pub mod json;
+pub mod layout;
+pub mod mst;
+pub mod simplify;
 #[cfg(feature = "sndlib_xml")]
 pub mod sndlib_xml;
 
 // Imports
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FResult};
+#[cfg(feature = "deterministic")]
+use std::hash::{BuildHasherDefault, DefaultHasher};
+#[cfg(not(feature = "deterministic"))]
+use std::hash::RandomState;
 use std::str::FromStr;
 
 use arrayvec::ArrayString;
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
 
+pub use crate::diff::GraphDiff;
+pub use crate::guard::GraphGuard;
+pub use crate::simplify::SimplifyReport;
+
+
+/***** TESTS *****/
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    /// Returns the edges of a graph as tuples of their identifier, nodes and cost, sorted by identifier.
+    fn edge_tuples(g: &Graph) -> Vec<(&str, &str, &str, f64)> {
+        let mut edges: Vec<(&str, &str, &str, f64)> = g.edges.values().map(|e| (e.id.as_str(), e.left.as_str(), e.right.as_str(), e.cost)).collect();
+        edges.sort_by(|e1, e2| e1.0.cmp(e2.0));
+        edges
+    }
+
+    #[test]
+    fn test_graph_to_directed_undirected() {
+        // Every edge of an undirected graph gets a reverse...
+        let g: Graph = json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        let d: Graph = g.to_directed().unwrap();
+        assert!(d.directed);
+        assert!(d.validate().is_ok());
+        assert_eq!(d.node_count(), g.node_count());
+        assert_eq!(d.edge_count(), 2 * g.edge_count());
+        let rev: &Edge = &d.edges["Amsterdam-Berlin-rev"];
+        assert_eq!((rev.left.as_str(), rev.right.as_str(), rev.cost), ("Berlin", "Amsterdam", g.edges["Amsterdam-Berlin"].cost));
+        assert_eq!(d.to_directed().unwrap().edge_count(), d.edge_count());
+
+        // ...which collapse back into the original
+        let u: Graph = d.to_undirected();
+        assert!(!u.directed);
+        assert_eq!(edge_tuples(&u), edge_tuples(&g));
+        assert_eq!(u.node_count(), g.node_count());
+        assert_eq!(edge_tuples(&u.to_undirected()), edge_tuples(&u));
+
+        // Opposing edges in directed graphs collapse into the cheapest, and self-loops aren't reversed
+        let mut g: Graph = Graph::from_edges([("A-B", "A", "B", 2.0), ("B-A", "B", "A", 1.0), ("B-C", "B", "C", 1.0), ("C-C", "C", "C", 1.0)]);
+        g.directed = true;
+        let u: Graph = g.to_undirected();
+        assert_eq!(edge_tuples(&u), vec![("B-A", "B", "A", 1.0), ("B-C", "B", "C", 1.0), ("C-C", "C", "C", 1.0)]);
+        let d: Graph = u.to_directed().unwrap();
+        assert_eq!(d.edge_count(), 2 * u.edge_count() - 1);
+        assert_eq!(edge_tuples(&d.to_undirected()), edge_tuples(&u));
+
+        // Identifiers that are taken are skipped, labels are copied, and identifiers that are too long are errors
+        let mut g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("A-B-rev", "C", "D", 2.0)]);
+        g.labels.edges.insert(ArrayString::from("A-B").unwrap(), Label::Number(3.0));
+        let d: Graph = g.to_directed().unwrap();
+        assert_eq!(edge_tuples(&d), vec![
+            ("A-B", "A", "B", 1.0),
+            ("A-B-rev", "C", "D", 2.0),
+            ("A-B-rev-2", "B", "A", 1.0),
+            ("A-B-rev-rev", "D", "C", 2.0),
+        ]);
+        assert_eq!(d.labels.edges["A-B-rev-2"], Label::Number(3.0));
+        let long: String = "x".repeat(61);
+        assert!(matches!(Graph::from_edges([(long.as_str(), "A", "B", 1.0)]).to_directed(), Err(ToDirectedError::ReverseIdTooLong { .. })));
+    }
+
+    #[test]
+    fn test_graph_neighbours() {
+        let g: Graph = json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        assert_eq!(g.node_count(), 5);
+        assert_eq!(g.edge_count(), 5);
+
+        let mut neighbours: Vec<(&str, &str)> = g.neighbours("Amsterdam").map(|(n, e)| (n, e.id.as_str())).collect();
+        neighbours.sort();
+        assert_eq!(neighbours, vec![
+            ("Berlin", "Amsterdam-Berlin"),
+            ("Dorchester", "Amsterdam-Dorchester"),
+            ("Edinburgh", "Amsterdam-Edinburgh")
+        ]);
+        assert_eq!(g.neighbours("Chicago").map(|(n, _)| n).collect::<Vec<&str>>(), vec!["Dorchester"]);
+        assert_eq!(g.neighbours("Rome").count(), 0);
+    }
+
+    #[test]
+    fn test_graph_validate() {
+        let mut g: Graph = json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        assert!(g.validate().is_ok());
+
+        // Removing a node without its edges leaves them dangling
+        g.nodes.remove("Chicago");
+        assert!(matches!(g.validate(), Err(ValidateError::DanglingEdge { edge, node }) if edge.as_str() == "Chicago-Dorchester" && node.as_str() == "Chicago"));
+        g.edges.remove("Chicago-Dorchester");
+        assert!(g.validate().is_ok());
+
+        // Identifiers must match their keys
+        let mut edge: Edge = g.edges.remove("Amsterdam-Berlin").unwrap();
+        edge.id = ArrayString::from("Berlin-Amsterdam").unwrap();
+        g.edges.insert(ArrayString::from("Amsterdam-Berlin").unwrap(), edge);
+        assert!(matches!(g.validate(), Err(ValidateError::EdgeIdMismatch { .. })));
+    }
+
+    #[test]
+    fn test_graph_from_edges() {
+        let g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 2.5), ("C-C", "C", "C", 0.5)]);
+        assert!(g.validate().is_ok());
+        assert!(!g.directed);
+        assert_eq!(g.node_count(), 3);
+        assert!(g.nodes.values().all(|node| node.pos == (0.0, 0.0)));
+        let edge: &Edge = &g.edges["B-C"];
+        assert_eq!((edge.id.as_str(), edge.left.as_str(), edge.right.as_str(), edge.cost), ("B-C", "B", "C", 2.5));
+
+        // Owned identifiers work too, and later edges replace earlier ones
+        let g: Graph = Graph::from_edges((0..3).map(|i| (format!("{}", i % 2), "A".to_string(), format!("N{i}"), i as f64)));
+        assert_eq!(g.node_count(), 4);
+        assert_eq!(g.edge_count(), 2);
+        assert_eq!(g.edges["0"].cost, 2.0);
+        assert!(Graph::from_edges::<&str, &str>([]).nodes.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_graph_from_edges_too_long() { Graph::from_edges([("A-B", "A", "B".repeat(65).as_str(), 1.0)]); }
+
+    #[test]
+    fn test_graph_remove_node() {
+        let mut g: Graph = json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+
+        // Its edges go with it, so nothing is left dangling
+        assert_eq!(g.remove_node("Dorchester").map(|n| n.id), Some(ArrayString::from("Dorchester").unwrap()));
+        assert!(g.validate().is_ok());
+        assert_eq!((g.node_count(), g.edge_count()), (4, 2));
+        assert!(g.remove_node("Dorchester").is_none());
+        assert_eq!(g.edge_count(), 2);
+
+        assert_eq!(g.remove_edge("Amsterdam-Berlin").map(|e| e.cost), Some(577.34));
+        assert!(g.remove_edge("Amsterdam-Berlin").is_none());
+        assert!(g.validate().is_ok());
+    }
+
+    #[test]
+    fn test_graph_relabel_nodes() {
+        let mut g: Graph = json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        g.labels.nodes.insert(ArrayString::from("Berlin").unwrap(), Label::Text("capital".into()));
+        g.relabel_nodes(&HashMap::from([("Amsterdam", "AMS"), ("Berlin", "Chicago"), ("Chicago", "Berlin")])).unwrap();
+        assert!(g.validate().is_ok());
+        assert_eq!(g.node_count(), 5);
+        assert!(!g.nodes.contains_key("Amsterdam"));
+        let mut neighbours: Vec<(&str, &str)> = g.neighbours("AMS").map(|(n, e)| (n, e.id.as_str())).collect();
+        neighbours.sort();
+        assert_eq!(neighbours, vec![("Chicago", "Amsterdam-Berlin"), ("Dorchester", "Amsterdam-Dorchester"), ("Edinburgh", "Amsterdam-Edinburgh")]);
+        assert_eq!(g.neighbours("Berlin").map(|(n, _)| n).collect::<Vec<&str>>(), vec!["Dorchester"]);
+        assert_eq!(g.labels.nodes.keys().map(|id| id.as_str()).collect::<Vec<&str>>(), vec!["Chicago"]);
+
+        // Nothing changes if any rename is wrong
+        let long: String = "x".repeat(65);
+        assert!(matches!(g.relabel_nodes(&HashMap::from([("Rome", "Roma")])), Err(RelabelError::UnknownNode { id }) if id == "Rome"));
+        assert!(matches!(g.relabel_nodes(&HashMap::from([("AMS", long.as_str())])), Err(RelabelError::IdTooLong { .. })));
+        assert!(matches!(g.relabel_nodes(&HashMap::from([("AMS", "Berlin")])), Err(RelabelError::Collision { id }) if id == "Berlin"));
+        assert!(matches!(
+            g.relabel_nodes(&HashMap::from([("AMS", "X"), ("Berlin", "X")])),
+            Err(RelabelError::Collision { id }) if id == "X"
+        ));
+        assert!(g.nodes.contains_key("AMS"));
+        assert!(g.validate().is_ok());
+    }
+
+    #[test]
+    fn test_graph_format() {
+        assert!(!GraphFormat::all().is_empty());
+        for fmt in GraphFormat::all() {
+            assert_eq!(fmt.to_string().parse::<GraphFormat>().unwrap(), *fmt);
+            assert_eq!(serde_json::from_str::<GraphFormat>(&serde_json::to_string(fmt).unwrap()).unwrap(), *fmt);
+            for ext in fmt.extensions() {
+                assert_eq!(GraphFormat::from_path(format!("benchmarks/graph.{ext}")), Some(*fmt));
+            }
+        }
+        assert_eq!(GraphFormat::Json.to_string(), "json");
+        assert_eq!(GraphFormat::from_path("benchmarks/graph"), None);
+        assert_eq!(GraphFormat::from_path("benchmarks/graph.txt"), None);
+    }
+
+    #[test]
+    fn test_label_display() {
+        assert_eq!(Label::from(1.0 / 3.0).to_string(), (1.0f64 / 3.0).to_string());
+        assert_eq!(format!("{:.2}", Label::from(1.0 / 3.0)), "0.33");
+        assert_eq!(format!("{:.1}", Label::from("red")), "red");
+        let nested: Label = Label::Tuple(vec![Label::from(2.25), Label::Tuple(vec![Label::from("a"), Label::from(0.5)])]);
+        assert_eq!(format!("{nested:.1}"), "(2.2, (a, 0.5))");
+    }
+
+    #[test]
+    fn test_graph_is_connected() {
+        let mut g: Graph = json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        assert!(g.is_connected());
+
+        // Cutting off Chicago disconnects it, as does an infinitely expensive edge
+        let edge: Edge = g.remove_edge("Chicago-Dorchester").unwrap();
+        assert!(!g.is_connected());
+        g.edges.insert(edge.id, Edge { cost: f64::INFINITY, ..edge });
+        assert!(!g.is_connected());
+
+        // Direction doesn't matter, even if some nodes can't be reached from others
+        let mut g: Graph = json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        g.directed = true;
+        assert!(!g.nodes.keys().all(|src| g.nodes.keys().all(|dst| g.are_connected(src, dst))));
+        assert!(g.is_connected());
+
+        // Trivial graphs are connected
+        let mut g: Graph = Graph { nodes: Default::default(), edges: Default::default(), directed: false, labels: Default::default() };
+        assert!(g.is_connected());
+        let id: ArrayString<64> = ArrayString::from("Amsterdam").unwrap();
+        g.nodes.insert(id, Node { id, pos: (0.0, 0.0) });
+        assert!(g.is_connected());
+        let id: ArrayString<64> = ArrayString::from("Berlin").unwrap();
+        g.nodes.insert(id, Node { id, pos: (0.0, 0.0) });
+        assert!(!g.is_connected());
+    }
+
+    #[test]
+    fn test_graph_are_connected() {
+        let mut g: Graph = json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        assert!(g.are_connected("Amsterdam", "Chicago"));
+        assert!(g.are_connected("Chicago", "Berlin"));
+        assert!(g.are_connected("Berlin", "Berlin"));
+        assert!(!g.are_connected("Berlin", "Rome"));
+
+        // Infinitely expensive edges don't count
+        g.edges.get_mut("Chicago-Dorchester").unwrap().cost = f64::INFINITY;
+        assert!(!g.are_connected("Amsterdam", "Chicago"));
+        assert!(g.are_connected("Amsterdam", "Dorchester"));
+    }
+
+    #[test]
+    fn test_graph_successors_predecessors() {
+        // A triangle A -> B -> C -> A
+        let mut g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 1.0), ("C-A", "C", "A", 1.0)]);
+        g.directed = true;
+
+        // Directed, they differ...
+        assert_eq!(g.successors("A").map(|(n, e)| (n, e.id.as_str())).collect::<Vec<(&str, &str)>>(), vec![("B", "A-B")]);
+        assert_eq!(g.predecessors("A").map(|(n, e)| (n, e.id.as_str())).collect::<Vec<(&str, &str)>>(), vec![("C", "C-A")]);
+        assert_eq!(g.neighbours("A").count(), 2);
+        assert!(g.are_connected("A", "C"));
+        g.edges.remove("C-A");
+        assert_eq!(g.predecessors("A").count(), 0);
+        assert!(g.are_connected("A", "C"));
+        assert!(!g.are_connected("C", "A"));
+        assert_eq!(g.successors("Rome").count(), 0);
+
+        // Reversing the graph swaps them
+        let r: Graph = g.reversed();
+        for id in ["A", "B", "C"] {
+            assert_eq!(r.successors(id).map(|(n, _)| n).collect::<Vec<&str>>(), g.predecessors(id).map(|(n, _)| n).collect::<Vec<&str>>());
+        }
+        assert!(r.are_connected("C", "A"));
+
+        // ...but undirected, they're all the incident edges
+        g.directed = false;
+        assert!(g.are_connected("C", "A"));
+        for id in ["A", "B", "C"] {
+            let mut neighbours: Vec<&str> = g.neighbours(id).map(|(n, _)| n).collect();
+            neighbours.sort();
+            let mut successors: Vec<&str> = g.successors(id).map(|(n, _)| n).collect();
+            successors.sort();
+            let mut predecessors: Vec<&str> = g.predecessors(id).map(|(n, _)| n).collect();
+            predecessors.sort();
+            assert_eq!(successors, neighbours);
+            assert_eq!(predecessors, neighbours);
+        }
+    }
+}
+
+
+
+
 
 /***** ERRORS *****/
+/// Defines the errors that [`Graph::to_directed()`] can return.
+#[derive(Debug)]
+pub enum ToDirectedError {
+    /// The identifier generated for the reverse of an edge doesn't fit in the 64 bytes an identifier can be.
+    ReverseIdTooLong { id: String },
+}
+impl Display for ToDirectedError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use ToDirectedError::*;
+        match self {
+            ReverseIdTooLong { id } => write!(f, "Identifier '{id}' generated for the reverse of an edge is longer than 64 bytes"),
+        }
+    }
+}
+impl Error for ToDirectedError {}
+
 /// Defines errors from parsing [`GraphFormat`]s from strings.
 #[derive(Debug)]
 pub struct GraphFormatParseError {
@@ -44,6 +347,82 @@ impl Error for GraphFormatParseError {}
 
 
 
+/// Defines the inconsistencies found by [`Graph::validate()`].
+#[derive(Debug)]
+pub enum ValidateError {
+    /// A node is stored under a different identifier than its own.
+    NodeIdMismatch { key: String, id: String },
+    /// An edge is stored under a different identifier than its own.
+    EdgeIdMismatch { key: String, id: String },
+    /// An edge refers to a node that is not in the graph.
+    DanglingEdge { edge: String, node: String },
+}
+impl Display for ValidateError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use ValidateError::*;
+        match self {
+            NodeIdMismatch { key, id } => write!(f, "Node '{id}' is stored as '{key}'"),
+            EdgeIdMismatch { key, id } => write!(f, "Edge '{id}' is stored as '{key}'"),
+            DanglingEdge { edge, node } => write!(f, "Edge '{edge}' refers to unknown node '{node}'"),
+        }
+    }
+}
+impl Error for ValidateError {}
+
+
+
+/// Defines the errors that [`Graph::relabel_nodes()`] can return.
+#[derive(Debug)]
+pub enum RelabelError {
+    /// A node to rename is not in the graph.
+    UnknownNode { id: String },
+    /// A new identifier doesn't fit in the 64 bytes an identifier can be.
+    IdTooLong { id: String },
+    /// Two nodes would have the same identifier after renaming.
+    Collision { id: String },
+}
+impl Display for RelabelError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use RelabelError::*;
+        match self {
+            UnknownNode { id } => write!(f, "Cannot rename unknown node '{id}'"),
+            IdTooLong { id } => write!(f, "New node identifier '{id}' is longer than 64 bytes"),
+            Collision { id } => write!(f, "Multiple nodes would be identified by '{id}'"),
+        }
+    }
+}
+impl Error for RelabelError {}
+
+
+
+
+
+/***** HELPERS *****/
+/// Generates the identifier for the reverse of an edge.
+///
+/// # Arguments
+/// - `id`: The identifier of the edge to reverse.
+/// - `taken`: Checks whether an identifier is already in use.
+///
+/// # Returns
+/// The identifier `<id>-rev`, or `<id>-rev-2`, `<id>-rev-3`, etc., if that is taken.
+///
+/// # Errors
+/// This function errors with the generated identifier if it's longer than 64 bytes.
+pub(crate) fn reverse_id(id: &str, taken: impl Fn(&str) -> bool) -> Result<ArrayString<64>, String> {
+    let mut rev: String = format!("{id}-rev");
+    let mut i: usize = 2;
+    while taken(&rev) {
+        rev = format!("{id}-rev-{i}");
+        i += 1;
+    }
+    ArrayString::from(&rev).map_err(|_| rev.clone())
+}
+
+
+
 
 
 /***** AUXILLARY *****/
@@ -71,6 +450,64 @@ impl GraphFormat {
             Self::SNDLibXml,
         ]
     }
+
+    /// Returns the identifier by which this format is parsed.
+    ///
+    /// # Returns
+    /// A static string that [`GraphFormat::from_str()`] parses back to this format.
+    #[inline]
+    pub const fn id(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "json")]
+            Self::Json => "json",
+            #[cfg(feature = "sndlib_xml")]
+            Self::SNDLibXml => "sndlib_xml",
+        }
+    }
+
+    /// Returns the file extensions of files in this format.
+    ///
+    /// # Returns
+    /// A `'static` slice of extensions (without leading dot) by which this format is recognized.
+    #[inline]
+    pub const fn extensions(&self) -> &'static [&'static str] {
+        match *self {
+            #[cfg(feature = "json")]
+            Self::Json => &["json"],
+            #[cfg(feature = "sndlib_xml")]
+            Self::SNDLibXml => &["xml"],
+        }
+    }
+
+    /// Deduces the format of a file from its extension.
+    ///
+    /// # Arguments
+    /// - `path`: The path of the file.
+    ///
+    /// # Returns
+    /// The format that has the file's extension as one of its [`GraphFormat::extensions()`], or [`None`] if there is none.
+    #[inline]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Option<Self> {
+        let ext: &str = path.as_ref().extension()?.to_str()?;
+        Self::all().iter().find(|fmt| fmt.extensions().contains(&ext)).copied()
+    }
+}
+impl Display for GraphFormat {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult { write!(f, "{}", self.id()) }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for GraphFormat {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw: String = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::from_str(&raw).map_err(serde::de::Error::custom)
+    }
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for GraphFormat {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { serializer.serialize_str(self.id()) }
 }
 impl FromStr for GraphFormat {
     type Err = GraphFormatParseError;
@@ -92,14 +529,411 @@ impl FromStr for GraphFormat {
 
 
 /***** LIBRARY *****/
+/// The hasher used by the maps of a [`Graph`] by default.
+///
+/// If the `deterministic`-feature is enabled, this hashes with fixed keys, such that iterating over the nodes and edges of graphs built
+/// the same way happens in the same order (across runs, too). Otherwise, it's the standard library's randomly seeded one.
+#[cfg(feature = "deterministic")]
+pub type GraphHasher = BuildHasherDefault<DefaultHasher>;
+/// The hasher used by the maps of a [`Graph`] by default.
+///
+/// If the `deterministic`-feature is enabled, this hashes with fixed keys, such that iterating over the nodes and edges of graphs built
+/// the same way happens in the same order (across runs, too). Otherwise, it's the standard library's randomly seeded one.
+#[cfg(not(feature = "deterministic"))]
+pub type GraphHasher = RandomState;
+
 /// Defines a graph of nodes linked by edges.
+///
+/// The nodes and edges are stored in [`HashMap`]s hashing with the [`GraphHasher`].
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "json", derive(Deserialize, Serialize))]
 pub struct Graph {
     /// The nodes in the graph.
-    pub nodes: HashMap<ArrayString<64>, Node>,
+    pub nodes:    HashMap<ArrayString<64>, Node, GraphHasher>,
     /// The edges in the graph.
-    pub edges: HashMap<ArrayString<64>, Edge>,
+    pub edges:    HashMap<ArrayString<64>, Edge, GraphHasher>,
+    /// Whether the edges are directed, i.e., can only be traversed from their `left` to their `right` node.
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub directed: bool,
+    /// Any [`Label`]s attached to the nodes and edges, e.g., by colouring algorithms.
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Labels::is_empty"))]
+    pub labels:   Labels,
+}
+impl Graph {
+    /// Builds an undirected graph from a list of edges.
+    ///
+    /// Every node mentioned by an edge is created at the origin. This is mostly useful for small, handwritten graphs (e.g., in tests). Set
+    /// [`Graph::directed`] afterwards to make it directed, and add nodes without any edges to [`Graph::nodes`] directly.
+    ///
+    /// # Arguments
+    /// - `edges`: The edges to add, as tuples of their identifier, left node, right node and cost. An edge replaces any earlier one with the
+    ///   same identifier.
+    ///
+    /// # Returns
+    /// A new [`Graph`] with the given edges and the nodes they link.
+    ///
+    /// # Panics
+    /// This function panics if any identifier is longer than 64 bytes.
+    pub fn from_edges<I: AsRef<str>, N: AsRef<str>>(edges: impl IntoIterator<Item = (I, N, N, f64)>) -> Self {
+        let mut graph: Self = Self { nodes: Default::default(), edges: Default::default(), directed: false, labels: Default::default() };
+        for (id, left, right, cost) in edges {
+            let [id, left, right]: [ArrayString<64>; 3] = [id.as_ref(), left.as_ref(), right.as_ref()]
+                .map(|id| ArrayString::from(id).unwrap_or_else(|_| panic!("Identifier '{id}' is longer than 64 bytes")));
+            for node in [left, right] {
+                graph.nodes.entry(node).or_insert(Node { id: node, pos: (0.0, 0.0) });
+            }
+            graph.edges.insert(id, Edge { id, left, right, cost });
+        }
+        graph
+    }
+
+    /// Returns the number of nodes in the graph.
+    ///
+    /// # Returns
+    /// The number of [`Node`]s in this graph.
+    #[inline]
+    pub fn node_count(&self) -> usize { self.nodes.len() }
+
+    /// Returns the number of edges in the graph.
+    ///
+    /// # Returns
+    /// The number of [`Edge`]s in this graph.
+    #[inline]
+    pub fn edge_count(&self) -> usize { self.edges.len() }
+
+    /// Returns the sum of the costs of all edges in the graph.
+    ///
+    /// Useful for, e.g., the weight of a [minimum spanning tree](Graph::minimum_spanning_tree()).
+    ///
+    /// # Returns
+    /// The total cost of all [`Edge`]s in this graph, which is `0.0` if there are none. The costs are added in ascending order, so the result
+    /// doesn't depend on the order of the map.
+    pub fn total_cost(&self) -> f64 {
+        let mut costs: Vec<f64> = self.edges.values().map(|e| e.cost).collect();
+        costs.sort_by(f64::total_cmp);
+        costs.into_iter().sum()
+    }
+
+    /// Iterates over the nodes in the graph.
+    ///
+    /// # Returns
+    /// An iterator over the [`Node`]s in this graph, in no particular order.
+    #[inline]
+    pub fn nodes_iter(&self) -> impl Iterator<Item = &Node> { self.nodes.values() }
+
+    /// Iterates over the edges in the graph.
+    ///
+    /// # Returns
+    /// An iterator over the [`Edge`]s in this graph, in no particular order.
+    #[inline]
+    pub fn edges_iter(&self) -> impl Iterator<Item = &Edge> { self.edges.values() }
+
+    /// Returns an undirected copy of this graph.
+    ///
+    /// If this graph is [directed](Graph::directed), every group of edges linking the same nodes (in either direction) is collapsed into the
+    /// cheapest of them, as by [`Graph::simplify()`]. This undoes [`Graph::to_directed()`], as an edge and its reverse cost the same and the
+    /// original has the smallest identifier. If this graph is undirected already, it's copied as-is.
+    ///
+    /// # Returns
+    /// A new, undirected [`Graph`] with the same nodes.
+    pub fn to_undirected(&self) -> Graph {
+        let mut res: Graph = self.clone();
+        if res.directed {
+            res.directed = false;
+            res.simplify(false);
+        }
+        res
+    }
+
+    /// Returns a directed copy of this graph.
+    ///
+    /// If this graph is undirected, every edge `<id>` (except self-loops) is accompanied by a reverse edge `<id>-rev` with the same cost
+    /// and label (or `<id>-rev-2`, etc., if that is taken), like two-way edges in [JSON files](crate::json). If this graph is
+    /// [directed](Graph::directed) already, it's copied as-is.
+    ///
+    /// # Returns
+    /// A new, directed [`Graph`] with the same nodes, in which every path of this graph can be taken.
+    ///
+    /// # Errors
+    /// This function errors if the identifier of a reverse edge would be longer than 64 bytes.
+    pub fn to_directed(&self) -> Result<Graph, ToDirectedError> {
+        let mut res: Graph = self.clone();
+        if res.directed {
+            return Ok(res);
+        }
+        res.directed = true;
+
+        // NOTE: Sorted, such that which edge gets which identifier doesn't depend on the order of the map
+        let mut edges: Vec<&Edge> = self.edges.values().filter(|e| e.left != e.right).collect();
+        edges.sort_by_key(|e| e.id);
+        for edge in edges {
+            let id: ArrayString<64> =
+                reverse_id(&edge.id, |id| res.edges.contains_key(id)).map_err(|id| ToDirectedError::ReverseIdTooLong { id })?;
+            res.edges.insert(id, Edge { id, left: edge.right, right: edge.left, cost: edge.cost });
+            if let Some(label) = self.labels.edges.get(&edge.id) {
+                res.labels.edges.insert(id, label.clone());
+            }
+        }
+        Ok(res)
+    }
+
+    /// Removes a node from the graph, together with all edges connected to it.
+    ///
+    /// Prefer this over removing it from [`Graph::nodes`] directly, which leaves its edges dangling. To undo the removal later, use
+    /// [`GraphGuard::remove_node()`] instead.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the node to remove.
+    ///
+    /// # Returns
+    /// The removed [`Node`], or [`None`] if there was no node with that identifier (in which case no edges are removed either).
+    pub fn remove_node(&mut self, id: &str) -> Option<Node> {
+        let node: Node = self.nodes.remove(id)?;
+        self.edges.retain(|_, e| e.left.as_str() != id && e.right.as_str() != id);
+        Some(node)
+    }
+
+    /// Renames nodes, updating the edges that refer to them.
+    ///
+    /// All nodes are renamed at once, so identifiers can be swapped. Edges keep their own identifiers, even if those are derived from the
+    /// names of their nodes (e.g., `Amsterdam-Berlin`). Node labels move along with their nodes.
+    ///
+    /// # Arguments
+    /// - `mapping`: Maps the current identifiers of the nodes to rename to their new ones. Nodes not in it keep their identifiers.
+    ///
+    /// # Errors
+    /// This function errors if a node in `mapping` is not in the graph, if a new identifier is too long, or if two nodes would end up with
+    /// the same identifier. The graph is left untouched if it does.
+    pub fn relabel_nodes(&mut self, mapping: &HashMap<&str, &str>) -> Result<(), RelabelError> {
+        // Check everything before changing anything
+        let mut renames: HashMap<ArrayString<64>, ArrayString<64>> = HashMap::with_capacity(mapping.len());
+        for (old, new) in mapping {
+            let old: ArrayString<64> = match self.nodes.get_key_value(*old) {
+                Some((old, _)) => *old,
+                None => return Err(RelabelError::UnknownNode { id: (*old).into() }),
+            };
+            let new: ArrayString<64> = ArrayString::from(new).map_err(|_| RelabelError::IdTooLong { id: (*new).into() })?;
+            renames.insert(old, new);
+        }
+        let mut ids: HashSet<ArrayString<64>> = HashSet::with_capacity(self.nodes.len());
+        for id in self.nodes.keys() {
+            let id: ArrayString<64> = renames.get(id).copied().unwrap_or(*id);
+            if !ids.insert(id) {
+                return Err(RelabelError::Collision { id: id.to_string() });
+            }
+        }
+
+        // Then rename
+        let rename = |id: &mut ArrayString<64>| {
+            if let Some(new) = renames.get(id) {
+                *id = *new;
+            }
+        };
+        let nodes: Vec<Node> = self.nodes.drain().map(|(_, node)| node).collect();
+        for mut node in nodes {
+            rename(&mut node.id);
+            self.nodes.insert(node.id, node);
+        }
+        for edge in self.edges.values_mut() {
+            rename(&mut edge.left);
+            rename(&mut edge.right);
+        }
+        let labels: Vec<(ArrayString<64>, Label)> = self.labels.nodes.drain().collect();
+        for (mut id, label) in labels {
+            rename(&mut id);
+            self.labels.nodes.insert(id, label);
+        }
+        Ok(())
+    }
+
+    /// Removes an edge from the graph.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the edge to remove.
+    ///
+    /// # Returns
+    /// The removed [`Edge`], or [`None`] if there was no edge with that identifier.
+    #[inline]
+    pub fn remove_edge(&mut self, id: &str) -> Option<Edge> { self.edges.remove(id) }
+
+    /// Iterates over the neighbours of a node.
+    ///
+    /// Edges are treated as undirected, so an edge connects a node to its neighbour regardless of which side it's on, even if the graph is
+    /// [directed](Graph::directed). Use [`Graph::successors()`] or [`Graph::predecessors()`] to honour the direction. Self-loops are skipped,
+    /// as they never lead anywhere new.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the node to find the neighbours of.
+    ///
+    /// # Returns
+    /// An iterator over pairs of the identifier of a neighbour and the [`Edge`] leading to it. A neighbour is yielded multiple times if there are
+    /// parallel edges leading to it. If `id` is not in the graph, the iterator is empty.
+    #[inline]
+    pub fn neighbours<'s>(&'s self, id: &'s str) -> impl 's + Iterator<Item = (&'s str, &'s Edge)> {
+        self.edges.values().filter_map(move |edge| {
+            if edge.left.as_str() == id && edge.right.as_str() != id {
+                Some((edge.right.as_str(), edge))
+            } else if edge.left.as_str() != id && edge.right.as_str() == id {
+                Some((edge.left.as_str(), edge))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Iterates over the nodes that can be reached from a node over a single edge.
+    ///
+    /// If the graph is [directed](Graph::directed), these are the nodes on the `right` of edges that have the given node on the `left`.
+    /// Otherwise, this is the same as [`Graph::neighbours()`]. Self-loops are skipped, as they never lead anywhere new.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the node to find the successors of.
+    ///
+    /// # Returns
+    /// An iterator over pairs of the identifier of a successor and the [`Edge`] leading to it. A successor is yielded multiple times if
+    /// there are parallel edges leading to it. If `id` is not in the graph, the iterator is empty.
+    #[inline]
+    pub fn successors<'s>(&'s self, id: &'s str) -> impl 's + Iterator<Item = (&'s str, &'s Edge)> {
+        let directed: bool = self.directed;
+        self.edges.values().filter_map(move |edge| {
+            if edge.left.as_str() == id && edge.right.as_str() != id {
+                Some((edge.right.as_str(), edge))
+            } else if !directed && edge.left.as_str() != id && edge.right.as_str() == id {
+                Some((edge.left.as_str(), edge))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Iterates over the nodes from which a node can be reached over a single edge.
+    ///
+    /// If the graph is [directed](Graph::directed), these are the nodes on the `left` of edges that have the given node on the `right`.
+    /// Otherwise, this is the same as [`Graph::neighbours()`]. Self-loops are skipped, as they never lead anywhere new.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the node to find the predecessors of.
+    ///
+    /// # Returns
+    /// An iterator over pairs of the identifier of a predecessor and the [`Edge`] leading from it. A predecessor is yielded multiple times
+    /// if there are parallel edges leading from it. If `id` is not in the graph, the iterator is empty.
+    #[inline]
+    pub fn predecessors<'s>(&'s self, id: &'s str) -> impl 's + Iterator<Item = (&'s str, &'s Edge)> {
+        let directed: bool = self.directed;
+        self.edges.values().filter_map(move |edge| {
+            if edge.right.as_str() == id && edge.left.as_str() != id {
+                Some((edge.left.as_str(), edge))
+            } else if !directed && edge.right.as_str() != id && edge.left.as_str() == id {
+                Some((edge.right.as_str(), edge))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns a copy of this graph with every edge turned around.
+    ///
+    /// This is useful to search a [directed](Graph::directed) graph backwards, e.g., to find the shortest paths _to_ a node. The edges keep
+    /// their identifiers and costs. For undirected graphs, the result is equivalent to the original.
+    ///
+    /// # Returns
+    /// A new [`Graph`] with the same nodes, where every edge's `left` and `right` are swapped.
+    pub fn reversed(&self) -> Graph {
+        let edges: HashMap<ArrayString<64>, Edge, GraphHasher> =
+            self.edges.iter().map(|(id, e)| (*id, Edge { id: e.id, left: e.right, right: e.left, cost: e.cost })).collect();
+        Graph { nodes: self.nodes.clone(), edges, directed: self.directed, labels: self.labels.clone() }
+    }
+
+    /// Checks whether the graph is consistent.
+    ///
+    /// This means that every node and edge is stored under its own identifier, and that every edge connects nodes that are in the graph.
+    ///
+    /// # Errors
+    /// This function errors with the first inconsistency found. Note that, as the graph is unordered, which one that is may differ between
+    /// runs.
+    pub fn validate(&self) -> Result<(), ValidateError> {
+        for (key, node) in &self.nodes {
+            if *key != node.id {
+                return Err(ValidateError::NodeIdMismatch { key: key.to_string(), id: node.id.to_string() });
+            }
+        }
+        for (key, edge) in &self.edges {
+            if *key != edge.id {
+                return Err(ValidateError::EdgeIdMismatch { key: key.to_string(), id: edge.id.to_string() });
+            }
+            for node in [edge.left, edge.right] {
+                if !self.nodes.contains_key(&node) {
+                    return Err(ValidateError::DanglingEdge { edge: edge.id.to_string(), node: node.to_string() });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether the whole graph is connected, i.e., whether there is a path between every pair of nodes.
+    ///
+    /// The direction of edges is ignored, so for [directed](Graph::directed) graphs, this checks whether they're weakly connected. Like for
+    /// [`Graph::are_connected()`], edges with an infinite cost are considered absent.
+    ///
+    /// Uses union-find, stopping as soon as all nodes are in the same set, so it takes at most one pass over the edges. This makes it a cheap
+    /// precondition to check before routing.
+    ///
+    /// # Returns
+    /// True if all nodes are connected, or false otherwise. Graphs with no nodes or only one are connected.
+    pub fn is_connected(&self) -> bool {
+        let index: HashMap<ArrayString<64>, usize> = self.nodes.keys().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut parents: Vec<usize> = (0..index.len()).collect();
+        let mut sets: usize = index.len();
+        if sets <= 1 {
+            return true;
+        }
+        for edge in self.edges.values() {
+            let (left, right): (usize, usize) = match (index.get(&edge.left), index.get(&edge.right)) {
+                (Some(left), Some(right)) if edge.cost < f64::INFINITY => (mst::find(&mut parents, *left), mst::find(&mut parents, *right)),
+                _ => continue,
+            };
+            if left != right {
+                parents[left] = right;
+                sets -= 1;
+                if sets == 1 {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Checks whether there is any path between two nodes.
+    ///
+    /// Edges with an infinite cost are considered absent. If the graph is [directed](Graph::directed), the path must follow the edges'
+    /// direction.
+    ///
+    /// # Arguments
+    /// - `src`: The identifier of the node to start from.
+    /// - `dst`: The identifier of the node to reach.
+    ///
+    /// # Returns
+    /// True if `dst` can be reached from `src` (or they're the same node), or false otherwise, including if either of them is not in the graph.
+    pub fn are_connected(&self, src: &str, dst: &str) -> bool {
+        if !self.nodes.contains_key(src) || !self.nodes.contains_key(dst) {
+            return false;
+        }
+
+        // Do a simple depth-first search
+        let mut visited: HashSet<&str> = HashSet::from([src]);
+        let mut todo: Vec<&str> = vec![src];
+        while let Some(node) = todo.pop() {
+            if node == dst {
+                return true;
+            }
+            for (next, edge) in self.successors(node) {
+                if edge.cost < f64::INFINITY && visited.insert(next) {
+                    todo.push(next);
+                }
+            }
+        }
+        false
+    }
 }
 
 
@@ -115,6 +949,9 @@ pub struct Node {
 }
 
 /// Defines a link between nodes in each graph.
+///
+/// Edges are undirected by default: they may be traversed from their `left` to their `right` node and vice versa at the same cost. In a
+/// [directed](Graph::directed) graph, they may only be traversed from `left` to `right`.
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "json", derive(Deserialize, Serialize))]
 pub struct Edge {
@@ -127,3 +964,74 @@ pub struct Edge {
     /// The cost associated with traversing the edge.
     pub cost:  f64,
 }
+
+/// Defines a value with which a node or edge can be labelled, e.g., by a colouring algorithm.
+///
+/// When displayed with a precision (e.g., `{:.2}`), numbers are rounded to it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Deserialize, Serialize), serde(untagged))]
+pub enum Label {
+    /// A numeric label, e.g., a centrality or a colour index.
+    Number(f64),
+    /// A textual label, e.g., a category.
+    Text(String),
+    /// A combination of labels.
+    Tuple(Vec<Label>),
+}
+impl Display for Label {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        match self {
+            Self::Number(n) => match f.precision() {
+                Some(prec) => write!(f, "{n:.prec$}"),
+                None => write!(f, "{n}"),
+            },
+            Self::Text(text) => write!(f, "{text}"),
+            Self::Tuple(labels) => {
+                write!(f, "(")?;
+                for (i, label) in labels.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    // NOTE: Pass on the formatter to keep the precision
+                    Display::fmt(label, f)?;
+                }
+                write!(f, ")")
+            },
+        }
+    }
+}
+impl From<f64> for Label {
+    #[inline]
+    fn from(value: f64) -> Self { Self::Number(value) }
+}
+impl From<&str> for Label {
+    #[inline]
+    fn from(value: &str) -> Self { Self::Text(value.into()) }
+}
+impl From<String> for Label {
+    #[inline]
+    fn from(value: String) -> Self { Self::Text(value) }
+}
+
+/// Defines the [`Label`]s attached to the nodes and edges of a [`Graph`].
+///
+/// They are kept separately from the nodes and edges themselves, such that algorithms can annotate a graph for later steps (e.g., rendering)
+/// without any of the others having to know. Labels of nodes or edges that aren't in the graph are ignored.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "json", derive(Deserialize, Serialize))]
+pub struct Labels {
+    /// The labels of nodes, by their identifier.
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+    pub nodes: HashMap<ArrayString<64>, Label, GraphHasher>,
+    /// The labels of edges, by their identifier.
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+    pub edges: HashMap<ArrayString<64>, Label, GraphHasher>,
+}
+impl Labels {
+    /// Checks whether there are no labels at all.
+    ///
+    /// # Returns
+    /// True if no node or edge is labelled, or false otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.nodes.is_empty() && self.edges.is_empty() }
+}
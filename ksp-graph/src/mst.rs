@@ -0,0 +1,111 @@
+//  MST.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:38:17
+//  Last edited:
+//    16 Oct 2026, 18:48:02
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements computing the minimum spanning tree of a [`Graph`].
+//
+
+use std::collections::HashMap;
+
+use arrayvec::ArrayString;
+
+use crate::{Edge, Graph, GraphHasher};
+
+
+/***** TESTS *****/
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_minimum_spanning_tree() {
+        let g: Graph = crate::json::parse(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.json")).unwrap();
+        let mst: Graph = g.minimum_spanning_tree();
+        assert!(mst.validate().is_ok());
+        assert_eq!(mst.node_count(), g.node_count());
+
+        // Only Amsterdam-Edinburgh closes a cycle (with Dorchester), and it's the most expensive one in it
+        let mut edges: Vec<&str> = mst.edges.keys().map(|id| id.as_str()).collect();
+        edges.sort();
+        assert_eq!(edges, vec!["Amsterdam-Berlin", "Amsterdam-Dorchester", "Chicago-Dorchester", "Dorchester-Edinburgh"]);
+        assert!((mst.total_cost() - (577.34 + 540.86 + 6249.15 + 589.23)).abs() < 1e-9);
+
+        // Disconnected graphs give a forest
+        let mut g: Graph = g;
+        g.edges.remove("Chicago-Dorchester");
+        let island: Graph = Graph::from_edges([("Paris-Rome", "Paris", "Rome", 1105.76)]);
+        g.nodes.extend(island.nodes);
+        g.edges.extend(island.edges);
+        let mst: Graph = g.minimum_spanning_tree();
+        assert_eq!(mst.node_count(), 7);
+        assert_eq!(mst.edge_count(), 4);
+        assert!((mst.total_cost() - (577.34 + 540.86 + 589.23 + 1105.76)).abs() < 1e-9);
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Finds the representative of a node's set in a union-find forest, compressing the path to it.
+///
+/// # Arguments
+/// - `parents`: The parent of every node, which is itself for representatives.
+/// - `node`: The node to find the representative of.
+///
+/// # Returns
+/// The representative of `node`'s set.
+pub(crate) fn find(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
+
+
+
+
+
+/***** LIBRARY *****/
+impl Graph {
+    /// Computes the minimum spanning tree of this graph.
+    ///
+    /// If this graph is disconnected, there is no tree that spans it, and this returns a minimum spanning forest instead: one tree for every
+    /// connected component. The direction of edges is ignored, so for [directed](Graph::directed) graphs, this is the tree of the underlying
+    /// undirected graph (and the result is undirected).
+    ///
+    /// Uses Kruskal's algorithm, so it takes O(m log m) time for m edges. Ties are broken by edge identifier, so the result is deterministic.
+    /// Use [`Graph::total_cost()`] on the result for its weight.
+    ///
+    /// # Returns
+    /// A new [`Graph`] with the same nodes (and positions), and only the edges in the tree (with their identifiers and costs). It has one edge
+    /// less than it has nodes for every connected component.
+    pub fn minimum_spanning_tree(&self) -> Graph {
+        let index: HashMap<ArrayString<64>, usize> = self.nodes.keys().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut candidates: Vec<&Edge> = self.edges.values().filter(|e| index.contains_key(&e.left) && index.contains_key(&e.right)).collect();
+        candidates.sort_by(|e1, e2| e1.cost.total_cmp(&e2.cost).then(e1.id.cmp(&e2.id)));
+
+        // Add the cheapest edges that don't close a cycle
+        let mut parents: Vec<usize> = (0..index.len()).collect();
+        let mut edges: HashMap<ArrayString<64>, Edge, GraphHasher> =
+            HashMap::with_capacity_and_hasher(index.len().saturating_sub(1), GraphHasher::default());
+        for edge in candidates {
+            let left: usize = find(&mut parents, index[&edge.left]);
+            let right: usize = find(&mut parents, index[&edge.right]);
+            if left != right {
+                parents[left] = right;
+                edges.insert(edge.id, *edge);
+            }
+        }
+        Graph { nodes: self.nodes.clone(), edges, directed: false, labels: self.labels.clone() }
+    }
+}
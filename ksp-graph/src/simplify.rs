@@ -0,0 +1,156 @@
+//  SIMPLIFY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:21:40
+//  Last edited:
+//    16 Oct 2026, 18:48:02
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements simplifying a [`Graph`] by removing parallel edges and
+//!   (optionally) self-loops.
+//
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter, Result as FResult};
+
+use arrayvec::ArrayString;
+
+use crate::{Edge, Graph};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Label;
+
+    /// Builds a graph A - B - C with two edges parallel to A - B (one of them reversed), a self-loop on C and a costlier parallel one.
+    fn graph() -> Graph {
+        Graph::from_edges([
+            ("1", "A", "B", 3.0),
+            ("2", "B", "A", 1.0),
+            ("3", "A", "B", 1.0),
+            ("4", "B", "C", 2.0),
+            ("5", "C", "C", 1.0),
+            ("6", "C", "C", 4.0),
+        ])
+    }
+
+    /// Returns the sorted identifiers of the edges in a graph.
+    fn edge_ids(g: &Graph) -> Vec<&str> {
+        let mut ids: Vec<&str> = g.edges.keys().map(|id| id.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_graph_simplify() {
+        // The cheapest edge is kept, and ties go to the smallest identifier
+        let mut g: Graph = graph();
+        g.labels.edges.insert(ArrayString::from("1").unwrap(), Label::Number(1.0));
+        let report: SimplifyReport = g.simplify(false);
+        assert_eq!(report, SimplifyReport { parallel: 3, self_loops: 0 });
+        assert_eq!(edge_ids(&g), vec!["2", "4", "5"]);
+        assert!(g.labels.edges.is_empty());
+        assert!(g.validate().is_ok());
+
+        // Self-loops can be dropped altogether
+        let mut g: Graph = graph();
+        assert_eq!(g.simplify(true), SimplifyReport { parallel: 2, self_loops: 2 });
+        assert_eq!(edge_ids(&g), vec!["2", "4"]);
+        assert_eq!(g.simplify(true).removed(), 0);
+
+        // In directed graphs, edges in opposite directions aren't parallel
+        let mut g: Graph = graph();
+        g.directed = true;
+        assert_eq!(g.simplify(true), SimplifyReport { parallel: 1, self_loops: 2 });
+        assert_eq!(edge_ids(&g), vec!["2", "3", "4"]);
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Reports what [`Graph::simplify()`] removed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SimplifyReport {
+    /// The number of edges removed because a cheaper one (or one with a smaller identifier) links the same nodes.
+    pub parallel:   usize,
+    /// The number of self-loops removed.
+    pub self_loops: usize,
+}
+impl SimplifyReport {
+    /// Returns the total number of edges removed.
+    ///
+    /// # Returns
+    /// The number of parallel edges plus the number of self-loops removed.
+    #[inline]
+    pub fn removed(&self) -> usize { self.parallel + self.self_loops }
+}
+impl Display for SimplifyReport {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        write!(f, "removed {} parallel edge(s) and {} self-loop(s)", self.parallel, self.self_loops)
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+impl Graph {
+    /// Simplifies this graph such that any pair of nodes is linked by at most one edge.
+    ///
+    /// Of the edges linking the same nodes, only the cheapest is kept. Ties are broken by edge identifier, so the result is deterministic.
+    /// For undirected graphs, edges linking the same nodes in the other direction are parallel too, while for [directed](Graph::directed)
+    /// ones, they aren't. Labels of removed edges are removed too.
+    ///
+    /// This is useful before running algorithms that assume a simple graph.
+    ///
+    /// # Arguments
+    /// - `drop_self_loops`: Whether to remove all edges from a node to itself, too. If false, they are simplified like other edges.
+    ///
+    /// # Returns
+    /// A [`SimplifyReport`] with how many edges were removed.
+    pub fn simplify(&mut self, drop_self_loops: bool) -> SimplifyReport {
+        let mut report: SimplifyReport = SimplifyReport::default();
+
+        // Find the edge to keep for every pair of nodes
+        let mut keep: HashMap<(ArrayString<64>, ArrayString<64>), &Edge> = HashMap::with_capacity(self.edges.len());
+        for edge in self.edges.values() {
+            if drop_self_loops && edge.left == edge.right {
+                continue;
+            }
+            let pair: (ArrayString<64>, ArrayString<64>) =
+                if self.directed || edge.left <= edge.right { (edge.left, edge.right) } else { (edge.right, edge.left) };
+            keep.entry(pair)
+                .and_modify(|kept| {
+                    if edge.cost.total_cmp(&kept.cost).then(edge.id.cmp(&kept.id)).is_lt() {
+                        *kept = edge;
+                    }
+                })
+                .or_insert(edge);
+        }
+        let keep: HashSet<ArrayString<64>> = keep.into_values().map(|e| e.id).collect();
+
+        // Remove the others
+        let remove: Vec<(ArrayString<64>, bool)> =
+            self.edges.values().filter(|e| !keep.contains(&e.id)).map(|e| (e.id, e.left == e.right)).collect();
+        for (id, self_loop) in remove {
+            self.edges.remove(&id);
+            self.labels.edges.remove(&id);
+            if self_loop && drop_self_loops {
+                report.self_loops += 1;
+            } else {
+                report.parallel += 1;
+            }
+        }
+        report
+    }
+}
@@ -4,18 +4,19 @@
 //  Created:
 //    16 Jul 2024, 00:54:32
 //  Last edited:
-//    25 Jul 2024, 00:08:56
+//    16 Oct 2026, 18:03:13
 //  Auto updated?
 //    Yes
 //
 //  Description:
-//!   Provides a parser for parsing [`Graph`]s from XML benchmark files.
+//!   Provides a parser for parsing [`Graph`]s from XML benchmark files,
+//!   and a writer for writing them back.
 //
 
 use std::error;
 use std::fmt::{Display, Formatter, Result as FResult};
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, BufWriter, Write as _};
 use std::path::{Path, PathBuf};
 
 use arrayvec::ArrayString;
@@ -24,14 +25,123 @@ use serde::{Deserialize, Serialize};
 use crate::{Edge, Graph, Node};
 
 
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_roundtrip() {
+        let path: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.xml");
+        let g: Graph = parse(path).unwrap();
+        let demands: Vec<XmlDemand> = vec![XmlDemand {
+            id: ArrayString::from("Berlin-Chicago").unwrap(),
+            source: ArrayString::from("Berlin").unwrap(),
+            target: ArrayString::from("Chicago").unwrap(),
+            demand_value: 2.5,
+            admissible_paths: Some(XmlAdmissiblePaths {
+                paths: vec![XmlAdmissiblePath {
+                    id: ArrayString::from("P_0").unwrap(),
+                    links: vec![
+                        ArrayString::from("Amsterdam-Berlin").unwrap(),
+                        ArrayString::from("Amsterdam-Dorchester").unwrap(),
+                        ArrayString::from("Chicago-Dorchester").unwrap(),
+                    ],
+                }],
+            }),
+        }];
+
+        let dir: PathBuf = std::env::temp_dir().join(format!("ksp-graph-test-sndlib-write-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let out: PathBuf = dir.join("cities.xml");
+        write(&out, &g, &demands).unwrap();
+
+        // The graph is the same...
+        let h: Graph = parse(&out).unwrap();
+        assert!(g.diff(&h).is_empty(), "Round-trip changed the graph:\n{}", g.diff(&h));
+        for node in g.nodes.values() {
+            assert_eq!(h.nodes[&node.id].pos, node.pos);
+        }
+        for edge in g.edges_iter() {
+            assert_eq!((h.edges[&edge.id].left, h.edges[&edge.id].right), (edge.left, edge.right));
+        }
+
+        // ...and so are the demands
+        let network: XmlNetwork = parse_network(&out).unwrap();
+        assert!(network.has_routing_costs());
+        assert_eq!(network.demands.demands.len(), 1);
+        let demand: &XmlDemand = &network.demands.demands[0];
+        assert_eq!((demand.id, demand.source, demand.target, demand.demand_value), (demands[0].id, demands[0].source, demands[0].target, 2.5));
+        let paths: &[XmlAdmissiblePath] = &demand.admissible_paths.as_ref().unwrap().paths;
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].id, demands[0].admissible_paths.as_ref().unwrap().paths[0].id);
+        assert_eq!(paths[0].links, demands[0].admissible_paths.as_ref().unwrap().paths[0].links);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_cost_field() {
+        let path: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/capacities.xml");
+        fn costs(g: &Graph) -> Vec<(&str, f64)> {
+            let mut costs: Vec<(&str, f64)> = g.edges.values().map(|e| (e.id.as_str(), e.cost)).collect();
+            costs.sort_by_key(|(id, _)| *id);
+            costs
+        }
+
+        // Routing costs are the default...
+        let g: Graph = parse_with(path, CostField::RoutingCost).unwrap();
+        assert_eq!(costs(&g), vec![("A-B", 1.0), ("A-C", 2.0), ("B-D", 1.0), ("C-D", 2.0)]);
+        assert!(g.diff(&parse(path).unwrap()).is_empty());
+        // ...but capacities come from the pre-installed module, or else the largest additional one...
+        let g: Graph = parse_with(path, CostField::Capacity).unwrap();
+        assert_eq!(costs(&g), vec![("A-B", 10.0), ("A-C", 100.0), ("B-D", 40.0), ("C-D", 100.0)]);
+        // ...and module costs from the cheapest additional module
+        let g: Graph = parse_with(path, CostField::ModuleCost).unwrap();
+        assert_eq!(costs(&g), vec![("A-B", 5.0), ("A-C", 7.0), ("B-D", 3.0), ("C-D", 4.0)]);
+
+        // Files without modules can't be parsed with them
+        let path: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/cities.xml");
+        assert!(matches!(parse_with(path, CostField::Capacity), Err(Error::MissingCost { field: CostField::Capacity, .. })));
+        assert!(matches!(parse_with(path, CostField::ModuleCost), Err(Error::MissingCost { field: CostField::ModuleCost, .. })));
+        assert!(parse_with(path, CostField::RoutingCost).is_ok());
+    }
+
+    #[test]
+    fn test_parse_invalid_cost() {
+        let path: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/nan_costs.xml");
+        let err: Error = parse(path).unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidCost { link, field: CostField::RoutingCost, cost, .. } if link.as_str() == "B-C" && cost.is_nan()),
+            "Unexpected result {err:?}"
+        );
+        assert!(err.to_string().contains("has invalid routing cost 'NaN'"));
+        assert!(matches!(parse_network(path), Err(Error::InvalidCost { .. })));
+        assert!(matches!(parse_with(path, CostField::Capacity), Err(Error::InvalidCost { .. })));
+    }
+}
+
+
+
+
+
 /***** ERRORS *****/
-/// Defines errors originating when parsing SNDLib XML graphs.
+/// Defines errors originating when parsing or writing SNDLib XML graphs.
 #[derive(Debug)]
 pub enum Error {
     /// Failed to open the graph file.
     FileOpen { path: PathBuf, err: std::io::Error },
     /// Failed to parse the graph file as XML.
     FileReadParse { path: PathBuf, err: quick_xml::de::DeError },
+    /// Failed to serialize the network as XML.
+    Serialize { path: PathBuf, err: quick_xml::de::DeError },
+    /// Failed to create the graph file.
+    FileCreate { path: PathBuf, err: std::io::Error },
+    /// Failed to write to the graph file.
+    FileWrite { path: PathBuf, err: std::io::Error },
+    /// A link did not have the field that was chosen as its cost.
+    MissingCost { path: PathBuf, link: ArrayString<64>, field: CostField },
+    /// A link had a cost (or module value) that is NaN or infinite.
+    InvalidCost { path: PathBuf, link: ArrayString<64>, field: CostField, cost: f64 },
 }
 impl Display for Error {
     #[inline]
@@ -40,6 +150,13 @@ impl Display for Error {
         match self {
             FileOpen { path, .. } => write!(f, "Failed to open benchmark file '{}'", path.display()),
             FileReadParse { path, .. } => write!(f, "Failed to read/parse benchmark file '{}' as SNDLib XML", path.display()),
+            Serialize { path, .. } => write!(f, "Failed to serialize network for benchmark file '{}' as SNDLib XML", path.display()),
+            FileCreate { path, .. } => write!(f, "Failed to create benchmark file '{}'", path.display()),
+            FileWrite { path, .. } => write!(f, "Failed to write to benchmark file '{}'", path.display()),
+            MissingCost { path, link, field } => write!(f, "Link '{link}' in benchmark file '{}' does not have a {field}", path.display()),
+            InvalidCost { path, link, field, cost } => {
+                write!(f, "Link '{link}' in benchmark file '{}' has invalid {field} '{cost}' (must be finite)", path.display())
+            },
         }
     }
 }
@@ -50,6 +167,11 @@ impl error::Error for Error {
         match self {
             FileOpen { err, .. } => Some(err),
             FileReadParse { err, .. } => Some(err),
+            Serialize { err, .. } => Some(err),
+            FileCreate { err, .. } => Some(err),
+            FileWrite { err, .. } => Some(err),
+            MissingCost { .. } => None,
+            InvalidCost { .. } => None,
         }
     }
 }
@@ -59,6 +181,33 @@ impl error::Error for Error {
 
 
 /***** AUXILLARY *****/
+/// Chooses which field of an [`XmlLink`] becomes the cost of its [`Edge`] when converting to a [`Graph`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum CostField {
+    /// Use the routing cost, or the (Euclidean) distance between the endpoints if a link doesn't have one.
+    #[default]
+    RoutingCost,
+    /// Use the capacity of the pre-installed module, or that of the largest additional module if a link doesn't have one.
+    ///
+    /// Useful for widest-path experiments.
+    Capacity,
+    /// Use the cost of the cheapest additional module, or that of the pre-installed module if a link can't have any additional ones.
+    ModuleCost,
+}
+impl Display for CostField {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use CostField::*;
+        match self {
+            RoutingCost => write!(f, "routing cost"),
+            Capacity => write!(f, "capacity"),
+            ModuleCost => write!(f, "module cost"),
+        }
+    }
+}
+
+
+
 /// Representation of a toplevel [`Graph`] in the XML files.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct XmlNetwork {
@@ -71,6 +220,127 @@ pub struct XmlNetwork {
     /// Defines any to-be-searched-for links.
     pub demands: XmlDemands,
 }
+impl XmlNetwork {
+    /// Converts a [`Graph`] to a network.
+    ///
+    /// Nodes and links are sorted by identifier, and the costs of edges are written as explicit routing costs.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to convert.
+    /// - `demands`: The [`XmlDemand`]s to include in the network.
+    ///
+    /// # Returns
+    /// A new XmlNetwork with geographical coordinates and no metadata.
+    pub fn from_graph(graph: &Graph, demands: Vec<XmlDemand>) -> Self {
+        let mut nodes: Vec<XmlNode> =
+            graph.nodes.values().map(|n| XmlNode { id: n.id, coordinates: XmlNodeCoords { x: n.pos.0, y: n.pos.1 } }).collect();
+        nodes.sort_by_key(|n| n.id);
+        let mut links: Vec<XmlLink> = graph
+            .edges
+            .values()
+            .map(|e| XmlLink {
+                id: e.id,
+                source: e.left,
+                target: e.right,
+                pre_installed_module: None,
+                routing_cost: Some(e.cost),
+                additional_modules: None,
+            })
+            .collect();
+        links.sort_by_key(|l| l.id);
+        Self {
+            meta: None,
+            network_structure: XmlNetworkStructure {
+                nodes: XmlNodes { coordinates_type: XmlCoordsType::Geographical, nodes },
+                links: XmlLinks { links },
+            },
+            demands: XmlDemands { demands },
+        }
+    }
+
+    /// Checks whether any of the links in this network have a routing cost.
+    ///
+    /// Note that a routing cost of `0.0` counts as having one.
+    ///
+    /// # Returns
+    /// True if at least one [`XmlLink`] has an explicit routing cost, or false if the file carries no cost information.
+    #[inline]
+    pub fn has_routing_costs(&self) -> bool { self.network_structure.links.links.iter().any(|l| l.routing_cost.is_some()) }
+
+    /// Converts this network to a [`Graph`].
+    ///
+    /// Links that do not have an explicit routing cost are given the (Euclidean) distance between their endpoints as cost.
+    ///
+    /// # Returns
+    /// A new [`Graph`] with the nodes and links in this network.
+    ///
+    /// # Panics
+    /// This function panics if a link without an explicit routing cost refers to an unknown node.
+    #[inline]
+    pub fn into_graph(self) -> Graph { self.into_graph_with(CostField::RoutingCost) }
+
+    /// Converts this network to a [`Graph`], choosing which field of the links becomes the cost of the edges.
+    ///
+    /// # Arguments
+    /// - `field`: The [`CostField`] to use as cost. If it's [`CostField::RoutingCost`], this is the same as [`XmlNetwork::into_graph()`].
+    ///
+    /// # Returns
+    /// A new [`Graph`] with the nodes and links in this network.
+    ///
+    /// # Panics
+    /// This function panics if a link without an explicit routing cost refers to an unknown node (for [`CostField::RoutingCost`]), or if a
+    /// link does not have the chosen field at all (for the others). Use [`XmlLink::cost()`] to check the latter beforehand.
+    #[track_caller]
+    pub fn into_graph_with(mut self, field: CostField) -> Graph {
+        // Resolve cost if not given
+        for link in &mut self.network_structure.links.links {
+            if field != CostField::RoutingCost {
+                match link.cost(field) {
+                    Some(cost) => link.routing_cost = Some(cost),
+                    None => panic!("Link '{}' does not have a {field}", link.id),
+                }
+            } else if link.routing_cost.is_none() {
+                // Fetch the two nodes
+                let source: &XmlNode = self
+                    .network_structure
+                    .nodes
+                    .nodes
+                    .iter()
+                    .find(|n| n.id == link.source)
+                    .unwrap_or_else(|| panic!("Encountered unknown source node '{}' in link '{}'", link.source, link.id));
+                let target: &XmlNode = self
+                    .network_structure
+                    .nodes
+                    .nodes
+                    .iter()
+                    .find(|n| n.id == link.target)
+                    .unwrap_or_else(|| panic!("Encountered unknown target node '{}' in link '{}'", link.source, link.id));
+
+                // The cost is their positional difference
+                let dx: f64 = source.coordinates.x - target.coordinates.x;
+                let dy: f64 = source.coordinates.y - target.coordinates.y;
+                link.routing_cost = Some((dx * dx + dy * dy).sqrt());
+            }
+        }
+
+        // Convert it to the standardized Graph.
+        Graph {
+            nodes: self.network_structure.nodes.nodes.into_iter().map(|n| (n.id, Node { id: n.id, pos: (n.coordinates.x, n.coordinates.y) })).collect(),
+            edges: self
+                .network_structure
+                .links
+                .links
+                .into_iter()
+                .map(|l| {
+                    // Write it an edge (cost is given, see above)
+                    (l.id, Edge { id: l.id, left: l.source, right: l.target, cost: l.routing_cost.unwrap() })
+                })
+                .collect(),
+            directed: false,
+            labels: Default::default(),
+        }
+    }
+}
 
 
 
@@ -110,7 +380,7 @@ pub struct XmlNodes {
     #[serde(rename = "@coordinatesType")]
     pub coordinates_type: XmlCoordsType,
     /// The nodes in this graph.
-    #[serde(rename = "$value")]
+    #[serde(rename(serialize = "node", deserialize = "$value"))]
     pub nodes: Vec<XmlNode>,
 }
 
@@ -149,12 +419,12 @@ pub struct XmlNodeCoords {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct XmlLinks {
     /// The links in this graph.
-    #[serde(rename = "$value")]
+    #[serde(rename(serialize = "link", deserialize = "$value"))]
     pub links: Vec<XmlLink>,
 }
 
 /// Representation of an [`Edge`] in the XML files.
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct XmlLink {
     /// The identifier of the link.
     #[serde(rename = "@id")]
@@ -163,9 +433,52 @@ pub struct XmlLink {
     pub source: ArrayString<64>,
     /// The target node.
     pub target: ArrayString<64>,
+    /// If present, the module that is already installed on this link.
+    #[serde(rename = "preInstalledModule", skip_serializing_if = "Option::is_none")]
+    pub pre_installed_module: Option<XmlModule>,
     /// If present, represents the cost it takes traffic to traverse this edge.
     #[serde(rename = "routingCost")]
     pub routing_cost: Option<f64>,
+    /// If present, the modules that may be installed on this link.
+    #[serde(rename = "additionalModules", skip_serializing_if = "Option::is_none")]
+    pub additional_modules: Option<XmlAdditionalModules>,
+}
+impl XmlLink {
+    /// Returns the value of the given field of this link, if it has it.
+    ///
+    /// # Arguments
+    /// - `field`: The [`CostField`] to return. See its variants for which values are used exactly.
+    ///
+    /// # Returns
+    /// The value of the field, or [`None`] if the link doesn't carry it. Note that this is also [`None`] for a missing
+    /// [routing cost](CostField::RoutingCost), even though [`XmlNetwork::into_graph()`] would fall back to the distance between the endpoints.
+    pub fn cost(&self, field: CostField) -> Option<f64> {
+        let modules: &[XmlModule] = self.additional_modules.as_ref().map(|m| m.modules.as_slice()).unwrap_or_default();
+        match field {
+            CostField::RoutingCost => self.routing_cost,
+            CostField::Capacity => {
+                self.pre_installed_module.map(|m| m.capacity).or_else(|| modules.iter().map(|m| m.capacity).max_by(f64::total_cmp))
+            },
+            CostField::ModuleCost => modules.iter().map(|m| m.cost).min_by(f64::total_cmp).or(self.pre_installed_module.map(|m| m.cost)),
+        }
+    }
+}
+
+/// Defines the list of modules that may be installed on a link.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct XmlAdditionalModules {
+    /// The modules in this list.
+    #[serde(rename = "addModule", default)]
+    pub modules: Vec<XmlModule>,
+}
+
+/// Representation of a module (i.e., an amount of capacity) that is or may be installed on a link in the XML files.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct XmlModule {
+    /// The capacity the module adds to the link.
+    pub capacity: f64,
+    /// The cost of installing the module.
+    pub cost:     f64,
 }
 
 
@@ -174,12 +487,12 @@ pub struct XmlLink {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct XmlDemands {
     /// Defines any to-be-searched-for links.
-    #[serde(rename = "$value")]
+    #[serde(rename(serialize = "demand", deserialize = "$value"))]
     pub demands: Vec<XmlDemand>,
 }
 
 /// Representation of a testcase in the XML files.
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct XmlDemand {
     /// The identifier of the demand.
     #[serde(rename = "@id")]
@@ -191,6 +504,28 @@ pub struct XmlDemand {
     /// The target path cost.
     #[serde(rename = "demandValue")]
     pub demand_value: f64,
+    /// If present, a list of precomputed paths that may be used to route this demand.
+    #[serde(rename = "admissiblePaths", skip_serializing_if = "Option::is_none")]
+    pub admissible_paths: Option<XmlAdmissiblePaths>,
+}
+
+/// Defines the list of admissible paths of a demand.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct XmlAdmissiblePaths {
+    /// The paths in this list.
+    #[serde(rename(serialize = "admissiblePath", deserialize = "$value"), default)]
+    pub paths: Vec<XmlAdmissiblePath>,
+}
+
+/// Representation of a path that may be used to route a demand in the XML files.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct XmlAdmissiblePath {
+    /// The identifier of the path.
+    #[serde(rename = "@id")]
+    pub id: ArrayString<64>,
+    /// The identifiers of the [`XmlLink`]s that make up this path, in order from source to target.
+    #[serde(rename = "linkId", default)]
+    pub links: Vec<ArrayString<64>>,
 }
 
 
@@ -198,21 +533,25 @@ pub struct XmlDemand {
 
 
 /***** LIBRARY FUNCTIONS *****/
-/// Parses a new [`Graph`] from the given SNDLib XML graph file.
+/// Parses the raw [`XmlNetwork`] in the given SNDLib XML graph file.
+///
+/// Use this instead of [`parse()`] to inspect the information in the file that isn't carried over to the [`Graph`], e.g., whether links have
+/// [routing costs](XmlNetwork::has_routing_costs()).
 ///
 /// # Arguments
 /// - `path`: The path where the XML file is located.
 ///
 /// # Returns
-/// A new [`Graph`], encoding the parsed graph.
+/// A new [`XmlNetwork`], encoding the file as-is.
 ///
 /// # Errors
-/// This function may error if we failed to read the target file or failed to parse it as (the right kind of) XML.
-pub fn parse(path: impl AsRef<Path>) -> Result<Graph, Error> {
+/// This function may error if we failed to read the target file, failed to parse it as (the right kind of) XML, or if a routing cost or
+/// module value in it is NaN or infinite.
+pub fn parse_network(path: impl AsRef<Path>) -> Result<XmlNetwork, Error> {
     let path: &Path = path.as_ref();
 
     // Open & parse the file
-    let mut bench: XmlNetwork = match File::open(&path) {
+    let network: XmlNetwork = match File::open(path) {
         Ok(handle) => match quick_xml::de::from_reader(BufReader::new(handle)) {
             Ok(bench) => bench,
             Err(err) => return Err(Error::FileReadParse { path: path.into(), err }),
@@ -220,44 +559,106 @@ pub fn parse(path: impl AsRef<Path>) -> Result<Graph, Error> {
         Err(err) => return Err(Error::FileOpen { path: path.into(), err }),
     };
 
-    // Resolve cost if not given
-    for link in &mut bench.network_structure.links.links {
-        if link.routing_cost.is_none() {
-            // Fetch the two nodes
-            let source: &XmlNode = bench
-                .network_structure
-                .nodes
-                .nodes
-                .iter()
-                .find(|n| n.id == link.source)
-                .unwrap_or_else(|| panic!("Encountered unknown source node '{}' in link '{}'", link.source, link.id));
-            let target: &XmlNode = bench
-                .network_structure
-                .nodes
-                .nodes
-                .iter()
-                .find(|n| n.id == link.target)
-                .unwrap_or_else(|| panic!("Encountered unknown target node '{}' in link '{}'", link.source, link.id));
-
-            // The cost is their positional difference
-            let dx: f64 = source.coordinates.x - target.coordinates.x;
-            let dy: f64 = source.coordinates.y - target.coordinates.y;
-            link.routing_cost = Some((dx * dx + dy * dy).sqrt());
+    // Reject any costs that would make paths incomparable
+    for link in &network.network_structure.links.links {
+        let modules: &[XmlModule] = link.additional_modules.as_ref().map(|m| m.modules.as_slice()).unwrap_or_default();
+        let mut values: Vec<(CostField, f64)> = link.routing_cost.map(|cost| (CostField::RoutingCost, cost)).into_iter().collect();
+        for module in link.pre_installed_module.iter().chain(modules) {
+            values.extend([(CostField::Capacity, module.capacity), (CostField::ModuleCost, module.cost)]);
+        }
+        for (field, cost) in values {
+            if !cost.is_finite() {
+                return Err(Error::InvalidCost { path: path.into(), link: link.id, field, cost });
+            }
         }
     }
+    Ok(network)
+}
 
-    // Convert it to the standardized Graph.
-    Ok(Graph {
-        nodes: bench.network_structure.nodes.nodes.into_iter().map(|n| (n.id, Node { id: n.id, pos: (n.coordinates.x, n.coordinates.y) })).collect(),
-        edges: bench
-            .network_structure
-            .links
-            .links
-            .into_iter()
-            .map(|l| {
-                // Write it an edge (cost is given, see above)
-                (l.id, Edge { id: l.id, left: l.source, right: l.target, cost: l.routing_cost.unwrap() })
-            })
-            .collect(),
-    })
+/// Parses a new [`Graph`] from the given SNDLib XML graph file.
+///
+/// # Arguments
+/// - `path`: The path where the XML file is located.
+///
+/// # Returns
+/// A new [`Graph`], encoding the parsed graph. See [`XmlNetwork::into_graph()`] for how missing costs are resolved.
+///
+/// # Errors
+/// This function may error if we failed to read the target file, failed to parse it as (the right kind of) XML, or if a cost in it is NaN or
+/// infinite.
+#[inline]
+pub fn parse(path: impl AsRef<Path>) -> Result<Graph, Error> { parse_network(path).map(XmlNetwork::into_graph) }
+
+/// Parses a new [`Graph`] from the given SNDLib XML graph file, choosing which field of the links becomes the cost of the edges.
+///
+/// # Arguments
+/// - `path`: The path where the XML file is located.
+/// - `field`: The [`CostField`] to use as cost. If it's [`CostField::RoutingCost`], this is the same as [`parse()`].
+///
+/// # Returns
+/// A new [`Graph`], encoding the parsed graph. See [`XmlNetwork::into_graph_with()`] for how the costs are resolved.
+///
+/// # Errors
+/// This function may error if we failed to read the target file, failed to parse it as (the right kind of) XML, if a cost in it is NaN or
+/// infinite, or if a link does not have the chosen field (for anything but [`CostField::RoutingCost`]).
+pub fn parse_with(path: impl AsRef<Path>, field: CostField) -> Result<Graph, Error> {
+    let path: &Path = path.as_ref();
+    let network: XmlNetwork = parse_network(path)?;
+    if field != CostField::RoutingCost {
+        if let Some(link) = network.network_structure.links.links.iter().find(|l| l.cost(field).is_none()) {
+            return Err(Error::MissingCost { path: path.into(), link: link.id, field });
+        }
+    }
+    Ok(network.into_graph_with(field))
+}
+
+
+
+/// Writes an [`XmlNetwork`] to an SNDLib XML file.
+///
+/// # Arguments
+/// - `path`: The path of the XML file to write to. Overwritten if it already exists.
+/// - `network`: The [`XmlNetwork`] to write.
+///
+/// # Errors
+/// This function may error if we failed to serialize the network (e.g., because a routing cost is not finite) or failed to create or write
+/// to the target file.
+pub fn write_network(path: impl AsRef<Path>, network: &XmlNetwork) -> Result<(), Error> {
+    let path: &Path = path.as_ref();
+
+    // Serialize it first, so that we don't leave half a file
+    let mut xml: String = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let mut ser: quick_xml::se::Serializer<String> = match quick_xml::se::Serializer::with_root(&mut xml, Some("network")) {
+        Ok(ser) => ser,
+        Err(err) => return Err(Error::Serialize { path: path.into(), err }),
+    };
+    ser.indent(' ', 1);
+    if let Err(err) = network.serialize(ser) {
+        return Err(Error::Serialize { path: path.into(), err });
+    }
+    xml.push('\n');
+
+    // Then write it
+    let mut handle: BufWriter<File> = match File::create(path) {
+        Ok(handle) => BufWriter::new(handle),
+        Err(err) => return Err(Error::FileCreate { path: path.into(), err }),
+    };
+    handle.write_all(xml.as_bytes()).and_then(|_| handle.flush()).map_err(|err| Error::FileWrite { path: path.into(), err })
+}
+
+/// Writes a [`Graph`] and demands on it to an SNDLib XML file.
+///
+/// See [`XmlNetwork::from_graph()`] for how the graph is converted. Reading the file back with [`parse()`] results in the same graph.
+///
+/// # Arguments
+/// - `path`: The path of the XML file to write to. Overwritten if it already exists.
+/// - `graph`: The [`Graph`] to write.
+/// - `demands`: Any [`XmlDemand`]s to write with it.
+///
+/// # Errors
+/// This function may error if we failed to serialize the network (e.g., because a routing cost is not finite) or failed to create or write
+/// to the target file.
+#[inline]
+pub fn write(path: impl AsRef<Path>, graph: &Graph, demands: &[XmlDemand]) -> Result<(), Error> {
+    write_network(path, &XmlNetwork::from_graph(graph, demands.to_vec()))
 }
@@ -4,7 +4,7 @@
 //  Created:
 //    19 Jul 2024, 00:54:49
 //  Last edited:
-//    19 Jul 2024, 00:55:42
+//    16 Oct 2026, 19:02:49
 //  Auto updated?
 //    Yes
 //
@@ -13,4 +13,7 @@
 //
 
 // Declare the modules
+pub mod output;
 pub mod render;
+#[cfg(test)]
+pub mod utils;
@@ -4,7 +4,7 @@
 //  Created:
 //    16 Jul 2024, 01:44:40
 //  Last edited:
-//    20 Jul 2024, 00:11:15
+//    16 Oct 2026, 18:03:13
 //  Auto updated?
 //    Yes
 //
@@ -12,8 +12,6 @@
 //!   Entrypoint for the `visualize` binary.
 //
 
-use std::borrow::Cow;
-use std::fs::File;
 use std::path::PathBuf;
 
 use clap::Parser;
@@ -21,8 +19,47 @@ use error_trace::trace;
 use humanlog::{DebugMode, HumanLogger};
 use image::{ImageFormat, RgbaImage};
 use ksp_graph::{Graph, GraphFormat};
+use ksp_vis::output::{format_from_path, parse_format, write_image};
 use ksp_vis::render::{render_graph, Options};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use image::ImageReader;
+
+    use super::*;
+
+    #[test]
+    fn test_output_format() {
+        let dir: PathBuf = std::env::temp_dir().join(format!("ksp-vis-test-output-format-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path: PathBuf = dir.join("output");
+
+        // By default, the extension decides (or PNG if there is none)
+        let args: Arguments = Arguments::parse_from(["visualize", "graph.json", "--output", "output.jpg"]);
+        assert_eq!(output_format(&args), ImageFormat::Jpeg);
+
+        // But it can be overridden
+        let args: Arguments = Arguments::parse_from(["visualize", "graph.json", "--output", "output.jpg", "--output-format", "bmp"]);
+        assert_eq!(output_format(&args), ImageFormat::Bmp);
+        assert!(Arguments::try_parse_from(["visualize", "graph.json", "--output-format", "tiff"]).is_err());
+
+        // Also for paths without an extension
+        let spath: &str = path.to_str().unwrap();
+        let args: Arguments = Arguments::parse_from(["visualize", "graph.json", "--output", spath, "--output-format", "png"]);
+        let fmt: ImageFormat = output_format(&args);
+        write_image(&RgbaImage::new(16, 8), &args.output, fmt).unwrap();
+        let reader = ImageReader::open(&path).unwrap().with_guessed_format().unwrap();
+        assert_eq!(reader.format(), Some(ImageFormat::Png));
+        assert_eq!(reader.decode().unwrap().into_rgba8().dimensions(), (16, 8));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+
+
 
 
 /***** ARGUMENTS *****/
@@ -43,12 +80,88 @@ struct Arguments {
         short,
         long,
         help = "If given, parses the given file according to the given format. Otherwise, it is automatically deduced from the given file's \
-                extension. Recognized extensions are: 'json', 'sndlib'"
+                extension. Recognized formats are 'json' (for '.json' files) and 'sndlib_xml' (for '.xml' files)."
     )]
     format: Option<GraphFormat>,
     /// The output file to write the visualization to.
-    #[clap(short, long, default_value = "./output.png", help = "The path to write the graph visualization to.")]
+    #[clap(
+        short,
+        long,
+        default_value = "./output.png",
+        help = "The path to write the graph visualization to. Unless '--output-format' is given, its extension determines the image format, \
+                which is one of '.png', '.jpg', '.jpeg', '.webp' or '.bmp' (others are written as PNG)."
+    )]
     output: PathBuf,
+    /// The format to write the output in.
+    #[clap(
+        long,
+        value_parser = parse_format,
+        help = "If given, writes the output image in the given format. Otherwise, it is automatically deduced from the output path's \
+                extension. Recognized formats are: 'png', 'jpg', 'jpeg', 'webp', 'bmp'"
+    )]
+    output_format: Option<ImageFormat>,
+
+    /// Whether to compute node positions.
+    #[clap(
+        long,
+        help = "If given, computes new positions for all nodes with a force-directed layout instead of using those in the graph file. This is \
+                done automatically if all nodes in the file are at the same position."
+    )]
+    layout: bool,
+    /// The number of iterations for the layout.
+    #[clap(long, default_value = "100", help = "The number of iterations to run the force-directed layout for (see '--layout').")]
+    layout_iterations: usize,
+
+    /// The size of the labels in the visualization.
+    #[clap(long, default_value = "16.0", help = "The size (in pixels) at which to render node- and edge labels.")]
+    font_size: f32,
+    /// A custom font to render labels with.
+    #[clap(long, help = "If given, renders labels with the TTF font at the given path instead of the embedded OpenSans font.")]
+    font: Option<PathBuf>,
+    /// Whether to hide node labels.
+    #[clap(long, help = "If given, does not annotate nodes with their identifiers.")]
+    no_node_labels: bool,
+    /// Whether to hide edge labels.
+    #[clap(long, help = "If given, does not annotate edges with their costs.")]
+    no_edge_labels: bool,
+    /// The precision of edge labels.
+    #[clap(long, default_value = "2", help = "The number of decimals with which to show edge costs.")]
+    edge_label_precision: usize,
+    /// Whether to hide the labels attached to the graph.
+    #[clap(long, help = "If given, does not add the labels attached to nodes and edges in the graph file to their annotations.")]
+    no_graph_labels: bool,
+    /// Whether to draw arrow heads.
+    #[clap(long, help = "If given, draws arrow heads on edges pointing from their left to their right node (i.e., as if the graph is directed).")]
+    arrows: bool,
+    /// The size of the arrow heads.
+    #[clap(long, default_value = "10.0", help = "The size (in pixels) of arrow heads drawn by '--arrows'.")]
+    arrow_size: f64,
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Resolves the format to write the output image as.
+///
+/// # Arguments
+/// - `args`: The [`Arguments`] given by the user.
+///
+/// # Returns
+/// The `--output-format` if given, or else the format deduced from the `--output` path's extension. Falls back to PNG if the extension is
+/// unknown.
+fn output_format(args: &Arguments) -> ImageFormat {
+    if let Some(fmt) = args.output_format {
+        return fmt;
+    }
+    match format_from_path(&args.output) {
+        Some(fmt) => fmt,
+        None => {
+            warn!("Unknown image extension for output '{}', writing as PNG", args.output.display());
+            ImageFormat::Png
+        },
+    }
 }
 
 
@@ -71,21 +184,19 @@ fn main() {
         Some(fmt) => fmt,
         None => {
             debug!("Deducing graph format from '{}'", args.graph.display());
-            let sgraph: Cow<str> = args.graph.to_string_lossy();
-            if sgraph.ends_with(".json") {
-                GraphFormat::Json
-            } else if sgraph.ends_with(".xml") {
-                GraphFormat::SNDLibXml
-            } else {
-                error!("Unknown graph format extension{}", if let Some(ext) = args.graph.extension() { format!(" {ext:?}") } else { String::new() });
-                std::process::exit(1);
+            match GraphFormat::from_path(&args.graph) {
+                Some(fmt) => fmt,
+                None => {
+                    error!("Unknown graph format extension{}", if let Some(ext) = args.graph.extension() { format!(" {ext:?}") } else { String::new() });
+                    std::process::exit(1);
+                },
             }
         },
     };
 
     // Load the graph we're told to load
-    debug!("Loading graph file '{}' as {:?}...", args.graph.display(), fmt);
-    let g: Graph = match fmt {
+    debug!("Loading graph file '{}' as {}...", args.graph.display(), fmt);
+    let mut g: Graph = match fmt {
         GraphFormat::Json => match ksp_graph::json::parse(&args.graph) {
             Ok(g) => g,
             Err(err) => {
@@ -102,9 +213,35 @@ fn main() {
         },
     };
 
+    // Give the nodes positions if they don't have any
+    if args.layout || g.has_degenerate_layout() {
+        if !args.layout {
+            info!("All nodes in graph '{}' are at the same position; computing a layout", args.graph.display());
+        }
+        debug!("Computing layout ({} iterations)...", args.layout_iterations);
+        g.auto_layout(args.layout_iterations);
+    }
+
     // Render
     debug!("Rendering graph...");
-    let img: RgbaImage = render_graph(&g, Options::default());
+    let opts: Options = Options {
+        font_size: args.font_size,
+        font_path: args.font.clone(),
+        show_node_labels: !args.no_node_labels,
+        show_edge_labels: !args.no_edge_labels,
+        edge_label_precision: args.edge_label_precision,
+        show_graph_labels: !args.no_graph_labels,
+        draw_arrows: args.arrows,
+        arrow_size: args.arrow_size,
+        ..Default::default()
+    };
+    let img: RgbaImage = match render_graph(&g, opts) {
+        Ok(img) => img,
+        Err(err) => {
+            error!("{}", trace!(("Failed to render graph '{}'", args.graph.display()), err));
+            std::process::exit(1);
+        },
+    };
     let mut flipped: RgbaImage = img.clone();
     for y in 0..img.height() {
         for x in 0..img.width() {
@@ -113,18 +250,11 @@ fn main() {
     }
 
     // Write the image
-    debug!("Writing rendered image to '{}'...", args.output.display());
-    match File::create(&args.output) {
-        Ok(mut handle) => {
-            if let Err(err) = flipped.write_to(&mut handle, ImageFormat::Png) {
-                error!("{}", trace!(("Failed to write to output image '{}'", args.output.display()), err));
-                std::process::exit(1);
-            }
-        },
-        Err(err) => {
-            error!("{}", trace!(("Failed to create output image '{}'", args.output.display()), err));
-            std::process::exit(1);
-        },
+    let out_fmt: ImageFormat = output_format(&args);
+    debug!("Writing rendered image to '{}' as {:?}...", args.output.display(), out_fmt);
+    if let Err(err) = write_image(&flipped, &args.output, out_fmt) {
+        error!("{}", trace!(("Failed to write output image"), err));
+        std::process::exit(1);
     }
 
     // Done!
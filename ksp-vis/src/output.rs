@@ -0,0 +1,163 @@
+//  OUTPUT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:41:52
+//  Last edited:
+//    16 Oct 2026, 15:42:06
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements writing rendered images to disk in various formats.
+//
+
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, ImageFormat, RgbaImage};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use image::ImageReader;
+
+    use super::*;
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(format_from_path("./output.png"), Some(ImageFormat::Png));
+        assert_eq!(format_from_path("./output.jpg"), Some(ImageFormat::Jpeg));
+        assert_eq!(format_from_path("./output.JPEG"), Some(ImageFormat::Jpeg));
+        assert_eq!(format_from_path("./output.webp"), Some(ImageFormat::WebP));
+        assert_eq!(format_from_path("./output.bmp"), Some(ImageFormat::Bmp));
+        assert_eq!(format_from_path("./output.tiff"), None);
+        assert_eq!(format_from_path("./output"), None);
+
+        assert_eq!(parse_format("png"), Ok(ImageFormat::Png));
+        assert_eq!(parse_format("JPG"), Ok(ImageFormat::Jpeg));
+        assert!(parse_format("tiff").is_err());
+    }
+
+    #[test]
+    fn test_write_image() {
+        let mut img: RgbaImage = RgbaImage::new(16, 8);
+        img.fill(255);
+        img[(4, 4)] = image::Rgba([255, 0, 0, 255]);
+
+        let dir: PathBuf = std::env::temp_dir().join(format!("ksp-vis-test-write-image-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for ext in ["png", "jpg", "jpeg", "webp", "bmp"] {
+            let path: PathBuf = dir.join(format!("output.{ext}"));
+            let fmt: ImageFormat = format_from_path(&path).unwrap();
+            write_image(&img, &path, fmt).unwrap();
+
+            // Read it back, deducing the format from its contents
+            let reader = ImageReader::open(&path).unwrap().with_guessed_format().unwrap();
+            assert_eq!(reader.format(), Some(fmt), "Wrong format for '.{ext}'");
+            assert_eq!(reader.decode().unwrap().into_rgba8().dimensions(), (16, 8));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from [`write_image()`].
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to create the output file.
+    FileCreate { path: PathBuf, err: std::io::Error },
+    /// Failed to encode the image in the given format.
+    Encode { path: PathBuf, fmt: ImageFormat, err: image::ImageError },
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            FileCreate { path, .. } => write!(f, "Failed to create output image '{}'", path.display()),
+            Encode { path, fmt, .. } => write!(f, "Failed to write to output image '{}' as {:?}", path.display(), fmt),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            FileCreate { err, .. } => Some(err),
+            Encode { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// The formats that rendered images may be written as.
+pub const SUPPORTED_FORMATS: &[ImageFormat] = &[ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::WebP, ImageFormat::Bmp];
+
+/// Deduces the format to write an image as from its path's extension.
+///
+/// # Arguments
+/// - `path`: The path to deduce the format of.
+///
+/// # Returns
+/// The [`ImageFormat`] matching the extension (i.e., `.png`, `.jpg`, `.jpeg`, `.webp` or `.bmp`), or [`None`] if the path has no extension or
+/// it isn't one of the [`SUPPORTED_FORMATS`].
+#[inline]
+pub fn format_from_path(path: impl AsRef<Path>) -> Option<ImageFormat> {
+    path.as_ref().extension().and_then(ImageFormat::from_extension).filter(|fmt| SUPPORTED_FORMATS.contains(fmt))
+}
+
+/// Parses the name of an image format, e.g., as given on the command line.
+///
+/// # Arguments
+/// - `name`: The name of the format, which is any of the extensions recognized by [`format_from_path()`] (without the dot).
+///
+/// # Returns
+/// The [`ImageFormat`] with that name.
+///
+/// # Errors
+/// This function errors with a human-readable message if `name` is not one of the [`SUPPORTED_FORMATS`].
+pub fn parse_format(name: &str) -> Result<ImageFormat, String> {
+    match ImageFormat::from_extension(name).filter(|fmt| SUPPORTED_FORMATS.contains(fmt)) {
+        Some(fmt) => Ok(fmt),
+        None => Err(format!("Unknown image format '{name}' (expected one of 'png', 'jpg', 'jpeg', 'webp' or 'bmp')")),
+    }
+}
+
+/// Writes a rendered image to disk.
+///
+/// # Arguments
+/// - `img`: The [`RgbaImage`] to write.
+/// - `path`: The path of the file to write to.
+/// - `fmt`: The [`ImageFormat`] to write the image as. Formats without an alpha channel (i.e., JPEG) have it dropped.
+///
+/// # Errors
+/// This function errors if we failed to create the file or failed to encode the image in the given format.
+pub fn write_image(img: &RgbaImage, path: impl AsRef<Path>, fmt: ImageFormat) -> Result<(), Error> {
+    let path: &Path = path.as_ref();
+    let mut handle: BufWriter<File> = match File::create(path) {
+        Ok(handle) => BufWriter::new(handle),
+        Err(err) => return Err(Error::FileCreate { path: path.into(), err }),
+    };
+
+    // JPEG does not do transparency, so convert first
+    let res: Result<(), image::ImageError> = if fmt == ImageFormat::Jpeg {
+        DynamicImage::ImageRgba8(img.clone()).into_rgb8().write_to(&mut handle, fmt)
+    } else {
+        img.write_to(&mut handle, fmt)
+    };
+    res.map_err(|err| Error::Encode { path: path.into(), fmt, err })
+}
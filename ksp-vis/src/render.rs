@@ -4,7 +4,7 @@
 //  Created:
 //    19 Jul 2024, 00:55:15
 //  Last edited:
-//    25 Jul 2024, 00:09:55
+//    16 Oct 2026, 19:02:49
 //  Auto updated?
 //    Yes
 //
@@ -12,23 +12,314 @@
 //!   Implements the actual renderer to write a [`Graph`] to an image.
 //
 
-use std::cmp::{max, min};
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::path::PathBuf;
 
 use image::{GenericImageView, Pixel, Rgb, Rgba, RgbaImage};
-use ksp_graph::Graph;
+use ksp_graph::{Edge, Graph};
 use lazy_static::lazy_static;
 use rusttype::{point, Font, PositionedGlyph, Scale, VMetrics};
 
 
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use arrayvec::ArrayString;
+    use ksp_graph::{Edge, Node};
+
+    use super::*;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_render_text_trimmed() {
+        let scale: Scale = Scale::uniform(16.0);
+        let v_metrics: VMetrics = FONT.v_metrics(scale);
+        for label in ["Amsterdam", "Chicago", "xxx", "42.00"] {
+            // Compute the vertical extent of the glyphs themselves
+            let (min_y, max_y): (i32, i32) = FONT
+                .layout(label, scale, point(0.0, v_metrics.ascent))
+                .filter_map(|g| g.pixel_bounding_box())
+                .fold((i32::MAX, i32::MIN), |(min_y, max_y), bb| (min_y.min(bb.min.y), max_y.max(bb.max.y)));
+
+            // The text should be trimmed to that, not to the font's full line height
+            let text: RgbaImage = render_text(label, &FONT, scale, None);
+            assert!(text.height() <= (max_y - min_y) as u32, "Label '{label}' not trimmed ({} > {})", text.height(), max_y - min_y);
+            assert!(text.height() + 2 >= (max_y - min_y) as u32, "Label '{label}' trimmed too much ({} < {})", text.height(), max_y - min_y);
+            assert!(text.rows().next().unwrap().any(|p| p.0[3] > 0));
+            assert!(text.rows().next_back().unwrap().any(|p| p.0[3] > 0));
+        }
+    }
+
+    #[test]
+    fn test_render_text_whitespace() {
+        let scale: Scale = Scale::uniform(16.0);
+        for label in ["", " ", "   "] {
+            assert_eq!(render_text(label, &FONT, scale, Some(Rgb([255, 255, 255]))).dimensions(), (0, 0), "Label '{label}' isn't empty");
+        }
+
+        // Surrounding whitespace isn't rendered
+        // NOTE: Up to a pixel of difference in width, as the glyphs may land on another subpixel offset
+        let text: RgbaImage = render_text("Amsterdam", &FONT, scale, None);
+        for label in [" Amsterdam", "Amsterdam ", "  Amsterdam  "] {
+            let padded: RgbaImage = render_text(label, &FONT, scale, None);
+            assert!(padded.width().abs_diff(text.width()) <= 1, "Label '{label}' not trimmed ({} != {})", padded.width(), text.width());
+            assert_eq!(padded.height(), text.height(), "Label '{label}' not trimmed");
+        }
+
+        // Drawing them is fine too
+        let mut img: RgbaImage = RgbaImage::new(100, 50);
+        draw_label(&mut img, (50, 25), "", &FONT, scale, None, true);
+        draw_label(&mut img, (50, 25), " ", &FONT, scale, Some(Rgb([255, 255, 255])), false);
+    }
+
+    #[test]
+    fn test_draw_near_edges() {
+        let mut img: RgbaImage = RgbaImage::new(100, 50);
+        img.fill(255);
+        for pos in [(0, 0), (99, 0), (0, 49), (99, 49), (2, 3), (250, 250)] {
+            draw_point(&mut img, pos);
+            draw_line(&mut img, pos, (50, 25));
+            draw_line(&mut img, pos, (pos.0, 25));
+            draw_label(&mut img, pos, "Amsterdam", &FONT, Scale::uniform(16.0), None, true);
+            draw_label(&mut img, pos, "42.00", &FONT, Scale::uniform(16.0), Some(Rgb([255, 255, 255])), false);
+        }
+        assert_eq!(logic_to_pixels((1.0, 1.0), ((0.0, 0.0), (1.0, 1.0)), (100, 50)), (99, 49));
+        assert_eq!(logic_to_pixels((-1.0, 0.0), ((0.0, 0.0), (1.0, 1.0)), (100, 50)), (0, 0));
+    }
+
+    #[test]
+    fn test_render_graph_degenerate() {
+        // An empty graph yields an empty image
+        let mut g: Graph = Graph { nodes: HashMap::default(), edges: HashMap::default(), directed: false, labels: Default::default() };
+        let img: RgbaImage = render_graph(&g, Options::default()).unwrap();
+        assert_eq!(img.dimensions(), (800, 600));
+        assert!(img.pixels().all(|p| p.0 == [255, 255, 255, 255]));
+
+        // A graph with all nodes in the same place has them in the centre
+        for id in ["Amsterdam", "Berlin"] {
+            let id: ArrayString<64> = ArrayString::from(id).unwrap();
+            g.nodes.insert(id, Node { id, pos: (4.0, 2.0) });
+        }
+        let id: ArrayString<64> = ArrayString::from("Amsterdam-Berlin").unwrap();
+        g.edges.insert(id, Edge { id, left: ArrayString::from("Amsterdam").unwrap(), right: ArrayString::from("Berlin").unwrap(), cost: 1.0 });
+        let img: RgbaImage = render_graph(&g, Options::default()).unwrap();
+        assert_eq!(img[(400, 300)], Rgba([255, 0, 0, 255]));
+        assert_eq!(img[(10, 10)], Rgba([255, 255, 255, 255]));
+
+        // Similarly, if they're on a line, that's centred too
+        g.nodes.get_mut("Berlin").unwrap().pos = (6.0, 2.0);
+        let img: RgbaImage = render_graph(&g, Options::default()).unwrap();
+        assert_eq!(img[(67, 300)], Rgba([255, 0, 0, 255]));
+        assert_eq!(img[(733, 300)], Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_render_graph_labels() {
+        let g: Graph = load_graph("cities");
+        let count = |img: &RgbaImage| img.pixels().filter(|p| p.0 != [255, 255, 255, 255]).count();
+        let full: RgbaImage = render_graph(&g, Options::default()).unwrap();
+        let no_edges: RgbaImage = render_graph(&g, Options { show_edge_labels: false, ..Default::default() }).unwrap();
+        let no_nodes: RgbaImage = render_graph(&g, Options { show_node_labels: false, ..Default::default() }).unwrap();
+        let none: RgbaImage = render_graph(&g, Options { show_node_labels: false, show_edge_labels: false, ..Default::default() }).unwrap();
+        assert!(count(&no_edges) < count(&full));
+        assert!(count(&no_nodes) < count(&full));
+        assert!(count(&none) < count(&no_edges));
+        assert!(count(&none) < count(&no_nodes));
+
+        // Longer labels take up more space
+        let precise: RgbaImage = render_graph(&g, Options { show_node_labels: false, edge_label_precision: 6, ..Default::default() }).unwrap();
+        assert!(count(&no_nodes) < count(&precise));
+    }
+
+    #[test]
+    fn test_render_graph_graph_labels() {
+        let mut g: Graph = load_graph("cities");
+        let count = |img: &RgbaImage| img.pixels().filter(|p| p.0 != [255, 255, 255, 255]).count();
+        let plain: RgbaImage = render_graph(&g, Options::default()).unwrap();
+
+        // Labels attached by an earlier step are rendered...
+        ksp::centrality::label_betweenness(&mut g);
+        let labelled: RgbaImage = render_graph(&g, Options::default()).unwrap();
+        assert!(count(&plain) < count(&labelled));
+
+        // ...unless told otherwise
+        let hidden: RgbaImage = render_graph(&g, Options { show_graph_labels: false, ..Default::default() }).unwrap();
+        assert_eq!(hidden, plain);
+    }
+
+    #[test]
+    fn test_render_graph_parallel() {
+        // Counts the number of separate red segments in a column of the image
+        let segments = |img: &RgbaImage, x: u32| {
+            let mut n: usize = 0;
+            let mut prev: bool = false;
+            for y in 0..img.height() {
+                let red: bool = img[(x, y)] == Rgba([255, 0, 0, 255]);
+                if red && !prev {
+                    n += 1;
+                }
+                prev = red;
+            }
+            n
+        };
+
+        // Build a graph with one edge first
+        let mut g: Graph = Graph { nodes: HashMap::default(), edges: HashMap::default(), directed: false, labels: Default::default() };
+        for (id, pos) in [("Amsterdam", (0.0, 0.0)), ("Berlin", (10.0, 0.0))] {
+            let id: ArrayString<64> = ArrayString::from(id).unwrap();
+            g.nodes.insert(id, Node { id, pos });
+        }
+        let id: ArrayString<64> = ArrayString::from("Amsterdam-Berlin").unwrap();
+        g.edges.insert(id, Edge { id, left: ArrayString::from("Amsterdam").unwrap(), right: ArrayString::from("Berlin").unwrap(), cost: 1.0 });
+        let img: RgbaImage = render_graph(&g, Options { show_node_labels: false, ..Default::default() }).unwrap();
+        assert_eq!(segments(&img, 200), 1);
+
+        // Then add a parallel one (in the other direction, for good measure)
+        let id: ArrayString<64> = ArrayString::from("Berlin-Amsterdam").unwrap();
+        g.edges.insert(id, Edge { id, left: ArrayString::from("Berlin").unwrap(), right: ArrayString::from("Amsterdam").unwrap(), cost: 2.0 });
+        let img: RgbaImage = render_graph(&g, Options { show_node_labels: false, ..Default::default() }).unwrap();
+        assert_eq!(segments(&img, 200), 2);
+        assert_eq!(segments(&img, 600), 2);
+    }
+
+    #[test]
+    fn test_render_graph_arrows() {
+        // Counts the red pixels just before the point at the end of a horizontal line, excluding the line itself
+        let count = |img: &RgbaImage, end: (u32, u32)| {
+            let mut n: usize = 0;
+            for y in end.1 - 6..=end.1 + 6 {
+                for x in end.0 - 20..end.0 - 5 {
+                    if y.abs_diff(end.1) > 1 && img[(x, y)] == Rgba([255, 0, 0, 255]) {
+                        n += 1;
+                    }
+                }
+            }
+            n
+        };
+
+        let mut g: Graph = Graph { nodes: HashMap::default(), edges: HashMap::default(), directed: false, labels: Default::default() };
+        for (id, pos) in [("Amsterdam", (0.0, 0.0)), ("Berlin", (10.0, 0.0))] {
+            let id: ArrayString<64> = ArrayString::from(id).unwrap();
+            g.nodes.insert(id, Node { id, pos });
+        }
+        let id: ArrayString<64> = ArrayString::from("Amsterdam-Berlin").unwrap();
+        g.edges.insert(id, Edge { id, left: ArrayString::from("Amsterdam").unwrap(), right: ArrayString::from("Berlin").unwrap(), cost: 1.0 });
+        let opts: Options = Options { show_node_labels: false, show_edge_labels: false, ..Default::default() };
+
+        // Undirected rendering has no arrow heads...
+        let img: RgbaImage = render_graph(&g, opts.clone()).unwrap();
+        assert_eq!(count(&img, (733, 300)), 0);
+
+        // ...but if we ask, we get one at the right node only
+        let img: RgbaImage = render_graph(&g, Options { draw_arrows: true, ..opts.clone() }).unwrap();
+        assert!(count(&img, (733, 300)) > 0);
+        let mut flipped: RgbaImage = img.clone();
+        image::imageops::flip_horizontal_in_place(&mut flipped);
+        assert_eq!(count(&flipped, (799 - 67, 300)), 0);
+
+        // Bigger arrows take more space
+        let bigger: RgbaImage = render_graph(&g, Options { draw_arrows: true, arrow_size: 14.0, ..opts }).unwrap();
+        assert!(count(&img, (733, 300)) < count(&bigger, (733, 300)));
+    }
+
+    #[test]
+    fn test_render_graph_tiled() {
+        let g: Graph = load_graph("cities");
+        let opts: Options = Options { dims: (2000, 1500), ..Default::default() };
+        let full: RgbaImage = render_graph(&g, opts.clone()).unwrap();
+        for tile in [(500, 500), (800, 600), (2000, 1500), (3000, 3000)] {
+            let tiles: Vec<(u32, u32, RgbaImage)> = render_graph_tiled(&g, opts.clone(), tile).unwrap();
+            assert_eq!(tiles.len() as u32, 2000u32.div_ceil(tile.0) * 1500u32.div_ceil(tile.1));
+
+            // Stitch them back together
+            let mut stitched: RgbaImage = RgbaImage::new(2000, 1500);
+            let mut area: u64 = 0;
+            for (x, y, img) in &tiles {
+                assert!(img.width() <= tile.0 && img.height() <= tile.1);
+                image::imageops::replace(&mut stitched, img, *x as i64, *y as i64);
+                area += img.width() as u64 * img.height() as u64;
+            }
+            assert_eq!(area, 2000 * 1500);
+            assert_eq!(stitched, full);
+        }
+    }
+
+    #[test]
+    fn test_render_graph_font_size() {
+        let g: Graph = load_graph("cities");
+        let small: RgbaImage = render_graph(&g, Options { font_size: 12.0, ..Default::default() }).unwrap();
+        let large: RgbaImage = render_graph(&g, Options { font_size: 24.0, ..Default::default() }).unwrap();
+        assert_eq!(small.dimensions(), large.dimensions());
+        // Larger labels cover more of the image
+        let count = |img: &RgbaImage| img.pixels().filter(|p| p.0 != [255, 255, 255, 255]).count();
+        assert!(count(&small) < count(&large));
+    }
+
+    #[test]
+    fn test_render_graph_font_path() {
+        let g: Graph = load_graph("cities");
+        let font_path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets").join("OpenSans-Regular.ttf");
+        assert_eq!(render_graph(&g, Options { font_path: Some(font_path), ..Default::default() }).unwrap(), render_graph(&g, Options::default()).unwrap());
+        assert!(matches!(
+            render_graph(&g, Options { font_path: Some("./does-not-exist.ttf".into()), ..Default::default() }),
+            Err(Error::FontRead { .. })
+        ));
+    }
+}
+
+
+
+
+
 /***** CONSTANTS *****/
 /// The embedded TTF file.
 const FONT_RAW: &[u8] = include_bytes!("../assets/OpenSans-Regular.ttf");
 
+/// The distance (in pixels) between parallel edges.
+const PARALLEL_EDGE_SPACING: f64 = 8.0;
+
 lazy_static! {
-    /// A parsed variation of the [`FONT_RAW`] font used for [`draw_label()`].
+    /// A parsed variation of the [`FONT_RAW`] font used for [`draw_label()`] if the user doesn't give any.
     static ref FONT: Font<'static> = Font::try_from_bytes(FONT_RAW).unwrap_or_else(|| panic!("Failed to construct font"));
-    /// The size at which we render text.
-    static ref FONT_SIZE: Scale = Scale::uniform(16.0);
+}
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from [`render_graph()`].
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to read the given font file.
+    FontRead { path: PathBuf, err: std::io::Error },
+    /// The given font file was not a valid TTF (or OTF) font.
+    FontParse { path: PathBuf },
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        use Error::*;
+        match self {
+            FontRead { path, .. } => write!(f, "Failed to read font file '{}'", path.display()),
+            FontParse { path } => write!(f, "Failed to parse font file '{}' as a TTF font", path.display()),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            FontRead { err, .. } => Some(err),
+            FontParse { .. } => None,
+        }
+    }
 }
 
 
@@ -36,6 +327,9 @@ lazy_static! {
 
 
 /***** HELPER FUNCTIONS *****/
+/// A pixel rectangle, given as its top-left and bottom-right corners.
+type BoundingBox = ((u32, u32), (u32, u32));
+
 /// Scales a given pair of coordinates to pixels.
 ///
 /// # Arguments
@@ -44,14 +338,35 @@ lazy_static! {
 /// - `dims`: The pixel dimensions of the image.
 ///
 /// # Returns
-/// A new pair of a (width, height) describing the pixel equivalent.
+/// A new pair of a (width, height) describing the pixel equivalent. Always lies within `dims`.
 fn logic_to_pixels(pos: (f64, f64), boundaries: ((f64, f64), (f64, f64)), dims: (u32, u32)) -> (u32, u32) {
     // Scale the positions to ratios over the world
     let pos: (f64, f64) =
         ((pos.0 - boundaries.0.0) / (boundaries.1.0 - boundaries.0.0), (pos.1 - boundaries.0.1) / (boundaries.1.1 - boundaries.0.1));
 
-    // Then discretize
-    (((pos.0 * (dims.0 as f64)) + 0.5) as u32, ((pos.1 * (dims.1 as f64)) + 0.5) as u32)
+    // Then discretize (clamping to the image, as positions on the upper boundary would otherwise fall just outside it)
+    // NOTE: Casting floats to integers saturates, so negative positions end up as 0
+    (
+        (((pos.0 * (dims.0 as f64)) + 0.5) as u32).min(dims.0.saturating_sub(1)),
+        (((pos.1 * (dims.1 as f64)) + 0.5) as u32).min(dims.1.saturating_sub(1)),
+    )
+}
+
+/// Moves a pair of pixel coordinates by some amount, clamping it to the image.
+///
+/// # Arguments
+/// - `pos`: The coordinates to move.
+/// - `delta`: The (fractional) amount to move the coordinates by.
+/// - `dims`: The pixel dimensions of the image.
+///
+/// # Returns
+/// The moved coordinates.
+fn offset_pixels(pos: (u32, u32), delta: (f64, f64), dims: (u32, u32)) -> (u32, u32) {
+    // NOTE: Casting floats to integers saturates, so negative positions end up as 0
+    (
+        ((pos.0 as f64 + delta.0).round() as u32).min(dims.0.saturating_sub(1)),
+        ((pos.1 as f64 + delta.1).round() as u32).min(dims.1.saturating_sub(1)),
+    )
 }
 
 /// Draws a line between two coordinates on the image.
@@ -67,8 +382,10 @@ fn draw_line(img: &mut RgbaImage, pos1: (u32, u32), pos2: (u32, u32)) {
     // Ensure the line isn't vertical
     if pos1.0 == pos2.0 {
         // It is; simply draw down
-        for y in std::cmp::min(pos1.1, pos2.1)..std::cmp::max(pos1.1, pos2.1) {
-            img[(pos1.0, y)] = Rgba([255, 0, 0, 255]);
+        if pos1.0 < img.width() {
+            for y in std::cmp::min(pos1.1, pos2.1)..std::cmp::min(std::cmp::max(pos1.1, pos2.1), img.height()) {
+                img[(pos1.0, y)] = Rgba([255, 0, 0, 255]);
+            }
         }
         return;
     }
@@ -79,9 +396,14 @@ fn draw_line(img: &mut RgbaImage, pos1: (u32, u32), pos2: (u32, u32)) {
     let (a, b, c): (f64, f64, f64) = (-a, 1.0, -b);
     let ab2: f64 = (a * a + b * b).sqrt();
 
-    // Create a bounding box around the positions
-    let bb: ((u32, u32), (u32, u32)) =
-        ((std::cmp::min(pos1.0, pos2.0), std::cmp::min(pos1.1, pos2.1)), (std::cmp::max(pos1.0, pos2.0), std::cmp::max(pos1.1, pos2.1)));
+    // Create a bounding box around the positions (that lies within the image)
+    let bb: BoundingBox = (
+        (std::cmp::min(pos1.0, pos2.0), std::cmp::min(pos1.1, pos2.1)),
+        (
+            std::cmp::min(std::cmp::max(pos1.0, pos2.0), img.width().saturating_sub(1)),
+            std::cmp::min(std::cmp::max(pos1.1, pos2.1), img.height().saturating_sub(1)),
+        ),
+    );
 
     // Now for all the pixels in the bounding box, colour those within the line
     for y in bb.0.1..=bb.1.1 {
@@ -96,15 +418,54 @@ fn draw_line(img: &mut RgbaImage, pos1: (u32, u32), pos2: (u32, u32)) {
     }
 }
 
+/// Draws an arrow head at the end of a line on the image.
+///
+/// The tip of the arrow is placed just outside of the point drawn by [`draw_point()`] at the end of the line.
+///
+/// # Arguments
+/// - `img`: The [`RgbaImage`] to draw to.
+/// - `from`: The coordinate where the line starts.
+/// - `to`: The coordinate where the line ends, i.e., where the arrow points to.
+/// - `size`: The length (and width) of the arrow head, in pixels.
+fn draw_arrow_head(img: &mut RgbaImage, from: (u32, u32), to: (u32, u32), size: f64) {
+    // Find the direction of the line
+    let (dx, dy): (f64, f64) = (to.0 as f64 - from.0 as f64, to.1 as f64 - from.1 as f64);
+    let len: f64 = (dx * dx + dy * dy).sqrt();
+    if len == 0.0 {
+        return;
+    }
+    let (dx, dy): (f64, f64) = (dx / len, dy / len);
+
+    // Compute the corners of the triangle
+    let tip: (f64, f64) = (to.0 as f64 - dx * 5.0, to.1 as f64 - dy * 5.0);
+    let base: (f64, f64) = (tip.0 - dx * size, tip.1 - dy * size);
+    let corners: [(f64, f64); 3] = [tip, (base.0 - dy * size / 2.0, base.1 + dx * size / 2.0), (base.0 + dy * size / 2.0, base.1 - dx * size / 2.0)];
+
+    // Colour all pixels within it (as far as it's on the image)
+    let side = |a: (f64, f64), b: (f64, f64), p: (f64, f64)| (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+    let (min_x, max_x): (f64, f64) = corners.iter().fold((f64::INFINITY, -f64::INFINITY), |(min, max), c| (min.min(c.0), max.max(c.0)));
+    let (min_y, max_y): (f64, f64) = corners.iter().fold((f64::INFINITY, -f64::INFINITY), |(min, max), c| (min.min(c.1), max.max(c.1)));
+    // NOTE: Casting floats to integers saturates, so negative positions end up as 0
+    for y in (min_y.floor() as u32)..std::cmp::min(max_y.ceil() as u32 + 1, img.height()) {
+        for x in (min_x.floor() as u32)..std::cmp::min(max_x.ceil() as u32 + 1, img.width()) {
+            let p: (f64, f64) = (x as f64, y as f64);
+            let (s1, s2, s3): (f64, f64, f64) = (side(corners[0], corners[1], p), side(corners[1], corners[2], p), side(corners[2], corners[0], p));
+            if (s1 >= 0.0 && s2 >= 0.0 && s3 >= 0.0) || (s1 <= 0.0 && s2 <= 0.0 && s3 <= 0.0) {
+                img[(x, y)] = Rgba([255, 0, 0, 255]);
+            }
+        }
+    }
+}
+
 /// Draws a point at a coordinate on the image.
 ///
 /// # Arguments
 /// - `img`: The [`RgbaImage`] to draw to.
 /// - `pos`: The coordinate to draw the point on.
 fn draw_point(img: &mut RgbaImage, pos: (u32, u32)) {
-    // Draw in a circle on the image
-    for y in pos.1 - 5..pos.1 + 5 {
-        for x in pos.0 - 5..pos.0 + 5 {
+    // Draw in a circle on the image (as far as it's on the image)
+    for y in pos.1.saturating_sub(5)..std::cmp::min(pos.1 + 5, img.height()) {
+        for x in pos.0.saturating_sub(5)..std::cmp::min(pos.0 + 5, img.width()) {
             let dx: f64 = pos.0 as f64 - x as f64;
             let dy: f64 = pos.1 as f64 - y as f64;
             let r: f64 = (dx * dx + dy * dy).sqrt();
@@ -115,122 +476,143 @@ fn draw_point(img: &mut RgbaImage, pos: (u32, u32)) {
     }
 }
 
-/// Draws a label next to a point on the image.
-///
-/// Attempts to do some clever placing if at all possible.
+/// Renders a label to a separate image, trimmed to the rows that actually contain text.
 ///
 /// Note that the main rendering algorithm of text is taken from:
 /// <https://gitlab.redox-os.org/redox-os/rusttype/-/blob/master/dev/examples/image.rs?ref_type=heads>
 ///
 /// # Arguments
-/// - `img`: The [`RgbaImage`] to draw to.
-/// - `pos`: The coordinate to draw the point on.
 /// - `label`: The label to write.
-/// - `bg`: If given, gives the labels a static background colour.
-/// - `clever_placement`: If true, then it will attempt to find a best place to display the label _around_ the chosen position. Else, will just place it over the given pos.
-fn draw_label(img: &mut RgbaImage, pos: (u32, u32), label: &str, bg: Option<Rgb<u8>>, clever_placement: bool) {
-    // Render the text to a smaller image
-    let text: RgbaImage = {
-        // Find out what the vertical properties are of this font
-        let v_metrics: VMetrics = FONT.v_metrics(*FONT_SIZE);
-
-        // Layout the glyphs
-        let glyphs: Vec<PositionedGlyph<'static>> = FONT.layout(label, *FONT_SIZE, point(0.0, v_metrics.ascent)).collect();
-
-        // Work out the total layout size
-        let (glyphs_width, x_offset): (u32, i32) = {
-            let min_x = glyphs.first().map(|g| g.pixel_bounding_box().unwrap().min.x).unwrap();
-            let max_x = glyphs.last().map(|g| g.pixel_bounding_box().unwrap().max.x).unwrap();
-            ((max_x - min_x) as u32, min_x)
-        };
-        let glyphs_height: u32 = (v_metrics.ascent - v_metrics.descent).ceil() as u32;
-
-        // Now actually render all those glyphs
-        let mut text: RgbaImage = RgbaImage::new(glyphs_width, glyphs_height);
-        for glyph in glyphs {
-            if let Some(bb) = glyph.pixel_bounding_box() {
-                // We draw the glyph pixel-for-pixel
-                glyph.draw(|x, y, v| {
-                    text.put_pixel(
-                        ((x as i32 + bb.min.x) - x_offset) as u32,
-                        glyphs_height - 1 - (y + bb.min.y as u32),
-                        Rgba([0, 0, 0, (v * 255.0 + 0.5) as u8]),
-                    );
-                })
-            }
+/// - `font`: The [`Font`] to write the label in.
+/// - `scale`: The [`Scale`] (i.e., font size) at which to write the label.
+/// - `bg`: If given, gives the label a static background colour.
+///
+/// # Returns
+/// A new [`RgbaImage`] with the rendered text.
+fn render_text(label: &str, font: &Font, scale: Scale, bg: Option<Rgb<u8>>) -> RgbaImage {
+    // Find out what the vertical properties are of this font
+    let v_metrics: VMetrics = font.v_metrics(scale);
+
+    // Layout the glyphs
+    let glyphs: Vec<PositionedGlyph> = font.layout(label, scale, point(0.0, v_metrics.ascent)).collect();
+
+    // Work out the total layout size
+    // NOTE: Whitespace has no bounding box, so if there's only that, there's nothing to render
+    let (min_x, max_x): (i32, i32) = match glyphs
+        .iter()
+        .filter_map(|g| g.pixel_bounding_box())
+        .map(|bb| (bb.min.x, bb.max.x))
+        .reduce(|(min1, max1), (min2, max2)| (min1.min(min2), max1.max(max2)))
+    {
+        Some(bounds) => bounds,
+        None => return RgbaImage::new(0, 0),
+    };
+    let (glyphs_width, x_offset): (u32, i32) = ((max_x - min_x) as u32, min_x);
+    let glyphs_height: u32 = (v_metrics.ascent - v_metrics.descent).ceil() as u32;
+
+    // Now actually render all those glyphs
+    let mut text: RgbaImage = RgbaImage::new(glyphs_width, glyphs_height);
+    for glyph in glyphs {
+        if let Some(bb) = glyph.pixel_bounding_box() {
+            // We draw the glyph pixel-for-pixel
+            glyph.draw(|x, y, v| {
+                text.put_pixel(
+                    ((x as i32 + bb.min.x) - x_offset) as u32,
+                    glyphs_height - 1 - (y + bb.min.y as u32),
+                    Rgba([0, 0, 0, (v * 255.0 + 0.5) as u8]),
+                );
+            })
         }
+    }
 
-        // Trim the top- and bottom layers
-        let mut n_top: u32 = 0;
-        for mut row in img.rows() {
-            if row.any(|p| p.0[3] > 0) {
-                break;
-            }
-            n_top += 1;
+    // Trim the top- and bottom layers
+    let mut n_top: u32 = 0;
+    for mut row in text.rows() {
+        if row.any(|p| p.0[3] > 0) {
+            break;
         }
-        let mut n_bot: u32 = 0;
-        for mut row in img.rows().rev() {
-            if row.any(|p| p.0[3] > 0) {
-                break;
-            }
-            n_bot += 1;
+        n_top += 1;
+    }
+    let mut n_bot: u32 = 0;
+    for mut row in text.rows().rev() {
+        if row.any(|p| p.0[3] > 0) {
+            break;
         }
-        text = text.view(0, n_top, text.width(), text.height() - n_top - n_bot).to_image();
-
-        // If there's a background colour, generate that first
-        if let Some(color) = bg {
-            let color: Rgba<u8> = color.to_rgba();
+        n_bot += 1;
+    }
+    // NOTE: If the text is fully transparent (e.g., only whitespace), both count the full height
+    text = text.view(0, n_top.min(text.height()), text.width(), text.height().saturating_sub(n_top + n_bot)).to_image();
 
-            // Generate the static background color
-            let mut bg: RgbaImage = RgbaImage::new(text.width(), text.height());
-            for pix in bg.pixels_mut() {
-                *pix = color;
-            }
+    // If there's a background colour, generate that first
+    if let Some(color) = bg {
+        let color: Rgba<u8> = color.to_rgba();
 
-            // Merge the text onto it
-            image::imageops::overlay(&mut bg, &text, 0, 0);
-            text = bg;
+        // Generate the static background color
+        let mut bg: RgbaImage = RgbaImage::new(text.width(), text.height());
+        for pix in bg.pixels_mut() {
+            *pix = color;
         }
 
-        // Done
-        text
-    };
+        // Merge the text onto it
+        image::imageops::overlay(&mut bg, &text, 0, 0);
+        text = bg;
+    }
+
+    // Done
+    text
+}
+
+/// Draws a label next to a point on the image.
+///
+/// Attempts to do some clever placing if at all possible.
+///
+/// # Arguments
+/// - `img`: The [`RgbaImage`] to draw to.
+/// - `pos`: The coordinate to draw the point on.
+/// - `label`: The label to write.
+/// - `font`: The [`Font`] to write the label in.
+/// - `scale`: The [`Scale`] (i.e., font size) at which to write the label.
+/// - `bg`: If given, gives the labels a static background colour.
+/// - `clever_placement`: If true, then it will attempt to find a best place to display the label _around_ the chosen position. Else, will just place it over the given pos.
+fn draw_label(img: &mut RgbaImage, pos: (u32, u32), label: &str, font: &Font, scale: Scale, bg: Option<Rgb<u8>>, clever_placement: bool) {
+    // Render the text to a smaller image
+    let text: RgbaImage = render_text(label, font, scale, bg);
 
-    // Define the positions to try
-    let posses: &[(((u32, u32), (u32, u32)), bool)] = if clever_placement {
+    // Define the positions to try, as top-left corners (which may lie outside of the image)
+    let (pos, width, height): ((i64, i64), i64, i64) = ((pos.0 as i64, pos.1 as i64), text.width() as i64, text.height() as i64);
+    let posses: &[((i64, i64), bool)] = if clever_placement {
         // Attempt to position it BOTTOM, LEFT, TOP, RIGHT, then BOTTOM but just forcing it
         &[
-            (((pos.0 - text.width() / 2, pos.1 - text.height() - 5), (pos.0 + text.width() / 2, pos.1 - 5)), false),
-            (((pos.0 - text.width() - 5, pos.1 - text.height() / 2), (pos.0 - 5, pos.1 + text.height() / 2)), false),
-            (((pos.0 - text.width() / 2, pos.1 + 5), (pos.0 + text.width() / 2, pos.1 + text.height() + 5)), false),
-            (((pos.0 + 5, pos.1 - text.height() / 2), (pos.0 + text.width() + 5, pos.1 + text.height() / 2)), false),
-            (((pos.0 - text.width() / 2, pos.1 - text.height() - 5), (pos.0 + text.width() / 2, pos.1 - 5)), true),
+            ((pos.0 - width / 2, pos.1 - height - 5), false),
+            ((pos.0 - width - 5, pos.1 - height / 2), false),
+            ((pos.0 - width / 2, pos.1 + 5), false),
+            ((pos.0 + 5, pos.1 - height / 2), false),
+            ((pos.0 - width / 2, pos.1 - height - 5), true),
         ]
     } else {
         // Just force it on the position itself
-        &[(((pos.0 - text.width() / 2, pos.1 - text.height() / 2), (pos.0 + text.width() / 2, pos.1 + text.height() / 2)), true)]
+        &[((pos.0 - width / 2, pos.1 - height / 2), true)]
     };
 
     // Attempt to position the label
-    for (bb, force) in posses {
-        // See if we're overlapping with anything
-        if !force && pos.1 >= 5 + text.height() {
-            let mut clear: bool = true;
-            for y in bb.0.1..bb.1.1 {
-                for x in bb.0.0..bb.1.0 {
-                    if text[(x - bb.0.0, y - bb.0.1)].0[3] > 0 && img[(x, y)] != Rgba([255, 255, 255, 255]) {
-                        clear = false;
-                        break;
-                    }
+    for (corner, force) in posses {
+        // See if we're overlapping with anything (or falling off the image)
+        if !force {
+            let clear: bool = text.enumerate_pixels().all(|(x, y, pix)| {
+                let (x, y): (i64, i64) = (corner.0 + x as i64, corner.1 + y as i64);
+                if x < 0 || y < 0 || x >= img.width() as i64 || y >= img.height() as i64 {
+                    return false;
                 }
-            }
+                pix.0[3] == 0 || img[(x as u32, y as u32)] == Rgba([255, 255, 255, 255])
+            });
             if !clear {
                 continue;
             }
         }
 
         // If we made it here, we're good to write
-        image::imageops::overlay(img, &text, bb.0.0 as i64, bb.0.1 as i64);
+        // NOTE: This clips anything that falls outside of the image
+        image::imageops::overlay(img, &text, corner.0, corner.1);
         return;
     }
 
@@ -244,14 +626,46 @@ fn draw_label(img: &mut RgbaImage, pos: (u32, u32), label: &str, bg: Option<Rgb<
 
 /***** AUXILLARY *****/
 /// Defines additional options for rendering.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Options {
     /// The width & height of the resulting image.
     pub dims: (u32, u32),
+    /// The size (in pixels) at which labels are rendered.
+    pub font_size: f32,
+    /// If given, loads the TTF font at this path to render labels with instead of the embedded OpenSans.
+    pub font_path: Option<PathBuf>,
+    /// Whether to annotate nodes with their identifiers.
+    pub show_node_labels: bool,
+    /// Whether to annotate edges with their costs.
+    pub show_edge_labels: bool,
+    /// The number of decimals with which edge costs are shown.
+    pub edge_label_precision: usize,
+    /// Whether to add the [`Label`](ksp_graph::Label)s attached to the graph (see [`Graph::labels`]) to the node and edge labels.
+    ///
+    /// Numeric labels are shown with [`Options::edge_label_precision`] decimals.
+    pub show_graph_labels: bool,
+    /// Whether to draw arrow heads on edges, pointing from their `left` to their `right` node.
+    ///
+    /// Off by default, as edges are treated as undirected.
+    pub draw_arrows: bool,
+    /// The length (and width) of arrow heads, in pixels.
+    pub arrow_size: f64,
 }
 impl Default for Options {
     #[inline]
-    fn default() -> Self { Self { dims: (800, 600) } }
+    fn default() -> Self {
+        Self {
+            dims: (800, 600),
+            font_size: 16.0,
+            font_path: None,
+            show_node_labels: true,
+            show_edge_labels: true,
+            edge_label_precision: 2,
+            show_graph_labels: true,
+            draw_arrows: false,
+            arrow_size: 10.0,
+        }
+    }
 }
 
 
@@ -267,10 +681,23 @@ impl Default for Options {
 ///
 /// # Returns
 /// A raw [`RgbaImage`] containing the rendered graph.
-pub fn render_graph(graph: &Graph, opts: Options) -> RgbaImage {
+///
+/// # Errors
+/// This function errors if [`Options::font_path`] is given but we failed to load it as a font.
+pub fn render_graph(graph: &Graph, opts: Options) -> Result<RgbaImage, Error> {
+    // Resolve the font to use
+    let font: Font = match &opts.font_path {
+        Some(path) => {
+            let raw: Vec<u8> = std::fs::read(path).map_err(|err| Error::FontRead { path: path.clone(), err })?;
+            Font::try_from_vec(raw).ok_or_else(|| Error::FontParse { path: path.clone() })?
+        },
+        None => FONT.clone(),
+    };
+    let scale: Scale = Scale::uniform(opts.font_size);
+
     // Find the logical boundaries in the graph
     let mut boundaries: (Option<f64>, Option<f64>, Option<f64>, Option<f64>) = (None, None, None, None);
-    for node in graph.nodes.values() {
+    for node in graph.nodes_iter() {
         if node.pos.0 < boundaries.0.unwrap_or(f64::INFINITY) {
             boundaries.0 = Some(node.pos.0);
         }
@@ -284,45 +711,144 @@ pub fn render_graph(graph: &Graph, opts: Options) -> RgbaImage {
             boundaries.3 = Some(node.pos.1);
         }
     }
-    let boundaries: ((f64, f64), (f64, f64)) = match boundaries {
-        // Return the found boundaries plus some 1/10th of the area extra for prettiness
-        (Some(x1), Some(y1), Some(x2), Some(y2)) => ((x1 - (x2 - x1) / 10.0, y1 - (y2 - y1) / 10.0), (x2 + (x2 - x1) / 10.0, y2 + (y2 - y1) / 10.0)),
-        _ => unimplemented!(),
-    };
 
     // Create a white image to draw on
     let mut img = RgbaImage::new(opts.dims.0, opts.dims.1);
     img.fill(255);
 
+    let boundaries: ((f64, f64), (f64, f64)) = match boundaries {
+        (Some(mut x1), Some(mut y1), Some(mut x2), Some(mut y2)) => {
+            // If all nodes are on a line (or the same point), invent a span for the missing dimension(s) to centre them in
+            let span: f64 = if x2 > x1 { x2 - x1 } else if y2 > y1 { y2 - y1 } else { 1.0 };
+            if x2 <= x1 {
+                x1 -= span / 2.0;
+                x2 += span / 2.0;
+            }
+            if y2 <= y1 {
+                y1 -= span / 2.0;
+                y2 += span / 2.0;
+            }
+
+            // Return the found boundaries plus some 1/10th of the area extra for prettiness
+            ((x1 - (x2 - x1) / 10.0, y1 - (y2 - y1) / 10.0), (x2 + (x2 - x1) / 10.0, y2 + (y2 - y1) / 10.0))
+        },
+        // There are no nodes, so nothing to draw
+        _ => return Ok(img),
+    };
+
+    // Group parallel edges together, so that we can spread them out
+    // NOTE: Ordered, to render labels in the same order (and thus at the same place) every time
+    let mut groups: BTreeMap<(&str, &str), Vec<&Edge>> = BTreeMap::new();
+    for edge in graph.edges_iter() {
+        let key: (&str, &str) =
+            if edge.left <= edge.right { (edge.left.as_str(), edge.right.as_str()) } else { (edge.right.as_str(), edge.left.as_str()) };
+        groups.entry(key).or_default().push(edge);
+    }
+
     // Draw all edges first
-    for edge in graph.edges.values() {
-        // Get the two points in pixels
-        let pos1: (u32, u32) = logic_to_pixels(graph.nodes.get(&edge.left).unwrap().pos, boundaries, opts.dims);
-        let pos2: (u32, u32) = logic_to_pixels(graph.nodes.get(&edge.right).unwrap().pos, boundaries, opts.dims);
-
-        // Draw a line between them
-        draw_line(&mut img, pos1, pos2);
-
-        // Annotate the cost
-        let bb: ((u32, u32), (u32, u32)) = ((min(pos1.0, pos2.0), min(pos1.1, pos2.1)), (max(pos1.0, pos2.0), max(pos1.1, pos2.1)));
-        draw_label(
-            &mut img,
-            (bb.0.0 + (bb.1.0 - bb.0.0) / 2, bb.0.1 + (bb.1.1 - bb.0.1) / 2),
-            &format!("{:.2}", edge.cost),
-            Some(Rgb([255, 255, 255])),
-            false,
-        );
+    for ((left, right), mut edges) in groups {
+        // Sort the edges to have a consistent ordering between renders
+        edges.sort_by_key(|e| e.id);
+
+        // Get the two points in pixels, and the direction perpendicular to the line between them
+        let pos1: (u32, u32) = logic_to_pixels(graph.nodes.get(left).unwrap().pos, boundaries, opts.dims);
+        let pos2: (u32, u32) = logic_to_pixels(graph.nodes.get(right).unwrap().pos, boundaries, opts.dims);
+        let (dx, dy): (f64, f64) = (pos2.0 as f64 - pos1.0 as f64, pos2.1 as f64 - pos1.1 as f64);
+        let len: f64 = (dx * dx + dy * dy).sqrt();
+        let perp: (f64, f64) = if len > 0.0 { (-dy / len, dx / len) } else { (0.0, 0.0) };
+
+        for (i, edge) in edges.iter().enumerate() {
+            // Draw a line between them, offset from the other parallel lines
+            let offset: f64 = (i as f64 - (edges.len() - 1) as f64 / 2.0) * PARALLEL_EDGE_SPACING;
+            let (off1, off2): ((u32, u32), (u32, u32)) =
+                (offset_pixels(pos1, (perp.0 * offset, perp.1 * offset), opts.dims), offset_pixels(pos2, (perp.0 * offset, perp.1 * offset), opts.dims));
+            draw_line(&mut img, off1, off2);
+
+            // Point out its direction, if told to do so
+            if opts.draw_arrows {
+                if edge.left.as_str() == left {
+                    draw_arrow_head(&mut img, off1, off2, opts.arrow_size);
+                } else {
+                    draw_arrow_head(&mut img, off2, off1, opts.arrow_size);
+                }
+            }
+
+            // Annotate the cost, spreading the labels out along the lines
+            if !opts.show_edge_labels {
+                continue;
+            }
+            let t: f64 = (i + 1) as f64 / (edges.len() + 1) as f64;
+            draw_label(
+                &mut img,
+                (
+                    (off1.0 as f64 + (off2.0 as f64 - off1.0 as f64) * t).floor() as u32,
+                    (off1.1 as f64 + (off2.1 as f64 - off1.1 as f64) * t).floor() as u32,
+                ),
+                &match graph.labels.edges.get(&edge.id).filter(|_| opts.show_graph_labels) {
+                    Some(label) => format!("{:.prec$} ({label:.prec$})", edge.cost, prec = opts.edge_label_precision),
+                    None => format!("{:.prec$}", edge.cost, prec = opts.edge_label_precision),
+                },
+                &font,
+                scale,
+                Some(Rgb([255, 255, 255])),
+                false,
+            );
+        }
     }
 
     // Draw the nodes
-    for node in graph.nodes.values() {
+    for node in graph.nodes_iter() {
         draw_point(&mut img, logic_to_pixels(node.pos, boundaries, opts.dims));
     }
     // Draw the labels to the nodes
-    for node in graph.nodes.values() {
-        draw_label(&mut img, logic_to_pixels(node.pos, boundaries, opts.dims), node.id.as_str(), None, true);
+    if opts.show_node_labels {
+        for node in graph.nodes_iter() {
+            let label: String = match graph.labels.nodes.get(&node.id).filter(|_| opts.show_graph_labels) {
+                Some(label) => format!("{} ({label:.prec$})", node.id, prec = opts.edge_label_precision),
+                None => node.id.to_string(),
+            };
+            draw_label(&mut img, logic_to_pixels(node.pos, boundaries, opts.dims), &label, &font, scale, None, true);
+        }
     }
 
     // Done
-    img
+    Ok(img)
+}
+
+/// Renders a given [`Graph`] to a grid of tiles.
+///
+/// This is useful for large graphs, which are unreadable when squeezed into a single image. Instead, set [`Options::dims`] to a large
+/// (virtual) canvas and page through the tiles. The graph is scaled exactly like [`render_graph()`] would for that canvas.
+///
+/// # Arguments
+/// - `graph`: The graph to render.
+/// - `opts`: An [`Options`] struct used to configure rendering. Its `dims` give the size of the full canvas.
+/// - `tile`: The width & height of every tile. Tiles at the right and bottom edges of the canvas are cut short if it's not a multiple of
+///   this.
+///
+/// # Returns
+/// A list of `(x, y, tile)`-triplets, where `x` and `y` are the pixel offset of every tile's top-left corner in the full canvas. They are
+/// ordered row by row.
+///
+/// # Errors
+/// This function errors if [`Options::font_path`] is given but we failed to load it as a font.
+///
+/// # Panics
+/// This function panics if either dimension of `tile` is zero.
+#[track_caller]
+pub fn render_graph_tiled(graph: &Graph, opts: Options, tile: (u32, u32)) -> Result<Vec<(u32, u32, RgbaImage)>, Error> {
+    if tile.0 == 0 || tile.1 == 0 {
+        panic!("Cannot render to tiles of {}x{} pixels", tile.0, tile.1);
+    }
+
+    // Render the canvas, then cut it up
+    let canvas: RgbaImage = render_graph(graph, opts)?;
+    let mut tiles: Vec<(u32, u32, RgbaImage)> = Vec::with_capacity((canvas.width().div_ceil(tile.0) * canvas.height().div_ceil(tile.1)) as usize);
+    for y in (0..canvas.height()).step_by(tile.1 as usize) {
+        for x in (0..canvas.width()).step_by(tile.0 as usize) {
+            let (w, h): (u32, u32) = (tile.0.min(canvas.width() - x), tile.1.min(canvas.height() - y));
+            tiles.push((x, y, canvas.view(x, y, w, h).to_image()));
+        }
+    }
+    Ok(tiles)
 }
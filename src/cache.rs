@@ -0,0 +1,319 @@
+//  CACHE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:11:38
+//  Last edited:
+//    16 Oct 2026, 17:17:31
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements an on-disk cache of the paths found for queries, such
+//!   that rerunning the same queries on the same graph is free.
+//
+
+use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ksp_graph::hash::Fnv1a;
+use ksp_graph::Graph;
+use serde::{Deserialize, Serialize};
+
+use crate::path::OwnedPath;
+use crate::Pipeline;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_query_cache() {
+        let dir: PathBuf = std::env::temp_dir().join(format!("ksp-test-query-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut g: Graph = load_graph("cities");
+        let pip: Pipeline = "peek->yen<dijkstra>".parse().unwrap();
+
+        // The first run computes, the second one hits
+        let cache: QueryCache = QueryCache::new(&dir).unwrap();
+        let paths: Vec<OwnedPath> = pip.k_shortest_paths_cached(&mut g, "Amsterdam", "Chicago", 2, &cache).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+        assert_eq!(pip.k_shortest_paths_cached(&mut g, "Amsterdam", "Chicago", 2, &cache).unwrap(), paths);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        // It persists across runs
+        let cache: QueryCache = QueryCache::new(&dir).unwrap();
+        assert_eq!(pip.k_shortest_paths_cached(&mut g, "Amsterdam", "Chicago", 2, &cache).unwrap(), paths);
+        assert_eq!((cache.hits(), cache.misses()), (1, 0));
+
+        // Other queries, pipelines and graphs don't
+        pip.k_shortest_paths_cached(&mut g, "Amsterdam", "Chicago", 1, &cache).unwrap();
+        pip.k_shortest_paths_cached(&mut g, "Chicago", "Amsterdam", 2, &cache).unwrap();
+        "yen<dijkstra>".parse::<Pipeline>().unwrap().k_shortest_paths_cached(&mut g, "Amsterdam", "Chicago", 2, &cache).unwrap();
+        g.edges.values_mut().next().unwrap().cost += 1.0;
+        pip.k_shortest_paths_cached(&mut g, "Amsterdam", "Chicago", 2, &cache).unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (1, 4));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors originating from the [`QueryCache`].
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to create the cache directory.
+    DirCreate { path: PathBuf, err: std::io::Error },
+    /// Failed to read a cached entry.
+    FileRead { path: PathBuf, err: std::io::Error },
+    /// Failed to parse a cached entry.
+    FileParse { path: PathBuf, err: serde_json::Error },
+    /// Failed to serialize an entry.
+    Serialize { err: serde_json::Error },
+    /// Failed to write an entry.
+    FileWrite { path: PathBuf, err: std::io::Error },
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use Error::*;
+        match self {
+            DirCreate { path, .. } => write!(f, "Failed to create cache directory '{}'", path.display()),
+            FileRead { path, .. } => write!(f, "Failed to read cache entry '{}'", path.display()),
+            FileParse { path, .. } => write!(f, "Failed to parse cache entry '{}'", path.display()),
+            Serialize { .. } => write!(f, "Failed to serialize cache entry"),
+            FileWrite { path, .. } => write!(f, "Failed to write cache entry '{}'", path.display()),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        use Error::*;
+        match self {
+            DirCreate { err, .. } => Some(err),
+            FileRead { err, .. } => Some(err),
+            FileParse { err, .. } => Some(err),
+            Serialize { err } => Some(err),
+            FileWrite { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Identifies a query in the [`QueryCache`].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct QueryKey {
+    /// The [content hash](Graph::content_hash()) of the graph queried.
+    pub graph:    u64,
+    /// The node the paths start at.
+    pub source:   String,
+    /// The node the paths end at.
+    pub target:   String,
+    /// The number of paths asked for.
+    pub k:        usize,
+    /// The pipeline that found the paths.
+    pub pipeline: Pipeline,
+}
+impl QueryKey {
+    /// Constructor for the QueryKey.
+    ///
+    /// Note that this hashes the whole graph. Use [`QueryKey::with_hash()`] to reuse its hash for many queries.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] queried.
+    /// - `source`: The node the paths start at.
+    /// - `target`: The node the paths end at.
+    /// - `k`: The number of paths asked for.
+    /// - `pipeline`: The [`Pipeline`] that finds the paths.
+    ///
+    /// # Returns
+    /// A new QueryKey for the query.
+    #[inline]
+    pub fn new(graph: &Graph, source: &str, target: &str, k: usize, pipeline: &Pipeline) -> Self {
+        Self::with_hash(graph.content_hash(), source, target, k, pipeline)
+    }
+
+    /// Constructor for the QueryKey that takes an already computed hash of the graph.
+    ///
+    /// # Arguments
+    /// - `graph`: The [content hash](Graph::content_hash()) of the graph queried.
+    /// - `source`: The node the paths start at.
+    /// - `target`: The node the paths end at.
+    /// - `k`: The number of paths asked for.
+    /// - `pipeline`: The [`Pipeline`] that finds the paths.
+    ///
+    /// # Returns
+    /// A new QueryKey for the query.
+    #[inline]
+    pub fn with_hash(graph: u64, source: &str, target: &str, k: usize, pipeline: &Pipeline) -> Self {
+        Self { graph, source: source.into(), target: target.into(), k, pipeline: pipeline.clone() }
+    }
+
+    /// Returns the name of the file this query is stored in.
+    ///
+    /// # Returns
+    /// A file name that is the same for equal keys, and that is safe to use regardless of the node identifiers.
+    fn file_name(&self) -> String {
+        let mut hasher: Fnv1a = Fnv1a::new();
+        hasher.write_str(&self.source);
+        hasher.write_str(&self.target);
+        hasher.write(&(self.k as u64).to_le_bytes());
+        hasher.write_str(&self.pipeline.id());
+        format!("{:016x}-{:016x}.json", self.graph, hasher.finish())
+    }
+}
+
+
+
+/// What is stored on disk for every query.
+#[derive(Deserialize, Serialize)]
+struct Entry {
+    /// The query, to tell apart the (unlikely) ones with the same file name.
+    key:   QueryKey,
+    /// The paths found for it.
+    paths: Vec<OwnedPath>,
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Caches the paths found for queries on disk, such that rerunning them (e.g., in repeated benchmark invocations) is free.
+///
+/// Every query is stored as a JSON file in the cache directory, named by the hash of the graph and of the query. As the graph is identified
+/// by its [content hash](Graph::content_hash()), changing it in any way invalidates the results for it.
+///
+/// The cache counts how often it was hit or missed. It can be shared between threads.
+#[derive(Debug)]
+pub struct QueryCache {
+    /// The directory to store entries in.
+    dir:    PathBuf,
+    /// The number of queries that were found in the cache.
+    hits:   AtomicUsize,
+    /// The number of queries that were not.
+    misses: AtomicUsize,
+}
+impl QueryCache {
+    /// Constructor for the QueryCache.
+    ///
+    /// # Arguments
+    /// - `dir`: The directory to store entries in. It is created if it doesn't exist yet; if it does, entries in it are used.
+    ///
+    /// # Returns
+    /// A new QueryCache that hasn't been hit or missed yet.
+    ///
+    /// # Errors
+    /// This function errors if we failed to create the directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, Error> {
+        let dir: PathBuf = dir.into();
+        fs::create_dir_all(&dir).map_err(|err| Error::DirCreate { path: dir.clone(), err })?;
+        Ok(Self { dir, hits: AtomicUsize::new(0), misses: AtomicUsize::new(0) })
+    }
+
+    /// Returns the directory entries are stored in.
+    ///
+    /// # Returns
+    /// The directory given to [`QueryCache::new()`].
+    #[inline]
+    pub fn dir(&self) -> &FsPath { &self.dir }
+
+    /// Returns how often a query was found in the cache.
+    ///
+    /// # Returns
+    /// The number of hits so far.
+    #[inline]
+    pub fn hits(&self) -> usize { self.hits.load(Ordering::Relaxed) }
+
+    /// Returns how often a query was not found in the cache.
+    ///
+    /// # Returns
+    /// The number of misses so far.
+    #[inline]
+    pub fn misses(&self) -> usize { self.misses.load(Ordering::Relaxed) }
+
+    /// Looks up the paths found for a query.
+    ///
+    /// # Arguments
+    /// - `key`: The [`QueryKey`] identifying the query.
+    ///
+    /// # Returns
+    /// The paths stored for the query, or [`None`] if it isn't cached. Counts as a hit or a miss, respectively.
+    ///
+    /// # Errors
+    /// This function errors if the query's entry exists, but we failed to read or parse it.
+    pub fn get(&self, key: &QueryKey) -> Result<Option<Vec<OwnedPath>>, Error> {
+        let path: PathBuf = self.dir.join(key.file_name());
+        let raw: Vec<u8> = match fs::read(&path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return Ok(None);
+            },
+            Err(err) => return Err(Error::FileRead { path, err }),
+        };
+        let entry: Entry = serde_json::from_slice(&raw).map_err(|err| Error::FileParse { path, err })?;
+        if entry.key != *key {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Ok(Some(entry.paths))
+    }
+
+    /// Stores the paths found for a query, replacing any stored before.
+    ///
+    /// # Arguments
+    /// - `key`: The [`QueryKey`] identifying the query.
+    /// - `paths`: The paths found for it.
+    ///
+    /// # Errors
+    /// This function errors if we failed to serialize or write the entry. In that case, the query is not cached.
+    pub fn insert(&self, key: &QueryKey, paths: &[OwnedPath]) -> Result<(), Error> {
+        let raw: Vec<u8> =
+            serde_json::to_vec(&Entry { key: key.clone(), paths: paths.to_vec() }).map_err(|err| Error::Serialize { err })?;
+
+        // NOTE: Write to a temporary file first, such that concurrent readers never see half an entry
+        let path: PathBuf = self.dir.join(key.file_name());
+        let tmp: PathBuf = path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&tmp, raw).map_err(|err| Error::FileWrite { path: tmp.clone(), err })?;
+        fs::rename(&tmp, &path).map_err(|err| Error::FileWrite { path, err })
+    }
+
+    /// Looks up the paths found for a query, computing and storing them if they're not cached.
+    ///
+    /// # Arguments
+    /// - `key`: The [`QueryKey`] identifying the query.
+    /// - `compute`: A closure that computes the paths on a miss.
+    ///
+    /// # Returns
+    /// The paths for the query, either from the cache or from `compute`.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read the query's entry or to store a new one.
+    pub fn get_or_insert_with(&self, key: &QueryKey, compute: impl FnOnce() -> Vec<OwnedPath>) -> Result<Vec<OwnedPath>, Error> {
+        if let Some(paths) = self.get(key)? {
+            return Ok(paths);
+        }
+        let paths: Vec<OwnedPath> = compute();
+        self.insert(key, &paths)?;
+        Ok(paths)
+    }
+}
@@ -0,0 +1,202 @@
+//  CENTRALITY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:40:14
+//  Last edited:
+//    16 Oct 2026, 18:45:25
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements computing the shortest-path betweenness centrality of
+//!   the nodes and edges in a [`Graph`].
+//
+
+use std::collections::HashMap;
+
+use arrayvec::ArrayString;
+use ksp_graph::{Edge, Graph, Label};
+
+use crate::sssp::queue::{BinaryHeapQueue, PriorityQueue};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an undirected graph from the given edges of cost 1, each named after the nodes it links.
+    fn make_graph(edges: &[(&str, &str)]) -> Graph {
+        Graph::from_edges(edges.iter().map(|(left, right)| (format!("{left}-{right}"), *left, *right, 1.0)))
+    }
+
+    #[test]
+    fn test_betweenness_line() {
+        // A - B - C - D
+        let g: Graph = make_graph(&[("A", "B"), ("B", "C"), ("C", "D")]);
+        assert_eq!(betweenness(&g), HashMap::from([("A", 0.0), ("B", 2.0), ("C", 2.0), ("D", 0.0)]));
+        assert_eq!(edge_betweenness(&g), HashMap::from([("A-B", 3.0), ("B-C", 4.0), ("C-D", 3.0)]));
+
+        // If directed, only the pairs going right count
+        let mut g: Graph = g;
+        g.directed = true;
+        assert_eq!(betweenness(&g), HashMap::from([("A", 0.0), ("B", 2.0), ("C", 2.0), ("D", 0.0)]));
+        assert_eq!(edge_betweenness(&g), HashMap::from([("A-B", 3.0), ("B-C", 4.0), ("C-D", 3.0)]));
+        g.edges.get_mut("B-C").unwrap().cost = f64::INFINITY;
+        assert_eq!(betweenness(&g), HashMap::from([("A", 0.0), ("B", 0.0), ("C", 0.0), ("D", 0.0)]));
+    }
+
+    #[test]
+    fn test_betweenness_star() {
+        // Every pair of leaves goes through the centre
+        let g: Graph = make_graph(&[("X", "1"), ("X", "2"), ("X", "3"), ("X", "4")]);
+        assert_eq!(betweenness(&g), HashMap::from([("X", 6.0), ("1", 0.0), ("2", 0.0), ("3", 0.0), ("4", 0.0)]));
+        assert_eq!(edge_betweenness(&g), HashMap::from([("X-1", 4.0), ("X-2", 4.0), ("X-3", 4.0), ("X-4", 4.0)]));
+    }
+
+    #[test]
+    fn test_betweenness_diamond() {
+        // A and D are connected by two equally short paths, which share the score
+        let g: Graph = make_graph(&[("A", "B"), ("A", "C"), ("B", "D"), ("C", "D")]);
+        assert_eq!(betweenness(&g), HashMap::from([("A", 0.5), ("B", 0.5), ("C", 0.5), ("D", 0.5)]));
+        assert_eq!(edge_betweenness(&g), HashMap::from([("A-B", 2.0), ("A-C", 2.0), ("B-D", 2.0), ("C-D", 2.0)]));
+    }
+
+    #[test]
+    fn test_label_betweenness() {
+        let mut g: Graph = make_graph(&[("A", "B"), ("B", "C"), ("C", "D")]);
+        label_betweenness(&mut g);
+        assert_eq!(g.labels.nodes["B"], Label::Number(2.0));
+        assert_eq!(g.labels.nodes["D"], Label::Number(0.0));
+        assert_eq!(g.labels.edges["B-C"], Label::Number(4.0));
+        assert_eq!((g.labels.nodes.len(), g.labels.edges.len()), (4, 3));
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Runs Brandes' algorithm \[5\] to compute the betweenness of all nodes and edges at once.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to compute in.
+///
+/// # Returns
+/// A tuple of the scores of the nodes and those of the edges, by identifier. If `graph` is undirected, every pair is counted once.
+///
+/// # References
+/// \[5\] Brandes, U. A faster algorithm for betweenness centrality.
+/// _J. Math. Sociol._ 25(2), 163–177 (2001). https://doi.org/10.1080/0022250X.2001.9990249.
+fn brandes(graph: &Graph) -> (HashMap<&str, f64>, HashMap<&str, f64>) {
+    // Index the nodes, such that they can be queued
+    let mut ids: Vec<&str> = graph.nodes.keys().map(|id| id.as_str()).collect();
+    ids.sort();
+    let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+    let n: usize = ids.len();
+    let mut node_scores: Vec<f64> = vec![0.0; n];
+    let mut edge_scores: HashMap<&str, f64> = graph.edges.keys().map(|id| (id.as_str(), 0.0)).collect();
+    for src in 0..n {
+        // Run Dijkstra, counting the number of shortest paths to every node and remembering all of their last hops
+        let mut dist: Vec<f64> = vec![f64::INFINITY; n];
+        let mut count: Vec<f64> = vec![0.0; n];
+        let mut preds: Vec<Vec<(usize, &Edge)>> = vec![Vec::new(); n];
+        let mut order: Vec<usize> = Vec::with_capacity(n);
+        dist[src] = 0.0;
+        count[src] = 1.0;
+        let mut todo: BinaryHeapQueue = BinaryHeapQueue::default();
+        todo.push(src, 0.0);
+        while let Some((node, cost)) = todo.pop_min() {
+            order.push(node);
+            for (neigh, edge) in graph.successors(ids[node]) {
+                let neigh: usize = index[neigh];
+                let new_dist: f64 = cost + edge.cost;
+                if new_dist == f64::INFINITY || new_dist > dist[neigh] {
+                    continue;
+                }
+                if new_dist < dist[neigh] {
+                    dist[neigh] = new_dist;
+                    count[neigh] = 0.0;
+                    preds[neigh].clear();
+                    todo.decrease_key(neigh, new_dist);
+                }
+                count[neigh] += count[node];
+                preds[neigh].push((node, edge));
+            }
+        }
+
+        // Then walk back from the furthest node, attributing every node's dependency to its predecessors
+        let mut dependency: Vec<f64> = vec![0.0; n];
+        while let Some(node) = order.pop() {
+            for (pred, edge) in &preds[node] {
+                let share: f64 = count[*pred] / count[node] * (1.0 + dependency[node]);
+                dependency[*pred] += share;
+                *edge_scores.get_mut(edge.id.as_str()).unwrap() += share;
+            }
+            if node != src {
+                node_scores[node] += dependency[node];
+            }
+        }
+    }
+
+    // Both directions of a pair have been counted if undirected
+    let factor: f64 = if graph.directed { 1.0 } else { 0.5 };
+    for score in edge_scores.values_mut() {
+        *score *= factor;
+    }
+    (ids.into_iter().zip(node_scores).map(|(id, score)| (id, score * factor)).collect(), edge_scores)
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Computes the shortest-path betweenness centrality of every node in a graph.
+///
+/// The betweenness of a node is the number of shortest paths between other pairs of nodes that go through it, where pairs connected by
+/// multiple equally short paths count fractionally for each of them. It's a measure of how important a node is to route through.
+///
+/// Uses Brandes' algorithm \[5\], which takes O(nm + n² log n) time for n nodes and m edges. Costs must be positive, and edges with an
+/// infinite cost are considered absent. Paths are only equally short if their costs are exactly equal.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to compute in.
+///
+/// # Returns
+/// The (unnormalized) betweenness of every node, by identifier. If `graph` is undirected, every (unordered) pair is counted once.
+///
+/// # References
+/// \[5\] Brandes, U. A faster algorithm for betweenness centrality.
+/// _J. Math. Sociol._ 25(2), 163–177 (2001). https://doi.org/10.1080/0022250X.2001.9990249.
+#[inline]
+pub fn betweenness(graph: &Graph) -> HashMap<&str, f64> { brandes(graph).0 }
+
+/// Computes the shortest-path betweenness centrality of every edge in a graph.
+///
+/// Like [`betweenness()`], but counts the shortest paths (between any pair of nodes) that traverse an edge.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to compute in.
+///
+/// # Returns
+/// The (unnormalized) betweenness of every edge, by identifier. If `graph` is undirected, every (unordered) pair is counted once.
+#[inline]
+pub fn edge_betweenness(graph: &Graph) -> HashMap<&str, f64> { brandes(graph).1 }
+
+/// Labels every node and edge in a graph with its shortest-path betweenness centrality.
+///
+/// This allows later steps to use the centrality without computing it again, e.g., to show it when rendering the graph.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to label. Any existing labels of its nodes and edges are overwritten.
+pub fn label_betweenness(graph: &mut Graph) {
+    let (nodes, edges): (HashMap<&str, f64>, HashMap<&str, f64>) = brandes(graph);
+    let nodes: Vec<(ArrayString<64>, Label)> = nodes.into_iter().map(|(id, b)| (ArrayString::from(id).unwrap(), Label::Number(b))).collect();
+    let edges: Vec<(ArrayString<64>, Label)> = edges.into_iter().map(|(id, b)| (ArrayString::from(id).unwrap(), Label::Number(b))).collect();
+    graph.labels.nodes.extend(nodes);
+    graph.labels.edges.extend(edges);
+}
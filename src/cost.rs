@@ -0,0 +1,136 @@
+//  COST.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 18:16:15
+//  Last edited:
+//    16 Oct 2026, 18:18:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines [`OrderedCost`], a totally ordered path cost for use in
+//!   heaps and sorted collections.
+//
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::hash::{Hash, Hasher};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, BinaryHeap};
+
+    use super::*;
+
+    #[test]
+    fn test_ordered_cost_nan() {
+        let nan: OrderedCost = OrderedCost(f64::NAN);
+        assert_eq!(nan, OrderedCost(-f64::NAN));
+        assert_eq!(nan.cmp(&nan), Ordering::Equal);
+        assert!(nan > OrderedCost(f64::INFINITY));
+        assert!(OrderedCost(-f64::NAN) > OrderedCost(f64::NEG_INFINITY));
+        assert!(nan.is_nan());
+
+        // NaNs don't disturb the order of the other costs
+        let mut costs: Vec<OrderedCost> = [3.0, f64::NAN, -1.0, -f64::NAN, f64::INFINITY, 0.5].into_iter().map(OrderedCost).collect();
+        costs.sort();
+        assert_eq!(&costs[..4], &[OrderedCost(-1.0), OrderedCost(0.5), OrderedCost(3.0), OrderedCost(f64::INFINITY)]);
+        assert!(costs[4..].iter().all(OrderedCost::is_nan));
+        let set: BTreeSet<OrderedCost> = costs.into_iter().collect();
+        assert_eq!(set.len(), 5);
+    }
+
+    #[test]
+    fn test_ordered_cost_stable() {
+        // Zeroes are the same cost, whatever their sign
+        assert_eq!(OrderedCost(0.0), OrderedCost(-0.0));
+        assert_eq!(OrderedCost(0.0).cmp(&OrderedCost(-0.0)), Ordering::Equal);
+        let hash = |cost: OrderedCost| {
+            let mut state = std::hash::DefaultHasher::new();
+            cost.hash(&mut state);
+            state.finish()
+        };
+        assert_eq!(hash(OrderedCost(0.0)), hash(OrderedCost(-0.0)));
+        assert_eq!(hash(OrderedCost(f64::NAN)), hash(OrderedCost(-f64::NAN)));
+
+        // The order doesn't depend on that of insertion
+        let costs: [f64; 6] = [2.0, f64::NAN, 1.0, 0.0, -0.0, 1.0];
+        let mut heap: BinaryHeap<OrderedCost> = costs.into_iter().map(OrderedCost).collect();
+        let mut rev_heap: BinaryHeap<OrderedCost> = costs.into_iter().rev().map(OrderedCost).collect();
+        while let Some(cost) = heap.pop() {
+            assert_eq!(Some(cost), rev_heap.pop());
+        }
+        assert!(rev_heap.is_empty());
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Wraps a cost such that it can be totally ordered, e.g., in a [`BinaryHeap`](std::collections::BinaryHeap).
+///
+/// Costs are compared by value, except that NaN sorts after everything else (including [`f64::INFINITY`]) and all NaNs are equal. `0.0`
+/// and `-0.0` are equal too. Use [`Reverse`](std::cmp::Reverse) or reverse the comparison to pop the lowest cost first.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrderedCost(pub f64);
+impl OrderedCost {
+    /// Checks whether this cost is NaN, i.e., sorts after all others.
+    ///
+    /// # Returns
+    /// True if the wrapped cost is NaN, or false otherwise.
+    #[inline]
+    pub fn is_nan(&self) -> bool { self.0.is_nan() }
+}
+impl Display for OrderedCost {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult { self.0.fmt(f) }
+}
+impl Eq for OrderedCost {}
+impl Hash for OrderedCost {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // NOTE: Hash equal costs the same, even if their bits differ
+        let bits: u64 = if self.0.is_nan() {
+            f64::NAN.to_bits()
+        } else if self.0 == 0.0 {
+            0
+        } else {
+            self.0.to_bits()
+        };
+        bits.hash(state)
+    }
+}
+impl Ord for OrderedCost {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0.is_nan(), other.0.is_nan()) {
+            // NOTE: Unlike `total_cmp()`, `0.0` and `-0.0` are the same cost
+            (false, false) if self.0 == other.0 => Ordering::Equal,
+            (false, false) => self.0.total_cmp(&other.0),
+            (false, true) => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            (true, true) => Ordering::Equal,
+        }
+    }
+}
+impl PartialEq for OrderedCost {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl PartialOrd for OrderedCost {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+impl From<f64> for OrderedCost {
+    #[inline]
+    fn from(value: f64) -> Self { Self(value) }
+}
+impl From<OrderedCost> for f64 {
+    #[inline]
+    fn from(value: OrderedCost) -> Self { value.0 }
+}
@@ -0,0 +1,236 @@
+//  FLOW.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:55:18
+//  Last edited:
+//    16 Oct 2026, 18:48:02
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements computing the maximum flow between two nodes in a
+//!   [`Graph`], where edges have a capacity.
+//
+
+use std::collections::{HashMap, VecDeque};
+
+use ksp_graph::{Edge, Graph};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::load_graph;
+
+    /// Asserts that a flow respects the capacities and is conserved in every node except the source and destination.
+    fn assert_valid_flow(graph: &Graph, src: &str, dst: &str, value: f64, flows: &HashMap<&str, f64>) {
+        let mut net: HashMap<&str, f64> = HashMap::new();
+        for (id, edge) in &graph.edges {
+            let flow: f64 = flows[id.as_str()];
+            assert!(flow.abs() <= edge.cost + 1e-9 && (!graph.directed || flow >= 0.0), "Edge '{id}' carries {flow}");
+            *net.entry(edge.left.as_str()).or_default() -= flow;
+            *net.entry(edge.right.as_str()).or_default() += flow;
+        }
+        for (node, net) in net {
+            let expected: f64 = if node == src { -value } else if node == dst { value } else { 0.0 };
+            assert!((net - expected).abs() < 1e-9, "Node '{node}' has net inflow {net} (expected {expected})");
+        }
+    }
+
+    #[test]
+    fn test_max_flow_directed() {
+        // The example network of Cormen et al., whose minimum cut is {v1-v3, v4-v3, v4-t} (12 + 7 + 4)
+        let mut g: Graph = Graph::from_edges(
+            [
+                ("s", "v1", 16.0),
+                ("s", "v2", 13.0),
+                ("v1", "v3", 12.0),
+                ("v2", "v1", 4.0),
+                ("v2", "v4", 14.0),
+                ("v3", "v2", 9.0),
+                ("v3", "t", 20.0),
+                ("v4", "v3", 7.0),
+                ("v4", "t", 4.0),
+            ]
+            .map(|(left, right, cost)| (format!("{left}-{right}"), left, right, cost)),
+        );
+        g.directed = true;
+        let (value, flows): (f64, HashMap<&str, f64>) = max_flow(&g, "s", "t");
+        assert_eq!(value, 23.0);
+        assert_valid_flow(&g, "s", "t", value, &flows);
+        assert_eq!((flows["v1-v3"], flows["v4-v3"], flows["v4-t"]), (12.0, 7.0, 4.0));
+
+        // Nothing flows against the direction of edges
+        assert_eq!(max_flow(&g, "t", "s").0, 0.0);
+    }
+
+    #[test]
+    fn test_max_flow_undirected() {
+        // Chicago is only reached through Dorchester, which is cut off cheapest by Amsterdam-Dorchester and Edinburgh-Dorchester
+        let g: Graph = load_graph("cities");
+        let (value, flows): (f64, HashMap<&str, f64>) = max_flow(&g, "Amsterdam", "Chicago");
+        assert!((value - (540.86 + 589.23)).abs() < 1e-9);
+        assert_valid_flow(&g, "Amsterdam", "Chicago", value, &flows);
+        assert_eq!(flows["Amsterdam-Berlin"], 0.0);
+
+        // Undirected edges carry flow either way
+        let (value, flows): (f64, HashMap<&str, f64>) = max_flow(&g, "Chicago", "Amsterdam");
+        assert!((value - (540.86 + 589.23)).abs() < 1e-9);
+        assert_valid_flow(&g, "Chicago", "Amsterdam", value, &flows);
+
+        // Capacities can be computed differently
+        let (value, _): (f64, HashMap<&str, f64>) = max_flow_with(&g, "Amsterdam", "Chicago", |_| 1.0);
+        assert_eq!(value, 1.0);
+        let (value, _): (f64, HashMap<&str, f64>) = max_flow_with(&g, "Amsterdam", "Dorchester", |_| 1.0);
+        assert_eq!(value, 2.0);
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Finds a path from a node to the destination in the level graph, and pushes as much flow along it as possible.
+///
+/// # Arguments
+/// - `arcs`: The target and residual capacity of every arc, where arc `i ^ 1` is the reverse of arc `i`. Updated with the pushed flow.
+/// - `adjacency`: The arcs leaving every node.
+/// - `levels`: The distance (in hops) from the source to every node, or [`usize::MAX`] if it can't be reached.
+/// - `next`: The next arc of every node to try. Arcs before it are known to lead nowhere anymore.
+/// - `node`: The node to push from.
+/// - `dst`: The node to push to.
+/// - `limit`: The most flow that can reach `node`.
+///
+/// # Returns
+/// The flow that was pushed, which is 0 if `dst` can't be reached anymore.
+fn augment(arcs: &mut [(usize, f64)], adjacency: &[Vec<usize>], levels: &[usize], next: &mut [usize], node: usize, dst: usize, limit: f64) -> f64 {
+    if node == dst {
+        return limit;
+    }
+    while next[node] < adjacency[node].len() {
+        let arc: usize = adjacency[node][next[node]];
+        let (to, residual): (usize, f64) = arcs[arc];
+        if residual > 0.0 && levels[to] == levels[node] + 1 {
+            let pushed: f64 = augment(arcs, adjacency, levels, next, to, dst, limit.min(residual));
+            if pushed > 0.0 {
+                arcs[arc].1 -= pushed;
+                arcs[arc ^ 1].1 += pushed;
+                return pushed;
+            }
+        }
+        next[node] += 1;
+    }
+    0.0
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Computes the maximum flow from one node to another, using the cost of edges as their capacity.
+///
+/// See [`max_flow_with()`] for details.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to compute in.
+/// - `src`: The node where the flow starts.
+/// - `dst`: The node where the flow ends.
+///
+/// # Returns
+/// A tuple of the value of the flow and the flow through every edge, by identifier.
+///
+/// # Panics
+/// This function panics if the cost of any edge is negative, NaN or infinite.
+#[inline]
+#[track_caller]
+pub fn max_flow<'g>(graph: &'g Graph, src: &str, dst: &str) -> (f64, HashMap<&'g str, f64>) { max_flow_with(graph, src, dst, |e: &Edge| e.cost) }
+
+/// Computes the maximum flow from one node to another, using a custom capacity for edges.
+///
+/// By the max-flow min-cut theorem, the value of the flow equals the total capacity of the cheapest set of edges that disconnects `dst`
+/// from `src`. Edges can only carry flow from `left` to `right` if the graph is [directed](Graph::directed), and either way (but not both)
+/// otherwise.
+///
+/// Uses Dinic's algorithm, which takes O(n²m) time for n nodes and m edges.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to compute in.
+/// - `src`: The node where the flow starts.
+/// - `dst`: The node where the flow ends.
+/// - `capacity`: A function computing the capacity of a particular [`Edge`]. [`max_flow()`] uses `|e| e.cost`.
+///
+/// # Returns
+/// A tuple of the value of the flow and the flow through every edge, by identifier. The flow through an edge is negative if it goes from
+/// `right` to `left` (which only happens if the graph is undirected). If `src` and `dst` are the same node, or if either isn't in the
+/// graph, no flow is possible and the value is 0.
+///
+/// # Panics
+/// This function panics if the capacity of any edge is negative, NaN or infinite.
+#[track_caller]
+pub fn max_flow_with<'g>(graph: &'g Graph, src: &str, dst: &str, capacity: impl Fn(&Edge) -> f64) -> (f64, HashMap<&'g str, f64>) {
+    // Index the nodes, such that the residual graph can be kept in flat arrays
+    let mut ids: Vec<&str> = graph.nodes.keys().map(|id| id.as_str()).collect();
+    ids.sort();
+    let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+    // Every edge becomes an arc and its reverse, which (if undirected) can carry as much flow back
+    let mut arcs: Vec<(usize, f64)> = Vec::with_capacity(2 * graph.edges.len());
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); ids.len()];
+    let mut edges: Vec<(&str, usize, f64)> = Vec::with_capacity(graph.edges.len());
+    for edge in graph.edges.values() {
+        let cap: f64 = capacity(edge);
+        if !(0.0..f64::INFINITY).contains(&cap) {
+            panic!("Edge '{}' has capacity {} (must be non-negative and finite)", edge.id, cap);
+        }
+        if let (Some(left), Some(right)) = (index.get(edge.left.as_str()), index.get(edge.right.as_str())) {
+            if left != right {
+                edges.push((edge.id.as_str(), arcs.len(), cap));
+                adjacency[*left].push(arcs.len());
+                arcs.push((*right, cap));
+                adjacency[*right].push(arcs.len());
+                arcs.push((*left, if graph.directed { 0.0 } else { cap }));
+                continue;
+            }
+        }
+        edges.push((edge.id.as_str(), usize::MAX, cap));
+    }
+
+    // Push blocking flows along the shortest (in hops) augmenting paths until there are none
+    let mut value: f64 = 0.0;
+    let endpoints: Option<(usize, usize)> = index.get(src).copied().zip(index.get(dst).copied()).filter(|(src, dst)| src != dst);
+    if let Some((src, dst)) = endpoints {
+        loop {
+            let mut levels: Vec<usize> = vec![usize::MAX; ids.len()];
+            levels[src] = 0;
+            let mut todo: VecDeque<usize> = VecDeque::from([src]);
+            while let Some(node) = todo.pop_front() {
+                for arc in &adjacency[node] {
+                    let (to, residual): (usize, f64) = arcs[*arc];
+                    if residual > 0.0 && levels[to] == usize::MAX {
+                        levels[to] = levels[node] + 1;
+                        todo.push_back(to);
+                    }
+                }
+            }
+            if levels[dst] == usize::MAX {
+                break;
+            }
+
+            let mut next: Vec<usize> = vec![0; ids.len()];
+            loop {
+                let pushed: f64 = augment(&mut arcs, &adjacency, &levels, &mut next, src, dst, f64::INFINITY);
+                if pushed == 0.0 {
+                    break;
+                }
+                value += pushed;
+            }
+        }
+    }
+
+    // The flow through an edge is what's missing from its arc
+    (value, edges.into_iter().map(|(id, arc, cap)| (id, if arc == usize::MAX { 0.0 } else { cap - arcs[arc].1 })).collect())
+}
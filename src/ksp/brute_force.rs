@@ -0,0 +1,188 @@
+//  BRUTE FORCE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:21:07
+//  Last edited:
+//    16 Oct 2026, 17:02:03
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a brute-force KSP algorithm that serves as a reference
+//!   for the others.
+//!   
+//!   Only available in tests or with the `reference`-feature, as it's
+//!   exponential in the size of the graph.
+//
+
+use arrayvec::ArrayString;
+use ksp_graph::{Edge, Graph};
+
+use super::{path_capacity, KShortestPath};
+use crate::path::Path;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_brute_force_ksp_simple() {
+        let g: Graph = load_graph("cities");
+        assert_eq!(BruteForceKSP::simple().k_shortest_paths(&g, "Amsterdam", "Chicago", 5), vec![
+            path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago"),
+            path!(crate : g, "Amsterdam" -> "Edinburgh" -> "Dorchester" -| "Chicago"),
+        ]);
+        assert_eq!(BruteForceKSP::simple().k_shortest_paths(&g, "Amsterdam", "Dorchester", 1), vec![path!(crate : g, "Amsterdam" -| "Dorchester")]);
+        assert_eq!(BruteForceKSP::simple().k_shortest_paths(&g, "Amsterdam", "Dorchester", 0), vec![]);
+    }
+
+    #[test]
+    fn test_brute_force_ksp_non_simple() {
+        let g: Graph = load_graph("cities");
+        assert_eq!(BruteForceKSP::non_simple().k_shortest_paths(&g, "Amsterdam", "Berlin", 3), vec![
+            path!(crate : g, "Amsterdam" -| "Berlin"),
+            path!(crate : g, "Amsterdam" -> "Dorchester" -> "Amsterdam" -| "Berlin"),
+            path!(crate : g, "Amsterdam" -> "Berlin" -> "Amsterdam" -| "Berlin"),
+        ]);
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Finds the K shortest paths by enumerating (nearly) all paths in the graph using a depth-first search.
+///
+/// This is intended as an oracle for testing the other algorithms, _not_ for production use: it is exponential in the size of the graph.
+/// The search is only bounded by pruning paths that are already more expensive than the K-th shortest path found so far.
+///
+/// Because there are infinitely many non-simple paths, these are found by repeatedly searching with an increasing limit on the number of hops
+/// until no longer path can be cheaper than the K-th one found. As such, edges must have a positive cost for the search to terminate.
+#[derive(Clone, Copy, Debug)]
+pub struct BruteForceKSP {
+    /// Whether to only find simple paths (i.e., like [`YenKSP`](super::yen::YenKSP)) or also paths with loops in them (i.e., like [`WikipediaKSP`](super::wikipedia::WikipediaKSP)).
+    simple: bool,
+}
+impl BruteForceKSP {
+    /// Constructor for a BruteForceKSP that only finds simple paths, i.e., paths that visit every node at most once.
+    ///
+    /// # Returns
+    /// A new BruteForceKSP instance.
+    #[inline]
+    pub const fn simple() -> Self { Self { simple: true } }
+
+    /// Constructor for a BruteForceKSP that also finds paths that visit the same node more than once.
+    ///
+    /// # Returns
+    /// A new BruteForceKSP instance.
+    #[inline]
+    pub const fn non_simple() -> Self { Self { simple: false } }
+
+    /// Recursively extends the given path with all of its end's neighbours.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `dst`: The destination node to find paths to.
+    /// - `k`: The number of paths to find.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`].
+    /// - `max_hops`: The maximum number of hops to search.
+    /// - `path`: The path found so far.
+    /// - `found`: The (sorted) list of at most `k` shortest paths found so far.
+    ///
+    /// # Returns
+    /// Whether the search was cut short by `max_hops`.
+    #[allow(clippy::too_many_arguments)]
+    fn dfs<'g>(
+        &self,
+        graph: &'g Graph,
+        dst: &str,
+        k: usize,
+        weight: &impl Fn(&Edge) -> f64,
+        max_hops: usize,
+        path: &mut Path<'g>,
+        found: &mut Vec<Path<'g>>,
+    ) -> bool {
+        // Stop if this path cannot become one of the K shortest anymore
+        let (end, cost): (&'g str, f64) = *path.hops.last().unwrap();
+        if found.len() >= k && cost >= found[k - 1].cost() {
+            return false;
+        }
+
+        // Store it if it reaches the destination
+        if end == dst {
+            let pos: usize = found.iter().position(|p| p.cost() > cost).unwrap_or(found.len());
+            found.insert(pos, path.clone());
+            found.truncate(k);
+            if self.simple {
+                return false;
+            }
+        }
+
+        // Otherwise, try all neighbours
+        let mut truncated: bool = false;
+        for (neighbour, edge) in graph.successors(end) {
+            if self.simple && path.contains_node(neighbour) {
+                continue;
+            }
+            let edge_cost: f64 = weight(edge);
+            if edge_cost == f64::INFINITY {
+                continue;
+            }
+
+            if path.hops.len() > max_hops {
+                truncated = true;
+                continue;
+            }
+
+            path.hops.push((neighbour, cost + edge_cost));
+            truncated |= self.dfs(graph, dst, k, weight, max_hops, path, found);
+            path.hops.pop();
+        }
+        truncated
+    }
+}
+impl KShortestPath for BruteForceKSP {
+    #[track_caller]
+    fn k_shortest_paths_weighted<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize, weight: impl Fn(&Edge) -> f64) -> Vec<Path<'g>> {
+        // Assert that both nodes exists
+        let src: &'g str = if let Some((key, _)) = graph.nodes.get_key_value(&ArrayString::from(src).unwrap()) {
+            key
+        } else {
+            panic!("Unknown source node '{src}'");
+        };
+        if !graph.nodes.contains_key(&ArrayString::from(dst).unwrap()) {
+            panic!("Unknown destination node '{dst}'");
+        }
+        // There's nothing to find if we're asked for no paths
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // Enumerate them all
+        let mut found: Vec<Path<'g>> = Vec::with_capacity(path_capacity(k));
+        if self.simple {
+            self.dfs(graph, dst, k, &weight, usize::MAX, &mut Path { hops: vec![(src, 0.0)] }, &mut found);
+            return found;
+        }
+
+        // For non-simple paths, search deeper and deeper until any longer path is guaranteed to be more expensive than what we found
+        let min_weight: f64 = graph.edges_iter().map(&weight).filter(|w| *w < f64::INFINITY).fold(f64::INFINITY, f64::min);
+        let mut max_hops: usize = graph.node_count().max(1);
+        loop {
+            found.clear();
+            if !self.dfs(graph, dst, k, &weight, max_hops, &mut Path { hops: vec![(src, 0.0)] }, &mut found) {
+                return found;
+            }
+            if found.len() >= k && found[k - 1].cost() <= (max_hops + 1) as f64 * min_weight {
+                return found;
+            }
+            max_hops *= 2;
+        }
+    }
+}
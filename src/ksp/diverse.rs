@@ -0,0 +1,182 @@
+//  DIVERSE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:20:29
+//  Last edited:
+//    16 Oct 2026, 19:00:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements selecting K short paths that are diverse, i.e., that
+//!   don't overlap too much, instead of just the K shortest ones.
+//
+
+use std::collections::HashSet;
+
+use ksp_graph::Graph;
+
+use super::{path_capacity, KShortestPath};
+use crate::path::Path;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ksp::yen::YenKSP;
+    use crate::path;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+
+    /// Builds a graph with a main route from `s` to `t` that can take two cheap detours, and a separate, more expensive route.
+    fn make_graph() -> Graph {
+        Graph::from_edges(
+            [
+                ("s", "a1", 1.0),
+                ("a1", "a2", 1.0),
+                ("a2", "a3", 1.0),
+                ("a3", "t", 1.0),
+                ("a1", "b1", 0.625),
+                ("b1", "a2", 0.625),
+                ("a2", "b2", 0.625),
+                ("b2", "a3", 0.625),
+                ("s", "c1", 2.0),
+                ("c1", "c2", 2.0),
+                ("c2", "t", 2.0),
+            ]
+            .map(|(left, right, cost)| (format!("{left}-{right}"), left, right, cost)),
+        )
+    }
+
+    #[test]
+    fn test_k_shortest_diverse() {
+        let g: Graph = make_graph();
+
+        // The shortest paths are all the same route, give or take a detour...
+        let shortest: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "s", "t", 3);
+        assert_eq!(shortest.iter().map(Path::cost).collect::<Vec<f64>>(), vec![4.0, 4.25, 4.25]);
+        assert!(shortest.iter().all(|path| path.contains_node("a1") && path.contains_node("a3")));
+
+        // ...but the diverse ones aren't
+        let diverse: Vec<Path> = k_shortest_diverse(&g, "s", "t", 3, 0.2, YenKSP::new(DijkstraSSSP::new()));
+        assert_eq!(diverse, vec![path!(crate : g, "s" -> "a1" -> "a2" -> "a3" -| "t"), path!(crate : g, "s" -> "c1" -> "c2" -| "t")]);
+        let covered = |paths: &[Path]| -> usize { paths.iter().flat_map(hops).collect::<HashSet<(&str, &str)>>().len() };
+        assert!(covered(&diverse) > covered(&shortest[..2]));
+
+        // Allowing more overlap takes both detours before the separate route, as that's cheaper
+        let diverse: Vec<Path> = k_shortest_diverse(&g, "s", "t", 2, 0.3, YenKSP::new(DijkstraSSSP::new()));
+        assert_eq!(diverse[1], path!(crate : g, "s" -> "a1" -> "b1" -> "a2" -> "b2" -> "a3" -| "t"));
+
+        // Allowing any overlap is just the K shortest paths
+        assert_eq!(k_shortest_diverse(&g, "s", "t", 3, 1.0, YenKSP::new(DijkstraSSSP::new())), shortest);
+        assert!(k_shortest_diverse(&g, "s", "t", 0, 0.2, YenKSP::new(DijkstraSSSP::new())).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_k_shortest_diverse_invalid_threshold() { k_shortest_diverse(&make_graph(), "s", "t", 2, 1.5, YenKSP::new(DijkstraSSSP::new())); }
+}
+
+
+
+
+
+/***** CONSTANTS *****/
+/// How many times more candidates than the paths asked for are considered.
+pub const CANDIDATE_FACTOR: usize = 8;
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Collects the edges traversed by a path as pairs of nodes.
+///
+/// # Arguments
+/// - `path`: The [`Path`] to collect the edges of.
+///
+/// # Returns
+/// An iterator over the pairs of consecutive nodes in `path`, with the smallest one first such that the direction doesn't matter.
+#[inline]
+fn hops<'p, 'g>(path: &'p Path<'g>) -> impl 'p + Iterator<Item = (&'g str, &'g str)> {
+    path.hops.windows(2).map(|hops| if hops[0].0 <= hops[1].0 { (hops[0].0, hops[1].0) } else { (hops[1].0, hops[0].0) })
+}
+
+/// Computes the Jaccard similarity of two sets of edges.
+///
+/// # Arguments
+/// - `lhs`: The one set.
+/// - `rhs`: The other set.
+///
+/// # Returns
+/// The size of their intersection divided by that of their union, which is 1 if both are empty.
+#[inline]
+fn jaccard(lhs: &HashSet<(&str, &str)>, rhs: &HashSet<(&str, &str)>) -> f64 {
+    let shared: usize = lhs.intersection(rhs).count();
+    let union: usize = lhs.len() + rhs.len() - shared;
+    if union == 0 { 1.0 } else { shared as f64 / union as f64 }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Finds K short paths from one node to another that are diverse, i.e., that don't share too many edges.
+///
+/// The K shortest paths are often nearly identical, e.g., differing in a single detour. Instead, this considers the
+/// [`CANDIDATE_FACTOR`]` * k` shortest paths in order of cost, and greedily selects those whose similarity to all paths selected before is
+/// at most `similarity_threshold`. The similarity of two paths is the Jaccard index of the edges they traverse (as unordered pairs of
+/// nodes), i.e., the number of edges they share divided by the number of edges in either.
+///
+/// Note that this trades optimality for diversity: the paths returned are not the K shortest ones (except for the first), nor are they
+/// guaranteed to be the K shortest diverse ones, as those may be beyond the candidates considered.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to find in.
+/// - `src`: The source node to find paths from.
+/// - `dst`: The destination node to find paths to.
+/// - `k`: The number of paths to find.
+/// - `similarity_threshold`: The largest similarity allowed between any two paths returned, between `0.0` (they may not share any edge)
+///   and `1.0` (anything goes, which gives the K shortest paths).
+/// - `alg`: The [`KShortestPath`] algorithm to find the candidates with.
+///
+/// # Returns
+/// A list of at most `k` paths, ordered by cost. It is shorter if not enough candidates are diverse enough.
+///
+/// # Panics
+/// This function panics if `similarity_threshold` is not between 0 and 1, or if `alg` panics (e.g., because `src` or `dst` are not in the
+/// given `graph`).
+#[track_caller]
+pub fn k_shortest_diverse<'g>(
+    graph: &'g Graph,
+    src: &str,
+    dst: &str,
+    k: usize,
+    similarity_threshold: f64,
+    mut alg: impl KShortestPath,
+) -> Vec<Path<'g>> {
+    if !(0.0..=1.0).contains(&similarity_threshold) {
+        panic!("Cannot select paths with a similarity of at most {similarity_threshold} (must be between 0 and 1)");
+    }
+    if k == 0 {
+        return Vec::new();
+    }
+
+    // Go through the candidates from cheap to expensive, keeping those that are different enough
+    let candidates: Vec<Path<'g>> = alg.k_shortest_paths(graph, src, dst, k.saturating_mul(CANDIDATE_FACTOR));
+    let mut res: Vec<Path<'g>> = Vec::with_capacity(path_capacity(k));
+    let mut chosen: Vec<HashSet<(&'g str, &'g str)>> = Vec::with_capacity(path_capacity(k));
+    for path in candidates {
+        let edges: HashSet<(&'g str, &'g str)> = hops(&path).collect();
+        if chosen.iter().all(|other| jaccard(&edges, other) <= similarity_threshold) {
+            chosen.push(edges);
+            res.push(path);
+            if res.len() >= k {
+                break;
+            }
+        }
+    }
+    res
+}
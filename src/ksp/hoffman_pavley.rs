@@ -0,0 +1,293 @@
+//  HOFFMAN PAVLEY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:10:37
+//  Last edited:
+//    16 Oct 2026, 19:28:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements the deviation-based KSP algorithm by Hoffman and Pavley
+//!   [3], which finds the K shortest paths that may visit nodes more than
+//!   once.
+//
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use arrayvec::ArrayString;
+use ksp_graph::{Edge, Graph};
+
+use super::{path_capacity, tie_limit, KShortestPath, TieBreak};
+use crate::cost::OrderedCost;
+use crate::path::Path;
+use crate::sssp::{ShortestPathTree, ShortestTree};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ksp::brute_force::BruteForceKSP;
+    use crate::path;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+    use crate::utils::{load_bench, load_graph};
+
+    #[test]
+    fn test_hoffman_pavley_ksp_cities() {
+        for _ in 0..10 {
+            let g: Graph = load_graph("cities");
+            assert_eq!(HoffmanPavleyKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Berlin", 1), vec![
+                path!(crate : g, "Amsterdam" -| "Berlin")
+            ]);
+            assert_eq!(HoffmanPavleyKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Berlin", "Chicago", 1), vec![
+                path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago")
+            ]);
+            assert_eq!(HoffmanPavleyKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Berlin", 3), vec![
+                path!(crate : g, "Amsterdam" -| "Berlin"),
+                path!(crate : g, "Amsterdam" -> "Dorchester" -> "Amsterdam" -| "Berlin"),
+                path!(crate : g, "Amsterdam" -> "Berlin" -> "Amsterdam" -| "Berlin"),
+            ]);
+        }
+    }
+
+    #[test]
+    fn test_hoffman_pavley_ksp_reference() {
+        let g: Graph = load_graph("cities");
+        for src in g.nodes.keys() {
+            for dst in g.nodes.keys() {
+                for k in 1..=4 {
+                    // NOTE: We compare costs, as going round a loop either way results in different paths with the same cost
+                    let paths: Vec<Path> = HoffmanPavleyKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, k);
+                    let reference: Vec<Path> = BruteForceKSP::non_simple().k_shortest_paths(&g, src, dst, k);
+                    assert_eq!(paths.len(), reference.len(), "Disagreement with reference for {src} -> {dst} (k = {k})");
+                    for (path, reference) in paths.iter().zip(&reference) {
+                        assert!(path.is_connected_in(&g));
+                        assert!((path.cost() - reference.cost()).abs() < 1e-6, "Disagreement with reference for {src} -> {dst} (k = {k})");
+                    }
+                    for (i, path) in paths.iter().enumerate() {
+                        assert!(!paths[i + 1..].contains(path), "Duplicate path {path} for {src} -> {dst} (k = {k})");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hoffman_pavley_ksp_unconnected() {
+        let g: Graph = load_graph("cities");
+        let blocked = |e: &Edge| if e.id.as_str() == "Chicago-Dorchester" { f64::INFINITY } else { e.cost };
+        assert_eq!(HoffmanPavleyKSP::new(DijkstraSSSP::new()).k_shortest_paths_weighted(&g, "Amsterdam", "Chicago", 3, blocked), vec![]);
+        assert_eq!(HoffmanPavleyKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Chicago", 0), vec![]);
+    }
+
+    #[test]
+    fn test_hoffman_pavley_ksp_zero_cost() {
+        // Going back and forth over the free edge gives infinitely many paths with the same cost, of which only some are looked at
+        let g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 0.0)]);
+        for tie_break in [TieBreak::CostOnly, TieBreak::FewerHops, TieBreak::Lexicographic] {
+            for k in 1..=3 {
+                let paths: Vec<Path> = HoffmanPavleyKSP::new(DijkstraSSSP::new()).with_tie_break(tie_break).k_shortest_paths(&g, "A", "B", k);
+                assert_eq!(paths.len(), k, "{tie_break:?}");
+                assert_eq!(paths[0], path!(crate : g, "A" -| "B"), "{tie_break:?}");
+                assert!(paths.iter().all(|path| path.cost() == 1.0), "{tie_break:?}");
+            }
+        }
+        assert_eq!(HoffmanPavleyKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "A", "B", 2), vec![
+            path!(crate : g, "A" -| "B"),
+            path!(crate : g, "A" -> "B" -> "C" -| "B")
+        ]);
+    }
+
+    #[test]
+    fn test_hoffman_pavley_ksp_india35() {
+        // Agrees with the other non-simple algorithm on a bigger graph
+        let g: Graph = load_bench("india35");
+        let paths: Vec<Path> = HoffmanPavleyKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "12", "33", 10);
+        let reference: Vec<Path> = crate::ksp::wikipedia::WikipediaKSP::new().k_shortest_paths(&g, "12", "33", 10);
+        assert_eq!(paths.len(), 10);
+        assert_eq!(paths[0], path!(crate : g, "12" -| "33"));
+        for (path, reference) in paths.iter().zip(&reference) {
+            assert!((path.cost() - reference.cost()).abs() < 1e-6);
+        }
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// A candidate path in the queue, which is a path that deviates from the shortest path tree at its end and then follows the tree to the
+/// destination.
+#[derive(Clone, Debug)]
+struct Candidate<'g> {
+    /// The total cost of the path, i.e., of the `prefix` plus that of the tree path from its end.
+    cost:   OrderedCost,
+    /// The order in which the candidate was found, to break ties deterministically.
+    order:  usize,
+    /// The explicit part of the path, up to and including its last deviation.
+    prefix: Path<'g>,
+}
+impl<'g> Eq for Candidate<'g> {}
+impl<'g> Ord for Candidate<'g> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // NOTE: Reversed, as the `BinaryHeap` is a max-heap
+        other.cost.cmp(&self.cost).then_with(|| other.order.cmp(&self.order))
+    }
+}
+impl<'g> PartialEq for Candidate<'g> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl<'g> PartialOrd for Candidate<'g> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines the deviation-based KSP algorithm by Hoffman and Pavley \[3\].
+///
+/// Like [`WikipediaKSP`](super::wikipedia::WikipediaKSP), it finds paths that may visit the same node more than once. However, instead of
+/// exploring all paths from the source in order, it computes the tree of shortest paths to the destination once and then only enumerates
+/// _deviations_ from it: every path is described by the edges where it leaves the tree, after which it follows the tree to the destination.
+/// Every candidate popped from the queue is the next shortest path, so only K of them are ever expanded (plus up to K more that tie with the K-th).
+///
+/// # References
+/// \[3\] Hoffman, W., Pavley, R. A Method for the Solution of the Nth Best Path Problem.
+/// _J. ACM_ 6, 4, 506–514 (1959). https://doi.org/10.1145/320998.321004.
+#[derive(Clone, Copy, Debug)]
+pub struct HoffmanPavleyKSP<S> {
+    /// The SSSP algorithm used to build the shortest path tree.
+    sssp:      S,
+    /// How to order paths with the same cost.
+    tie_break: TieBreak,
+}
+impl<S> HoffmanPavleyKSP<S> {
+    /// Constructor for the HoffmanPavleyKSP.
+    ///
+    /// # Arguments
+    /// - `sssp`: The SSSP algorithm to use. Must be able to compute a [`ShortestTree`].
+    ///
+    /// # Returns
+    /// A new HoffmanPavleyKSP instance that breaks ties with [`TieBreak::FewerHops`].
+    #[inline]
+    pub const fn new(sssp: S) -> Self { Self { sssp, tie_break: TieBreak::FewerHops } }
+
+    /// Sets how paths with the same cost are ordered.
+    ///
+    /// This also decides which paths are returned if more paths have the same cost as the K-th one than fit. To do so, up to K of those are
+    /// found before cutting off, which costs extra searches (except for [`TieBreak::CostOnly`]). See [`TieBreak`] for more information.
+    ///
+    /// # Arguments
+    /// - `tie_break`: The [`TieBreak`] to use.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub const fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+}
+impl<S: ShortestPathTree> KShortestPath for HoffmanPavleyKSP<S> {
+    #[track_caller]
+    fn k_shortest_paths_weighted<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize, weight: impl Fn(&Edge) -> f64) -> Vec<Path<'g>> {
+        // Assert that both nodes exists
+        let src: &'g str = if let Some((key, _)) = graph.nodes.get_key_value(&ArrayString::from(src).unwrap()) {
+            key
+        } else {
+            panic!("Unknown source node '{src}'");
+        };
+        if !graph.nodes.contains_key(&ArrayString::from(dst).unwrap()) {
+            panic!("Unknown destination node '{dst}'");
+        }
+        // There's nothing to find if we're asked for no paths
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // Build the tree of shortest paths _to_ the destination. If edges are undirected, that's the same as the one from it, where the
+        // predecessor of every node is its next hop towards the destination. Else, it's the one from it in the reversed graph.
+        let tree: ShortestTree<'g> = if graph.directed {
+            let reversed: Graph = graph.reversed();
+            let key = |node: &str| -> &'g str { graph.nodes.get_key_value(node).unwrap().0.as_str() };
+            let tree: ShortestTree = self.sssp.shortest_tree_weighted(&reversed, dst, &weight);
+            tree.into_iter().map(|(node, (cost, next))| (key(node), (cost, next.map(key)))).collect()
+        } else {
+            self.sssp.shortest_tree_weighted(graph, dst, &weight)
+        };
+        let (src_cost, _): (f64, Option<&'g str>) = tree[src];
+        if src_cost == f64::INFINITY {
+            return Vec::new();
+        }
+
+        // Start with the shortest path itself, which deviates nowhere
+        let mut shortest: Vec<Path<'g>> = Vec::with_capacity(path_capacity(k));
+        let mut todo: BinaryHeap<Candidate<'g>> =
+            BinaryHeap::from([Candidate { cost: OrderedCost(src_cost), order: 0, prefix: Path { hops: vec![(src, 0.0)] } }]);
+        let mut order: usize = 1;
+        // NOTE: Beyond K paths, only ones tying with the K-th matter, and only if the tie-break may prefer them
+        while shortest.len() < tie_limit(self.tie_break, k) {
+            let Candidate { prefix, .. } = match todo.pop() {
+                Some(cand) => cand,
+                None => break,
+            };
+
+            // Complete the path by following the tree from the prefix' end
+            let deviation: usize = prefix.hops.len() - 1;
+            let mut path: Path<'g> = prefix;
+            let (mut node, mut cost): (&'g str, f64) = path.hops[deviation];
+            while let (_, Some(next)) = tree[node] {
+                // NOTE: We re-add the edge's cost instead of using the tree's distances to accumulate rounding errors like other algorithms
+                cost += graph.successors(node).filter(|(n, _)| *n == next).map(|(_, e)| weight(e)).fold(f64::INFINITY, f64::min);
+                path.hops.push((next, cost));
+                node = next;
+            }
+            if shortest.len() >= k && OrderedCost(path.cost()) != OrderedCost(shortest[k - 1].cost()) {
+                break;
+            }
+
+            // Then find the new candidates by deviating from the tree part of this path. Note that this includes the destination itself, as
+            // a path may reach it, leave it and come back.
+            for hop in deviation..path.hops.len() {
+                let (node, cost): (&'g str, f64) = path.hops[hop];
+                let (_, next): (f64, Option<&'g str>) = tree[node];
+
+                // Only consider the cheapest edge to every neighbour, as parallel ones result in the same path
+                let mut neighbours: HashMap<&'g str, f64> = HashMap::new();
+                for (neighbour, e) in graph.successors(node) {
+                    let w: f64 = weight(e);
+                    if Some(neighbour) == next || w == f64::INFINITY || tree[neighbour].0 == f64::INFINITY {
+                        continue;
+                    }
+                    let best: &mut f64 = neighbours.entry(neighbour).or_insert(f64::INFINITY);
+                    if w < *best {
+                        *best = w;
+                    }
+                }
+                let mut neighbours: Vec<(&'g str, f64)> = neighbours.into_iter().collect();
+                neighbours.sort_by_key(|(neighbour, _)| *neighbour);
+                for (neighbour, w) in neighbours {
+                    let mut prefix: Path<'g> = Path { hops: path.hops[..=hop].to_vec() };
+                    prefix.hops.push((neighbour, cost + w));
+                    todo.push(Candidate { cost: OrderedCost(cost + w + tree[neighbour].0), order, prefix });
+                    order += 1;
+                }
+            }
+            shortest.push(path);
+        }
+
+        // Deviations with the same cost are only found after the path they deviate from, so order those as asked before cutting off
+        shortest.sort_by(|p1, p2| self.tie_break.compare(p1, p2));
+        shortest.truncate(k);
+        shortest
+    }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    24 Jul 2024, 01:44:45
 //  Last edited:
-//    24 Jul 2024, 23:31:21
+//    16 Oct 2026, 19:28:17
 //  Auto updated?
 //    Yes
 //
@@ -12,19 +12,151 @@
 //!   Defines the various K-shortest Path algorithms.
 //
 
+#[cfg(any(test, feature = "reference"))]
+pub mod brute_force;
+pub mod diverse;
+pub mod hoffman_pavley;
+pub mod multi;
+pub mod sensitivity;
+pub mod suurballe;
+pub mod turn_penalty;
 pub mod wikipedia;
 pub mod yen;
 
 // Imports
+use std::cmp::Ordering;
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FResult};
 use std::str::FromStr;
 
-use ksp_graph::Graph;
+use ksp_graph::{Edge, Graph};
 
+use crate::cost::OrderedCost;
 use crate::path::Path;
 
 
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::path;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_directed() {
+        // All algorithms only follow edges forwards
+        // NOTE: Hoffman-Pavley considers parallel edges to give the same path, so we leave those out
+        let mut g: Graph = load_graph("directed");
+        g.edges.remove("B-C-2");
+        for src in g.nodes.keys() {
+            for dst in g.nodes.keys() {
+                for k in 1..=4 {
+                    let simple: Vec<f64> = brute_force::BruteForceKSP::simple().k_shortest_paths(&g, src, dst, k).iter().map(Path::cost).collect();
+                    let non_simple: Vec<f64> =
+                        brute_force::BruteForceKSP::non_simple().k_shortest_paths(&g, src, dst, k).iter().map(Path::cost).collect();
+                    let paths: [(Vec<Path>, &Vec<f64>); 3] = [
+                        (wikipedia::WikipediaKSP::new().k_shortest_paths(&g, src, dst, k), &non_simple),
+                        (yen::YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, k), &simple),
+                        (hoffman_pavley::HoffmanPavleyKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, k), &non_simple),
+                    ];
+                    for (paths, reference) in paths {
+                        assert!(paths.iter().all(|p| p.validate_against(&g).is_ok()), "Invalid path for {src} -> {dst} (k = {k})");
+                        assert_eq!(paths.iter().map(Path::cost).collect::<Vec<f64>>(), *reference, "Disagreement for {src} -> {dst} (k = {k})");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_relabelled() {
+        // Routing finds the same paths after renaming nodes, just with the new names
+        let g: Graph = load_graph("cities");
+        let mut renamed: Graph = g.clone();
+        renamed.relabel_nodes(&HashMap::from([("Berlin", "BER"), ("Dorchester", "Node 1"), ("Chicago", "ORD")])).unwrap();
+        let paths: Vec<Path> = yen::YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Berlin", "Chicago", 3);
+        let renamed_paths: Vec<Path> = yen::YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&renamed, "BER", "ORD", 3);
+        assert_eq!(renamed_paths.len(), paths.len());
+        for (path, renamed_path) in paths.iter().zip(&renamed_paths) {
+            assert!(renamed_path.validate_against(&renamed).is_ok());
+            assert_eq!(renamed_path.cost(), path.cost());
+        }
+        assert_eq!(renamed_paths[0].hops.iter().map(|(n, _)| *n).collect::<Vec<&str>>(), vec!["BER", "Amsterdam", "Node 1", "ORD"]);
+    }
+
+    #[test]
+    fn test_start_cost() {
+        let g: Graph = load_graph("cities");
+        for src in g.nodes.keys() {
+            for dst in g.nodes.keys() {
+                let paths: Vec<Path> = yen::YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, 3);
+                for start_cost in [0.0, 12.5, -3.0] {
+                    let started: Vec<Path> = yen::YenKSP::new(DijkstraSSSP::new()).k_shortest_paths_with_start_cost(&g, src, dst, 3, start_cost);
+                    assert_eq!(started, paths);
+                    for (started, path) in started.iter().zip(&paths) {
+                        assert_eq!(started.hops[0].1, start_cost);
+                        assert!((started.cost() - (path.cost() + start_cost)).abs() < 1e-9, "{src} -> {dst} (start cost {start_cost})");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_start_cost_infinite() {
+        wikipedia::WikipediaKSP::new().k_shortest_paths_with_start_cost(&load_graph("cities"), "Berlin", "Chicago", 1, f64::INFINITY);
+    }
+
+    #[test]
+    fn test_tie_break() {
+        // There are two routes costing 3.0 from A to E, one of which has fewer hops and the other which is lexicographically first
+        let g: Graph = load_graph("ties");
+        let short: Path = path!(crate : g, "A" -> "D" -| "E");
+        let long: Path = path!(crate : g, "A" -> "B" -> "C" -| "E");
+        assert_eq!(short.cost(), long.cost());
+        assert_eq!(TieBreak::FewerHops.compare(&short, &long), Ordering::Less);
+        assert_eq!(TieBreak::Lexicographic.compare(&short, &long), Ordering::Greater);
+        assert_eq!(TieBreak::CostOnly.compare(&short, &long), Ordering::Equal);
+        assert_eq!(TieBreak::default(), TieBreak::FewerHops);
+
+        // All algorithms order them as asked
+        for _ in 0..10 {
+            for (tie_break, expected) in [(TieBreak::FewerHops, [&short, &long]), (TieBreak::Lexicographic, [&long, &short])] {
+                let paths: [Vec<Path>; 4] = [
+                    wikipedia::WikipediaKSP::new().with_tie_break(tie_break).k_shortest_paths(&g, "A", "E", 2),
+                    yen::YenKSP::new(DijkstraSSSP::new()).with_tie_break(tie_break).k_shortest_paths(&g, "A", "E", 2),
+                    hoffman_pavley::HoffmanPavleyKSP::new(DijkstraSSSP::new()).with_tie_break(tie_break).k_shortest_paths(&g, "A", "E", 2),
+                    turn_penalty::TurnPenaltyKSP::new(|_: &Edge, _: &str, _: &Edge| 0.0).with_tie_break(tie_break).k_shortest_paths(&g, "A", "E", 2),
+                ];
+                for paths in paths {
+                    assert_eq!(paths.iter().collect::<Vec<&Path>>(), expected, "{tie_break:?}");
+                }
+            }
+
+            // They also pick the right one if only one fits
+            for (tie_break, expected) in [(TieBreak::FewerHops, &short), (TieBreak::Lexicographic, &long)] {
+                let paths: [Vec<Path>; 4] = [
+                    wikipedia::WikipediaKSP::new().with_tie_break(tie_break).k_shortest_paths(&g, "A", "E", 1),
+                    yen::YenKSP::new(DijkstraSSSP::new()).with_tie_break(tie_break).k_shortest_paths(&g, "A", "E", 1),
+                    hoffman_pavley::HoffmanPavleyKSP::new(DijkstraSSSP::new()).with_tie_break(tie_break).k_shortest_paths(&g, "A", "E", 1),
+                    turn_penalty::TurnPenaltyKSP::new(|_: &Edge, _: &str, _: &Edge| 0.0).with_tie_break(tie_break).k_shortest_paths(&g, "A", "E", 1),
+                ];
+                for paths in paths {
+                    assert_eq!(paths, vec![expected.clone()], "{tie_break:?}");
+                }
+            }
+        }
+    }
+}
+
+
+
+
+
 /***** ERRORS *****/
 /// Defines the error thrown when an unknown [`Algorithm`] was parsed.
 #[derive(Debug)]
@@ -42,10 +174,68 @@ impl Error for UnknownAlgorithmError {}
 
 
 
+/***** CONSTANTS *****/
+/// The most paths that algorithms allocate room for before finding them.
+const MAX_PREALLOCATED_PATHS: usize = 256;
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Computes for how many paths to allocate room up front when asked for K of them.
+///
+/// K may be far larger than the number of paths that exist (e.g., [`usize::MAX`] to ask for all of them), so it isn't trusted blindly.
+///
+/// # Arguments
+/// - `k`: The number of paths asked for.
+///
+/// # Returns
+/// `k`, but at most [`MAX_PREALLOCATED_PATHS`].
+#[inline]
+pub(crate) fn path_capacity(k: usize) -> usize { k.min(MAX_PREALLOCATED_PATHS) }
+
+/// Computes the maximum cost of paths returned by [`KShortestPath::paths_within()`].
+///
+/// # Arguments
+/// - `shortest`: The cost of the shortest path.
+/// - `ratio`: The factor by which paths may be more expensive than the shortest one.
+///
+/// # Returns
+/// `ratio * shortest`, plus a small tolerance such that paths with the same cost but summed in a different order are not missed.
+#[inline]
+pub(crate) fn cost_ceiling(shortest: f64, ratio: f64) -> f64 {
+    // NOTE: Special case to avoid `inf * 0.0` being NaN
+    if ratio == f64::INFINITY {
+        return f64::INFINITY;
+    }
+    ratio * shortest + shortest.abs() * 1e-9
+}
+
+/// Computes how many paths an algorithm that doesn't find them in tie-break order may find before cutting off at K.
+///
+/// Paths found after the K-th one may still be preferred by the tie-break if they have the same cost. However, there may be very many of
+/// those (or infinitely many, for algorithms whose paths can go round cycles of zero cost), so at most another K are considered.
+///
+/// # Arguments
+/// - `tie_break`: How paths with the same cost are ordered.
+/// - `k`: The number of paths asked for.
+///
+/// # Returns
+/// `k` for [`TieBreak::CostOnly`], where the order of paths with the same cost doesn't matter, or `2 * k` otherwise.
+#[inline]
+pub(crate) fn tie_limit(tie_break: TieBreak, k: usize) -> usize { if tie_break == TieBreak::CostOnly { k } else { k.saturating_mul(2) } }
+
+
+
+
+
 /***** LIBRARY *****/
 /// Overview of all algorithms in the libary.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Algorithm {
+    /// The deviation-based algorithm by Hoffman and Pavley [3], which finds paths that may visit nodes more than once.
+    HoffmanPavley,
     /// The default, simplest version of a KSP-algorithm as presented by Wikipedia.
     Wikipedia,
     /// The default, simplest version of a KSP-algorithm as presented by the `PeeK`-paper [1].
@@ -57,7 +247,20 @@ impl Algorithm {
     /// # Returns
     /// A static list of the implemented algorithms.
     #[inline]
-    pub const fn all() -> &'static [Self] { &[Self::Wikipedia, Self::Yen] }
+    pub const fn all() -> &'static [Self] { &[Self::HoffmanPavley, Self::Wikipedia, Self::Yen] }
+
+    /// Returns the identifier by which this algorithm is parsed.
+    ///
+    /// # Returns
+    /// A static string that [`Algorithm::from_str()`] parses back to this algorithm.
+    #[inline]
+    pub const fn id(&self) -> &'static str {
+        match self {
+            Self::HoffmanPavley => "hoffman-pavley",
+            Self::Wikipedia => "wikipedia",
+            Self::Yen => "yen",
+        }
+    }
 
     /// Whether this algorithms _needs_ an SSSP defined or not.
     ///
@@ -66,6 +269,7 @@ impl Algorithm {
     #[inline]
     pub const fn needs_sssp(&self) -> bool {
         match self {
+            Self::HoffmanPavley => true,
             Self::Wikipedia => false,
             Self::Yen => true,
         }
@@ -77,6 +281,7 @@ impl FromStr for Algorithm {
     #[inline]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
+            "hoffman-pavley" => Ok(Self::HoffmanPavley),
             "wikipedia" => Ok(Self::Wikipedia),
             "yen" => Ok(Self::Yen),
             other => Err(UnknownAlgorithmError { unknown: other.into() }),
@@ -86,6 +291,45 @@ impl FromStr for Algorithm {
 
 
 
+/// Defines how KSP algorithms order paths with the same cost.
+///
+/// Algorithms that don't find paths in this order, like [`YenKSP`](yen::YenKSP) and [`HoffmanPavleyKSP`](hoffman_pavley::HoffmanPavleyKSP),
+/// keep searching after the K-th path for up to K more with the same cost, such that the tie-break decides which are returned. This costs
+/// extra searches for anything but [`TieBreak::CostOnly`], including the default, so use that to measure the algorithms proper.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum TieBreak {
+    /// Only compare paths by cost. Paths with the same cost are found in whatever order the algorithm happens to find them in.
+    CostOnly,
+    /// Prefer the path with fewer hops (e.g., as a proxy for latency), and then the [lexicographically](TieBreak::Lexicographic) smaller
+    /// one.
+    #[default]
+    FewerHops,
+    /// Prefer the path whose sequence of node identifiers is lexicographically smaller.
+    Lexicographic,
+}
+impl TieBreak {
+    /// Compares two paths by cost, breaking ties as defined by this TieBreak.
+    ///
+    /// # Arguments
+    /// - `lhs`: The first [`Path`] to compare.
+    /// - `rhs`: The second [`Path`] to compare.
+    ///
+    /// # Returns
+    /// [`Ordering::Less`] if `lhs` should be preferred over `rhs`, [`Ordering::Greater`] if the other way around, or [`Ordering::Equal`] if
+    /// they're the same as far as this TieBreak is concerned.
+    pub fn compare(&self, lhs: &Path, rhs: &Path) -> Ordering {
+        let cost: Ordering = OrderedCost(lhs.cost()).cmp(&OrderedCost(rhs.cost()));
+        let lexicographic = || lhs.hops.iter().map(|(node, _)| node).cmp(rhs.hops.iter().map(|(node, _)| node));
+        match self {
+            Self::CostOnly => cost,
+            Self::FewerHops => cost.then_with(|| lhs.hops.len().cmp(&rhs.hops.len())).then_with(lexicographic),
+            Self::Lexicographic => cost.then_with(lexicographic),
+        }
+    }
+}
+
+
+
 /// Defines an abstraction over various algorithms.
 pub trait KShortestPath {
     /// Finds The K shortest paths from one node to another.
@@ -99,7 +343,165 @@ pub trait KShortestPath {
     /// # Returns
     /// A list of the shortest paths found. Is at most `k` elements long.
     ///
+    /// Note that `k` is unsigned, so there is no such thing as a negative `k`. If `k` is 0, then no work is done and an empty list is returned.
+    ///
     /// # Panics
     /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
-    fn k_shortest_paths<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize) -> Vec<Path<'g>>;
+    #[inline]
+    #[track_caller]
+    fn k_shortest_paths<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize) -> Vec<Path<'g>> {
+        self.k_shortest_paths_weighted(graph, src, dst, k, |e: &Edge| e.cost)
+    }
+
+    /// Finds The K shortest paths from one node to another, using a custom metric for edges.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `k`: The number of paths to find.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`]. [`KShortestPath::k_shortest_paths()`] uses `|e| e.cost`.
+    ///
+    /// # Returns
+    /// A list of the shortest paths found. Is at most `k` elements long. Their costs are those computed by `weight`.
+    ///
+    /// Note that `k` is unsigned, so there is no such thing as a negative `k`. If `k` is 0, then no work is done and an empty list is returned.
+    ///
+    /// The paths returned are always distinct. If fewer than `k` distinct paths exist, then only those are returned (i.e., the list is never padded with repeats).
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
+    fn k_shortest_paths_weighted<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize, weight: impl Fn(&Edge) -> f64) -> Vec<Path<'g>>;
+
+    /// Finds The K shortest paths from one node to another, reporting every path to a callback as it's found.
+    ///
+    /// By default, this finds all paths with [`KShortestPath::k_shortest_paths()`] first and only then calls `f` for each of them.
+    /// Algorithms that finalize paths one-by-one override this to call it right away, e.g., [`WikipediaKSP`](wikipedia::WikipediaKSP). This
+    /// allows streaming the paths somewhere without waiting for all K of them.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `k`: The number of paths to find.
+    /// - `f`: A function called with the index and the path for every path found, in order of cost.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
+    #[inline]
+    #[track_caller]
+    fn k_shortest_paths_with_callback<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize, mut f: impl FnMut(usize, &Path<'g>)) {
+        for (i, path) in self.k_shortest_paths(graph, src, dst, k).iter().enumerate() {
+            f(i, path);
+        }
+    }
+
+    /// Finds The K shortest paths from one node to another, where entering the network at `src` has a cost.
+    ///
+    /// As every path pays the same cost to start, this doesn't change which paths are found, only their costs. This is useful to compare
+    /// paths from different sources fairly.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `k`: The number of paths to find.
+    /// - `start_cost`: The cost of the first hop of every path, which is added to the costs of all further hops. `0.0` gives the same paths
+    ///   as [`KShortestPath::k_shortest_paths()`].
+    ///
+    /// # Returns
+    /// A list of the shortest paths found, as [`KShortestPath::k_shortest_paths()`] does, except that all costs are `start_cost` higher.
+    ///
+    /// # Panics
+    /// This function panics if `start_cost` is not finite. It is also allowed to panic if the given `src` or `dst` are not in the given
+    /// `graph`.
+    #[track_caller]
+    fn k_shortest_paths_with_start_cost<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize, start_cost: f64) -> Vec<Path<'g>> {
+        if !start_cost.is_finite() {
+            panic!("Cannot start paths at a cost of {start_cost} (must be finite)");
+        }
+
+        let mut paths: Vec<Path<'g>> = self.k_shortest_paths(graph, src, dst, k);
+        for path in &mut paths {
+            for (_, cost) in &mut path.hops {
+                *cost += start_cost;
+            }
+        }
+        paths
+    }
+
+    /// Finds all paths from one node to another that are at most a factor more expensive than the shortest one.
+    ///
+    /// By default, this repeatedly asks for [`KShortestPath::k_shortest_paths()`] with a doubling `k` until a path beyond the ceiling is
+    /// found. Algorithms may override this if they can stop at a cost more efficiently.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `ratio`: The factor by which paths may be more expensive than the shortest one. `1.0` only returns the optimal path(s).
+    ///
+    /// # Returns
+    /// A list of all paths with a cost of at most `ratio` times that of the shortest one, ordered by cost. It is empty if `dst` is not
+    /// reachable from `src`.
+    ///
+    /// Note that, unlike for [`KShortestPath::k_shortest_paths()`], the size of the result depends on the graph and can be very large. In
+    /// fact, for algorithms that find paths visiting the same node more than once, this doesn't terminate if there are (infinitely many)
+    /// paths within the ceiling because of cycles of zero cost.
+    ///
+    /// # Panics
+    /// This function panics if `ratio` is NaN or smaller than 1. It is also allowed to panic if the given `src` or `dst` are not in the
+    /// given `graph`.
+    #[track_caller]
+    fn paths_within<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, ratio: f64) -> Vec<Path<'g>> {
+        if ratio.is_nan() || ratio < 1.0 {
+            panic!("Cannot find paths within {ratio} times the shortest one (must be at least 1)");
+        }
+
+        let mut k: usize = 1;
+        loop {
+            let mut paths: Vec<Path<'g>> = self.k_shortest_paths(graph, src, dst, k);
+            let ceiling: f64 = match paths.first() {
+                Some(path) => cost_ceiling(path.cost(), ratio),
+                None => return paths,
+            };
+
+            // We've seen everything if there are no more paths, or if the last one is already too expensive
+            if paths.len() < k || paths[paths.len() - 1].cost() > ceiling {
+                paths.retain(|path| path.cost() <= ceiling);
+                return paths;
+            }
+            k = k.saturating_mul(2);
+        }
+    }
+
+    /// Finds more shortest paths from one node to another, continuing from paths found earlier.
+    ///
+    /// By default, this computes the K shortest paths from scratch and drops the ones already found. Algorithms that can continue where they
+    /// left off expose that more efficiently, e.g., [`WikipediaKSP::start()`](wikipedia::WikipediaKSP::start()).
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `already`: The paths found earlier by this algorithm for the same graph and endpoints, i.e., the first `already.len()` shortest
+    ///   paths.
+    /// - `k`: The total number of paths to have found, including `already`.
+    ///
+    /// # Returns
+    /// A list of the next shortest paths after `already`, such that together they're the same as [`KShortestPath::k_shortest_paths()`] with
+    /// `k`. Is at most `k - already.len()` elements long, and empty if `k` isn't larger than that.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
+    #[inline]
+    #[track_caller]
+    fn k_shortest_paths_resume<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, already: &[Path<'g>], k: usize) -> Vec<Path<'g>> {
+        if k <= already.len() {
+            return Vec::new();
+        }
+        let mut paths: Vec<Path<'g>> = self.k_shortest_paths(graph, src, dst, k);
+        paths.drain(..already.len().min(paths.len()));
+        paths
+    }
 }
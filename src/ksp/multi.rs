@@ -0,0 +1,143 @@
+//  MULTI.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:22:04
+//  Last edited:
+//    16 Oct 2026, 19:00:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements finding the K shortest paths from any of a set of sources
+//!   to any of a set of targets.
+//
+
+use arrayvec::ArrayString;
+use ksp_graph::Graph;
+
+use super::{path_capacity, KShortestPath};
+use crate::path::Path;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ksp::wikipedia::WikipediaKSP;
+    use crate::ksp::yen::YenKSP;
+    use crate::path;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_k_shortest_multi() {
+        let g: Graph = load_graph("cities");
+
+        // Chicago is closer to Edinburgh than to Berlin, but Berlin's path beats Edinburgh's second one
+        let paths: Vec<Path> = k_shortest_multi(&g, &["Berlin", "Edinburgh"], &["Chicago"], 2, YenKSP::new(DijkstraSSSP::new()));
+        assert_eq!(paths, vec![
+            path!(crate : g, "Edinburgh" -> "Dorchester" -| "Chicago"),
+            path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago"),
+        ]);
+        assert!(paths.iter().all(|path| path.hops.iter().all(|(node, _)| g.nodes.contains_key(*node))));
+
+        // The same goes for algorithms that may revisit nodes
+        let paths: Vec<Path> = k_shortest_multi(&g, &["Berlin", "Edinburgh"], &["Chicago"], 2, WikipediaKSP::new());
+        assert_eq!(paths[0], path!(crate : g, "Edinburgh" -> "Dorchester" -| "Chicago"));
+        assert!(paths.iter().all(|path| path.hops.iter().all(|(node, _)| g.nodes.contains_key(*node))));
+
+        // Multiple targets work the same, and nodes in both are paths of their own
+        let paths: Vec<Path> = k_shortest_multi(&g, &["Amsterdam"], &["Berlin", "Dorchester"], 2, YenKSP::new(DijkstraSSSP::new()));
+        assert_eq!(paths, vec![path!(crate : g, "Amsterdam" -| "Dorchester"), path!(crate : g, "Amsterdam" -| "Berlin")]);
+        let paths: Vec<Path> = k_shortest_multi(&g, &["Amsterdam", "Berlin"], &["Berlin"], 1, YenKSP::new(DijkstraSSSP::new()));
+        assert_eq!(paths, vec![Path { hops: vec![("Berlin", 0.0)] }]);
+        assert!(k_shortest_multi(&g, &[], &["Berlin"], 1, YenKSP::new(DijkstraSSSP::new())).is_empty());
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Chooses an identifier for a virtual node that isn't in the graph yet.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to add the node to.
+/// - `base`: The preferred identifier, which is suffixed with `'` until it's unique.
+///
+/// # Returns
+/// An identifier that no node in `graph` has.
+fn virtual_id(graph: &Graph, base: &str) -> ArrayString<64> {
+    let mut id: String = base.into();
+    while graph.nodes.contains_key(id.as_str()) {
+        id.push('\'');
+    }
+    ArrayString::from(&id).unwrap_or_else(|_| panic!("Cannot find an identifier for virtual node '{base}' that fits"))
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Finds the K shortest paths from any of a set of sources to any of a set of targets.
+///
+/// This runs the given algorithm on a copy of the graph with a virtual super-source, which has a zero-cost edge to every source, and a
+/// virtual super-sink, which every target has a zero-cost edge to. The copy is directed (undirected edges are added both ways), such that
+/// paths can't pass through the virtual nodes. They are stripped from the paths found, which thus start at one of the sources and end at
+/// one of the targets.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to find in.
+/// - `srcs`: The source nodes to find paths from.
+/// - `dsts`: The destination nodes to find paths to.
+/// - `k`: The number of paths to find.
+/// - `alg`: The [`KShortestPath`] algorithm to find the paths with.
+///
+/// # Returns
+/// A list of the (at most) `k` shortest paths from any node in `srcs` to any node in `dsts`. If a node is in both, the path consisting of
+/// only that node is among them (with cost 0).
+///
+/// # Panics
+/// This function panics if any of the `srcs` or `dsts` is not in the given `graph`, or if `alg` panics.
+#[track_caller]
+pub fn k_shortest_multi<'g>(graph: &'g Graph, srcs: &[&str], dsts: &[&str], k: usize, mut alg: impl KShortestPath) -> Vec<Path<'g>> {
+    for node in srcs.iter().chain(dsts) {
+        if !graph.nodes.contains_key(*node) {
+            panic!("Unknown node '{node}'");
+        }
+    }
+    if k == 0 || srcs.is_empty() || dsts.is_empty() {
+        return Vec::new();
+    }
+
+    // Build the directed copy with the virtual nodes
+    let (source, sink): (ArrayString<64>, ArrayString<64>) = (virtual_id(graph, "<super-source>"), virtual_id(graph, "<super-sink>"));
+    let mut edges: Vec<(ArrayString<64>, ArrayString<64>, f64)> = Vec::with_capacity(2 * graph.edges.len() + srcs.len() + dsts.len());
+    for edge in graph.edges.values() {
+        edges.push((edge.left, edge.right, edge.cost));
+        if !graph.directed && edge.left != edge.right {
+            edges.push((edge.right, edge.left, edge.cost));
+        }
+    }
+    edges.extend(srcs.iter().map(|src| (source, ArrayString::from(src).unwrap(), 0.0)));
+    edges.extend(dsts.iter().map(|dst| (ArrayString::from(dst).unwrap(), sink, 0.0)));
+    // NOTE: The identifiers don't matter, as paths only list nodes
+    let mut copy: Graph = Graph::from_edges(edges.into_iter().enumerate().map(|(i, (left, right, cost))| (i.to_string(), left, right, cost)));
+    copy.directed = true;
+    copy.nodes.extend(graph.nodes.clone());
+
+    // Find the paths, and then strip the virtual hops (which are free, so don't affect the costs)
+    let paths: Vec<Path> = alg.k_shortest_paths(&copy, source.as_str(), sink.as_str(), k);
+    let mut res: Vec<Path<'g>> = Vec::with_capacity(path_capacity(k));
+    for path in paths {
+        let hops: Vec<(&'g str, f64)> = path.hops[1..path.hops.len() - 1]
+            .iter()
+            .map(|(node, cost)| (graph.nodes.get_key_value(*node).map(|(id, _)| id.as_str()).unwrap(), *cost))
+            .collect();
+        res.push(Path { hops });
+    }
+    res
+}
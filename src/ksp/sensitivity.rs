@@ -0,0 +1,158 @@
+//  SENSITIVITY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 14:31:52
+//  Last edited:
+//    16 Oct 2026, 19:04:15
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a sensitivity analysis of the K shortest paths with
+//!   respect to the cost of a single edge.
+//
+
+use ksp_graph::{Edge, Graph};
+
+use super::KShortestPath;
+use crate::path::{OwnedPath, Path};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ksp::wikipedia::WikipediaKSP;
+    use crate::ksp::yen::YenKSP;
+    use crate::path;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+    use crate::utils::load_graph;
+
+    /// Computes what [`sensitivity()`] should compute by cloning the graph for every delta.
+    fn naive(graph: &Graph, src: &str, dst: &str, edge_id: &str, deltas: &[f64], k: usize, mut alg: impl KShortestPath) -> Vec<(f64, Vec<f64>)> {
+        let mut res: Vec<(f64, Vec<f64>)> = Vec::with_capacity(deltas.len());
+        for delta in deltas {
+            let mut graph: Graph = graph.clone();
+            graph.edges.get_mut(edge_id).unwrap().cost += delta;
+            res.push((*delta, alg.k_shortest_paths(&graph, src, dst, k).iter().map(Path::cost).collect()));
+        }
+        res
+    }
+
+    #[test]
+    fn test_sensitivity() {
+        let g: Graph = load_graph("cities");
+        let deltas: [f64; 6] = [-500.0, 0.0, 100.0, 1000.0, 10000.0, 100.0];
+        for (src, dst) in [("Amsterdam", "Chicago"), ("Berlin", "Chicago"), ("Amsterdam", "Berlin"), ("Chicago", "Chicago")] {
+            for k in 0..4 {
+                let res: Vec<(f64, Vec<f64>)> = sensitivity(&g, src, dst, "Chicago-Dorchester", deltas, k, YenKSP::new(DijkstraSSSP::new()))
+                    .into_iter()
+                    .map(|(delta, paths)| (delta, paths.iter().map(OwnedPath::cost).collect()))
+                    .collect();
+                assert_eq!(res, naive(&g, src, dst, "Chicago-Dorchester", &deltas, k, YenKSP::new(DijkstraSSSP::new())), "{src} -> {dst} (k = {k})");
+
+                let res: Vec<(f64, Vec<f64>)> = sensitivity(&g, src, dst, "Chicago-Dorchester", deltas, k, WikipediaKSP::new())
+                    .into_iter()
+                    .map(|(delta, paths)| (delta, paths.iter().map(OwnedPath::cost).collect()))
+                    .collect();
+                assert_eq!(res, naive(&g, src, dst, "Chicago-Dorchester", &deltas, k, WikipediaKSP::new()), "{src} -> {dst} (k = {k})");
+            }
+        }
+
+        // The paths themselves are those in the original graph
+        assert_eq!(sensitivity(&g, "Amsterdam", "Chicago", "Chicago-Dorchester", [1000.0], 1, YenKSP::new(DijkstraSSSP::new())), vec![(1000.0, vec![
+            Path { hops: vec![("Amsterdam", 0.0), ("Dorchester", 540.86), ("Chicago", 540.86 + 6249.15 + 1000.0)] }.to_owned_path()
+        ])]);
+        assert_eq!(sensitivity(&g, "Amsterdam", "Berlin", "Chicago-Dorchester", [1000.0], 1, YenKSP::new(DijkstraSSSP::new())), vec![(1000.0, vec![
+            path!(crate : g, "Amsterdam" -| "Berlin").to_owned_path()
+        ])]);
+
+        // They outlive the graph
+        let res: Vec<(f64, Vec<OwnedPath>)> = {
+            let g: Graph = load_graph("cities");
+            sensitivity(&g, "Amsterdam", "Dorchester", "Amsterdam-Dorchester", [0.0, 1000.0], 1, YenKSP::new(DijkstraSSSP::new()))
+        };
+        assert_eq!(res[1].1[0].to_path(&g), Some(path!(crate : g, "Amsterdam" -> "Edinburgh" -| "Dorchester")));
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Checks whether the given path traverses the given edge.
+///
+/// Note that this is conservative: if there are parallel edges, a path traversing any of them is considered to traverse all of them.
+///
+/// # Arguments
+/// - `path`: The [`OwnedPath`] to check.
+/// - `edge`: The [`Edge`] to look for.
+///
+/// # Returns
+/// True if any pair of consecutive hops in `path` is connected by `edge`.
+#[inline]
+fn traverses(path: &OwnedPath, edge: &Edge) -> bool {
+    path.hops.windows(2).any(|hops| (hops[0].0 == edge.left && hops[1].0 == edge.right) || (hops[0].0 == edge.right && hops[1].0 == edge.left))
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Studies how the K shortest paths change as the cost of a single edge varies.
+///
+/// Instead of cloning the graph for every variation, the cost of the edge is varied through [`KShortestPath::k_shortest_paths_weighted()`].
+/// Moreover, results are reused between subsequent deltas where possible: if a delta is not smaller than the previous one and none of the
+/// previous paths traverse the edge, then making the edge even more expensive cannot change the result.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to find in.
+/// - `src`: The source node to find paths from.
+/// - `dst`: The destination node to find paths to.
+/// - `edge_id`: The identifier of the edge whose cost to vary.
+/// - `deltas`: The amounts to add to the edge's (original) cost. Using ascending deltas results in the most reuse.
+/// - `k`: The number of paths to find.
+/// - `alg`: The [`KShortestPath`] algorithm to find paths with.
+///
+/// # Returns
+/// A list with, for every delta, the delta and the K shortest paths found with it. The costs of the paths reflect the varied edge cost. They
+/// are [`OwnedPath`]s, such that the result can be kept after `graph` is changed or dropped.
+///
+/// # Panics
+/// This function panics if `edge_id` is not in the given `graph`, or if `alg` panics (e.g., because `src` or `dst` are not in the given `graph`).
+#[track_caller]
+pub fn sensitivity(
+    graph: &Graph,
+    src: &str,
+    dst: &str,
+    edge_id: &str,
+    deltas: impl IntoIterator<Item = f64>,
+    k: usize,
+    mut alg: impl KShortestPath,
+) -> Vec<(f64, Vec<OwnedPath>)> {
+    let edge: &Edge = match graph.edges.get(edge_id) {
+        Some(edge) => edge,
+        None => panic!("Unknown edge '{edge_id}'"),
+    };
+
+    let deltas = deltas.into_iter();
+    let mut res: Vec<(f64, Vec<OwnedPath>)> = Vec::with_capacity(deltas.size_hint().0);
+    for delta in deltas {
+        // See if we can re-use the previous result
+        if let Some((prev_delta, prev)) = res.last() {
+            if delta >= *prev_delta && !prev.iter().any(|path| traverses(path, edge)) {
+                let prev: Vec<OwnedPath> = prev.clone();
+                res.push((delta, prev));
+                continue;
+            }
+        }
+
+        // Otherwise, compute it anew
+        let paths: Vec<Path> = alg.k_shortest_paths_weighted(graph, src, dst, k, |e: &Edge| if e.id == edge.id { e.cost + delta } else { e.cost });
+        res.push((delta, paths.iter().map(Path::to_owned_path).collect()));
+    }
+    res
+}
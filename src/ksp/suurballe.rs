@@ -0,0 +1,265 @@
+//  SUURBALLE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:26:36
+//  Last edited:
+//    16 Oct 2026, 19:00:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements Suurballe's algorithm for finding the cheapest pair of
+//!   edge-disjoint paths between two nodes.
+//
+
+use std::collections::{HashMap, HashSet};
+
+use ksp_graph::{Edge, Graph};
+
+use crate::path::Path;
+use crate::sssp::queue::{BinaryHeapQueue, PriorityQueue};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use arrayvec::ArrayString;
+
+    use super::*;
+    use crate::path;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+    use crate::sssp::SingleShortestPath;
+    use crate::utils::load_graph;
+
+    /// Builds a graph where the shortest path from `s` to `t` blocks the cheapest disjoint pair, which goes through `a` and `b` separately.
+    fn make_graph(directed: bool) -> Graph {
+        let mut g: Graph = Graph::from_edges(
+            [("s", "a", 1.0), ("a", "b", 1.0), ("b", "t", 1.0), ("s", "b", 2.5), ("a", "t", 2.5), ("s", "c", 5.0), ("c", "t", 5.0)]
+                .map(|(left, right, cost)| (format!("{left}-{right}"), left, right, cost)),
+        );
+        g.directed = directed;
+        g
+    }
+
+    #[test]
+    fn test_suurballe() {
+        for directed in [false, true] {
+            let g: Graph = make_graph(directed);
+
+            // Taking the shortest path first and then the shortest one without its edges gives an expensive pair...
+            let first: Path = DijkstraSSSP::new().shortest(&g, "s", "t");
+            assert_eq!(first, path!(crate : g, "s" -> "a" -> "b" -| "t"));
+            let used: Vec<ArrayString<64>> = first.edge_ids(&g).unwrap();
+            let second: Path =
+                DijkstraSSSP::new().try_shortest_weighted(&g, "s", "t", |e| if used.contains(&e.id) { f64::INFINITY } else { e.cost }).unwrap();
+            assert_eq!(first.cost() + second.cost(), 13.0);
+
+            // ...whereas Suurballe finds the cheapest one, which avoids the shortest path
+            let (first, second): (Path, Path) = suurballe(&g, "s", "t").unwrap();
+            assert_eq!((&first, &second), (&path!(crate : g, "s" -> "a" -| "t"), &path!(crate : g, "s" -> "b" -| "t")));
+            assert_eq!(first.cost() + second.cost(), 7.0);
+        }
+
+        // Nodes with only one edge have no disjoint pair
+        let g: Graph = load_graph("cities");
+        assert_eq!(suurballe(&g, "Berlin", "Edinburgh"), None);
+        assert_eq!(
+            suurballe(&g, "Amsterdam", "Dorchester"),
+            Some((path!(crate : g, "Amsterdam" -| "Dorchester"), path!(crate : g, "Amsterdam" -> "Edinburgh" -| "Dorchester")))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_suurballe_unknown_node() { suurballe(&load_graph("cities"), "Amsterdam", "Paris"); }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// An edge in the (residual) graph searched by [`suurballe()`], which can only be traversed one way.
+#[derive(Clone, Copy, Debug)]
+struct Arc {
+    /// The node (by index) the arc starts at.
+    from:     usize,
+    /// The node (by index) the arc ends at.
+    to:       usize,
+    /// The cost of traversing the arc.
+    cost:     f64,
+    /// The edge (by index) that the arc traverses.
+    edge:     usize,
+    /// Whether the arc traverses its edge backwards, which cancels the first path using it.
+    reversed: bool,
+}
+
+
+
+/// Finds the shortest paths from one node to all others over a set of arcs.
+///
+/// # Arguments
+/// - `n`: The number of nodes.
+/// - `arcs`: The [`Arc`]s to traverse, whose costs must be non-negative.
+/// - `src`: The node (by index) to find paths from.
+///
+/// # Returns
+/// For every node (by index), its distance from `src` and the arc (by index) through which it is reached, if it can be.
+fn shortest_arcs(n: usize, arcs: &[Arc], src: usize) -> Vec<(f64, Option<usize>)> {
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, arc) in arcs.iter().enumerate() {
+        adjacency[arc.from].push(i);
+    }
+
+    let mut tree: Vec<(f64, Option<usize>)> = vec![(f64::INFINITY, None); n];
+    let mut visited: Vec<bool> = vec![false; n];
+    tree[src] = (0.0, None);
+    let mut todo: BinaryHeapQueue = BinaryHeapQueue::default();
+    todo.push(src, 0.0);
+    while let Some((node, cost)) = todo.pop_min() {
+        visited[node] = true;
+        for i in &adjacency[node] {
+            let arc: &Arc = &arcs[*i];
+            let new_dist: f64 = cost + arc.cost;
+            if !visited[arc.to] && new_dist < tree[arc.to].0 {
+                tree[arc.to] = (new_dist, Some(*i));
+                todo.decrease_key(arc.to, new_dist);
+            }
+        }
+    }
+    tree
+}
+
+/// Collects the arcs on the shortest path to a node.
+///
+/// # Arguments
+/// - `tree`: The result of [`shortest_arcs()`].
+/// - `arcs`: The [`Arc`]s the tree was computed over.
+/// - `dst`: The node (by index) to collect the path to.
+///
+/// # Returns
+/// The arcs (by index) from the root of the tree to `dst`, or [`None`] if it's unreachable.
+fn tree_arcs(tree: &[(f64, Option<usize>)], arcs: &[Arc], dst: usize) -> Option<Vec<usize>> {
+    if tree[dst].0 == f64::INFINITY {
+        return None;
+    }
+    let mut res: Vec<usize> = Vec::new();
+    let mut node: usize = dst;
+    while let Some(arc) = tree[node].1 {
+        res.push(arc);
+        node = arcs[arc].from;
+    }
+    res.reverse();
+    Some(res)
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Finds the cheapest pair of edge-disjoint paths from one node to another, using Suurballe's algorithm.
+///
+/// Finding the shortest path and then the shortest path that avoids its edges doesn't always give the cheapest pair; the first path may
+/// even block every second one. Instead, this finds the shortest path, reweights the edges by their reduced cost (which is zero along the
+/// shortest-path tree), and then finds a second shortest path in the residual graph, in which the first path can be walked backwards for
+/// free. Where the second path does so, both paths cancel out, after which the remaining edges form the cheapest pair.
+///
+/// Edges in an undirected graph can be traversed either way, but by only one of the paths. Parallel edges count as different edges.
+///
+/// Note that this requires costs to be non-negative, as Dijkstra's algorithm is used to find both paths.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] to find in.
+/// - `src`: The source node to find paths from.
+/// - `dst`: The destination node to find paths to.
+///
+/// # Returns
+/// The two paths, cheapest one first, such that no edge is in both and their total cost is minimal. They are simple. If `src` and `dst`
+/// are the same node, both paths consist of only that node. Returns [`None`] if there are no two edge-disjoint paths.
+///
+/// # Panics
+/// This function panics if `src` or `dst` is not in the given `graph`, or if any edge has a negative or NaN cost.
+#[track_caller]
+pub fn suurballe<'g>(graph: &'g Graph, src: &str, dst: &str) -> Option<(Path<'g>, Path<'g>)> {
+    // Index the nodes and edges, such that the search can be done over flat arrays
+    let mut ids: Vec<&'g str> = graph.nodes.keys().map(|id| id.as_str()).collect();
+    ids.sort();
+    let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let (src, dst): (usize, usize) = match (index.get(src), index.get(dst)) {
+        (Some(src), Some(dst)) => (*src, *dst),
+        (None, _) => panic!("Unknown node '{src}'"),
+        (_, None) => panic!("Unknown node '{dst}'"),
+    };
+    if src == dst {
+        let path: Path<'g> = Path { hops: vec![(ids[src], 0.0)] };
+        return Some((path.clone(), path));
+    }
+
+    // Every edge becomes one arc, or two if the graph is undirected
+    let mut edges: Vec<&str> = graph.edges.keys().map(|id| id.as_str()).collect();
+    edges.sort();
+    let mut arcs: Vec<Arc> = Vec::with_capacity(2 * edges.len());
+    for (i, id) in edges.iter().enumerate() {
+        let edge: &Edge = &graph.edges[*id];
+        if edge.cost.is_nan() || edge.cost < 0.0 {
+            panic!("Edge '{}' has cost {} (must be non-negative)", edge.id, edge.cost);
+        }
+        let (Some(left), Some(right)) = (index.get(edge.left.as_str()), index.get(edge.right.as_str())) else { continue };
+        if left == right {
+            continue;
+        }
+        arcs.push(Arc { from: *left, to: *right, cost: edge.cost, edge: i, reversed: false });
+        if !graph.directed {
+            arcs.push(Arc { from: *right, to: *left, cost: edge.cost, edge: i, reversed: false });
+        }
+    }
+
+    // Find the first shortest path
+    let tree: Vec<(f64, Option<usize>)> = shortest_arcs(ids.len(), &arcs, src);
+    let first: Vec<usize> = tree_arcs(&tree, &arcs, dst)?;
+    let first_edges: HashSet<usize> = first.iter().map(|arc| arcs[*arc].edge).collect();
+
+    // Build the residual graph, where costs are reduced and the first path can only be walked backwards (for free)
+    let mut residual: Vec<Arc> = Vec::with_capacity(arcs.len());
+    for arc in &arcs {
+        if tree[arc.from].0 < f64::INFINITY && !first_edges.contains(&arc.edge) {
+            // NOTE: Clamped, as rounding errors may make tight arcs slightly negative
+            residual.push(Arc { cost: (arc.cost + tree[arc.from].0 - tree[arc.to].0).max(0.0), ..*arc });
+        }
+    }
+    for arc in &first {
+        let arc: &Arc = &arcs[*arc];
+        residual.push(Arc { from: arc.to, to: arc.from, cost: 0.0, edge: arc.edge, reversed: true });
+    }
+
+    // Find the second path in it, and cancel the edges that both walk
+    let second: Vec<usize> = tree_arcs(&shortest_arcs(ids.len(), &residual, src), &residual, dst)?;
+    let cancelled: HashSet<usize> = second.iter().filter(|arc| residual[**arc].reversed).map(|arc| residual[*arc].edge).collect();
+    // NOTE: The first path's arcs are pushed last, such that they are walked first
+    let mut out: Vec<Vec<&Arc>> = vec![Vec::new(); ids.len()];
+    for arc in second.iter().map(|arc| &residual[*arc]).filter(|arc| !arc.reversed) {
+        // NOTE: Use the original arc to get the original cost
+        out[arc.from].push(arcs.iter().find(|orig| orig.edge == arc.edge && orig.from == arc.from).unwrap());
+    }
+    for arc in first.iter().map(|arc| &arcs[*arc]).filter(|arc| !cancelled.contains(&arc.edge)) {
+        out[arc.from].push(arc);
+    }
+
+    // The remaining arcs form two paths (and possibly some free cycles, which are cut), so walk them
+    let mut walk = || -> Path<'g> {
+        let mut hops: Vec<(usize, f64)> = vec![(src, 0.0)];
+        while hops[hops.len() - 1].0 != dst {
+            let (node, cost): (usize, f64) = hops[hops.len() - 1];
+            let arc: &Arc = out[node].pop().unwrap_or_else(|| panic!("Combined paths do not conserve flow in node '{}'", ids[node]));
+            match hops.iter().position(|(node, _)| *node == arc.to) {
+                Some(i) => hops.truncate(i + 1),
+                None => hops.push((arc.to, cost + arc.cost)),
+            }
+        }
+        Path { hops: hops.into_iter().map(|(node, cost)| (ids[node], cost)).collect() }
+    };
+    let (first, second): (Path<'g>, Path<'g>) = (walk(), walk());
+    if second.cost() < first.cost() { Some((second, first)) } else { Some((first, second)) }
+}
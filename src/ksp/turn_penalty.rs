@@ -0,0 +1,246 @@
+//  TURN PENALTY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:54:22
+//  Last edited:
+//    16 Oct 2026, 19:28:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements a KSP algorithm where the cost of a path also depends on
+//!   the turns it takes at nodes, e.g., for road-like networks.
+//
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use arrayvec::ArrayString;
+use ksp_graph::{Edge, Graph};
+
+use super::{path_capacity, KShortestPath, TieBreak};
+use crate::cost::OrderedCost;
+use crate::path::Path;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ksp::wikipedia::WikipediaKSP;
+    use crate::path;
+    use crate::utils::{load_bench, load_graph};
+
+    #[test]
+    fn test_turn_penalty_ksp_free() {
+        // Without penalties, it's the same as any other non-simple algorithm
+        let g: Graph = load_graph("cities");
+        for src in g.nodes.keys() {
+            for dst in g.nodes.keys() {
+                let paths: Vec<Path> = TurnPenaltyKSP::new(|_: &Edge, _: &str, _: &Edge| 0.0).k_shortest_paths(&g, src, dst, 4);
+                let reference: Vec<Path> = WikipediaKSP::new().k_shortest_paths(&g, src, dst, 4);
+                assert_eq!(paths.len(), reference.len(), "Disagreement with reference for {src} -> {dst}");
+                for (path, reference) in paths.iter().zip(&reference) {
+                    assert!(path.validate_against(&g).is_ok());
+                    assert!((path.cost() - reference.cost()).abs() < 1e-6, "Disagreement with reference for {src} -> {dst}");
+                }
+            }
+        }
+        let g: Graph = load_bench("india35");
+        let paths: Vec<Path> = TurnPenaltyKSP::new(|_: &Edge, _: &str, _: &Edge| 0.0).k_shortest_paths(&g, "12", "33", 10);
+        let reference: Vec<Path> = WikipediaKSP::new().k_shortest_paths(&g, "12", "33", 10);
+        assert_eq!(paths.iter().map(Path::cost).collect::<Vec<f64>>(), reference.iter().map(Path::cost).collect::<Vec<f64>>());
+    }
+
+    #[test]
+    fn test_turn_penalty_ksp_u_turns() {
+        let g: Graph = load_graph("cities");
+        let u_turn = |prev: &Edge, _: &str, next: &Edge| if prev.id == next.id { 10000.0 } else { 0.0 };
+
+        // Normally, the second path bounces back from Dorchester...
+        let paths: Vec<Path> = WikipediaKSP::new().k_shortest_paths(&g, "Amsterdam", "Berlin", 2);
+        assert_eq!(paths[1], path!(crate : g, "Amsterdam" -> "Dorchester" -> "Amsterdam" -| "Berlin"));
+
+        // ...but that's now more expensive than going round via Edinburgh
+        let paths: Vec<Path> = TurnPenaltyKSP::new(u_turn).k_shortest_paths(&g, "Amsterdam", "Berlin", 2);
+        assert_eq!(paths[0], path!(crate : g, "Amsterdam" -| "Berlin"));
+        assert_eq!(paths[1].hops.len(), 5);
+        assert!(paths[1].contains_node("Edinburgh"));
+        assert!((paths[1].cost() - (660.68 + 589.23 + 540.86 + 577.34)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_turn_penalty_ksp_forbidden() {
+        // Turning from Amsterdam to Chicago in Dorchester is not allowed, so we have to come from Edinburgh
+        let g: Graph = load_graph("cities");
+        let no_left = |prev: &Edge, node: &str, next: &Edge| {
+            if node == "Dorchester" && prev.id.as_str() == "Amsterdam-Dorchester" && next.id.as_str() == "Chicago-Dorchester" {
+                f64::INFINITY
+            } else {
+                0.0
+            }
+        };
+        assert_eq!(TurnPenaltyKSP::new(no_left).k_shortest_paths(&g, "Amsterdam", "Chicago", 1), vec![path!(
+            crate : g,
+            "Amsterdam" -> "Edinburgh" -> "Dorchester" -| "Chicago"
+        )]);
+        // Which doesn't affect the other direction
+        assert_eq!(TurnPenaltyKSP::new(no_left).k_shortest_paths(&g, "Chicago", "Amsterdam", 1), vec![path!(
+            crate : g,
+            "Chicago" -> "Dorchester" -| "Amsterdam"
+        )]);
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// A path in the queue, together with the edge it arrived over at its end.
+#[derive(Clone, Debug)]
+struct Candidate<'g> {
+    /// The total cost of the path, including turn penalties.
+    cost:      OrderedCost,
+    /// How to order candidates with the same cost.
+    tie_break: TieBreak,
+    /// The order in which the candidate was found, to break remaining ties deterministically.
+    order:     usize,
+    /// The path itself.
+    path:      Path<'g>,
+    /// The edge over which the path arrived at its last node, or [`None`] if it hasn't left the source yet.
+    last:      Option<&'g Edge>,
+}
+impl<'g> Eq for Candidate<'g> {}
+impl<'g> Ord for Candidate<'g> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // NOTE: Reversed, as the `BinaryHeap` is a max-heap
+        other
+            .cost
+            .cmp(&self.cost)
+            .then_with(|| self.tie_break.compare(&other.path, &self.path))
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+impl<'g> PartialEq for Candidate<'g> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl<'g> PartialOrd for Candidate<'g> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines a KSP algorithm where taking a turn at a node may cost extra.
+///
+/// The penalty of a turn is given by a function of the edge over which a path arrives at a node, the node itself and the edge over which it
+/// leaves it. For example, returning a large penalty if both edges are the same discourages U-turns, whereas returning [`f64::INFINITY`]
+/// forbids a turn altogether. Paths pay no penalty when they first leave the source, as they haven't arrived over any edge yet.
+///
+/// Because the cost of extending a path now depends on how it arrived at its end, the algorithm works like
+/// [`WikipediaKSP`](super::wikipedia::WikipediaKSP) but counts visits per pair of a node and the edge it was entered by, instead of per node.
+/// Like it, paths may visit the same node more than once, and are found in the order of their [`TieBreak`].
+///
+/// Note that the costs of the paths returned include the penalties, so they won't
+/// [validate](crate::path::Path::validate_against()) against the graph if any were paid.
+#[derive(Clone, Copy, Debug)]
+pub struct TurnPenaltyKSP<F> {
+    /// Computes the penalty of a turn.
+    penalty:   F,
+    /// How to order paths with the same cost.
+    tie_break: TieBreak,
+}
+impl<F: Fn(&Edge, &str, &Edge) -> f64> TurnPenaltyKSP<F> {
+    /// Constructor for the TurnPenaltyKSP.
+    ///
+    /// # Arguments
+    /// - `penalty`: A function computing the extra cost of arriving at a node (the second argument) over one [`Edge`] (the first) and leaving
+    ///   it over another (the third). Should not be negative.
+    ///
+    /// # Returns
+    /// A new TurnPenaltyKSP instance that breaks ties with [`TieBreak::FewerHops`].
+    #[inline]
+    pub const fn new(penalty: F) -> Self { Self { penalty, tie_break: TieBreak::FewerHops } }
+
+    /// Sets how paths with the same cost are ordered.
+    ///
+    /// As this algorithm expands paths in order, this also decides which of them are returned if more have the same cost as the K-th one
+    /// than fit.
+    ///
+    /// # Arguments
+    /// - `tie_break`: The [`TieBreak`] to use.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub const fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+}
+impl<F: Fn(&Edge, &str, &Edge) -> f64> KShortestPath for TurnPenaltyKSP<F> {
+    #[track_caller]
+    fn k_shortest_paths_weighted<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize, weight: impl Fn(&Edge) -> f64) -> Vec<Path<'g>> {
+        // Assert that both nodes exists
+        let src: &'g str = if let Some((key, _)) = graph.nodes.get_key_value(&ArrayString::from(src).unwrap()) {
+            key
+        } else {
+            panic!("Unknown source node '{src}'");
+        };
+        if !graph.nodes.contains_key(&ArrayString::from(dst).unwrap()) {
+            panic!("Unknown destination node '{dst}'");
+        }
+        // There's nothing to find if we're asked for no paths
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // Expand paths in order of cost, but now keep count per node _and_ the edge by which it was reached
+        let mut shortest: Vec<Path<'g>> = Vec::with_capacity(path_capacity(k));
+        let mut counts: HashMap<(&'g str, Option<&'g str>), usize> = HashMap::with_capacity(graph.nodes.len());
+        let mut todo: BinaryHeap<Candidate<'g>> = BinaryHeap::from([Candidate {
+            cost:      OrderedCost(0.0),
+            tie_break: self.tie_break,
+            order:     0,
+            path:      Path { hops: vec![(src, 0.0)] },
+            last:      None,
+        }]);
+        let mut order: usize = 1;
+        while shortest.len() < k {
+            let Candidate { cost, path, last, .. } = match todo.pop() {
+                Some(cand) => cand,
+                None => break,
+            };
+            let end: &'g str = path.end().unwrap();
+            if dst == end {
+                shortest.push(path.clone());
+            }
+
+            // Only expand states that haven't been reached K times already
+            let count: &mut usize = counts.entry((end, last.map(|e| e.id.as_str()))).or_default();
+            *count += 1;
+            if *count > k {
+                continue;
+            }
+            for (neighbour, e) in graph.successors(end) {
+                let penalty: f64 = if let Some(last) = last { (self.penalty)(last, end, e) } else { 0.0 };
+                let new_cost: f64 = cost.0 + weight(e) + penalty;
+                if new_cost == f64::INFINITY {
+                    continue;
+                }
+                let mut new_path: Path<'g> = path.clone();
+                new_path.hops.push((neighbour, new_cost));
+                todo.push(Candidate { cost: OrderedCost(new_cost), tie_break: self.tie_break, order, path: new_path, last: Some(e) });
+                order += 1;
+            }
+        }
+        shortest
+    }
+}
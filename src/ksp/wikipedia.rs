@@ -4,7 +4,7 @@
 //  Created:
 //    16 Jul 2024, 00:10:52
 //  Last edited:
-//    24 Jul 2024, 20:47:54
+//    16 Oct 2026, 19:26:18
 //  Auto updated?
 //    Yes
 //
@@ -14,12 +14,13 @@
 //!   Based on: <https://en.wikipedia.org/wiki/K_shortest_path_routing#Algorithm>
 //
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use arrayvec::ArrayString;
-use ksp_graph::Graph;
+use ksp_graph::{Edge, Graph};
 
-use super::KShortestPath;
+use super::{KShortestPath, TieBreak};
 use crate::path::Path;
 
 
@@ -27,7 +28,10 @@ use crate::path::Path;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ksp::brute_force::BruteForceKSP;
+    use crate::ksp::yen::YenKSP;
     use crate::path;
+    use crate::sssp::dijkstra::DijkstraSSSP;
     use crate::utils::{load_bench, load_graph};
 
     #[test]
@@ -35,23 +39,176 @@ mod tests {
         // Run it quite some times to catch hashmap problems
         for _ in 0..10 {
             let g: Graph = load_graph("cities");
-            assert_eq!(WikipediaKSP.k_shortest_paths(&g, "Amsterdam", "Berlin", 1), vec![path!(crate : g, "Amsterdam" -| "Berlin")]);
-            assert_eq!(WikipediaKSP.k_shortest_paths(&g, "Amsterdam", "Dorchester", 1), vec![path!(crate : g, "Amsterdam" -| "Dorchester")]);
-            assert_eq!(WikipediaKSP.k_shortest_paths(&g, "Amsterdam", "Chicago", 1), vec![
+            assert_eq!(WikipediaKSP::new().k_shortest_paths(&g, "Amsterdam", "Berlin", 1), vec![path!(crate : g, "Amsterdam" -| "Berlin")]);
+            assert_eq!(WikipediaKSP::new().k_shortest_paths(&g, "Amsterdam", "Dorchester", 1), vec![path!(crate : g, "Amsterdam" -| "Dorchester")]);
+            assert_eq!(WikipediaKSP::new().k_shortest_paths(&g, "Amsterdam", "Chicago", 1), vec![
                 path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago")
             ]);
-            assert_eq!(WikipediaKSP.k_shortest_paths(&g, "Berlin", "Chicago", 1), vec![
+            assert_eq!(WikipediaKSP::new().k_shortest_paths(&g, "Berlin", "Chicago", 1), vec![
                 path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago")
             ]);
         }
     }
 
+    #[test]
+    fn test_wikipedia_ksp_reference() {
+        let g: Graph = load_graph("cities");
+        for src in g.nodes.keys() {
+            for dst in g.nodes.keys() {
+                for k in 1..=4 {
+                    // NOTE: We compare costs, as going round a loop either way results in different paths with the same cost
+                    let paths: Vec<Path> = WikipediaKSP::new().k_shortest_paths(&g, src, dst, k);
+                    let reference: Vec<Path> = BruteForceKSP::non_simple().k_shortest_paths(&g, src, dst, k);
+                    assert_eq!(paths.len(), reference.len(), "Disagreement with reference for {src} -> {dst} (k = {k})");
+                    for (path, reference) in paths.iter().zip(&reference) {
+                        assert!(path.is_connected_in(&g));
+                        assert!((path.cost() - reference.cost()).abs() < 1e-6, "Disagreement with reference for {src} -> {dst} (k = {k})");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_wikipedia_ksp_zero_k() {
+        let g: Graph = load_graph("cities");
+        assert_eq!(WikipediaKSP::new().k_shortest_paths(&g, "Amsterdam", "Berlin", 0), vec![]);
+        assert_eq!(WikipediaKSP::new().k_shortest_paths(&g, "Berlin", "Chicago", 0), vec![]);
+        assert_eq!(WikipediaKSP::new().k_shortest_paths(&g, "Chicago", "Chicago", 0), vec![]);
+    }
+
+    #[test]
+    fn test_wikipedia_ksp_weighted() {
+        for _ in 0..10 {
+            let g: Graph = load_graph("cities");
+
+            // Both metrics agree on the route, but only hop count sees the two alternatives as equally long
+            let by_cost: Vec<Path> = WikipediaKSP::new().k_shortest_paths_weighted(&g, "Edinburgh", "Dorchester", 2, |e| e.cost);
+            let by_hops: Vec<Path> = WikipediaKSP::new().k_shortest_paths_weighted(&g, "Edinburgh", "Dorchester", 2, |_| 1.0);
+            assert_eq!(by_cost, vec![path!(crate : g, "Edinburgh" -| "Dorchester"), path!(crate : g, "Edinburgh" -> "Amsterdam" -| "Dorchester")]);
+            assert_eq!(by_cost.iter().map(Path::cost).collect::<Vec<f64>>(), vec![589.23, 660.68 + 540.86]);
+            assert_eq!(by_hops.iter().map(Path::cost).collect::<Vec<f64>>(), vec![1.0, 2.0]);
+
+            // If the cheapest route isn't the one with the fewest hops, the metrics rank them the other way around
+            let d: Graph = load_graph("detour");
+            let by_cost: Vec<Path> = WikipediaKSP::new().k_shortest_paths_weighted(&d, "A", "E", 2, |e| e.cost);
+            let by_hops: Vec<Path> = WikipediaKSP::new().k_shortest_paths_weighted(&d, "A", "E", 2, |_| 1.0);
+            assert_eq!(by_cost, vec![path!(crate : d, "A" -> "B" -> "C" -| "E"), path!(crate : d, "A" -> "D" -| "E")]);
+            assert_eq!(by_cost.iter().map(Path::cost).collect::<Vec<f64>>(), vec![3.0, 4.0]);
+            assert_eq!(by_hops, vec![path!(crate : d, "A" -> "D" -| "E"), path!(crate : d, "A" -> "B" -> "C" -| "E")]);
+            assert_eq!(by_hops.iter().map(Path::cost).collect::<Vec<f64>>(), vec![2.0, 3.0]);
+        }
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_wikipedia_ksp_logs() {
+        let g: Graph = load_graph("cities");
+        let (paths, logs): (Vec<Path>, Vec<(log::Level, String)>) =
+            crate::utils::capture_logs(|| WikipediaKSP::new().k_shortest_paths(&g, "Amsterdam", "Chicago", 3));
+        assert_eq!(paths.len(), 3);
+
+        // Every path found is reported...
+        for i in 1..=paths.len() {
+            assert!(
+                logs.iter().any(|(level, msg)| *level == log::Level::Debug && msg.contains(&format!("found path {i}/3"))),
+                "No debug record for path {i}"
+            );
+        }
+        // ...and every iteration is traced
+        let iterations: usize = logs.iter().filter(|(level, msg)| *level == log::Level::Trace && msg.starts_with("Wikipedia iteration")).count();
+        assert!(iterations >= paths.len());
+        for i in 1..=iterations {
+            assert!(logs.iter().any(|(_, msg)| msg.starts_with(&format!("Wikipedia iteration {i}:"))));
+        }
+    }
+
+    #[test]
+    fn test_wikipedia_ksp_paths_within() {
+        let g: Graph = load_graph("cities");
+        assert_eq!(WikipediaKSP::new().paths_within(&g, "Amsterdam", "Chicago", 1.0), vec![path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago")]);
+        assert_eq!(WikipediaKSP::new().paths_within(&g, "Chicago", "Chicago", 1.0), vec![Path { hops: vec![("Chicago", 0.0)] }]);
+        assert_eq!(WikipediaKSP::new().paths_within(&g, "Edinburgh", "Dorchester", 2.1), vec![
+            path!(crate : g, "Edinburgh" -| "Dorchester"),
+            path!(crate : g, "Edinburgh" -> "Amsterdam" -| "Dorchester")
+        ]);
+
+        // It agrees with the default implementation
+        let g: Graph = load_bench("india35");
+        let paths: Vec<Path> = WikipediaKSP::new().paths_within(&g, "12", "33", 3.2);
+        let reference: Vec<Path> =
+            crate::ksp::hoffman_pavley::HoffmanPavleyKSP::new(crate::sssp::dijkstra::DijkstraSSSP::new()).paths_within(&g, "12", "33", 3.2);
+        assert_eq!(paths.len(), 6);
+        assert_eq!(paths.len(), reference.len());
+        for (path, reference) in paths.iter().zip(&reference) {
+            assert!((path.cost() - reference.cost()).abs() < 1e-6);
+            assert!(path.cost() <= 3.2 * paths[0].cost() + 1e-6);
+        }
+
+        // It stops if the destination can't be reached, even if there are cycles
+        let g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-C", "B", "C", 1.0), ("C-A", "C", "A", 1.0), ("D-E", "D", "E", 1.0)]);
+        assert_eq!(WikipediaKSP::new().paths_within(&g, "A", "D", 2.0), vec![]);
+        let mut d: Graph = g.clone();
+        d.directed = true;
+        assert_eq!(WikipediaKSP::new().paths_within(&d, "B", "A", 2.0), vec![path!(crate : d, "B" -> "C" -| "A")]);
+        assert_eq!(WikipediaKSP::new().paths_within(&d, "E", "D", 2.0), vec![]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wikipedia_ksp_paths_within_infinite() { WikipediaKSP::new().paths_within(&load_graph("cities"), "Amsterdam", "Chicago", f64::INFINITY); }
+
+    #[test]
+    fn test_wikipedia_ksp_resume() {
+        let g: Graph = load_graph("cities");
+        for src in g.nodes.keys() {
+            for dst in g.nodes.keys() {
+                // Resuming from 3 to 5 paths is the same as finding 5 right away
+                let mut state: KspState = WikipediaKSP::new().start(&g, src, dst);
+                let mut paths: Vec<Path> = state.resume(3);
+                assert_eq!(paths, WikipediaKSP::new().k_shortest_paths(&g, src, dst, 3), "Disagreement for {src} -> {dst} (k = 3)");
+                paths.extend(state.resume(5));
+                assert_eq!(paths, WikipediaKSP::new().k_shortest_paths(&g, src, dst, 5), "Disagreement for {src} -> {dst} (k = 3 -> 5)");
+                assert_eq!(state.paths(), paths.as_slice());
+
+                // Asking for fewer finds nothing new
+                assert_eq!(state.resume(4), vec![]);
+
+                // The default implementation agrees
+                let yen: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, 3);
+                let more: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths_resume(&g, src, dst, &yen, 5);
+                assert_eq!([yen, more].concat(), YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, 5));
+            }
+        }
+    }
+
+    #[test]
+    fn test_wikipedia_ksp_callback() {
+        let g: Graph = load_graph("cities");
+        for k in 0..=5 {
+            // Every path is reported once, in order...
+            let mut found: Vec<(usize, Path)> = Vec::new();
+            WikipediaKSP::new().k_shortest_paths_with_callback(&g, "Amsterdam", "Chicago", k, |i, path| found.push((i, path.clone())));
+            let paths: Vec<Path> = WikipediaKSP::new().k_shortest_paths(&g, "Amsterdam", "Chicago", k);
+            assert_eq!(found.len(), paths.len());
+            assert_eq!(found, paths.into_iter().enumerate().collect::<Vec<(usize, Path)>>());
+
+            // ...which the default implementation agrees with
+            let mut count: usize = 0;
+            YenKSP::new(DijkstraSSSP::new()).k_shortest_paths_with_callback(&g, "Amsterdam", "Chicago", k, |i, _| {
+                assert_eq!(i, count);
+                count += 1;
+            });
+            assert_eq!(count, YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Chicago", k).len());
+        }
+    }
+
     #[test]
     fn test_wikipedia_ksp_india35() {
         // Run some more difficult ones
         for _ in 0..10 {
             let g: Graph = load_bench("india35");
-            assert_eq!(WikipediaKSP.k_shortest_paths(&g, "12", "33", 1), vec![path!(crate : g, "12" -| "33")]);
+            assert_eq!(WikipediaKSP::new().k_shortest_paths(&g, "12", "33", 1), vec![path!(crate : g, "12" -| "33")]);
         }
     }
 }
@@ -60,80 +217,319 @@ mod tests {
 
 
 
+/***** HELPERS *****/
+/// Inserts a path in the (descending) queue of candidates, such that ties are popped as configured.
+///
+/// # Arguments
+/// - `tie_break`: How to order paths with the same cost.
+/// - `todo`: The queue of candidates, ordered from most to least expensive.
+/// - `path`: The path to insert.
+fn insert<'g>(tie_break: TieBreak, todo: &mut Vec<Path<'g>>, path: Path<'g>) {
+    for i in 0..todo.len() {
+        // Insert it after the largest one
+        if tie_break.compare(&todo[i], &path) == Ordering::Greater {
+            continue;
+        }
+        todo.insert(i, path);
+        return;
+    }
+    todo.push(path);
+}
+
+
+
+
+
 /***** LIBRARY *****/
-/// Defines the vanilla, simplest version of a KSP-algorithm.
+/// The state of a run of the [`WikipediaKSP`], which can be resumed to find more paths.
 ///
-/// Based on: <https://en.wikipedia.org/wiki/K_shortest_path_routing#Algorithm>
-#[derive(Clone, Copy, Debug)]
-pub struct WikipediaKSP;
-impl KShortestPath for WikipediaKSP {
-    #[track_caller]
-    fn k_shortest_paths<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize) -> Vec<Path<'g>> {
-        // Assert that both nodes exists
-        let src: &'g str = if let Some((key, _)) = graph.nodes.get_key_value(&ArrayString::from(src).unwrap()) {
-            key
-        } else {
-            panic!("Unknown source node '{src}'");
-        };
-        if !graph.nodes.contains_key(&ArrayString::from(dst).unwrap()) {
-            panic!("Unknown source node '{dst}'");
+/// Created by [`WikipediaKSP::start()`]. Use [`KspState::resume()`] to find paths.
+#[derive(Clone, Debug)]
+pub struct KspState<'g> {
+    /// The graph to find in.
+    graph:       &'g Graph,
+    /// The source node to find paths from.
+    src:         &'g str,
+    /// The destination node to find paths to.
+    dst:         &'g str,
+    /// How to order paths with the same cost.
+    tie_break:   TieBreak,
+    /// The factor by which paths may be more expensive than the shortest one.
+    ratio:       f64,
+    /// The maximum cost of paths to find, once the shortest one is known.
+    ceiling:     f64,
+    /// The K for which nodes have been expanded so far.
+    k:           usize,
+    /// The paths found so far.
+    shortest:    Vec<Path<'g>>,
+    /// The number of times a path to every node has been popped.
+    shortest_to: HashMap<&'g str, usize>,
+    /// The paths yet to pop, ordered from most to least expensive.
+    todo:        Vec<Path<'g>>,
+    /// The paths that have been popped but not expanded, because their end was already reached K times.
+    deferred:    Vec<Path<'g>>,
+    /// The number of paths popped so far.
+    #[cfg(feature = "log")]
+    iteration:   usize,
+}
+impl<'g> KspState<'g> {
+    /// Returns all paths found so far.
+    ///
+    /// # Returns
+    /// The paths returned by all calls to [`KspState::resume()`] so far, ordered by cost.
+    #[inline]
+    pub fn paths(&self) -> &[Path<'g>] { &self.shortest }
+
+    /// Continues the algorithm until K paths have been found in total.
+    ///
+    /// This continues where the previous call left off, so finding 3 and then 5 paths costs (about) as much as finding 5 right away. The
+    /// paths are the same, too.
+    ///
+    /// # Arguments
+    /// - `k`: The total number of paths to have found, including the ones found by previous calls.
+    ///
+    /// # Returns
+    /// The paths found by this call, i.e., the next shortest paths after [`KspState::paths()`]. Is empty if `k` is not larger than the
+    /// number of paths already found, or if there are no more paths.
+    #[inline]
+    pub fn resume(&mut self, k: usize) -> Vec<Path<'g>> { self.resume_weighted(k, |e: &Edge| e.cost, |_, _| {}) }
+
+    /// Continues the algorithm until K paths have been found in total, using a custom cost for every edge.
+    ///
+    /// # Arguments
+    /// - `k`: The total number of paths to have found, including the ones found by previous calls.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`]. Must be the same for all calls on this state.
+    /// - `found_path`: A function called with the index (among all paths found so far) and the path as soon as a path is found.
+    ///
+    /// # Returns
+    /// The paths found by this call.
+    fn resume_weighted(&mut self, k: usize, weight: impl Fn(&Edge) -> f64, mut found_path: impl FnMut(usize, &Path<'g>)) -> Vec<Path<'g>> {
+        let found: usize = self.shortest.len();
+        let Self { graph, src, dst, tie_break, ratio, ref mut ceiling, .. } = *self;
+        #[cfg(not(feature = "log"))]
+        let _ = src;
+
+        // Paths that weren't expanded for a smaller K may be now, so pop them again
+        if k > self.k {
+            for path in std::mem::take(&mut self.deferred) {
+                *self.shortest_to.get_mut(path.end().unwrap()).unwrap() -= 1;
+                insert(tie_break, &mut self.todo, path);
+            }
+            self.k = k;
         }
 
-        // Then do the algorithm
-        // > P = empty,
-        let mut shortest: Vec<Path<'g>> = Vec::with_capacity(k);
-        // > count_u = 0, for all u in V
-        let mut shortest_to: HashMap<&str, usize> = HashMap::with_capacity(graph.nodes.len());
-        // > insert path p_s = {s} into B with cost 0
-        let mut todo: Vec<Path<'g>> = Vec::from([Path { hops: vec![(src, 0.0)] }]);
         // > while B is not empty and count_t < K:
-        while !todo.is_empty() && *shortest_to.entry(dst).or_default() < k {
+        while !self.todo.is_empty() && *self.shortest_to.entry(dst).or_default() < k {
             // > let p_u be the shortest cost path in B with cost C
             // > B = B - {p_u},
-            let path: Path<'g> = todo.pop().unwrap();
+            let path: Path<'g> = self.todo.pop().unwrap();
             let cost: f64 = path.cost();
-            let end: &str = path.end().unwrap();
+            if cost > *ceiling {
+                // NOTE: Put it back, as it's still the cheapest one if the ceiling is ever relevant again
+                self.todo.push(path);
+                break;
+            }
+            let end: &'g str = path.end().unwrap();
+            #[cfg(feature = "log")]
+            {
+                self.iteration += 1;
+                log::trace!(
+                    "Wikipedia iteration {}: popped path to '{end}' with cost {cost} ({} candidate(s) left)",
+                    self.iteration,
+                    self.todo.len()
+                );
+            }
 
             // > count_u = count_u + 1
-            *shortest_to.entry(end).or_default() += 1;
+            let count: &mut usize = self.shortest_to.entry(end).or_default();
+            *count += 1;
+            let count: usize = *count;
 
             // > if u = t then P = P \cup {p_u}
             if dst == end {
-                shortest.push(path.clone());
+                #[cfg(feature = "log")]
+                log::debug!(
+                    "Wikipedia iteration {}: found path {}/{k} from '{src}' to '{dst}' with cost {cost} ({} candidate(s) left)",
+                    self.iteration,
+                    self.shortest.len() + 1,
+                    self.todo.len()
+                );
+                if self.shortest.is_empty() {
+                    *ceiling = super::cost_ceiling(cost, ratio);
+                }
+                found_path(self.shortest.len(), &path);
+                self.shortest.push(path.clone());
             }
 
             // > if count_u \leq K then
-            if *shortest_to.get(end).unwrap() <= k {
-                // > \circ for each vertex v adjacent to u:
-                'edges: for e in graph.edges.values() {
-                    // > - let p_v be a new path with cost C + w(u, v) formed by concatenating edge (u, v) to path p_u
-                    let neighbour: &str = if e.left.as_str() == end && e.right.as_str() != end {
-                        e.right.as_str()
-                    } else if e.left.as_str() != end && e.right.as_str() == end {
-                        e.left.as_str()
-                    } else {
-                        continue;
-                    };
-                    let new_cost: f64 = cost + e.cost;
-                    let mut new_path: Path<'g> = path.clone();
-                    new_path.hops.push((neighbour, cost + e.cost));
-
-                    // > - insert p_v into B
-                    // NOTE: We do this ordered
-                    for i in 0..todo.len() {
-                        // Insert it after the largest one
-                        if todo[i].cost() > new_cost {
-                            continue;
-                        }
-                        todo.insert(i, new_path);
-                        continue 'edges;
-                    }
-                    todo.push(new_path);
+            if count > k {
+                self.deferred.push(path);
+                continue;
+            }
+            // > \circ for each vertex v adjacent to u:
+            for (neighbour, e) in graph.successors(end) {
+                // > - let p_v be a new path with cost C + w(u, v) formed by concatenating edge (u, v) to path p_u
+                let new_cost: f64 = cost + weight(e);
+                debug_assert!(!new_cost.is_nan(), "Accumulated cost to node '{neighbour}' is NaN");
+                if new_cost > *ceiling {
+                    continue;
                 }
+                let mut new_path: Path<'g> = path.clone();
+                new_path.hops.push((neighbour, new_cost));
+
+                // > - insert p_v into B
+                insert(tie_break, &mut self.todo, new_path);
             }
         }
 
         // > return P
-        shortest
+        self.shortest[found..].to_vec()
+    }
+}
+
+
+
+/// Defines the vanilla, simplest version of a KSP-algorithm.
+///
+/// Based on: <https://en.wikipedia.org/wiki/K_shortest_path_routing#Algorithm>
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WikipediaKSP {
+    /// How to order paths with the same cost.
+    tie_break: TieBreak,
+}
+impl WikipediaKSP {
+    /// Constructor for the WikipediaKSP.
+    ///
+    /// # Returns
+    /// A new WikipediaKSP instance that breaks ties with [`TieBreak::FewerHops`].
+    #[inline]
+    pub fn new() -> Self { Self::default() }
+
+    /// Sets how paths with the same cost are ordered.
+    ///
+    /// As this algorithm expands paths in order, this also decides which of them are returned if more have the same cost as the K-th one
+    /// than fit.
+    ///
+    /// # Arguments
+    /// - `tie_break`: The [`TieBreak`] to use.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub const fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Starts a run of the algorithm that can be resumed to find more paths later.
+    ///
+    /// Unlike [`KShortestPath::k_shortest_paths()`], this doesn't have to start over if more paths are needed after all. Paths are found
+    /// with [`KspState::resume()`], using the costs of the edges.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find paths from.
+    /// - `dst`: The destination node to find paths to.
+    ///
+    /// # Returns
+    /// A new [`KspState`] that hasn't found any paths yet.
+    ///
+    /// # Panics
+    /// This function panics if the given `src` or `dst` are not in the given `graph`.
+    #[inline]
+    #[track_caller]
+    pub fn start<'g>(&self, graph: &'g Graph, src: &str, dst: &str) -> KspState<'g> {
+        Self::start_within(self.tie_break, graph, src, dst, f64::INFINITY)
+    }
+
+    /// Starts a run of the algorithm that only finds paths within a factor of the shortest one.
+    ///
+    /// # Arguments
+    /// - `tie_break`: How to order paths with the same cost.
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find paths from.
+    /// - `dst`: The destination node to find paths to.
+    /// - `ratio`: The factor by which paths may be more expensive than the shortest one. Give [`f64::INFINITY`] to not limit them.
+    ///
+    /// # Returns
+    /// A new [`KspState`] that hasn't found any paths yet.
+    ///
+    /// # Panics
+    /// This function panics if the given `src` or `dst` are not in the given `graph`.
+    #[track_caller]
+    fn start_within<'g>(tie_break: TieBreak, graph: &'g Graph, src: &str, dst: &str, ratio: f64) -> KspState<'g> {
+        // Assert that both nodes exists
+        let src: &'g str = if let Some((key, _)) = graph.nodes.get_key_value(&ArrayString::from(src).unwrap()) {
+            key
+        } else {
+            panic!("Unknown source node '{src}'");
+        };
+        let dst: &'g str = if let Some((key, _)) = graph.nodes.get_key_value(&ArrayString::from(dst).unwrap()) {
+            key
+        } else {
+            panic!("Unknown source node '{dst}'");
+        };
+
+        // > P = empty,
+        // > count_u = 0, for all u in V
+        // > insert path p_s = {s} into B with cost 0
+        KspState {
+            graph,
+            src,
+            dst,
+            tie_break,
+            ratio,
+            // NOTE: Not part of the original algorithm, but allows us to stop at a cost instead of a count
+            ceiling: f64::INFINITY,
+            k: 0,
+            shortest: Vec::new(),
+            shortest_to: HashMap::with_capacity(graph.nodes.len()),
+            todo: Vec::from([Path { hops: vec![(src, 0.0)] }]),
+            deferred: Vec::new(),
+            #[cfg(feature = "log")]
+            iteration: 0,
+        }
+    }
+}
+impl KShortestPath for WikipediaKSP {
+    #[inline]
+    #[track_caller]
+    fn k_shortest_paths_weighted<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize, weight: impl Fn(&Edge) -> f64) -> Vec<Path<'g>> {
+        Self::start_within(self.tie_break, graph, src, dst, f64::INFINITY).resume_weighted(k, weight, |_, _| {})
+    }
+
+    /// Finds The K shortest paths from one node to another, reporting every path to a callback as it's found.
+    ///
+    /// As this algorithm finds paths in order of cost, `f` is called as soon as each of them is popped. See
+    /// [`KShortestPath::k_shortest_paths_with_callback()`] for more information.
+    #[inline]
+    #[track_caller]
+    fn k_shortest_paths_with_callback<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize, f: impl FnMut(usize, &Path<'g>)) {
+        Self::start_within(self.tie_break, graph, src, dst, f64::INFINITY).resume_weighted(k, |e: &Edge| e.cost, f);
+    }
+
+    /// Finds all paths from one node to another that are at most a factor more expensive than the shortest one.
+    ///
+    /// Because this algorithm expands paths in order of cost, it stops as soon as it pops one beyond the ceiling instead of re-running with a
+    /// larger K. See [`KShortestPath::paths_within()`] for more information.
+    ///
+    /// # Panics
+    /// Next to the reasons given by [`KShortestPath::paths_within()`], this function also panics if `ratio` is infinite. As the paths found
+    /// by this algorithm may visit nodes more than once, there are infinitely many of them as soon as the graph has a cycle.
+    #[track_caller]
+    fn paths_within<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, ratio: f64) -> Vec<Path<'g>> {
+        if ratio.is_nan() || ratio < 1.0 {
+            panic!("Cannot find paths within {ratio} times the shortest one (must be at least 1)");
+        }
+        if ratio.is_infinite() {
+            panic!("Cannot find paths within infinite times the shortest one (there are infinitely many if the graph has a cycle)");
+        }
+        let mut state: KspState<'g> = Self::start_within(self.tie_break, graph, src, dst, ratio);
+
+        // NOTE: Without a path to the destination, there's no ceiling to stop at, so we'd keep expanding paths around cycles forever
+        if !graph.are_connected(src, dst) {
+            return Vec::new();
+        }
+        state.resume(usize::MAX)
     }
 }
@@ -4,7 +4,7 @@
 //  Created:
 //    16 Jul 2024, 00:10:52
 //  Last edited:
-//    24 Jul 2024, 20:48:58
+//    16 Oct 2026, 19:31:15
 //  Auto updated?
 //    Yes
 //
@@ -14,56 +14,317 @@
 //!   See the [`peek`](super::peek) module for the reference.
 //
 
-use std::cmp::Ordering;
 use std::collections::HashSet;
 
 use arrayvec::ArrayString;
-use ksp_graph::Graph;
+use ksp_graph::{Edge, Graph};
 
-use super::KShortestPath;
+use super::{path_capacity, tie_limit, KShortestPath, TieBreak};
+use crate::cost::OrderedCost;
 use crate::path::Path;
-use crate::sssp::SingleShortestPath;
+use crate::sssp::{ShortestPathTree, ShortestTree};
 
 
 /***** TESTS *****/
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, Instant};
+
     use super::*;
+    use crate::ksp::brute_force::BruteForceKSP;
     use crate::path;
+    use crate::prep::peek::PeekPreprocess;
     use crate::sssp::dijkstra::DijkstraSSSP;
-    use crate::utils::load_graph;
+    use crate::sssp::profiled::ProfilingSSSP;
+    use crate::utils::{load_bench, load_graph};
 
     #[test]
     fn test_yen_ksp() {
         // Run it quite some times to catch hashmap problems
         for _ in 0..10 {
             let g: Graph = load_graph("cities");
-            assert_eq!(YenKSP::new(DijkstraSSSP).k_shortest_paths(&g, "Amsterdam", "Berlin", 1), vec![path!(crate : g, "Amsterdam" -| "Berlin")]);
-            assert_eq!(YenKSP::new(DijkstraSSSP).k_shortest_paths(&g, "Amsterdam", "Dorchester", 1), vec![
+            assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Berlin", 1), vec![
+                path!(crate : g, "Amsterdam" -| "Berlin")
+            ]);
+            assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Dorchester", 1), vec![
                 path!(crate : g, "Amsterdam" -| "Dorchester")
             ]);
-            assert_eq!(YenKSP::new(DijkstraSSSP).k_shortest_paths(&g, "Amsterdam", "Chicago", 1), vec![
+            assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Chicago", 1), vec![
                 path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago")
             ]);
-            assert_eq!(YenKSP::new(DijkstraSSSP).k_shortest_paths(&g, "Berlin", "Chicago", 1), vec![
+            assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Berlin", "Chicago", 1), vec![
                 path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago")
             ]);
         }
     }
+
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn test_yen_ksp_deterministic() {
+        // Graphs loaded the same way are iterated in the same order, so a single run is representative
+        let g: Graph = load_graph("cities");
+        let other: Graph = load_graph("cities");
+        assert!(g.nodes.keys().eq(other.nodes.keys()));
+        assert!(g.edges.keys().eq(other.edges.keys()));
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Berlin", "Chicago", 2), vec![
+            path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago"),
+            path!(crate : g, "Berlin" -> "Amsterdam" -> "Edinburgh" -> "Dorchester" -| "Chicago"),
+        ]);
+    }
+
+    #[test]
+    fn test_yen_ksp_exhausted() {
+        for _ in 0..10 {
+            // There are only two simple paths from Amsterdam to Chicago
+            let g: Graph = load_graph("cities");
+            assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Chicago", 5), vec![
+                path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago"),
+                path!(crate : g, "Amsterdam" -> "Edinburgh" -> "Dorchester" -| "Chicago"),
+            ]);
+            // Same for Chicago to Berlin
+            assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Chicago", "Berlin", 5), vec![
+                path!(crate : g, "Chicago" -> "Dorchester" -> "Amsterdam" -| "Berlin"),
+                path!(crate : g, "Chicago" -> "Dorchester" -> "Edinburgh" -> "Amsterdam" -| "Berlin"),
+            ]);
+        }
+    }
+
+    #[test]
+    fn test_yen_ksp_large_k() {
+        // Asking for (far) more paths than exist returns the ones that do without doing anything more
+        let g: Graph = load_graph("cities");
+        let start: Instant = Instant::now();
+        for k in [1000, usize::MAX] {
+            assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Chicago", k), vec![
+                path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago"),
+                path!(crate : g, "Amsterdam" -> "Edinburgh" -> "Dorchester" -| "Chicago"),
+            ]);
+            assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Edinburgh", "Dorchester", k).len(), 2);
+        }
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_yen_ksp_reference() {
+        let g: Graph = load_graph("cities");
+        for src in g.nodes.keys() {
+            for dst in g.nodes.keys() {
+                for k in 1..=4 {
+                    assert_eq!(
+                        YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, k),
+                        BruteForceKSP::simple().k_shortest_paths(&g, src, dst, k),
+                        "Disagreement with reference for {src} -> {dst} (k = {k})"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_yen_ksp_paths_within() {
+        let g: Graph = load_graph("cities");
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).paths_within(&g, "Amsterdam", "Chicago", 1.0), vec![
+            path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago")
+        ]);
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).paths_within(&g, "Edinburgh", "Dorchester", 2.1), vec![
+            path!(crate : g, "Edinburgh" -| "Dorchester"),
+            path!(crate : g, "Edinburgh" -> "Amsterdam" -| "Dorchester")
+        ]);
+        // Every simple path
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).paths_within(&g, "Edinburgh", "Dorchester", f64::INFINITY).len(), 2);
+    }
+
+    #[test]
+    fn test_yen_ksp_zero_k() {
+        let g: Graph = load_graph("cities");
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Berlin", 0), vec![]);
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Berlin", "Chicago", 0), vec![]);
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Chicago", "Chicago", 0), vec![]);
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_yen_ksp_logs() {
+        let g: Graph = load_graph("cities");
+        let (paths, logs): (Vec<Path>, Vec<(log::Level, String)>) =
+            crate::utils::capture_logs(|| YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Amsterdam", "Chicago", 5));
+        assert_eq!(paths.len(), 2);
+
+        // There's a debug record for every iteration, including the one that ran out of candidates
+        for i in 0..=paths.len() {
+            assert!(
+                logs.iter().any(|(level, msg)| *level == log::Level::Debug && msg.starts_with(&format!("Yen iteration {i}:"))),
+                "No debug record for iteration {i}"
+            );
+        }
+        assert!(logs.iter().any(|(level, msg)| *level == log::Level::Trace && msg.contains("candidate with cost")));
+    }
+
+    #[test]
+    fn test_yen_ksp_max_candidates() {
+        let g: Graph = load_bench("india35");
+        let exact: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "12", "33", 10);
+        assert_eq!(exact.len(), 10);
+
+        // A generous cap changes nothing...
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).with_max_candidates(Some(1000)).k_shortest_paths(&g, "12", "33", 10), exact);
+
+        // ...while a tiny one still finds the shortest path, and otherwise only valid ones
+        for max in [0, 1] {
+            let capped: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).with_max_candidates(Some(max)).k_shortest_paths(&g, "12", "33", 10);
+            assert!(!capped.is_empty());
+            assert_eq!(capped[0], exact[0]);
+            for (i, path) in capped.iter().enumerate() {
+                assert!(path.is_connected_in(&g));
+                assert!(path.cost() >= exact[i].cost() - 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_yen_ksp_bound() {
+        let g: Graph = load_bench("india35");
+        let mut sssp: ProfilingSSSP<DijkstraSSSP> = ProfilingSSSP::new(DijkstraSSSP::new());
+        let exact: Vec<Path> = YenKSP::new(&mut sssp).k_shortest_paths(&g, "12", "33", 3);
+        let n_exact: usize = sssp.timings.len();
+
+        // The PeeK bound gives the same paths with fewer searches
+        let bound: Option<f64> = PeekPreprocess::default().bound(&g, "12", "33", 3);
+        assert!(bound.is_some());
+        let mut sssp: ProfilingSSSP<DijkstraSSSP> = ProfilingSSSP::new(DijkstraSSSP::new());
+        assert_eq!(YenKSP::new(&mut sssp).with_bound(bound).k_shortest_paths(&g, "12", "33", 3), exact);
+        assert!(sssp.timings.len() < n_exact, "Expected fewer than {n_exact} SSSP calls with a bound, got {}", sssp.timings.len());
+
+        // Also for directed graphs
+        let mut g: Graph = load_graph("cities");
+        g.directed = true;
+        let exact: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "Berlin", "Chicago", 3);
+        let bound: Option<f64> = PeekPreprocess::default().bound(&g, "Berlin", "Chicago", 3);
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).with_bound(bound).k_shortest_paths(&g, "Berlin", "Chicago", 3), exact);
+    }
+
+    #[test]
+    fn test_yen_ksp_tree() {
+        // Spurs that can follow the tree of shortest paths to the destination don't search, so only the tree and the first path do...
+        // NOTE: Ties aren't broken, such that the run stops right after the K-th path
+        let g: Graph = Graph::from_edges([("A-B", "A", "B", 1.0), ("B-D", "B", "D", 1.0), ("A-C", "A", "C", 1.5), ("C-D", "C", "D", 1.5)]);
+        let mut sssp: ProfilingSSSP<DijkstraSSSP> = ProfilingSSSP::new(DijkstraSSSP::new());
+        let paths: Vec<Path> = YenKSP::new(&mut sssp).with_tie_break(TieBreak::CostOnly).k_shortest_paths(&g, "A", "D", 2);
+        assert_eq!(paths, vec![path!(crate : g, "A" -> "B" -| "D"), path!(crate : g, "A" -> "C" -| "D")]);
+        assert_eq!(sssp.timings.len(), 2);
+
+        // ...while spurs whose tree path goes back through the spur node do
+        let g: Graph = Graph::from_edges([
+            ("S-A", "S", "A", 1.0),
+            ("A-D", "A", "D", 1.0),
+            ("S-B", "S", "B", 1.0),
+            ("B-A", "B", "A", 0.5),
+            ("B-D", "B", "D", 2.0),
+        ]);
+        let mut sssp: ProfilingSSSP<DijkstraSSSP> = ProfilingSSSP::new(DijkstraSSSP::new());
+        let paths: Vec<Path> = YenKSP::new(&mut sssp).with_tie_break(TieBreak::CostOnly).k_shortest_paths(&g, "S", "D", 2);
+        assert_eq!(paths, vec![path!(crate : g, "S" -> "A" -| "D"), path!(crate : g, "S" -> "B" -> "A" -| "D")]);
+        assert_eq!(sssp.timings.len(), 3);
+
+        // ...but still find the same paths, also if the tree is that of the reversed graph
+        let mut g: Graph = load_graph("cities");
+        g.directed = true;
+        for src in g.nodes.keys() {
+            for dst in g.nodes.keys() {
+                for k in 1..=4 {
+                    assert_eq!(
+                        YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, k),
+                        BruteForceKSP::simple().k_shortest_paths(&g, src, dst, k),
+                        "Disagreement with reference for {src} -> {dst} (k = {k})"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_yen_ksp_weighted() {
+        for _ in 0..10 {
+            let g: Graph = load_graph("cities");
+            let by_cost: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths_weighted(&g, "Berlin", "Chicago", 1, |e| e.cost);
+            let by_hops: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths_weighted(&g, "Berlin", "Chicago", 1, |_| 1.0);
+            assert_eq!(by_cost, by_hops);
+            assert_eq!(by_cost[0].cost(), 577.34 + 540.86 + 6249.15);
+            assert_eq!(by_hops[0].cost(), 3.0);
+
+            // The cheapest route has more hops than the next one, so the metrics rank them the other way around
+            let d: Graph = load_graph("detour");
+            let by_cost: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths_weighted(&d, "A", "E", 2, |e| e.cost);
+            let by_hops: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths_weighted(&d, "A", "E", 2, |_| 1.0);
+            assert_eq!(by_cost, vec![path!(crate : d, "A" -> "B" -> "C" -| "E"), path!(crate : d, "A" -> "D" -| "E")]);
+            assert_eq!(by_cost.iter().map(Path::cost).collect::<Vec<f64>>(), vec![3.0, 4.0]);
+            assert_eq!(by_hops, vec![path!(crate : d, "A" -> "D" -| "E"), path!(crate : d, "A" -> "B" -> "C" -| "E")]);
+            assert_eq!(by_hops.iter().map(Path::cost).collect::<Vec<f64>>(), vec![2.0, 3.0]);
+        }
+    }
 }
 
 
 
 
 
+/***** HELPERS *****/
+/// Extends a path along a shortest-path tree to its root, unless that visits a blocked node.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] the tree is in.
+/// - `tree`: The [`ShortestTree`] of shortest paths _to_ its root, i.e., where the predecessor of every node is its next hop.
+/// - `path`: The [`Path`] to extend from its last hop.
+/// - `blocked`: Decides whether a node may not be visited.
+/// - `weight`: The cost of every edge.
+///
+/// # Returns
+/// `path` followed by the tree path from its end to the root, or [`None`] if the tree path visits a blocked node.
+fn follow_tree<'g>(
+    graph: &'g Graph,
+    tree: &ShortestTree<'g>,
+    mut path: Path<'g>,
+    blocked: impl Fn(&str) -> bool,
+    weight: impl Fn(&Edge) -> f64,
+) -> Option<Path<'g>> {
+    let (mut node, mut cost): (&'g str, f64) = *path.hops.last()?;
+    while let Some((_, Some(next))) = tree.get(node) {
+        if blocked(next) {
+            return None;
+        }
+        // NOTE: We re-add the edge's cost instead of using the tree's distances to accumulate rounding errors like the SSSP does
+        cost += graph.successors(node).filter(|(n, _)| n == next).map(|(_, e)| weight(e)).fold(f64::INFINITY, f64::min);
+        path.hops.push((next, cost));
+        node = next;
+    }
+    Some(path)
+}
+
+
+
+
 /***** LIBRARY *****/
 /// Defines the vanilla, simplest version of a KSP-algorithm.
 ///
+/// Only finds simple paths, i.e., paths that don't visit any node twice. If there are fewer than K of them, the search stops as soon as no
+/// new candidate path can be found, and all simple paths are returned. Thus, asking for a K larger than the number of paths (even
+/// [`usize::MAX`]) is safe and costs no more than enumerating all of them, plus one iteration to find out there are no more. Every
+/// iteration does at most one SSSP search per hop of the previous path, so finding K paths takes O(Kn) SSSP searches for n nodes. No search is
+/// needed if the spur path can leave the spur node by its cheapest allowed edge and then follow the tree of shortest paths to the
+/// destination (computed once per run with [`ShortestPathTree::shortest_tree_weighted()`]) without visiting the root, or if it can't be
+/// reached at all.
+///
 /// Based on: <https://en.wikipedia.org/wiki/K_shortest_path_routing#Algorithm>
 #[derive(Clone, Copy, Debug)]
 pub struct YenKSP<S> {
     /// The SSSP algorithm used.
-    sssp: S,
+    sssp:           S,
+    /// How to order paths with the same cost.
+    tie_break:      TieBreak,
+    /// The maximum number of candidate paths to keep around, if any.
+    max_candidates: Option<usize>,
+    /// An upper bound on the cost of the K-th shortest path, if known.
+    bound:          Option<f64>,
 }
 impl<S> YenKSP<S> {
     /// Constructor for the YenKSP.
@@ -72,13 +333,66 @@ impl<S> YenKSP<S> {
     /// - `sssp`: The SSSP algorithm to use.
     ///
     /// # Returns
-    /// A new YenKSP instance.
+    /// A new YenKSP instance that breaks ties with [`TieBreak::FewerHops`] and keeps all candidates.
     #[inline]
-    pub const fn new(sssp: S) -> Self { Self { sssp } }
+    pub const fn new(sssp: S) -> Self { Self { sssp, tie_break: TieBreak::FewerHops, max_candidates: None, bound: None } }
+
+    /// Sets how paths with the same cost are ordered.
+    ///
+    /// This also decides which paths are returned if more paths have the same cost as the K-th one than fit. To do so, up to K of those are
+    /// found before cutting off, which costs extra searches (except for [`TieBreak::CostOnly`]). See [`TieBreak`] for more information.
+    ///
+    /// # Arguments
+    /// - `tie_break`: The [`TieBreak`] to use.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub const fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Bounds the number of candidate paths kept in between iterations.
+    ///
+    /// On dense graphs, every iteration may add many candidates that are never returned, driving up memory. With a cap, only the cheapest
+    /// `max` of them are kept after every iteration and the rest are dropped. Note that this makes the results suboptimal if the cap is
+    /// set too low, as a dropped candidate may have been one of the K shortest paths after all. The first path is always the shortest one,
+    /// though.
+    ///
+    /// # Arguments
+    /// - `max`: The maximum number of candidates to keep. [`None`] (the default) keeps all of them, preserving exactness.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub const fn with_max_candidates(mut self, max: Option<usize>) -> Self {
+        self.max_candidates = max;
+        self
+    }
+
+    /// Gives an upper bound on the cost of the K-th shortest path, e.g., as found by
+    /// [`PeekPreprocess::bound()`](crate::prep::peek::PeekPreprocess::bound()).
+    ///
+    /// Candidates above the bound are discarded. Spur searches are skipped if every edge leaving the spur node leads to a path above it,
+    /// which is judged by the shortest distance of every node to the destination (computed once per run). Neither changes the paths found
+    /// if the bound is correct, but it saves SSSP searches.
+    ///
+    /// # Arguments
+    /// - `bound`: The bound, in terms of the `weight` used to find paths (i.e., edge costs for [`KShortestPath::k_shortest_paths()`]). [`None`]
+    ///   (the default) doesn't bound paths. Note that a bound that is too low makes the results wrong.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub const fn with_bound(mut self, bound: Option<f64>) -> Self {
+        self.bound = bound;
+        self
+    }
 }
-impl<S: SingleShortestPath> KShortestPath for YenKSP<S> {
+impl<S: ShortestPathTree> KShortestPath for YenKSP<S> {
     #[track_caller]
-    fn k_shortest_paths<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize) -> Vec<Path<'g>> {
+    fn k_shortest_paths_weighted<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, k: usize, weight: impl Fn(&Edge) -> f64) -> Vec<Path<'g>> {
         // Assert that both nodes exists
         let src: &'g str = if let Some((key, _)) = graph.nodes.get_key_value(&ArrayString::from(src).unwrap()) {
             key
@@ -88,30 +402,162 @@ impl<S: SingleShortestPath> KShortestPath for YenKSP<S> {
         if !graph.nodes.contains_key(&ArrayString::from(dst).unwrap()) {
             panic!("Unknown source node '{dst}'");
         }
+        // There's nothing to find if we're asked for no paths
+        if k == 0 {
+            return Vec::new();
+        }
 
         // Then do the algorithm
-        let mut shortest: Vec<Path<'g>> = Vec::with_capacity(k);
-        shortest.push(self.sssp.shortest(graph, src, dst));
-        let mut candidates: HashSet<Path<'g>> = HashSet::with_capacity(k);
-        for i in 1..k {
-            // Consider the shortest paths of this length
-            // candidates.clear();
-            for hop in 0..shortest[i - 1].hops.len() {
-                let prefix: &[(&'g str, f64)] = &shortest[i - 1].hops[..i];
-                let suffix: Path<'g> = self.sssp.shortest(graph, shortest[i - 1].hops[hop].0, dst);
-                let path: Path<'g> = Path {
-                    hops: prefix.into_iter().copied().chain(suffix.hops.into_iter().map(|(n, c)| (n, prefix.last().unwrap().1 + c))).collect(),
+        let mut shortest: Vec<Path<'g>> = Vec::with_capacity(path_capacity(k));
+        match self.sssp.try_shortest_weighted(graph, src, dst, &weight) {
+            Some(path) => {
+                #[cfg(feature = "log")]
+                log::debug!("Yen iteration 0: found path 1/{k} from '{src}' to '{dst}' with cost {}", path.cost());
+                shortest.push(path);
+            },
+            None => {
+                #[cfg(feature = "log")]
+                log::debug!("Yen iteration 0: no path from '{src}' to '{dst}'");
+                return shortest;
+            },
+        }
+        let mut candidates: HashSet<Path<'g>> = HashSet::with_capacity(path_capacity(k));
+
+        // Find the shortest paths from every node to the destination, which are the spur paths wherever they avoid what's blocked. It also
+        // tells which spurs can't stay under the bound, if any.
+        // NOTE: If edges are undirected, the tree to the destination is the same as the one from it; else, it's the one from it in the
+        //       reversed graph.
+        let to_dst: ShortestTree<'g> = if graph.directed {
+            let reversed: Graph = graph.reversed();
+            let key = |node: &str| -> &'g str { graph.nodes.get_key_value(node).unwrap().0.as_str() };
+            let tree: ShortestTree = self.sssp.shortest_tree_weighted(&reversed, dst, &weight);
+            tree.into_iter().map(|(node, (cost, next))| (key(node), (cost, next.map(key)))).collect()
+        } else {
+            self.sssp.shortest_tree_weighted(graph, dst, &weight)
+        };
+        for i in 1.. {
+            // Beyond K paths, only ones tying with the K-th matter, and only if the tie-break may prefer them
+            if i >= tie_limit(self.tie_break, k) {
+                break;
+            }
+
+            // Consider deviating from the previous shortest path at every hop but the last
+            let prev: &Path<'g> = &shortest[i - 1];
+            for hop in 0..prev.hops.len() - 1 {
+                let (root, _): (Path<'g>, Path<'g>) = prev.split_at(hop);
+                let spur_node: &'g str = prev.hops[hop].0;
+
+                // Block the links used by the paths we already found that share this root...
+                let mut blocked_links: Vec<(&'g str, &'g str)> = Vec::with_capacity(shortest.len());
+                for path in &shortest {
+                    if path.hops.len() > hop + 1 && path.hops[..=hop].iter().zip(&root.hops).all(|((n1, _), (n2, _))| n1 == n2) {
+                        blocked_links.push((path.hops[hop].0, path.hops[hop + 1].0));
+                    }
+                }
+                // ...and the root itself, to keep the path simple
+                let blocked_nodes: HashSet<&'g str> = root.hops[..hop].iter().map(|(n, _)| *n).collect();
+
+                // Find the cheapest edge to leave the spur node by, if the tree of shortest paths is followed after it. No spur path can be
+                // cheaper, so there's none if that's infinite, and it's one that doesn't stay under the bound if that's too much.
+                let mut first: Option<(&'g str, f64)> = None;
+                let mut cheapest: f64 = f64::INFINITY;
+                for (next, e) in graph.successors(spur_node) {
+                    let (left, right): (&str, &str) = (e.left.as_str(), e.right.as_str());
+                    if blocked_nodes.contains(next)
+                        || blocked_links.iter().any(|(n1, n2)| (*n1 == left && *n2 == right) || (*n1 == right && *n2 == left))
+                    {
+                        continue;
+                    }
+                    let w: f64 = weight(e);
+                    let cost: f64 = w + to_dst.get(next).map(|(cost, _)| *cost).unwrap_or(f64::INFINITY);
+                    // NOTE: Ties are broken by node, for determinism
+                    if cost < cheapest || (cost == cheapest && cost < f64::INFINITY && first.is_some_and(|(n, _)| next < n)) {
+                        first = Some((next, w));
+                        cheapest = cost;
+                    }
+                }
+                let (next, w): (&'g str, f64) = match first {
+                    Some(first) => first,
+                    None => continue,
+                };
+                if let Some(bound) = self.bound {
+                    if prev.hops[hop].1 + cheapest > bound {
+                        #[cfg(feature = "log")]
+                        log::trace!("Yen iteration {i}: skipping spur at '{spur_node}', as it can't stay within bound {bound}");
+                        continue;
+                    }
+                }
+
+                // Find the spur path in the remaining graph. If following the tree from that edge avoids the root and the spur node itself,
+                // that's it; else, search for it.
+                let spur: Option<Path<'g>> = match follow_tree(
+                    graph,
+                    &to_dst,
+                    Path { hops: vec![(spur_node, 0.0), (next, w)] },
+                    |node: &str| node == spur_node || blocked_nodes.contains(node),
+                    &weight,
+                ) {
+                    Some(spur) => Some(spur),
+                    None => self.sssp.try_shortest_weighted(graph, spur_node, dst, |e: &Edge| {
+                        let (left, right): (&str, &str) = (e.left.as_str(), e.right.as_str());
+                        if blocked_nodes.contains(left)
+                            || blocked_nodes.contains(right)
+                            || blocked_links.iter().any(|(n1, n2)| (*n1 == left && *n2 == right) || (*n1 == right && *n2 == left))
+                        {
+                            f64::INFINITY
+                        } else {
+                            weight(e)
+                        }
+                    }),
                 };
-                candidates.insert(path);
+                if let Some(path) = spur.and_then(|spur| Path::concat(&root, &spur)) {
+                    if !shortest.contains(&path) && self.bound.is_none_or(|bound| path.cost() <= bound) {
+                        #[cfg(feature = "log")]
+                        log::trace!("Yen iteration {i}: spur at '{spur_node}' gives candidate with cost {}", path.cost());
+                        candidates.insert(path);
+                    }
+                }
+            }
+
+            // Drop the most expensive candidates if there are too many
+            if let Some(max) = self.max_candidates {
+                if candidates.len() > max {
+                    let mut sorted: Vec<Path<'g>> = candidates.drain().collect();
+                    sorted.sort_by(|p1, p2| self.tie_break.compare(p1, p2));
+                    #[cfg(feature = "log")]
+                    log::trace!("Yen iteration {i}: dropping {} candidate(s) beyond the cap of {max}", sorted.len() - max);
+                    sorted.truncate(max);
+                    candidates.extend(sorted);
+                }
             }
 
-            // Store it
-            if let Some(min) = candidates.iter().min_by(|p1, p2| p1.cost().partial_cmp(&p2.cost()).unwrap_or(Ordering::Equal)) {
-                shortest.push(min.clone());
+            // Store the cheapest candidate; if there are none, there are no more paths to find
+            let min: Path<'g> = match candidates.iter().min_by(|p1, p2| self.tie_break.compare(p1, p2)) {
+                Some(min) => min.clone(),
+                None => {
+                    #[cfg(feature = "log")]
+                    log::debug!("Yen iteration {i}: no candidates left, stopping at {i}/{k} path(s)");
+                    break;
+                },
+            };
+            if i >= k {
+                if OrderedCost(min.cost()) != OrderedCost(shortest[k - 1].cost()) {
+                    break;
+                }
+                #[cfg(feature = "log")]
+                log::debug!("Yen iteration {i}: found path with cost {} tying with path {k}/{k}", min.cost());
+            } else {
+                #[cfg(feature = "log")]
+                log::debug!("Yen iteration {i}: found path {}/{k} with cost {} ({} candidate(s) left)", i + 1, min.cost(), candidates.len());
             }
+            candidates.remove(&min);
+            shortest.push(min);
         }
 
-        // OK, done
+        // The first path came from the SSSP before its alternatives were known, and the K-th may tie with ones after it, so order all of
+        // those as asked before cutting off
+        shortest.sort_by(|p1, p2| self.tie_break.compare(p1, p2));
+        shortest.truncate(k);
         shortest
     }
 }
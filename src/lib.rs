@@ -4,7 +4,7 @@
 //  Created:
 //    16 Jul 2024, 00:06:19
 //  Last edited:
-//    24 Jul 2024, 23:33:03
+//    16 Oct 2026, 19:30:05
 //  Auto updated?
 //    Yes
 //
@@ -13,10 +13,19 @@
 //!   algorithms.
 //
 
+// We keep tests at the top of every module
+#![allow(clippy::items_after_test_module)]
+
 // Declare modules
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod centrality;
+pub mod cost;
+pub mod flow;
 pub mod ksp;
 pub mod path;
 pub mod prep;
+pub mod query;
 pub mod sssp;
 #[cfg(test)]
 pub mod utils;
@@ -26,15 +35,233 @@ use std::fmt::{Display, Formatter, Result as FResult};
 use std::str::FromStr;
 use std::time::{Duration, Instant};
 
-use ksp_graph::Graph;
+use ksp_graph::{Graph, GraphGuard};
 use sssp::profiled::ProfilingSSSP;
 use sssp::Sssp;
 
 // Use some of it in this namespace
+pub use crate::cost::OrderedCost;
 pub use crate::ksp::*;
 pub use crate::path::*;
 
 
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_validate() {
+        for raw in ["wikipedia", "yen<dijkstra>", "peek->hoffman-pavley<dijkstra>"] {
+            assert_eq!(raw.parse::<Pipeline>().unwrap().validate(), Ok(()), "{raw}");
+        }
+
+        // SSSPs that aren't used
+        let pip: Pipeline = "peek->wikipedia<dijkstra>".parse().unwrap();
+        assert_eq!(pip.validate(), Err(vec![PipelineWarning::UnusedSssp { alg: Algorithm::Wikipedia, sssp: Sssp::Dijkstra }]));
+        assert_eq!(
+            pip.validate().unwrap_err()[0].to_string(),
+            "SSSP algorithm 'Dijkstra' is ignored, because algorithm 'Wikipedia' does not use one"
+        );
+
+        // Steps that are repeated
+        let pip: Pipeline = "peek->peek->peek->yen<dijkstra>".parse().unwrap();
+        assert_eq!(
+            pip.validate(),
+            Err(vec![
+                PipelineWarning::RepeatedStep { step: prep::Step::Peek, index: 1 },
+                PipelineWarning::RepeatedStep { step: prep::Step::Peek, index: 2 },
+            ])
+        );
+
+        // Both
+        let pip: Pipeline = "peek->peek->wikipedia<dijkstra>".parse().unwrap();
+        assert_eq!(pip.validate().unwrap_err().len(), 2);
+    }
+
+    #[test]
+    fn test_pipeline_example() {
+        let pip: Pipeline = Pipeline::example();
+        assert_eq!(pip.id(), "peek->yen<dijkstra>");
+        assert_eq!(pip.id().parse::<Pipeline>().unwrap(), pip);
+
+        // It's serialized as its identifier
+        #[cfg(feature = "serde")]
+        {
+            let raw: String = serde_json::to_string(&pip).unwrap();
+            assert_eq!(raw, "\"peek->yen<dijkstra>\"");
+            assert_eq!(serde_json::from_str::<Pipeline>(&raw).unwrap(), pip);
+            assert!(serde_json::from_str::<Pipeline>("\"yen\"").is_err());
+        }
+    }
+
+    #[test]
+    fn test_pipeline_plan() {
+        assert_eq!(Pipeline::example().plan(), vec!["Prep: Peek", "KSP: Yen<Dijkstra>"]);
+        assert_eq!("contract-chains->peek->wikipedia".parse::<Pipeline>().unwrap().plan(), vec![
+            "Prep: ContractChains",
+            "Prep: Peek",
+            "KSP: Wikipedia"
+        ]);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_pipeline_json_schema() {
+        let schema: serde_json::Value = Pipeline::json_schema();
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["examples"][0], "peek->yen<dijkstra>");
+        assert_eq!(
+            schema["pattern"],
+            "^(?:(?:peek|contract-chains)->)*(?:hoffman-pavley<(?:dijkstra)>|wikipedia(?:<(?:dijkstra)>)?|yen<(?:dijkstra)>)$"
+        );
+    }
+
+    #[test]
+    fn test_pipeline_fallback() {
+        let mut g: Graph = utils::load_graph("cities");
+        let pip: Pipeline = "yen<dijkstra>".parse().unwrap();
+        let expected: Vec<OwnedPath> = {
+            let mut guard: GraphGuard = GraphGuard::new(&mut g);
+            pip.k_shortest_paths_profiled(&mut guard, "Amsterdam", "Chicago", 2).0.iter().map(Path::to_owned_path).collect()
+        };
+        assert_eq!(expected.len(), 2);
+
+        // A prune that is too tight removes the second path, so it's run again on the original
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        guard.set_cost("Amsterdam-Berlin", 1.0);
+        let paths: Vec<OwnedPath> = pip
+            .k_shortest_paths_with_fallback(&mut guard, "Amsterdam", "Chicago", 2, |g: &mut GraphGuard| {
+                g.remove_node("Edinburgh");
+                vec![Duration::ZERO]
+            })
+            .0
+            .iter()
+            .map(Path::to_owned_path)
+            .collect();
+        assert_eq!(paths, expected);
+        // Only the prune is undone
+        assert!(guard.nodes.contains_key("Edinburgh"));
+        assert_eq!(guard.edges["Amsterdam-Berlin"].cost, 1.0);
+        drop(guard);
+
+        // Same if it prunes the endpoints
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        let (paths, profile): (Vec<Path>, PipelineProfile) =
+            pip.k_shortest_paths_with_fallback(&mut guard, "Amsterdam", "Chicago", 2, |g: &mut GraphGuard| {
+                g.retain_nodes(|_| false);
+                vec![Duration::ZERO]
+            });
+        assert_eq!(paths.iter().map(Path::to_owned_path).collect::<Vec<OwnedPath>>(), expected);
+        assert_eq!(profile.prep.len(), 1);
+        drop(guard);
+
+        // But a prune that keeps enough paths is kept
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        let paths: Vec<OwnedPath> = pip
+            .k_shortest_paths_with_fallback(&mut guard, "Amsterdam", "Chicago", 2, |g: &mut GraphGuard| {
+                g.remove_node("Berlin");
+                vec![Duration::ZERO]
+            })
+            .0
+            .iter()
+            .map(Path::to_owned_path)
+            .collect();
+        assert_eq!(paths, expected);
+        assert!(!guard.nodes.contains_key("Berlin"));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_pipeline_tracing() {
+        use crate::utils::CapturedSpan;
+
+        let mut g: Graph = crate::utils::load_graph("cities");
+        let pip: Pipeline = "peek->yen<dijkstra>".parse().unwrap();
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        let (n, spans): (usize, Vec<CapturedSpan>) =
+            crate::utils::capture_spans(|| pip.k_shortest_paths_profiled(&mut guard, "Amsterdam", "Chicago", 2).0.len());
+        assert_eq!(n, 2);
+
+        // The preprocessing, the KSP call and every SSSP call are spanned
+        let prep: &CapturedSpan = spans.iter().find(|span| span.metadata.name() == "preprocess_steps").expect("No preprocessing span");
+        assert_eq!(prep.field("k"), Some("2"));
+        assert!(prep.field("pruned_nodes").is_some());
+        let ksp: &CapturedSpan = spans.iter().find(|span| span.metadata.name() == "k_shortest_paths_prepped").expect("No KSP span");
+        assert_eq!(ksp.field("pipeline"), Some("Peek->Yen<Dijkstra>"));
+        assert_eq!(ksp.field("src"), Some("\"Amsterdam\""));
+        assert_eq!(ksp.field("dst"), Some("\"Chicago\""));
+        assert_eq!(ksp.field("paths"), Some("2"));
+        assert!(spans.iter().any(|span| span.metadata.name() == "try_shortest_node_weighted" && span.field("found") == Some("true")));
+    }
+
+    #[test]
+    fn test_pipeline_k_shortest_paths_all() {
+        let mut g: Graph = utils::load_graph("cities");
+        let original: Graph = g.clone();
+        let pips: [Pipeline; 3] = ["peek->yen<dijkstra>".parse().unwrap(), "wikipedia".parse().unwrap(), "yen<dijkstra>".parse().unwrap()];
+        let res: Vec<(usize, Vec<OwnedPath>)> = Pipeline::k_shortest_paths_all(&pips, &mut g, "Amsterdam", "Chicago", 3);
+        assert!(original.diff(&g).is_empty());
+
+        // Every pipeline's result is there, in order
+        assert_eq!(res.iter().map(|(i, _)| *i).collect::<Vec<usize>>(), vec![0, 1, 2]);
+        for (i, paths) in &res {
+            let mut guard: GraphGuard = GraphGuard::new(&mut g);
+            let (expected, _): (Vec<Path>, PipelineProfile) = pips[*i].k_shortest_paths_profiled(&mut guard, "Amsterdam", "Chicago", 3);
+            assert_eq!(*paths, expected.iter().map(Path::to_owned_path).collect::<Vec<OwnedPath>>());
+        }
+        // The simple and non-simple algorithms disagree on the last one
+        assert_eq!(res[0].1, res[2].1);
+        assert_ne!(res[1].1, res[2].1);
+    }
+
+    #[test]
+    fn test_pipeline_k_shortest_paths_batch() {
+        for (name, mut g) in [("cities", utils::load_graph("cities")), ("abilene", utils::load_bench("abilene"))] {
+            let original: Graph = g.clone();
+            let mut ids: Vec<String> = g.nodes.keys().map(|id| id.to_string()).collect();
+            ids.sort();
+            let queries: Vec<(&str, &str, usize)> =
+                ids.iter().flat_map(|src| ids.iter().map(move |dst| (src.as_str(), dst.as_str(), 3))).collect();
+
+            // The batch gives the same as every query on its own
+            for pip in ["peek->yen<dijkstra>", "wikipedia", "yen<dijkstra>"] {
+                let pip: Pipeline = pip.parse().unwrap();
+                let res: Vec<Result<Option<Vec<OwnedPath>>, BatchQueryError>> = pip.k_shortest_paths_batch(&mut g, &queries);
+                assert!(original.diff(&g).is_empty());
+                assert_eq!(res.len(), queries.len());
+                for ((src, dst, k), res) in queries.iter().zip(res) {
+                    let mut guard: GraphGuard = GraphGuard::new(&mut g);
+                    let (expected, _): (Vec<Path>, PipelineProfile) = pip.k_shortest_paths_profiled(&mut guard, src, dst, *k);
+                    let expected: Vec<OwnedPath> = expected.iter().map(Path::to_owned_path).collect();
+                    assert_eq!(res.unwrap(), Some(expected), "Disagreement for {src} -> {dst} in {name} with {pip}");
+                }
+            }
+        }
+
+        // Bad queries fail on their own, and no paths is given as none
+        let mut g: Graph = utils::load_graph("cities");
+        let pip: Pipeline = "peek->yen<dijkstra>".parse().unwrap();
+        g.edges.remove("Chicago-Dorchester");
+        let res: Vec<Result<Option<Vec<OwnedPath>>, BatchQueryError>> = pip.k_shortest_paths_batch(&mut g, &[
+            ("Amsterdam", "Utrecht", 1),
+            ("Utrecht", "Amsterdam", 1),
+            ("Amsterdam", "Chicago", 1),
+            ("Amsterdam", "Chicago", 0),
+            ("Amsterdam", "Berlin", 1),
+        ]);
+        assert!(matches!(&res[0], Err(BatchQueryError::UnknownDestination { dst }) if dst == "Utrecht"));
+        assert!(matches!(&res[1], Err(BatchQueryError::UnknownSource { src }) if src == "Utrecht"));
+        assert_eq!(res[2].as_ref().unwrap(), &None);
+        assert_eq!(res[3].as_ref().unwrap(), &Some(vec![]));
+        assert_eq!(res[4].as_ref().unwrap(), &Some(vec![crate::path!(crate : g, "Amsterdam" -| "Berlin").to_owned_path()]));
+    }
+}
+
+
+
+
+
 /***** ERRORS *****/
 /// Failed to parse a [`Pipeline`] from a string.
 #[derive(Debug)]
@@ -81,6 +308,39 @@ impl Error for PipelineParseError {
 
 
 
+/// A query given to [`Pipeline::k_shortest_paths_batch()`] could not be run.
+#[derive(Debug)]
+pub enum BatchQueryError {
+    /// The source node of the query is not in the graph.
+    UnknownSource { src: String },
+    /// The destination node of the query is not in the graph.
+    UnknownDestination { dst: String },
+}
+impl Display for BatchQueryError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use BatchQueryError::*;
+        match self {
+            UnknownSource { src } => write!(f, "Unknown source node '{src}'"),
+            UnknownDestination { dst } => write!(f, "Unknown destination node '{dst}'"),
+        }
+    }
+}
+impl Error for BatchQueryError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use BatchQueryError::*;
+        match self {
+            UnknownSource { .. } => None,
+            UnknownDestination { .. } => None,
+        }
+    }
+}
+
+
+
+
+
 /***** HELPERS *****/
 /// Defines profile timings of a [`Pipeline`]-run.
 #[derive(Clone, Debug)]
@@ -95,6 +355,29 @@ pub struct PipelineProfile {
 
 
 
+/// Defines things in a [`Pipeline`] that are allowed, but probably not what the user intended.
+///
+/// Found by [`Pipeline::validate()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PipelineWarning {
+    /// An SSSP algorithm was given for an algorithm that doesn't use one, so it has no effect on the paths found.
+    UnusedSssp { alg: Algorithm, sssp: Sssp },
+    /// A preprocessing step is the same as the one before it. It has already done all it can, so this only costs time.
+    RepeatedStep { step: prep::Step, index: usize },
+}
+impl Display for PipelineWarning {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use PipelineWarning::*;
+        match self {
+            UnusedSssp { alg, sssp } => write!(f, "SSSP algorithm '{sssp:?}' is ignored, because algorithm '{alg:?}' does not use one"),
+            RepeatedStep { step, index } => write!(f, "Preprocessing step {index} ('{step:?}') repeats the step before it"),
+        }
+    }
+}
+
+
+
 
 
 /***** LIBRARY *****/
@@ -109,50 +392,430 @@ pub struct Pipeline {
     sssp: Option<sssp::Sssp>,
 }
 impl Pipeline {
-    /// Computes the K-Shortest Path algorithm as defined by this [`Pipeline`].
+    /// Returns a pipeline showing all parts of the format.
+    ///
+    /// # Returns
+    /// The pipeline `peek->yen<dijkstra>`.
+    #[inline]
+    pub fn example() -> Self { Self { prep: vec![prep::Step::Peek], alg: Algorithm::Yen, sssp: Some(Sssp::Dijkstra) } }
+
+    /// Returns the identifier by which this pipeline is parsed.
+    ///
+    /// Unlike the [`Display`]-implementation, which is meant for humans, this is the format accepted by [`Pipeline::from_str()`] (and used
+    /// to (de)serialize it).
+    ///
+    /// # Returns
+    /// A string that [`Pipeline::from_str()`] parses back to this pipeline, e.g., `peek->yen<dijkstra>`.
+    pub fn id(&self) -> String {
+        let mut id: String = String::new();
+        for step in &self.prep {
+            id.push_str(step.id());
+            id.push_str("->");
+        }
+        id.push_str(self.alg.id());
+        if let Some(sssp) = &self.sssp {
+            id.push('<');
+            id.push_str(sssp.id());
+            id.push('>');
+        }
+        id
+    }
+
+    /// Returns a JSON Schema describing how pipelines are serialized.
+    ///
+    /// Pipelines are serialized as the string returned by [`Pipeline::id()`], so the schema is that of a string that matches only valid
+    /// pipelines.
+    ///
+    /// # Returns
+    /// The schema as a [`serde_json::Value`].
+    #[cfg(feature = "schema")]
+    #[inline]
+    pub fn json_schema() -> serde_json::Value { schemars::schema_for!(Pipeline).to_value() }
+
+    /// Lists what this pipeline does, without doing any of it.
+    ///
+    /// # Returns
+    /// A description of every step in the order they're run: first the preprocessing steps (e.g., `Prep: Peek`), then the algorithm with
+    /// its SSSP algorithm, if any (e.g., `KSP: Yen<Dijkstra>`).
+    pub fn plan(&self) -> Vec<String> {
+        let mut plan: Vec<String> = Vec::with_capacity(self.prep.len() + 1);
+        for step in &self.prep {
+            plan.push(format!("Prep: {step:?}"));
+        }
+        plan.push(match &self.sssp {
+            Some(sssp) => format!("KSP: {:?}<{sssp:?}>", self.alg),
+            None => format!("KSP: {:?}", self.alg),
+        });
+        plan
+    }
+
+    /// Returns whether this pipeline has any preprocessing steps.
+    ///
+    /// # Returns
+    /// True if [`Pipeline::preprocess()`] alters the graph at all, or false if it's a no-op.
+    #[inline]
+    pub fn has_prep(&self) -> bool { !self.prep.is_empty() }
+
+    /// Checks this pipeline for parts that are probably not intended.
+    ///
+    /// Such pipelines can still be run (which is why this isn't done when parsing them), but some of their parts won't have any effect on
+    /// the paths found. [`Pipeline::k_shortest_paths_profiled()`] logs these as warnings if the `log`-feature is enabled.
+    ///
+    /// # Errors
+    /// This function errors with a list of all [`PipelineWarning`]s that apply, in the order they occur in the pipeline.
+    pub fn validate(&self) -> Result<(), Vec<PipelineWarning>> {
+        let mut warns: Vec<PipelineWarning> = Vec::new();
+        for (index, step) in self.prep.iter().enumerate().skip(1) {
+            if *step == self.prep[index - 1] {
+                warns.push(PipelineWarning::RepeatedStep { step: step.clone(), index });
+            }
+        }
+        if let Some(sssp) = &self.sssp {
+            if !self.alg.needs_sssp() {
+                warns.push(PipelineWarning::UnusedSssp { alg: self.alg.clone(), sssp: sssp.clone() });
+            }
+        }
+        if warns.is_empty() { Ok(()) } else { Err(warns) }
+    }
+
+    /// Runs only the preprocessing steps of this [`Pipeline`].
     ///
     /// # Arguments
-    /// - `graph`: The [`Graph`] to find in.
+    /// - `graph`: The [`GraphGuard`] wrapping the graph to preprocess. Dropping it afterwards undoes the preprocessing.
     /// - `src`: The source node to find a path from.
     /// - `dst`: The destination node to find a path to.
     /// - `k`: The number of paths to find.
     ///
     /// # Returns
-    /// A pair of the list of the shortest paths found and a [`PipelineProfile`] detailling how long every step took.
-    ///
-    /// The path list is at most `k` elements long.
+    /// The amount of time each step took.
     ///
     /// # Panics
     /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
     #[inline]
-    pub fn k_shortest_paths_profiled<'g>(&self, graph: &'g mut Graph, src: &str, dst: &str, k: usize) -> (Vec<Path<'g>>, PipelineProfile) {
-        // First, pre-process the graph
-        let mut prep_timings: Vec<Duration> = Vec::with_capacity(self.prep.len());
-        for p in &self.prep {
+    pub fn preprocess(&self, graph: &mut GraphGuard, src: &str, dst: &str, k: usize) -> Vec<Duration> {
+        Self::preprocess_steps(&self.prep, graph, src, dst, k)
+    }
+
+    /// Implements [`Pipeline::preprocess()`] for only some of the steps.
+    ///
+    /// # Arguments
+    /// - `steps`: The [`prep::Step`]s to run, in order.
+    /// - `graph`: The [`GraphGuard`] wrapping the graph to preprocess.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `k`: The number of paths to find.
+    ///
+    /// # Returns
+    /// The amount of time each step took.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(steps, graph), fields(pruned_nodes, pruned_edges)))]
+    fn preprocess_steps(steps: &[prep::Step], graph: &mut GraphGuard, src: &str, dst: &str, k: usize) -> Vec<Duration> {
+        #[cfg(feature = "tracing")]
+        let (nodes, edges): (usize, usize) = (graph.nodes.len(), graph.edges.len());
+        let mut timings: Vec<Duration> = Vec::with_capacity(steps.len());
+        for p in steps {
             use prep::PreprocessStep as _;
             match p {
                 prep::Step::Peek => {
                     let start: Instant = Instant::now();
-                    prep::peek::PeekPreprocess::preprocess(graph, src, dst, k);
-                    prep_timings.push(start.elapsed());
+                    prep::peek::PeekPreprocess::default().preprocess(graph, src, dst, k);
+                    timings.push(start.elapsed());
                 },
+                prep::Step::ContractChains => {
+                    let start: Instant = Instant::now();
+                    prep::chains::ContractChainsPreprocess.preprocess(graph, src, dst, k);
+                    timings.push(start.elapsed());
+                },
+            }
+        }
+        #[cfg(feature = "tracing")]
+        tracing::Span::current()
+            .record("pruned_nodes", nodes.saturating_sub(graph.nodes.len()))
+            .record("pruned_edges", edges.saturating_sub(graph.edges.len()));
+        timings
+    }
+
+    /// Computes the K-Shortest Path algorithm as defined by this [`Pipeline`].
+    ///
+    /// # Arguments
+    /// - `graph`: The [`GraphGuard`] wrapping the graph to find in. Any preprocessing is done through it, so dropping it afterwards restores
+    ///   the graph.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `k`: The number of paths to find.
+    ///
+    /// # Returns
+    /// A pair of the list of the shortest paths found and a [`PipelineProfile`] detailling how long every step took.
+    ///
+    /// The path list is at most `k` elements long. If `k` is 0, then the list is always empty.
+    ///
+    /// If the preprocessing changed the graph such that fewer than `k` paths are found, then the algorithm is run again on the graph as it
+    /// was before preprocessing (and a warning is logged). Steps only prune if there are at least `k` paths, so this only happens if a step
+    /// prunes too much.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
+    #[inline]
+    pub fn k_shortest_paths_profiled<'g>(
+        &self,
+        graph: &'g mut GraphGuard,
+        src: &str,
+        dst: &str,
+        k: usize,
+    ) -> (Vec<Path<'g>>, PipelineProfile) {
+        #[cfg(feature = "log")]
+        if let Err(warns) = self.validate() {
+            for warn in warns {
+                log::warn!("Pipeline {self}: {warn}");
+            }
+        }
+        self.k_shortest_paths_with_fallback(graph, src, dst, k, |graph: &mut GraphGuard| self.preprocess(graph, src, dst, k))
+    }
+
+    /// Implements [`Pipeline::k_shortest_paths_profiled()`] for a custom preprocessing function.
+    ///
+    /// Unlike it, this doesn't log whether the pipeline is [valid](Pipeline::validate()), so it's quiet when running the same pipeline many
+    /// times (e.g., in benchmarks).
+    ///
+    /// # Arguments
+    /// - `graph`: The [`GraphGuard`] wrapping the graph to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `k`: The number of paths to find.
+    /// - `prep`: A function that preprocesses the graph and returns the time each step took.
+    ///
+    /// # Returns
+    /// A pair of the list of the shortest paths found and a [`PipelineProfile`]. If the algorithm had to be re-run, then the profile
+    /// contains the time of both runs.
+    pub fn k_shortest_paths_with_fallback<'g>(
+        &self,
+        graph: &'g mut GraphGuard,
+        src: &str,
+        dst: &str,
+        k: usize,
+        prep: impl FnOnce(&mut GraphGuard) -> Vec<Duration>,
+    ) -> (Vec<Path<'g>>, PipelineProfile) {
+        let checkpoint: usize = graph.checkpoint();
+        let prep_timings: Vec<Duration> = prep(graph);
+        if graph.checkpoint() == checkpoint {
+            // Nothing changed, so nothing to fall back to either
+            let graph: &'g GraphGuard = graph;
+            let (paths, mut profile): (Vec<Path<'g>>, PipelineProfile) = self.k_shortest_paths_prepped(graph, src, dst, k);
+            profile.prep = prep_timings;
+            return (paths, profile);
+        }
+
+        // Run on the preprocessed graph first. Note that the endpoints may have been pruned, too.
+        // NOTE: We own the paths, as they must not borrow the graph if we need to restore it
+        let (paths, mut profile): (Vec<OwnedPath>, PipelineProfile) = if graph.nodes.contains_key(src) && graph.nodes.contains_key(dst) {
+            let (paths, profile): (Vec<Path>, PipelineProfile) = self.k_shortest_paths_prepped(graph, src, dst, k);
+            (paths.iter().map(Path::to_owned_path).collect(), profile)
+        } else {
+            (Vec::new(), PipelineProfile { prep: vec![], alg: Duration::ZERO, sssp: vec![] })
+        };
+        profile.prep = prep_timings;
+        if paths.len() >= k {
+            let graph: &'g GraphGuard = graph;
+            let paths: Vec<Path<'g>> = paths.iter().map(|path| path.to_path(graph).expect("Found path with nodes not in the graph")).collect();
+            return (paths, profile);
+        }
+
+        // Else, the preprocessing pruned too much; fall back to the original graph
+        #[cfg(feature = "log")]
+        log::warn!(
+            "Pipeline {self}: found only {}/{k} paths from '{src}' to '{dst}' after preprocessing; re-running on the unpreprocessed graph",
+            paths.len()
+        );
+        graph.restore_to(checkpoint);
+        let graph: &'g GraphGuard = graph;
+        let (paths, fallback): (Vec<Path<'g>>, PipelineProfile) = self.k_shortest_paths_prepped(graph, src, dst, k);
+        profile.alg += fallback.alg;
+        profile.sssp.extend(fallback.sssp);
+        (paths, profile)
+    }
+
+    /// Computes the K shortest paths with multiple [`Pipeline`]s on the same graph, e.g., to compare them.
+    ///
+    /// The graph is restored after every pipeline, such that each of them sees the original one.
+    ///
+    /// # Arguments
+    /// - `pipelines`: The [`Pipeline`]s to run, in order.
+    /// - `graph`: The [`Graph`] to find in. Is the same as it was after this function returns.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `k`: The number of paths to find.
+    ///
+    /// # Returns
+    /// A list of pairs of the index of every pipeline in `pipelines` and the paths it found. As the graph is borrowed mutably, these are
+    /// returned as [`OwnedPath`]s.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
+    pub fn k_shortest_paths_all(pipelines: &[Self], graph: &mut Graph, src: &str, dst: &str, k: usize) -> Vec<(usize, Vec<OwnedPath>)> {
+        let mut res: Vec<(usize, Vec<OwnedPath>)> = Vec::with_capacity(pipelines.len());
+        for (i, pip) in pipelines.iter().enumerate() {
+            let mut guard: GraphGuard = GraphGuard::new(graph);
+            let (paths, _): (Vec<Path>, PipelineProfile) = pip.k_shortest_paths_profiled(&mut guard, src, dst, k);
+            res.push((i, paths.iter().map(Path::to_owned_path).collect()));
+        }
+        res
+    }
+
+    /// Computes the K-Shortest Path algorithm as defined by this [`Pipeline`], unless the result is already in a [`cache::QueryCache`].
+    ///
+    /// On a miss, the paths are computed like [`Pipeline::k_shortest_paths_profiled()`] does and stored in the cache.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in. Is the same as it was after this function returns.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `k`: The number of paths to find.
+    /// - `cache`: The [`cache::QueryCache`] to look in and store in.
+    ///
+    /// # Returns
+    /// The list of the shortest paths found. As the graph is borrowed mutably, these are returned as [`OwnedPath`]s.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read from or write to the `cache`.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
+    #[cfg(feature = "cache")]
+    pub fn k_shortest_paths_cached(
+        &self,
+        graph: &mut Graph,
+        src: &str,
+        dst: &str,
+        k: usize,
+        cache: &cache::QueryCache,
+    ) -> Result<Vec<OwnedPath>, cache::Error> {
+        let key: cache::QueryKey = cache::QueryKey::new(graph, src, dst, k, self);
+        cache.get_or_insert_with(&key, || {
+            let mut guard: GraphGuard = GraphGuard::new(graph);
+            let (paths, _): (Vec<Path>, PipelineProfile) = self.k_shortest_paths_profiled(&mut guard, src, dst, k);
+            paths.iter().map(Path::to_owned_path).collect()
+        })
+    }
+
+    /// Computes the K-Shortest Path algorithm as defined by this [`Pipeline`] for many queries on the same graph.
+    ///
+    /// This is faster than running [`Pipeline::k_shortest_paths_profiled()`] for every query, as the preprocessing steps at the start of the
+    /// pipeline that don't [depend on the query](prep::Step::depends_on_query()) are only run once. The steps from the first one that does
+    /// are still run per query. Either way, the paths found are the same as if every query was run on its own, including the fallback to the
+    /// unpreprocessed graph if the preprocessing pruned too much.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in. Is the same as it was after this function returns.
+    /// - `queries`: The queries to run, as triples of the source node, the destination node and the number of paths to find.
+    ///
+    /// # Returns
+    /// The result of every query, in the same order as `queries`. Each is [`None`] if no path at all was found (and K isn't 0), or else the
+    /// list of at most K shortest paths. As the graph is borrowed mutably, these are returned as [`OwnedPath`]s.
+    ///
+    /// # Errors
+    /// A query fails with a [`BatchQueryError`] if its source or destination is not in the `graph`. This doesn't affect the other queries.
+    pub fn k_shortest_paths_batch(
+        &self,
+        graph: &mut Graph,
+        queries: &[(&str, &str, usize)],
+    ) -> Vec<Result<Option<Vec<OwnedPath>>, BatchQueryError>> {
+        #[cfg(feature = "log")]
+        if let Err(warns) = self.validate() {
+            for warn in warns {
+                log::warn!("Pipeline {self}: {warn}");
             }
         }
 
+        // Check the endpoints before anything is pruned
+        let mut res: Vec<Result<Option<Vec<OwnedPath>>, BatchQueryError>> = queries
+            .iter()
+            .map(|(src, dst, _)| {
+                if !graph.nodes.contains_key(*src) {
+                    Err(BatchQueryError::UnknownSource { src: (*src).into() })
+                } else if !graph.nodes.contains_key(*dst) {
+                    Err(BatchQueryError::UnknownDestination { dst: (*dst).into() })
+                } else {
+                    Ok(None)
+                }
+            })
+            .collect();
+
+        // Only the steps before the first one that depends on the query can be shared
+        let shared: usize = self.prep.iter().take_while(|step| !step.depends_on_query()).count();
+        let mut guard: GraphGuard = GraphGuard::new(graph);
+        let mut shared_done: bool = false;
+        for ((src, dst, k), res) in queries.iter().zip(res.iter_mut()) {
+            if res.is_err() {
+                continue;
+            }
+            if !shared_done {
+                // NOTE: These steps don't depend on the query, so which one we give doesn't matter
+                Self::preprocess_steps(&self.prep[..shared], &mut guard, src, dst, *k);
+                shared_done = true;
+            }
+
+            // Then run the rest per query
+            let checkpoint: usize = guard.checkpoint();
+            Self::preprocess_steps(&self.prep[shared..], &mut guard, src, dst, *k);
+            let prepped: usize = guard.checkpoint();
+            let mut paths: Vec<OwnedPath> = if guard.nodes.contains_key(*src) && guard.nodes.contains_key(*dst) {
+                let (paths, _): (Vec<Path>, PipelineProfile) = self.k_shortest_paths_prepped(&guard, src, dst, *k);
+                paths.iter().map(Path::to_owned_path).collect()
+            } else {
+                Vec::new()
+            };
+            guard.restore_to(checkpoint);
+
+            // Fall back to the original graph like a single query would, which means re-doing the shared steps for the next one
+            if paths.len() < *k && prepped > 0 {
+                #[cfg(feature = "log")]
+                log::warn!(
+                    "Pipeline {self}: found only {}/{k} paths from '{src}' to '{dst}' after preprocessing; re-running on the unpreprocessed graph",
+                    paths.len()
+                );
+                guard.restore();
+                shared_done = false;
+                let (fallback, _): (Vec<Path>, PipelineProfile) = self.k_shortest_paths_prepped(&guard, src, dst, *k);
+                paths = fallback.iter().map(Path::to_owned_path).collect();
+            }
+            *res = Ok(if paths.is_empty() && *k > 0 { None } else { Some(paths) });
+        }
+        res
+    }
+
+    /// Computes the K-Shortest Path algorithm as defined by this [`Pipeline`], skipping the preprocessing steps.
+    ///
+    /// This is useful to keep the paths found while inspecting the (already [preprocessed](Pipeline::preprocess())) graph.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `k`: The number of paths to find.
+    ///
+    /// # Returns
+    /// A pair of the list of the shortest paths found and a [`PipelineProfile`] detailling how long the algorithm took. Its `prep` is always
+    /// empty.
+    ///
+    /// The path list is at most `k` elements long. If `k` is 0, then the list is always empty.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, graph), fields(pipeline = %self, paths)))]
+    pub fn k_shortest_paths_prepped<'g>(&self, graph: &'g Graph, src: &str, dst: &str, k: usize) -> (Vec<Path<'g>>, PipelineProfile) {
         // Run the appropriate KSP algorithm
-        match (&self.alg, &self.sssp) {
+        let (paths, profile): (Vec<Path<'g>>, PipelineProfile) = match (&self.alg, &self.sssp) {
             (Algorithm::Wikipedia, _) => {
                 // Run the alg with timings
                 let start: Instant = Instant::now();
-                let paths: Vec<Path<'g>> = ksp::wikipedia::WikipediaKSP.k_shortest_paths(graph, src, dst, k);
+                let paths: Vec<Path<'g>> = ksp::wikipedia::WikipediaKSP::new().k_shortest_paths(graph, src, dst, k);
                 let time: Duration = start.elapsed();
 
                 // Return the full profile
-                (paths, PipelineProfile { prep: prep_timings, alg: time, sssp: vec![] })
+                (paths, PipelineProfile { prep: vec![], alg: time, sssp: vec![] })
             },
             (Algorithm::Yen, Some(sssp::Sssp::Dijkstra)) => {
                 // Prepare the wrapped SSSP profiler
-                let mut sssp: ProfilingSSSP<sssp::dijkstra::DijkstraSSSP> = ProfilingSSSP::new(sssp::dijkstra::DijkstraSSSP);
+                let mut sssp: ProfilingSSSP<sssp::dijkstra::DijkstraSSSP> = ProfilingSSSP::new(sssp::dijkstra::DijkstraSSSP::new());
 
                 // Run the alg with timings
                 let start: Instant = Instant::now();
@@ -160,10 +823,26 @@ impl Pipeline {
                 let time: Duration = start.elapsed();
 
                 // Return the full profile
-                (paths, PipelineProfile { prep: prep_timings, alg: time, sssp: sssp.timings })
+                (paths, PipelineProfile { prep: vec![], alg: time, sssp: sssp.timings })
             },
+            (Algorithm::HoffmanPavley, Some(sssp::Sssp::Dijkstra)) => {
+                // Prepare the wrapped SSSP profiler
+                let mut sssp: ProfilingSSSP<sssp::dijkstra::DijkstraSSSP> = ProfilingSSSP::new(sssp::dijkstra::DijkstraSSSP::new());
+
+                // Run the alg with timings
+                let start: Instant = Instant::now();
+                let paths: Vec<Path<'g>> = ksp::hoffman_pavley::HoffmanPavleyKSP::new(&mut sssp).k_shortest_paths(graph, src, dst, k);
+                let time: Duration = start.elapsed();
+
+                // Return the full profile
+                (paths, PipelineProfile { prep: vec![], alg: time, sssp: sssp.timings })
+            },
+            (Algorithm::HoffmanPavley, None) => panic!("Cannot run Hoffman-Pavley without SSSP defined"),
             (Algorithm::Yen, None) => panic!("Cannot run Yen without SSSP defined"),
-        }
+        };
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("paths", paths.len());
+        (paths, profile)
     }
 }
 impl Display for Pipeline {
@@ -179,6 +858,41 @@ impl Display for Pipeline {
         Ok(())
     }
 }
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Pipeline {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw: String = String::deserialize(deserializer)?;
+        Self::from_str(&raw).map_err(serde::de::Error::custom)
+    }
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pipeline {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { serializer.serialize_str(&self.id()) }
+}
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Pipeline {
+    #[inline]
+    fn schema_name() -> std::borrow::Cow<'static, str> { "Pipeline".into() }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        // Build a pattern that only matches valid pipelines, i.e., that requires an SSSP for the algorithms that need one
+        let steps: String = prep::Step::all().iter().map(prep::Step::id).collect::<Vec<&str>>().join("|");
+        let sssps: String = Sssp::all().iter().map(Sssp::id).collect::<Vec<&str>>().join("|");
+        let algs: String = Algorithm::all()
+            .iter()
+            .map(|alg| if alg.needs_sssp() { format!("{}<(?:{sssps})>", alg.id()) } else { format!("{}(?:<(?:{sssps})>)?", alg.id()) })
+            .collect::<Vec<String>>()
+            .join("|");
+        schemars::json_schema!({
+            "type": "string",
+            "description": "A KSP pipeline, given as zero or more preprocessing steps, the algorithm and (if it needs one) an SSSP algorithm.",
+            "pattern": format!("^(?:(?:{steps})->)*(?:{algs})$"),
+            "examples": [Pipeline::example().id()],
+        })
+    }
+}
 impl FromStr for Pipeline {
     type Err = PipelineParseError;
 
@@ -4,7 +4,7 @@
 //  Created:
 //    16 Jul 2024, 02:05:23
 //  Last edited:
-//    23 Jul 2024, 01:45:54
+//    16 Oct 2026, 19:25:10
 //  Auto updated?
 //    Yes
 //
@@ -13,13 +13,314 @@
 //
 
 use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FResult};
 use std::hash::{Hash, Hasher};
 
+use arrayvec::ArrayString;
+use ksp_graph::{Edge, Graph, Label};
+
+use crate::cost::OrderedCost;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path;
+    use crate::utils::load_graph;
+
+    #[test]
+    fn test_path_contains_node() {
+        let g: Graph = load_graph("cities");
+        let path: Path = path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago");
+        assert!(path.contains_node("Berlin"));
+        assert!(path.contains_node("Dorchester"));
+        assert!(path.contains_node("Chicago"));
+        assert!(!path.contains_node("Edinburgh"));
+        assert!(!Path { hops: vec![] }.contains_node("Berlin"));
+    }
+
+    #[test]
+    fn test_path_is_simple() {
+        let g: Graph = load_graph("cities");
+        assert!(path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago").is_simple());
+        assert!(!path!(crate : g, "Amsterdam" -> "Edinburgh" -> "Dorchester" -| "Amsterdam").is_simple());
+        assert!(!path!(crate : g, "Amsterdam" -> "Berlin" -| "Amsterdam").is_simple());
+        assert!(Path { hops: vec![] }.is_simple());
+    }
+
+    #[test]
+    fn test_path_macro_directed() {
+        // The link is stored as Amsterdam-Berlin, so can only be walked that way when directed
+        let g: Graph = load_graph("cities");
+        assert_eq!(path!(crate : directed : g, "Amsterdam" -| "Berlin"), path!(crate : g, "Amsterdam" -| "Berlin"));
+        assert_eq!(path!(crate : g, "Berlin" -| "Amsterdam").cost(), 577.34);
+        assert_eq!(path!(crate : directed : g, "Chicago" -> "Dorchester" -| "Edinburgh").cost(), 6249.15 + 589.23);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_path_macro_directed_reversed() {
+        let g: Graph = load_graph("cities");
+        path!(crate : directed : g, "Berlin" -| "Amsterdam");
+    }
+
+    #[test]
+    fn test_path_is_connected_in() {
+        let g: Graph = load_graph("cities");
+        assert!(path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago").is_connected_in(&g));
+        assert!(path!(crate : g, "Amsterdam" -> "Edinburgh" -> "Dorchester" -| "Amsterdam").is_connected_in(&g));
+        assert!(Path { hops: vec![("Amsterdam", 0.0)] }.is_connected_in(&g));
+
+        // There is no direct link between Berlin and Chicago
+        assert!(!Path { hops: vec![("Amsterdam", 0.0), ("Berlin", 577.34), ("Chicago", 1000.0)] }.is_connected_in(&g));
+        // Nor is Paris part of the graph at all
+        assert!(!Path { hops: vec![("Paris", 0.0)] }.is_connected_in(&g));
+    }
+
+    #[test]
+    fn test_path_pretty() {
+        let g: Graph = load_graph("cities");
+        let path: Path = path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago");
+        assert_eq!(
+            path.pretty(&g),
+            "\
+#  Node        Edge                     Cost    Total
+0  Berlin      -                           -     0.00
+1  Amsterdam   Amsterdam-Berlin       577.34   577.34
+2  Dorchester  Amsterdam-Dorchester   540.86  1118.20
+3  Chicago     Chicago-Dorchester    6249.15  7367.35
+"
+        );
+
+        // Paths that don't exist are still printed
+        assert_eq!(Path { hops: vec![("Berlin", 0.0), ("Chicago", 1.0)] }.pretty(&g), "\
+#  Node     Edge  Cost  Total
+0  Berlin   -        -   0.00
+1  Chicago  ?     1.00   1.00
+");
+    }
+
+    #[test]
+    fn test_path_split_at() {
+        let g: Graph = load_graph("cities");
+        let path: Path = path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago");
+        let (root, spur): (Path, Path) = path.split_at(1);
+        assert_eq!(root, path!(crate : g, "Berlin" -| "Amsterdam"));
+        assert_eq!(spur, path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago"));
+        assert_eq!(root.cost(), 577.34);
+        assert_eq!(spur.cost(), 540.86 + 6249.15);
+
+        // Splitting at the ends gives singleton paths
+        let (root, spur): (Path, Path) = path.split_at(0);
+        assert_eq!(root, Path { hops: vec![("Berlin", 0.0)] });
+        assert_eq!(spur, path);
+        let (root, spur): (Path, Path) = path.split_at(3);
+        assert_eq!(root, path);
+        assert_eq!(spur, Path { hops: vec![("Chicago", 0.0)] });
+        assert_eq!(spur.cost(), 0.0);
+    }
+
+    #[test]
+    fn test_path_concat() {
+        let g: Graph = load_graph("cities");
+        let path: Path = path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago");
+        for i in 0..path.hops.len() {
+            let (root, spur): (Path, Path) = path.split_at(i);
+            let joined: Path = Path::concat(&root, &spur).unwrap();
+            assert_eq!(joined, path);
+            assert_eq!(joined.hops.iter().map(|(n, _)| *n).collect::<Vec<&str>>(), vec!["Berlin", "Amsterdam", "Dorchester", "Chicago"]);
+            assert!((joined.cost() - path.cost()).abs() < 1e-9);
+            assert!((joined.cost() - (root.cost() + spur.cost())).abs() < 1e-9);
+        }
+
+        // Joining paths with different endpoints is not possible
+        assert_eq!(Path::concat(&path!(crate : g, "Berlin" -| "Amsterdam"), &path!(crate : g, "Dorchester" -| "Chicago")), None);
+    }
+
+    #[test]
+    fn test_owned_path_validate_against() {
+        let g: Graph = load_graph("cities");
+        let path: OwnedPath = path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago").to_owned_path();
+        assert_eq!(path.hops.iter().map(|(n, _)| n.as_str()).collect::<Vec<&str>>(), vec!["Berlin", "Amsterdam", "Dorchester", "Chicago"]);
+        assert!(path.validate_against(&g).is_ok());
+        assert!(OwnedPath::default().validate_against(&g).is_ok());
+
+        // It survives the graph being reloaded
+        drop(g);
+        let g: Graph = load_graph("cities");
+        assert!(path.validate_against(&g).is_ok());
+        assert_eq!(path.to_path(&g).unwrap(), path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago"));
+
+        // Nodes that aren't in the graph are caught
+        let mut h: Graph = g.clone();
+        h.nodes.remove("Dorchester");
+        assert!(matches!(path.validate_against(&h), Err(PathError::UnknownNode { index: 2, .. })));
+        assert_eq!(path.to_path(&h), None);
+        // As are missing edges
+        let mut h: Graph = g.clone();
+        h.edges.remove("Amsterdam-Dorchester");
+        assert!(matches!(path.validate_against(&h), Err(PathError::NotConnected { index: 2, .. })));
+        // And changed costs
+        let mut h: Graph = g.clone();
+        h.edges.get_mut("Amsterdam-Berlin").unwrap().cost = 1.0;
+        let err: PathError = path.validate_against(&h).unwrap_err();
+        assert!(matches!(err, PathError::CostMismatch { index: 1, .. }));
+        assert_eq!(err.to_string(), "Hop 1 (Berlin -> Amsterdam) costs 577.34, but the closest matching edge in the graph costs 1");
+    }
+
+    #[test]
+    fn test_path_edge_ids() {
+        let g: Graph = load_graph("cities");
+        let path: Path = path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago");
+        assert_eq!(path.edge_ids(&g).unwrap(), vec![
+            ArrayString::<64>::from("Amsterdam-Berlin").unwrap(),
+            ArrayString::<64>::from("Amsterdam-Dorchester").unwrap(),
+            ArrayString::<64>::from("Chicago-Dorchester").unwrap(),
+        ]);
+        assert!(Path { hops: vec![("Berlin", 0.0)] }.edge_ids(&g).unwrap().is_empty());
+
+        // The cheapest of parallel edges is taken
+        let mut h: Graph = g.clone();
+        let mut edge: ksp_graph::Edge = h.edges["Amsterdam-Dorchester"];
+        edge.id = ArrayString::from("Dorchester-Amsterdam").unwrap();
+        edge.cost = 1.0;
+        h.edges.insert(edge.id, edge);
+        assert_eq!(path.edge_ids(&h).unwrap()[1].as_str(), "Dorchester-Amsterdam");
+
+        // Paths that can't be walked are caught
+        let mut h: Graph = g.clone();
+        h.edges.remove("Amsterdam-Dorchester");
+        assert!(matches!(path.edge_ids(&h), Err(PathError::NotConnected { index: 2, .. })));
+        let mut h: Graph = g.clone();
+        h.nodes.remove("Chicago");
+        assert!(matches!(path.edge_ids(&h), Err(PathError::UnknownNode { index: 3, .. })));
+    }
+
+    #[test]
+    fn test_path_metrics() {
+        let mut g: Graph = load_graph("cities");
+        let path: Path = path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago");
+        let metrics: PathMetrics = path.metrics(&g).unwrap();
+        assert_eq!(metrics.hops, 3);
+        assert!((metrics.cost - (577.34 + 540.86 + 6249.15)).abs() < 1e-9);
+        assert_eq!(metrics.costliest, 6249.15);
+        assert_eq!(metrics.label_total, None);
+        assert_eq!(metrics.bottleneck, None);
+
+        // Numeric edge labels (e.g., capacities) are summarized too, but only if every edge has one
+        g.labels.edges.insert(ArrayString::from("Amsterdam-Berlin").unwrap(), Label::Number(10.0));
+        g.labels.edges.insert(ArrayString::from("Amsterdam-Dorchester").unwrap(), Label::Number(2.5));
+        assert_eq!(path.metrics(&g).unwrap().bottleneck, None);
+        g.labels.edges.insert(ArrayString::from("Chicago-Dorchester").unwrap(), Label::Number(40.0));
+        let metrics: PathMetrics = path.metrics(&g).unwrap();
+        assert_eq!(metrics.label_total, Some(52.5));
+        assert_eq!(metrics.bottleneck, Some(2.5));
+        assert_eq!(metrics.to_string(), "3 hop(s), cost 7367.35 (costliest hop 6249.15), labels 52.50 (bottleneck 2.50)");
+
+        // Trivial paths have no hops, and unwalkable ones aren't summarized
+        let metrics: PathMetrics = Path { hops: vec![("Berlin", 0.0)] }.metrics(&g).unwrap();
+        assert_eq!((metrics.hops, metrics.cost, metrics.costliest), (0, 0.0, 0.0));
+        g.edges.remove("Amsterdam-Dorchester");
+        assert!(matches!(path.metrics(&g), Err(PathError::NotConnected { index: 2, .. })));
+    }
+}
+
+
+
+
+
+/***** ERRORS *****/
+/// Describes why a path is not valid in a particular [`Graph`].
+#[derive(Debug)]
+pub enum PathError {
+    /// A node in the path does not exist in the graph.
+    UnknownNode { index: usize, node: String },
+    /// Two consecutive nodes in the path are not linked by any edge in the graph.
+    NotConnected { index: usize, from: String, to: String },
+    /// The cost recorded for a hop does not match any edge between its nodes.
+    CostMismatch { index: usize, from: String, to: String, expected: f64, got: f64 },
+}
+impl Display for PathError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use PathError::*;
+        match self {
+            UnknownNode { index, node } => write!(f, "Node '{node}' (hop {index}) does not exist in the graph"),
+            NotConnected { index, from, to } => write!(f, "Hop {index} ({from} -> {to}) is not an edge in the graph"),
+            CostMismatch { index, from, to, expected, got } => {
+                write!(f, "Hop {index} ({from} -> {to}) costs {got}, but the closest matching edge in the graph costs {expected}")
+            },
+        }
+    }
+}
+impl Error for PathError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use PathError::*;
+        match self {
+            UnknownNode { .. } => None,
+            NotConnected { .. } => None,
+            CostMismatch { .. } => None,
+        }
+    }
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Checks whether the given hops form a path in the given graph.
+///
+/// # Arguments
+/// - `hops`: The hops of the path, as `(node, cost so far)`.
+/// - `graph`: The [`Graph`] to check the path against.
+///
+/// # Errors
+/// This function errors if any node doesn't exist, if any consecutive pair of nodes is not linked, or if the cost of any hop differs from
+/// that of all edges linking its nodes.
+fn validate_hops<'h>(hops: impl Iterator<Item = (&'h str, f64)>, graph: &Graph) -> Result<(), PathError> {
+    let mut prev: Option<(&str, f64)> = None;
+    for (index, (node, total)) in hops.enumerate() {
+        if !graph.nodes.contains_key(node) {
+            return Err(PathError::UnknownNode { index, node: node.into() });
+        }
+        if let Some((from, prev_total)) = prev {
+            // Find the edge closest in cost, in case there are parallel ones
+            let got: f64 = total - prev_total;
+            let expected: f64 = match graph
+                .successors(from)
+                .filter(|(neigh, _)| *neigh == node)
+                .map(|(_, e)| e.cost)
+                .min_by(|c1, c2| (c1 - got).abs().total_cmp(&(c2 - got).abs()))
+            {
+                Some(cost) => cost,
+                None => return Err(PathError::NotConnected { index, from: from.into(), to: node.into() }),
+            };
+            // NOTE: The tolerance scales with the total, as that's what the rounding errors accumulate in
+            if (expected - got).abs() > 1e-6 * total.abs().max(1.0) {
+                return Err(PathError::CostMismatch { index, from: from.into(), to: node.into(), expected, got });
+            }
+        }
+        prev = Some((node, total));
+    }
+    Ok(())
+}
+
+
+
+
 
 /***** LIBRARY *****/
 /// Convenience macro for building paths with auto-computed cost.
 ///
+/// By default, hops follow the [successors](ksp_graph::Graph::successors()) of nodes, i.e., edges in either direction if the graph is
+/// undirected. Prefix the graph with `directed :` (e.g., `path!(directed : g, "A" -> "B" -| "C")`) to only follow edges from their `left`
+/// to their `right`, regardless of the graph.
+///
 /// # Panics
 /// The produced code will generate an error if the given path does not exist with direct links in the given graph.
 #[macro_export]
@@ -27,11 +328,40 @@ macro_rules! path {
     // Counting of path nodes
     (__COUNT) => { 0 };
     (__COUNT $node:literal) => { 1 };
-    (__COUNT $node:literal $($nodes:literal)+) => { 1 + ::ksp::path!(__COUNT $($nodes)+) };
+    (__COUNT $node:literal $($nodes:literal)+) => { 1 + $crate::path!(__COUNT $($nodes)+) };
     // Counting of path nodes (local)
     (__COUNT crate:) => { 0 };
     (__COUNT crate: $node:literal) => { 1 };
-    (__COUNT crate: $node:literal $($nodes:literal)+) => { 1 + crate::path!(__COUNT $crt $($nodes)+) };
+    (__COUNT crate: $node:literal $($nodes:literal)+) => { 1 + $crate::path!(__COUNT crate: $($nodes)+) };
+
+    // Directed interface
+    (directed : $graph:expr, $start:literal $(-> $nodes:literal)* -| $end:literal) => {
+        $crate::path!(__DIRECTED $graph, [$start $(,$nodes)*], [$($nodes,)* $end])
+    };
+    (crate : directed : $graph:expr, $start:literal $(-> $nodes:literal)* -| $end:literal) => {
+        $crate::path!(__DIRECTED $graph, [$start $(,$nodes)*], [$($nodes,)* $end])
+    };
+    (__DIRECTED $graph:expr, [$start:literal $(,$lefts:literal)*], [$($rights:literal),+]) => {
+        {
+            // Build the path components
+            let graph = &$graph;
+            let mut cost: f64 = 0.0;
+            let mut hops: Vec<(&'static str, f64)> = Vec::with_capacity(1 + $crate::path!(__COUNT $($lefts)*) + 1);
+            hops.push(($start, cost));
+            'hops: for (left, right) in [$start $(,$lefts)*].into_iter().zip([$($rights),+]) {
+                // Find an edge from left-to-right, and only that way around
+                for edge in graph.edges.values() {
+                    if edge.left.as_str() == left && edge.right.as_str() == right {
+                        cost += edge.cost;
+                        hops.push((right, cost));
+                        continue 'hops;
+                    }
+                }
+                panic!("There is no directed link from node {left:?} to {right:?}");
+            }
+            $crate::path::Path { hops }
+        }
+    };
 
     // Main interface
     ($graph:expr, $start:literal -> $end:literal) => {
@@ -43,8 +373,8 @@ macro_rules! path {
             hops.push(($start, cost));
             'hops: for (left, right) in [$start].into_iter().zip([$end]) {
                 // Find an edge from left-to-right
-                for edge in graph.edges.values() {
-                    if (edge.left.as_str() == left && edge.right.as_str() == right) || (edge.left.as_str() == right && edge.right.as_str() == left) {
+                for (neigh, edge) in graph.successors(left) {
+                    if neigh == right {
                         cost += edge.cost;
                         hops.push((right, cost));
                         break 'hops;
@@ -60,12 +390,33 @@ macro_rules! path {
             // Build the path components
             let graph = &$graph;
             let mut cost: f64 = 0.0;
-            let mut hops: Vec<(&'static str, f64)> = Vec::with_capacity(1 + crate::path!(__COUNT crate :) + 1);
+            let mut hops: Vec<(&'static str, f64)> = Vec::with_capacity(1 + $crate::path!(__COUNT crate :) + 1);
             hops.push(($start, cost));
             'hops: for (left, right) in [$start $(,$nodes)*].into_iter().zip([$($nodes,)* $end]) {
                 // Find an edge from left-to-right
-                for edge in graph.edges.values() {
-                    if (edge.left.as_str() == left && edge.right.as_str() == right) || (edge.left.as_str() == right && edge.right.as_str() == left) {
+                for (neigh, edge) in graph.successors(left) {
+                    if neigh == right {
+                        cost += edge.cost;
+                        hops.push((right, cost));
+                        continue 'hops;
+                    }
+                }
+                panic!("There is no link between nodes {left:?} and {right:?}");
+            }
+            $crate::path::Path { hops }
+        }
+    };
+    ($graph:expr, $start:literal $(-> $nodes:literal)* -| $end:literal) => {
+        {
+            // Build the path components
+            let graph = &$graph;
+            let mut cost: f64 = 0.0;
+            let mut hops: Vec<(&'static str, f64)> = Vec::with_capacity(1 + $crate::path!(__COUNT $($nodes)*) + 1);
+            hops.push(($start, cost));
+            'hops: for (left, right) in [$start $(,$nodes)*].into_iter().zip([$($nodes,)* $end]) {
+                // Find an edge from left-to-right
+                for (neigh, edge) in graph.successors(left) {
+                    if neigh == right {
                         cost += edge.cost;
                         hops.push((right, cost));
                         continue 'hops;
@@ -73,13 +424,42 @@ macro_rules! path {
                 }
                 panic!("There is no link between nodes {left:?} and {right:?}");
             }
-            crate::path::Path { hops }
+            $crate::path::Path { hops }
         }
     };
 }
 
 
 
+/// Summarizes the edges traversed by a [`Path`], as computed by [`Path::metrics()`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PathMetrics {
+    /// The number of edges traversed.
+    pub hops:        usize,
+    /// The total cost of the edges traversed.
+    pub cost:        f64,
+    /// The cost of the most expensive edge traversed, or `0.0` if there are none.
+    pub costliest:   f64,
+    /// The total of the numeric labels of the edges traversed, if they all have one.
+    pub label_total: Option<f64>,
+    /// The smallest numeric label of the edges traversed (e.g., the bottleneck capacity), if they all have one and there is at least one.
+    pub bottleneck:  Option<f64>,
+}
+impl Display for PathMetrics {
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        write!(f, "{} hop(s), cost {:.2} (costliest hop {:.2})", self.hops, self.cost, self.costliest)?;
+        if let Some(total) = self.label_total {
+            write!(f, ", labels {total:.2}")?;
+            if let Some(bottleneck) = self.bottleneck {
+                write!(f, " (bottleneck {bottleneck:.2})")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+
+
 /// Defines a path between two nodes.
 #[derive(Clone, Debug)]
 pub struct Path<'g> {
@@ -100,6 +480,234 @@ impl<'g> Path<'g> {
     /// The cost of the entire path.
     #[inline]
     pub fn cost(&self) -> f64 { self.hops.last().map(|(_, c)| *c).unwrap_or(0.0) }
+
+    /// Splits this path in two at the given hop.
+    ///
+    /// The node at the given index is shared by both halves, i.e., it's the end of the first path and the start of the second.
+    ///
+    /// # Arguments
+    /// - `idx`: The index of the hop to split at.
+    ///
+    /// # Returns
+    /// A pair of a _root_ path, being all hops up to and including `idx`, and a _spur_ path, being all hops from `idx` onwards.
+    /// The costs of the spur path are rebased to start at 0.
+    ///
+    /// # Panics
+    /// This function panics if `idx` is out-of-bounds for this path.
+    #[inline]
+    #[track_caller]
+    pub fn split_at(&self, idx: usize) -> (Self, Self) {
+        let offset: f64 = self.hops[idx].1;
+        (Self { hops: self.hops[..=idx].to_vec() }, Self { hops: self.hops[idx..].iter().map(|(n, c)| (*n, c - offset)).collect() })
+    }
+
+    /// Joins two paths together, where the second one continues from the end of the first one.
+    ///
+    /// This is the inverse of [`Path::split_at()`].
+    ///
+    /// # Arguments
+    /// - `root`: The first part of the new path.
+    /// - `spur`: The second part of the new path. Must start at the node that `root` ends in.
+    ///
+    /// # Returns
+    /// A new [`Path`] that walks `root` and then `spur`, with the costs of `spur` added on top of those of `root`. If either path is empty, or `spur` doesn't start where `root` ends, then [`None`] is returned instead.
+    pub fn concat(root: &Self, spur: &Self) -> Option<Self> {
+        let (end, cost): (&'g str, f64) = *root.hops.last()?;
+        let (start, offset): (&'g str, f64) = *spur.hops.first()?;
+        if end != start {
+            return None;
+        }
+
+        // Join the hops, rebasing the cost of the spur onto the root
+        let mut hops: Vec<(&'g str, f64)> = Vec::with_capacity(root.hops.len() + spur.hops.len() - 1);
+        hops.extend(root.hops.iter().copied());
+        hops.extend(spur.hops[1..].iter().map(|(n, c)| (*n, cost + (c - offset))));
+        Some(Self { hops })
+    }
+
+    /// Checks whether this path visits the given node.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the node to look for.
+    ///
+    /// # Returns
+    /// True if any of the hops in this path is `id`, or false otherwise.
+    #[inline]
+    pub fn contains_node(&self, id: &str) -> bool { self.hops.iter().any(|(n, _)| *n == id) }
+
+    /// Checks whether this path is simple, i.e., does not visit any node more than once.
+    ///
+    /// # Returns
+    /// True if all hops in this path are unique, or false otherwise.
+    #[inline]
+    pub fn is_simple(&self) -> bool {
+        let mut seen: HashSet<&str> = HashSet::with_capacity(self.hops.len());
+        self.hops.iter().all(|(n, _)| seen.insert(*n))
+    }
+
+    /// Formats this path as a table listing every hop on its own line.
+    ///
+    /// Next to the node, every hop shows the [`Edge`](ksp_graph::Edge) used to reach it, the cost of that edge and the cost of the path up to
+    /// that point, rounded to two decimals. Columns are aligned, which makes this more readable than the [`Display`]-implementation for long
+    /// paths.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] in which to look up the edges. If there are parallel edges, the one whose cost is closest to that of the hop
+    ///   is shown. If there are none (i.e., the path is not connected in `graph`), a `?` is shown instead.
+    ///
+    /// # Returns
+    /// A [`String`] with the table, one line per hop (plus a header), each terminated by a newline.
+    pub fn pretty(&self, graph: &Graph) -> String {
+        // Collect the cells first to know how wide the columns are
+        let mut rows: Vec<[String; 5]> = Vec::with_capacity(1 + self.hops.len());
+        rows.push(["#".into(), "Node".into(), "Edge".into(), "Cost".into(), "Total".into()]);
+        for (i, (node, total)) in self.hops.iter().enumerate() {
+            if i == 0 {
+                rows.push([i.to_string(), node.to_string(), "-".into(), "-".into(), format!("{total:.2}")]);
+                continue;
+            }
+            let (prev, prev_total): (&str, f64) = self.hops[i - 1];
+            let cost: f64 = total - prev_total;
+            let edge: Option<&str> = graph
+                .successors(prev)
+                .filter(|(neigh, _)| neigh == node)
+                .min_by(|(_, e1), (_, e2)| (e1.cost - cost).abs().total_cmp(&(e2.cost - cost).abs()))
+                .map(|(_, e)| e.id.as_str());
+            rows.push([i.to_string(), node.to_string(), edge.unwrap_or("?").into(), format!("{cost:.2}"), format!("{total:.2}")]);
+        }
+        let mut widths: [usize; 5] = [0; 5];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        // Then write them, with the numbers right-aligned
+        let mut res: String = String::new();
+        for [i, node, edge, cost, total] in &rows {
+            let line: String = format!(
+                "{i:>w0$}  {node:<w1$}  {edge:<w2$}  {cost:>w3$}  {total:>w4$}",
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3],
+                w4 = widths[4]
+            );
+            res.push_str(line.trim_end());
+            res.push('\n');
+        }
+        res
+    }
+
+    /// Checks whether this path actually exists in the given graph.
+    ///
+    /// This means that every node in the path exists, and that every consecutive pair of nodes is directly linked by some edge.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to check the path against.
+    ///
+    /// # Returns
+    /// True if this path can be walked in the given `graph`, or false otherwise.
+    pub fn is_connected_in(&self, graph: &Graph) -> bool {
+        // Check all nodes exist
+        for (node, _) in &self.hops {
+            if !graph.nodes.contains_key(*node) {
+                return false;
+            }
+        }
+
+        // Check all consecutive nodes are linked
+        for i in 1..self.hops.len() {
+            let n1: &str = self.hops[i - 1].0;
+            let n2: &str = self.hops[i].0;
+            if !graph.successors(n1).any(|(neigh, _)| neigh == n2) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Checks whether this path actually exists in the given graph with the costs it records.
+    ///
+    /// This is a stricter version of [`Path::is_connected_in()`] that also checks that the cost of every hop matches that of an edge linking
+    /// its nodes.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to check the path against.
+    ///
+    /// # Errors
+    /// This function errors if any node doesn't exist, if any consecutive pair of nodes is not linked, or if the cost of any hop differs from
+    /// that of all edges linking its nodes.
+    #[inline]
+    pub fn validate_against(&self, graph: &Graph) -> Result<(), PathError> { validate_hops(self.hops.iter().copied(), graph) }
+
+    /// Finds the edges this path traverses in the given graph.
+    ///
+    /// If multiple edges link two consecutive nodes, the cheapest one is taken.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find the edges in.
+    ///
+    /// # Returns
+    /// The identifiers of the edges between every consecutive pair of nodes, in order. Empty if the path has less than two hops.
+    ///
+    /// # Errors
+    /// This function errors if any node doesn't exist, or if any consecutive pair of nodes is not linked.
+    pub fn edge_ids(&self, graph: &Graph) -> Result<Vec<ArrayString<64>>, PathError> {
+        let mut edges: Vec<ArrayString<64>> = Vec::with_capacity(self.hops.len().saturating_sub(1));
+        for (index, (node, _)) in self.hops.iter().enumerate() {
+            if !graph.nodes.contains_key(*node) {
+                return Err(PathError::UnknownNode { index, node: (*node).into() });
+            }
+            if index == 0 {
+                continue;
+            }
+            let from: &str = self.hops[index - 1].0;
+            match graph.successors(from).filter(|(neigh, _)| neigh == node).min_by(|(_, e1), (_, e2)| e1.cost.total_cmp(&e2.cost)) {
+                Some((_, edge)) => edges.push(edge.id),
+                None => return Err(PathError::NotConnected { index, from: from.into(), to: (*node).into() }),
+            }
+        }
+        Ok(edges)
+    }
+
+    /// Summarizes the edges this path traverses in the given graph.
+    ///
+    /// Unlike [`Path::cost()`], this takes the costs from the edges themselves, and also considers any numeric
+    /// [edge labels](ksp_graph::Labels::edges) (e.g., capacities). Edges are found like [`Path::edge_ids()`] does.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find the edges in.
+    ///
+    /// # Returns
+    /// A [`PathMetrics`] describing the traversed edges.
+    ///
+    /// # Errors
+    /// This function errors if any node doesn't exist, or if any consecutive pair of nodes is not linked.
+    pub fn metrics(&self, graph: &Graph) -> Result<PathMetrics, PathError> {
+        let edges: Vec<&Edge> = self.edge_ids(graph)?.into_iter().map(|id| &graph.edges[&id]).collect();
+        let labels: Option<Vec<f64>> = edges
+            .iter()
+            .map(|e| match graph.labels.edges.get(&e.id) {
+                Some(Label::Number(value)) => Some(*value),
+                _ => None,
+            })
+            .collect();
+        Ok(PathMetrics {
+            hops: edges.len(),
+            cost: edges.iter().map(|e| e.cost).sum(),
+            costliest: edges.iter().map(|e| e.cost).fold(0.0, f64::max),
+            label_total: labels.as_ref().map(|labels| labels.iter().sum()),
+            bottleneck: labels.and_then(|labels| labels.into_iter().reduce(f64::min)),
+        })
+    }
+
+    /// Copies this path into one that doesn't borrow the graph.
+    ///
+    /// # Returns
+    /// An [`OwnedPath`] with the same hops as this one.
+    #[inline]
+    pub fn to_owned_path(&self) -> OwnedPath { OwnedPath::from(self) }
 }
 
 impl<'g> Display for Path<'g> {
@@ -138,16 +746,83 @@ impl<'g> PartialEq for Path<'g> {
                 return false;
             }
         }
-        return true;
+        true
     }
 }
 impl<'g> PartialOrd for Path<'g> {
     #[inline]
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.hops.last().map(|(_, cost)| *cost).unwrap_or(0.0).partial_cmp(other.hops.last().map(|(_, cost)| cost).unwrap_or(&0.0))
-    }
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
 }
 impl<'g> Ord for Path<'g> {
     #[inline]
-    fn cmp(&self, other: &Self) -> Ordering { self.partial_cmp(other).unwrap() }
+    fn cmp(&self, other: &Self) -> Ordering { OrderedCost(self.cost()).cmp(&OrderedCost(other.cost())) }
+}
+
+
+
+/// Defines a path between two nodes that owns its node identifiers.
+///
+/// Unlike a [`Path`], it doesn't borrow the [`Graph`] it was found in. This means it can outlive it, but also that it may not be valid
+/// anymore in a graph that is reloaded or changed. Use [`OwnedPath::validate_against()`] to check that.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct OwnedPath {
+    /// The hops of the path.
+    pub hops: Vec<(ArrayString<64>, f64)>,
+}
+impl OwnedPath {
+    /// Returns the cost of this path.
+    ///
+    /// # Returns
+    /// The cost of the entire path.
+    #[inline]
+    pub fn cost(&self) -> f64 { self.hops.last().map(|(_, c)| *c).unwrap_or(0.0) }
+
+    /// Checks whether this path exists in the given graph with the costs it records.
+    ///
+    /// See [`Path::validate_against()`] for more information.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to check the path against.
+    ///
+    /// # Errors
+    /// This function errors if any node doesn't exist, if any consecutive pair of nodes is not linked, or if the cost of any hop differs from
+    /// that of all edges linking its nodes.
+    #[inline]
+    pub fn validate_against(&self, graph: &Graph) -> Result<(), PathError> { validate_hops(self.hops.iter().map(|(n, c)| (n.as_str(), *c)), graph) }
+
+    /// Turns this path back into one that borrows its node identifiers from a graph.
+    ///
+    /// This is the inverse of [`Path::to_owned_path()`]. Note that only the nodes are looked up; use [`OwnedPath::validate_against()`] to
+    /// check that the path actually exists in `graph`.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to borrow the identifiers from.
+    ///
+    /// # Returns
+    /// A [`Path`] with the same hops as this one, or [`None`] if any of its nodes is not in `graph`.
+    pub fn to_path<'g>(&self, graph: &'g Graph) -> Option<Path<'g>> {
+        let mut hops: Vec<(&'g str, f64)> = Vec::with_capacity(self.hops.len());
+        for (node, cost) in &self.hops {
+            let (id, _) = graph.nodes.get_key_value(node)?;
+            hops.push((id.as_str(), *cost));
+        }
+        Some(Path { hops })
+    }
+}
+impl Display for OwnedPath {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        let path: Path = Path { hops: self.hops.iter().map(|(n, c)| (n.as_str(), *c)).collect() };
+        path.fmt(f)
+    }
+}
+impl<'g> From<&Path<'g>> for OwnedPath {
+    /// Copies the hops of the given path.
+    ///
+    /// # Panics
+    /// This function panics if any of the nodes has an identifier longer than 64 bytes, which is impossible for paths found in a [`Graph`].
+    #[inline]
+    #[track_caller]
+    fn from(value: &Path<'g>) -> Self { Self { hops: value.hops.iter().map(|(n, c)| (ArrayString::from(n).unwrap(), *c)).collect() } }
 }
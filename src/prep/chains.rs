@@ -0,0 +1,372 @@
+//  CHAINS.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 17:03:24
+//  Last edited:
+//    16 Oct 2026, 19:00:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements contracting chains of degree-2 nodes into single edges,
+//!   and expanding paths found in the contracted graph back.
+//
+
+use std::collections::{HashMap, HashSet};
+
+use arrayvec::ArrayString;
+use ksp_graph::{Edge, Graph, GraphGuard};
+
+use super::PreprocessStep;
+use crate::path::Path;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ksp::yen::YenKSP;
+    use crate::ksp::KShortestPath as _;
+    use crate::path;
+    use crate::path::OwnedPath;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+
+    /// Builds a graph from the given edges, each named after the nodes it links and its index (such that parallel edges are kept).
+    fn make_graph(edges: &[(&str, &str, f64)], directed: bool) -> Graph {
+        let mut g: Graph =
+            Graph::from_edges(edges.iter().enumerate().map(|(i, (left, right, cost))| (format!("{left}-{right}-{i}"), *left, *right, *cost)));
+        g.directed = directed;
+        g
+    }
+
+    #[test]
+    fn test_contract_chains() {
+        // A long chain from A to B, next to a detour through D (which has too many neighbours to be contracted), and then on to C
+        let mut g: Graph = make_graph(
+            &[
+                ("A", "x1", 1.0),
+                ("x1", "x2", 2.0),
+                ("x3", "x2", 3.0),
+                ("x3", "B", 4.0),
+                ("A", "D", 10.0),
+                ("D", "B", 10.0),
+                ("D", "C", 50.0),
+                ("B", "C", 1.0),
+                ("A", "C", 100.0),
+            ],
+            false,
+        );
+        let original: Graph = g.clone();
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        let chains: Chains = ContractChainsPreprocess.contract(&mut guard, &["C"]);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(guard.node_count(), 4);
+        assert_eq!(guard.edge_count(), 6);
+
+        // The paths found are the same, but for the contracted nodes...
+        let paths: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&guard, "C", "A", 2);
+        assert_eq!(paths.iter().map(Path::cost).collect::<Vec<f64>>(), vec![11.0, 21.0]);
+        let expanded: Vec<Path> = paths.iter().map(|path| chains.expand_path(&original, path).unwrap()).collect();
+        drop(guard);
+        assert!(original.diff(&g).is_empty());
+
+        // ...which expanding restores
+        assert_eq!(expanded, YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, "C", "A", 2));
+        assert_eq!(expanded[0], path!(crate : g, "C" -> "B" -> "x3" -> "x2" -> "x1" -| "A"));
+        for path in &expanded {
+            assert!(path.validate_against(&g).is_ok());
+        }
+        let forward: Path = Path { hops: vec![("A", 0.0), ("B", 10.0)] };
+        assert_eq!(chains.expand_path(&g, &forward).unwrap(), path!(crate : g, "A" -> "x1" -> "x2" -> "x3" -| "B"));
+
+        // Kept nodes split chains
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        let chains: Chains = ContractChainsPreprocess.contract(&mut guard, &["x2"]);
+        assert_eq!(chains.len(), 2);
+        assert_eq!(guard.node_count(), 5);
+        drop(guard);
+
+        // Preprocessing keeps the endpoints of the query
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        ContractChainsPreprocess.preprocess(&mut guard, "x1", "C", 1);
+        assert!(guard.nodes.contains_key("x1") && !guard.nodes.contains_key("x2"));
+        assert_eq!(YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&guard, "x1", "C", 1)[0].cost(), 10.0);
+    }
+
+    #[test]
+    fn test_contract_chains_directed() {
+        // A chain both ways, and a node that can't be passed through
+        let mut g: Graph =
+            make_graph(&[("A", "x", 1.0), ("x", "B", 2.0), ("B", "y", 3.0), ("y", "A", 4.0), ("A", "z", 5.0), ("B", "z", 6.0)], true);
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        let chains: Chains = ContractChainsPreprocess.contract(&mut guard, &[]);
+        assert_eq!(chains.len(), 2);
+        assert_eq!(guard.node_count(), 3);
+        let path: OwnedPath = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&guard, "B", "A", 1)[0].to_owned_path();
+        assert_eq!(path.cost(), 7.0);
+        drop(guard);
+        assert_eq!(chains.expand_path(&g, &path.to_path(&g).unwrap()).unwrap(), path!(crate : g, "B" -> "y" -| "A"));
+
+        // Cycles without any other nodes are left alone
+        let mut g: Graph = make_graph(&[("p", "q", 1.0), ("q", "r", 1.0), ("r", "p", 1.0)], false);
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        assert!(ContractChainsPreprocess.contract(&mut guard, &[]).is_empty());
+        assert!(guard.is_pristine());
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Finds the two edges over which a chain passes through a node, if it's in the middle of one.
+///
+/// # Arguments
+/// - `graph`: The [`Graph`] the node is in.
+/// - `incident`: The edges connected to every node.
+/// - `node`: The node to check.
+///
+/// # Returns
+/// The edges and the neighbours they lead to, towards the `left` and then the `right` of the chain, or [`None`] if `node` has any other
+/// number of (distinct) neighbours. For directed graphs, it must have exactly one incoming and one outgoing edge (in that order).
+fn links<'g>(graph: &Graph, incident: &HashMap<&str, Vec<&'g Edge>>, node: &str) -> Option<[(&'g Edge, &'g str); 2]> {
+    let edges: &[&'g Edge] = incident.get(node).map(Vec::as_slice).unwrap_or(&[]);
+    let [e1, e2]: [&'g Edge; 2] = edges.try_into().ok()?;
+    let other = |e: &'g Edge| -> &'g str { if e.left.as_str() == node { e.right.as_str() } else { e.left.as_str() } };
+    let (n1, n2): (&'g str, &'g str) = (other(e1), other(e2));
+    if n1 == node || n2 == node || n1 == n2 {
+        return None;
+    }
+    if !graph.directed || (e1.right.as_str() == node && e2.left.as_str() == node) {
+        Some([(e1, n1), (e2, n2)])
+    } else if e2.right.as_str() == node && e1.left.as_str() == node {
+        Some([(e2, n2), (e1, n1)])
+    } else {
+        None
+    }
+}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// A chain of nodes that was contracted into a single edge by [`ContractChainsPreprocess::contract()`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chain {
+    /// The identifier of the edge that replaced the chain.
+    pub edge:  ArrayString<64>,
+    /// The node where the chain starts.
+    pub left:  ArrayString<64>,
+    /// The node where the chain ends.
+    pub right: ArrayString<64>,
+    /// The contracted nodes, in order from `left` to `right`, with the cost of reaching each of them from `left`.
+    pub nodes: Vec<(ArrayString<64>, f64)>,
+    /// The cost of the whole chain, i.e., of the edge replacing it.
+    pub cost:  f64,
+}
+
+
+
+/// Remembers which chains were contracted by [`ContractChainsPreprocess::contract()`], such that paths can be expanded back.
+#[derive(Clone, Debug, Default)]
+pub struct Chains {
+    /// The chains, by their `left` and `right` node.
+    chains:   HashMap<(ArrayString<64>, ArrayString<64>), Vec<Chain>>,
+    /// Whether the graph was directed, i.e., chains can only be traversed from `left` to `right`.
+    directed: bool,
+}
+impl Chains {
+    /// Returns the number of chains that were contracted.
+    ///
+    /// # Returns
+    /// The number of edges that replaced a chain.
+    #[inline]
+    pub fn len(&self) -> usize { self.chains.values().map(Vec::len).sum() }
+
+    /// Returns whether no chains were contracted at all.
+    ///
+    /// # Returns
+    /// True if the graph was left untouched.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.chains.is_empty() }
+
+    /// Iterates over the chains that were contracted.
+    ///
+    /// # Returns
+    /// An iterator over every [`Chain`], in no particular order.
+    #[inline]
+    pub fn iter(&self) -> impl '_ + Iterator<Item = &Chain> { self.chains.values().flatten() }
+
+    /// Expands a path found in the contracted graph to the one it is in the original graph.
+    ///
+    /// Every hop between the ends of a chain is replaced by the chain if their cost matches. Other hops are kept as-is.
+    ///
+    /// # Arguments
+    /// - `graph`: The original [`Graph`], to borrow the identifiers of the restored nodes from.
+    /// - `path`: The [`Path`] to expand.
+    ///
+    /// # Returns
+    /// The expanded [`Path`], with the same cost as `path`, or [`None`] if any of its nodes is not in `graph`.
+    pub fn expand_path<'g>(&self, graph: &'g Graph, path: &Path) -> Option<Path<'g>> {
+        let lookup = |node: &str| -> Option<&'g str> { graph.nodes.get_key_value(node).map(|(id, _)| id.as_str()) };
+        let mut hops: Vec<(&'g str, f64)> = Vec::with_capacity(path.hops.len());
+        for (i, (node, cost)) in path.hops.iter().enumerate() {
+            if i > 0 {
+                // Find the chain this hop took, if any
+                let (prev, prev_cost): (&str, f64) = path.hops[i - 1];
+                let delta: f64 = cost - prev_cost;
+                let ends = |left: &str, right: &str| -> Option<(ArrayString<64>, ArrayString<64>)> {
+                    Some((ArrayString::from(left).ok()?, ArrayString::from(right).ok()?))
+                };
+                let forward = ends(prev, node).and_then(|key| self.chains.get(&key)).into_iter().flatten().map(|chain| (chain, false));
+                let backward = ends(node, prev)
+                    .filter(|_| !self.directed)
+                    .and_then(|key| self.chains.get(&key))
+                    .into_iter()
+                    .flatten()
+                    .map(|chain| (chain, true));
+                let chain: Option<(&Chain, bool)> = forward
+                    .chain(backward)
+                    .filter(|(chain, _)| (chain.cost - delta).abs() <= 1e-9 * delta.abs().max(1.0))
+                    .min_by(|(c1, _), (c2, _)| (c1.cost - delta).abs().total_cmp(&(c2.cost - delta).abs()));
+                match chain {
+                    Some((chain, false)) => {
+                        for (inner, offset) in &chain.nodes {
+                            hops.push((lookup(inner)?, prev_cost + offset));
+                        }
+                    },
+                    Some((chain, true)) => {
+                        for (inner, offset) in chain.nodes.iter().rev() {
+                            hops.push((lookup(inner)?, prev_cost + (chain.cost - offset)));
+                        }
+                    },
+                    None => {},
+                }
+            }
+            hops.push((lookup(node)?, *cost));
+        }
+        Some(Path { hops })
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Contracts every maximal chain of degree-2 nodes into a single edge.
+///
+/// A node is in the middle of a chain if it has exactly two edges, to two different neighbours (or, for directed graphs, exactly one
+/// incoming and one outgoing edge). Such nodes can only ever be passed through, so a chain of them between two other nodes can be replaced
+/// by a single edge with the summed cost of the chain. This shrinks graphs with long chains (e.g., road or fibre networks) without changing
+/// the costs of any paths between the remaining nodes. Chains that would become self-loops, that form a cycle on their own or that run
+/// parallel to an existing edge (or another chain) are left alone, as paths only differing in those would become indistinguishable.
+///
+/// The edge replacing a chain takes the identifier of its first edge. Paths found in the contracted graph only visit the ends of chains;
+/// use [`Chains::expand_path()`] on the result of [`ContractChainsPreprocess::contract()`] to restore the nodes in between. As a
+/// [`PreprocessStep`], the source and destination of the query are never contracted, but the mapping is not kept.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ContractChainsPreprocess;
+impl ContractChainsPreprocess {
+    /// Contracts every maximal chain of degree-2 nodes into a single edge.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`GraphGuard`] wrapping the graph to contract.
+    /// - `keep`: Any nodes that must not be contracted, e.g., the endpoints of a query. They split any chain they are in.
+    ///
+    /// # Returns
+    /// The [`Chains`] that were contracted, which can expand paths found in the contracted graph.
+    pub fn contract(&self, graph: &mut GraphGuard, keep: &[&str]) -> Chains {
+        let g: &Graph = graph;
+        let mut incident: HashMap<&str, Vec<&Edge>> = HashMap::with_capacity(g.nodes.len());
+        for edge in g.edges.values() {
+            incident.entry(edge.left.as_str()).or_default().push(edge);
+            if edge.right != edge.left {
+                incident.entry(edge.right.as_str()).or_default().push(edge);
+            }
+        }
+        let is_inner = |node: &str| -> Option<[(&Edge, &str); 2]> { if keep.contains(&node) { None } else { links(g, &incident, node) } };
+
+        // Walk both ways from every inner node we haven't seen yet to find its chain
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut chains: Vec<Chain> = Vec::new();
+        let mut first_edges: Vec<Edge> = Vec::new();
+        let mut ids: Vec<&str> = g.nodes.keys().map(|id| id.as_str()).collect();
+        ids.sort();
+        for node in ids {
+            if seen.contains(node) {
+                continue;
+            }
+            let [left, right]: [(&Edge, &str); 2] = match is_inner(node) {
+                Some(links) => links,
+                None => continue,
+            };
+            seen.insert(node);
+
+            // Every side is a list of edges and the nodes they lead to, ending at one that isn't inner
+            let mut sides: [Vec<(&Edge, &str)>; 2] = [vec![left], vec![right]];
+            let mut cycle: bool = false;
+            for side in &mut sides {
+                while let Some((edge, next)) = side.last().copied() {
+                    if next == node {
+                        cycle = true;
+                        break;
+                    }
+                    let links: [(&Edge, &str); 2] = match is_inner(next) {
+                        Some(links) => links,
+                        None => break,
+                    };
+                    seen.insert(next);
+                    side.push(if links[0].0.id == edge.id { links[1] } else { links[0] });
+                }
+            }
+            let [left, right]: [Vec<(&Edge, &str)>; 2] = sides;
+            let (start, end): (&str, &str) = (left.last().unwrap().1, right.last().unwrap().1);
+            let connects = |left: &str, right: &str| -> bool {
+                incident[left].iter().any(|e| {
+                    let (l, r): (&str, &str) = (e.left.as_str(), e.right.as_str());
+                    (l == left && r == right) || (!g.directed && l == right && r == left)
+                })
+            };
+            let parallel = |c: &Chain| -> bool {
+                let (l, r): (&str, &str) = (c.left.as_str(), c.right.as_str());
+                (l == start && r == end) || (!g.directed && l == end && r == start)
+            };
+            if cycle || start == end || connects(start, end) || chains.iter().any(parallel) {
+                continue;
+            }
+
+            // Lay it out from start to end
+            let mut nodes: Vec<(ArrayString<64>, f64)> = Vec::with_capacity(left.len() + right.len() - 1);
+            let mut cost: f64 = 0.0;
+            let mut edges = left.iter().rev().map(|(e, _)| *e).chain(right.iter().map(|(e, _)| *e));
+            let path = left.iter().rev().skip(1).map(|(_, n)| *n).chain([node]).chain(right.iter().take(right.len() - 1).map(|(_, n)| *n));
+            let first: &Edge = edges.next().unwrap();
+            cost += first.cost;
+            for (inner, edge) in path.zip(edges) {
+                nodes.push((ArrayString::from(inner).unwrap(), cost));
+                cost += edge.cost;
+            }
+            let (start, end): (ArrayString<64>, ArrayString<64>) = (ArrayString::from(start).unwrap(), ArrayString::from(end).unwrap());
+            chains.push(Chain { edge: first.id, left: start, right: end, nodes, cost });
+            first_edges.push(*first);
+        }
+
+        // Replace them in the graph
+        let inner: HashSet<ArrayString<64>> = chains.iter().flat_map(|c| c.nodes.iter().map(|(n, _)| *n)).collect();
+        graph.retain_nodes(|n| !inner.contains(&n.id));
+        let mut res: Chains = Chains { chains: HashMap::with_capacity(chains.len()), directed: graph.directed };
+        for (chain, first) in chains.into_iter().zip(first_edges) {
+            graph.add_edge(Edge { id: first.id, left: chain.left, right: chain.right, cost: chain.cost });
+            res.chains.entry((chain.left, chain.right)).or_default().push(chain);
+        }
+        res
+    }
+}
+impl PreprocessStep for ContractChainsPreprocess {
+    #[inline]
+    fn preprocess(&self, graph: &mut GraphGuard, src: &str, dst: &str, _k: usize) { self.contract(graph, &[src, dst]); }
+}
@@ -4,7 +4,7 @@
 //  Created:
 //    24 Jul 2024, 01:48:03
 //  Last edited:
-//    24 Jul 2024, 02:05:42
+//    16 Oct 2026, 17:10:00
 //  Auto updated?
 //    Yes
 //
@@ -16,9 +16,10 @@ use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FResult};
 use std::str::FromStr;
 
-use ksp_graph::Graph;
+use ksp_graph::GraphGuard;
 
 // Declare the modules
+pub mod chains;
 pub mod peek;
 
 
@@ -45,6 +46,10 @@ impl Error for UnknownStepError {}
 pub enum Step {
     /// The pruning of the graph as proposed by [1].
     Peek,
+    /// The contraction of chains of degree-2 nodes into single edges (see [`chains::ContractChainsPreprocess`]).
+    ///
+    /// Paths found after this step skip the contracted nodes, but have the same cost.
+    ContractChains,
 }
 impl Step {
     /// Returns all implemented steps.
@@ -52,7 +57,35 @@ impl Step {
     /// # Returns
     /// A static list of the implemented steps.
     #[inline]
-    pub const fn all() -> &'static [Self] { &[Self::Peek] }
+    pub const fn all() -> &'static [Self] { &[Self::Peek, Self::ContractChains] }
+
+    /// Returns the identifier by which this step is parsed.
+    ///
+    /// # Returns
+    /// A static string that [`Step::from_str()`] parses back to this step.
+    #[inline]
+    pub const fn id(&self) -> &'static str {
+        match self {
+            Self::Peek => "peek",
+            Self::ContractChains => "contract-chains",
+        }
+    }
+
+    /// Returns whether this step depends on the query, i.e., the source, destination or K.
+    ///
+    /// Steps that don't only have to be run once for a graph, regardless of how many queries are run on it (see
+    /// [`Pipeline::k_shortest_paths_batch()`](crate::Pipeline::k_shortest_paths_batch())).
+    ///
+    /// # Returns
+    /// True if the step's changes to a graph differ per query, or false if they're the same for all of them.
+    #[inline]
+    pub const fn depends_on_query(&self) -> bool {
+        match self {
+            Self::Peek => true,
+            // NOTE: The source and destination are never contracted
+            Self::ContractChains => true,
+        }
+    }
 }
 impl FromStr for Step {
     type Err = UnknownStepError;
@@ -61,6 +94,7 @@ impl FromStr for Step {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "peek" => Ok(Self::Peek),
+            "contract-chains" => Ok(Self::ContractChains),
             other => Err(UnknownStepError { unknown: other.into() }),
         }
     }
@@ -75,13 +109,15 @@ impl FromStr for Step {
 pub trait PreprocessStep {
     /// Preprocesses a graph before applying K-Shortest Path to it.
     ///
+    /// All changes are made through a [`GraphGuard`], such that callers can cheaply undo them afterwards.
+    ///
     /// # Arguments
-    /// - `graph`: The [`Graph`] to find in.
+    /// - `graph`: The [`GraphGuard`] wrapping the graph to find in.
     /// - `src`: The source node to find a path from.
     /// - `dst`: The destination node to find a path to.
     /// - `k`: The number of paths to find.
     ///
     /// # Panics
     /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
-    fn preprocess(graph: &mut Graph, src: &str, dst: &str, k: usize);
+    fn preprocess(&self, graph: &mut GraphGuard, src: &str, dst: &str, k: usize);
 }
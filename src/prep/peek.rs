@@ -4,7 +4,7 @@
 //  Created:
 //    16 Jul 2024, 20:42:17
 //  Last edited:
-//    24 Jul 2024, 02:05:51
+//    16 Oct 2026, 19:00:47
 //  Auto updated?
 //    Yes
 //
@@ -18,21 +18,358 @@
 //!       https://doi.ieeecomputersociety.org/10.1145/3581784.3607110
 //
 
-use ksp_graph::Graph;
+use std::collections::{HashMap, HashSet};
+
+use arrayvec::ArrayString;
+use ksp_graph::compact::CompactGraph;
+use ksp_graph::{Graph, GraphGuard};
 
 use super::PreprocessStep;
+use crate::ksp::path_capacity;
 use crate::path::Path;
-use crate::sssp::dijkstra::DijkstraSSSP;
+use crate::sssp::compact::CompactDijkstraSSSP;
+use crate::sssp::dijkstra::{tree_path, ShortestTree};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ksp::yen::YenKSP;
+    use crate::ksp::KShortestPath as _;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+    use crate::utils::{load_bench, load_graph};
+
+    /// Checks that pruning doesn't change the costs of the paths found by Yen.
+    fn assert_prune_preserves(g: &mut Graph, src: &str, dst: &str, k: usize) -> usize {
+        let expected: Vec<f64> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(g, src, dst, k).iter().map(Path::cost).collect();
+        let mut guard: GraphGuard = GraphGuard::new(g);
+        PeekPreprocess::default().preprocess(&mut guard, src, dst, k);
+        let got: Vec<f64> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&guard, src, dst, k).iter().map(Path::cost).collect();
+        assert_eq!(got, expected, "Pruning changed the paths for {src} -> {dst} (k = {k})");
+        guard.node_count()
+    }
+
+    #[test]
+    fn test_peek_cities() {
+        let mut g: Graph = load_graph("cities");
+
+        // Only the direct edge is needed for a single path...
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        PeekPreprocess::default().preprocess(&mut guard, "Amsterdam", "Berlin", 1);
+        assert_eq!(guard.node_count(), 2);
+        assert_eq!(guard.edge_count(), 1);
+        drop(guard);
+
+        // ...but the pruning never loses paths
+        let nodes: Vec<String> = g.nodes.keys().map(|id| id.to_string()).collect();
+        for src in &nodes {
+            for dst in &nodes {
+                for k in 0..=4 {
+                    assert_prune_preserves(&mut g, src, dst, k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_peek_directed() {
+        let mut g: Graph = load_graph("directed");
+
+        // Going from A to B is direct, but going back means going round
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        PeekPreprocess::default().preprocess(&mut guard, "A", "B", 1);
+        assert_eq!(guard.node_count(), 2);
+        guard.restore();
+        PeekPreprocess::default().preprocess(&mut guard, "B", "A", 1);
+        assert_eq!(guard.node_count(), 3);
+        drop(guard);
+
+        let nodes: Vec<String> = g.nodes.keys().map(|id| id.to_string()).collect();
+        for src in &nodes {
+            for dst in &nodes {
+                for k in 0..=4 {
+                    assert_prune_preserves(&mut g, src, dst, k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_peek_no_dangling_edges() {
+        for name in ["india35", "abilene", "germany50"] {
+            let mut g: Graph = load_bench(name);
+            let mut nodes: Vec<String> = g.nodes.keys().map(|id| id.to_string()).collect();
+            nodes.sort();
+            for (src, dst) in nodes.iter().zip(nodes.iter().rev()).take(4) {
+                for k in [1, 2, 5] {
+                    let mut guard: GraphGuard = GraphGuard::new(&mut g);
+                    PeekPreprocess::default().preprocess(&mut guard, src, dst, k);
+                    if let Err(err) = guard.validate() {
+                        panic!("PeeK corrupted {name} for {src} -> {dst} (k = {k}): {err}");
+                    }
+                    // Every edge left must be between surviving nodes
+                    assert!(guard.edges.values().all(|e| guard.nodes.contains_key(&e.left) && guard.nodes.contains_key(&e.right)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_peek_slack() {
+        let mut g: Graph = load_bench("india35");
+        for (src, dst) in [("12", "33"), ("0", "34"), ("7", "20")] {
+            for k in [1, 3] {
+                let mut kept: Vec<usize> = Vec::with_capacity(3);
+                for slack in [1.0, 1.5, 3.0] {
+                    let mut guard: GraphGuard = GraphGuard::new(&mut g);
+                    PeekPreprocess::with_slack(slack).preprocess(&mut guard, src, dst, k);
+                    let paths: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&guard, src, dst, k);
+                    assert_eq!(paths.len(), k);
+                    kept.push(guard.node_count());
+                }
+                // A wider bound never prunes more
+                assert!(kept.windows(2).all(|w| w[0] <= w[1]), "Slack pruned more for {src} -> {dst} (k = {k}): {kept:?}");
+            }
+        }
+
+        // The bound is strict without slack, so the smallest slack prunes nodes the largest does not
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        PeekPreprocess::default().preprocess(&mut guard, "12", "33", 3);
+        let strict: usize = guard.node_count();
+        guard.restore();
+        PeekPreprocess::with_slack(3.0).preprocess(&mut guard, "12", "33", 3);
+        assert!(strict < guard.node_count());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_peek_slack_too_small() { PeekPreprocess::with_slack(0.9); }
+
+    #[test]
+    fn test_peek_india35() {
+        let mut g: Graph = load_bench("india35");
+        let total: usize = g.node_count();
+        let mut pruned: usize = 0;
+        for (src, dst) in [("12", "33"), ("0", "34"), ("7", "20"), ("33", "2")] {
+            for k in [1, 3, 8] {
+                pruned += total - assert_prune_preserves(&mut g, src, dst, k);
+            }
+        }
+        assert!(pruned > 0);
+    }
+
+    #[test]
+    fn test_peek_edge_list() {
+        let mut g: Graph = load_bench("india35");
+        let before: String = g.edge_list().to_string();
+        assert_eq!(before.lines().count(), g.edge_count());
+
+        // The dump of the pruned graph only lists the edges that survived
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        PeekPreprocess::default().preprocess(&mut guard, "12", "33", 1);
+        let after: String = guard.edge_list().to_string();
+        assert_eq!(after.lines().count(), guard.edge_count());
+        assert!(after.lines().count() < before.lines().count());
+        assert!(after.lines().all(|l| before.lines().any(|b| b == l)));
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Finds the shortest path from the source to the destination that goes through a particular node.
+///
+/// # Arguments
+/// - `from`: The [`ShortestTree`] rooted at the source.
+/// - `to`: The [`ShortestTree`] rooted at the destination.
+/// - `via`: The node to go through.
+///
+/// # Returns
+/// The shortest path through `via`, or [`None`] if it isn't connected to either endpoint. Note that the path may not be simple.
+fn path_via<'g>(from: &ShortestTree<'g>, to: &ShortestTree<'g>, via: &str) -> Option<Path<'g>> {
+    let head: Path<'g> = tree_path(from, via)?;
+    let tail: Path<'g> = tree_path(to, via)?;
+
+    // The tail runs from the destination to `via`, so turn it around
+    let cost: f64 = tail.cost();
+    let tail: Path<'g> = Path { hops: tail.hops.into_iter().rev().map(|(n, c)| (n, cost - c)).collect() };
+    Path::concat(&head, &tail)
+}
+
+
+
 
 
 /***** LIBRARY *****/
 /// Defines a prune-centric approach for K-Shortest Path Computation (i.e., it be faster).
 ///
+/// Every node is _coloured_ with the cost of the shortest path from the source to the destination that goes through it. Then, the shortest
+/// such paths are collected (in order of colour) until there are K distinct simple ones, the most expensive of which bounds the cost of the
+/// K-th shortest path. Any node coloured above that bound cannot be on any of the K shortest paths, and is pruned.
+///
+/// Edges are pruned based on their endpoints only, i.e., an edge is removed if and only if either of the nodes it connects is. This keeps
+/// the graph consistent, as no edge ever refers to a pruned node.
+///
+/// If there are fewer than K distinct simple paths to collect, the graph is left untouched.
+///
+/// The bound can be widened by a [`slack`](PeekPreprocess::slack), which keeps more of the graph around to be on the safe side.
+///
 /// Based on \[1\].
 #[derive(Clone, Copy, Debug)]
-pub struct PeekPreprocess;
+pub struct PeekPreprocess {
+    /// The factor by which to multiply the bound before pruning. `1.0` prunes as much as possible, higher values prune less.
+    slack: f64,
+}
+impl Default for PeekPreprocess {
+    #[inline]
+    fn default() -> Self { Self { slack: 1.0 } }
+}
+impl PeekPreprocess {
+    /// Constructor for a PeekPreprocess that widens its bound.
+    ///
+    /// # Arguments
+    /// - `slack`: The factor by which to multiply the bound before pruning. Must be at least `1.0`, as anything lower may prune paths that
+    ///   are needed.
+    ///
+    /// # Returns
+    /// A new PeekPreprocess instance.
+    ///
+    /// # Panics
+    /// This function panics if `slack` is lower than `1.0` or NaN.
+    #[inline]
+    #[track_caller]
+    pub fn with_slack(slack: f64) -> Self {
+        if slack.is_nan() || slack < 1.0 {
+            panic!("PeeK's slack must be at least 1.0 (got {slack})");
+        }
+        Self { slack }
+    }
+
+    /// Returns the factor by which the bound is multiplied before pruning.
+    ///
+    /// # Returns
+    /// The slack, which is at least `1.0`.
+    #[inline]
+    pub const fn slack(&self) -> f64 { self.slack }
+
+    /// Computes the bound on the cost of the K-th shortest path that PeeK prunes with.
+    ///
+    /// This is useful to give to algorithms that can discard paths above it, e.g.,
+    /// [`YenKSP::with_bound()`](crate::ksp::yen::YenKSP::with_bound()).
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to compute the bound in.
+    /// - `src`: The source node of the paths.
+    /// - `dst`: The destination node of the paths.
+    /// - `k`: The number of paths to bound.
+    ///
+    /// # Returns
+    /// The bound, widened by the [`slack`](PeekPreprocess::slack()) exactly as when pruning. There are at least `k` simple paths from `src`
+    /// to `dst` that cost at most this much. Is [`None`] if there are fewer than `k` simple paths (found), or if `k` is 0.
+    ///
+    /// # Panics
+    /// This function panics if the given `src` or `dst` are not in the given `graph`.
+    #[track_caller]
+    pub fn bound(&self, graph: &Graph, src: &str, dst: &str, k: usize) -> Option<f64> {
+        if !graph.nodes.contains_key(dst) {
+            panic!("Unknown destination node '{dst}'");
+        }
+        if k == 0 {
+            return None;
+        }
+        Self::colour(graph, src, dst, k).1.map(|bound| self.widen(bound))
+    }
+
+    /// Widens a bound found by [`PeekPreprocess::colour()`] by the slack.
+    ///
+    /// # Arguments
+    /// - `bound`: The bound to widen.
+    ///
+    /// # Returns
+    /// The bound to prune with.
+    #[inline]
+    fn widen(&self, bound: f64) -> f64 {
+        // NOTE: Colours of nodes on the same path may differ slightly due to rounding, so always allow a little slack
+        bound * self.slack + bound.abs() * 1e-9
+    }
+
+    /// Colours every node with the cost of the shortest path through it, and finds the bound on the cost of the K-th shortest path.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to colour.
+    /// - `src`: The source node of the paths.
+    /// - `dst`: The destination node of the paths.
+    /// - `k`: The number of paths to bound. Must be at least 1.
+    ///
+    /// # Returns
+    /// A pair of the colour of every node and the (unwidened) bound, which is [`None`] if fewer than `k` distinct simple paths were found.
+    fn colour(graph: &Graph, src: &str, dst: &str, k: usize) -> (HashMap<ArrayString<64>, f64>, Option<f64>) {
+        // Colour the nodes by the shortest path through them
+        let (compact, ids): (CompactGraph, Vec<&str>) = graph.compact();
+        let from: ShortestTree = CompactDijkstraSSSP.shortest_tree(&compact, &ids, src);
+        let to: ShortestTree = if graph.directed {
+            // The paths to the destination follow the edges backwards
+            // NOTE: The reversed graph has the same nodes, so the indices match
+            let (reversed, _): (CompactGraph, Vec<&str>) = graph.reversed().compact();
+            CompactDijkstraSSSP.shortest_tree(&reversed, &ids, dst)
+        } else {
+            CompactDijkstraSSSP.shortest_tree(&compact, &ids, dst)
+        };
+        let colours: HashMap<ArrayString<64>, f64> =
+            from.iter().map(|(node, (cost, _))| (ArrayString::from(node).unwrap(), cost + to[node].0)).collect();
+
+        // Find the bound by collecting distinct, simple paths from the cheapest nodes
+        let mut order: Vec<(&str, f64)> = colours.iter().filter(|(_, c)| **c < f64::INFINITY).map(|(n, c)| (n.as_str(), *c)).collect();
+        order.sort_by(|(n1, c1), (n2, c2)| c1.total_cmp(c2).then_with(|| n1.cmp(n2)));
+        let mut paths: HashSet<Path> = HashSet::with_capacity(path_capacity(k));
+        let mut bound: Option<f64> = None;
+        for (node, colour) in order {
+            if let Some(path) = path_via(&from, &to, node) {
+                if path.is_simple() && paths.insert(path) && paths.len() >= k {
+                    bound = Some(colour);
+                    break;
+                }
+            }
+        }
+        #[cfg(feature = "log")]
+        if bound.is_none() {
+            log::debug!("PeeK found only {} of {k} path(s) from '{src}' to '{dst}', so there is no bound", paths.len());
+        }
+        (colours, bound)
+    }
+}
 impl PreprocessStep for PeekPreprocess {
-    fn preprocess(graph: &mut Graph, src: &str, dst: &str, k: usize) {
-        todo!();
+    #[track_caller]
+    fn preprocess(&self, graph: &mut GraphGuard, src: &str, dst: &str, k: usize) {
+        if !graph.nodes.contains_key(dst) {
+            panic!("Unknown destination node '{dst}'");
+        }
+        // Nothing needs to be preserved if nothing is asked
+        if k == 0 {
+            return;
+        }
+
+        let (colours, bound): (HashMap<ArrayString<64>, f64>, Option<f64>) = Self::colour(graph, src, dst, k);
+        let bound: f64 = match bound {
+            Some(bound) => bound,
+            None => return,
+        };
+
+        // Prune the nodes above it
+        #[cfg(feature = "log")]
+        let (nodes, edges): (usize, usize) = (graph.node_count(), graph.edge_count());
+        let bound: f64 = self.widen(bound);
+        graph.retain_nodes(|n| colours[&n.id] <= bound);
+        #[cfg(feature = "log")]
+        log::debug!(
+            "PeeK pruned {} -> {} node(s) and {} -> {} edge(s) with bound {bound}",
+            nodes,
+            graph.node_count(),
+            edges,
+            graph.edge_count()
+        );
     }
 }
@@ -0,0 +1,169 @@
+//  QUERY.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:01:10
+//  Last edited:
+//    16 Oct 2026, 15:31:03
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines a full K-Shortest Path query, which can be given as a
+//!   single string.
+//
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use crate::{Pipeline, PipelineParseError};
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_roundtrip() {
+        for raw in ["Amsterdam->Chicago#3@yen<dijkstra>", "Berlin->Berlin#0@wikipedia", "12->33#1@peek->yen<dijkstra>", "a b->c-d#10@wikipedia"] {
+            let query: Query = raw.parse().unwrap();
+            assert_eq!(query.to_string(), raw);
+            assert_eq!(query.to_string().parse::<Query>().unwrap(), query);
+        }
+
+        let query: Query = "Amsterdam->Chicago#3@peek->yen<dijkstra>".parse().unwrap();
+        assert_eq!(query.src, "Amsterdam");
+        assert_eq!(query.dst, "Chicago");
+        assert_eq!(query.k, 3);
+        assert_eq!(query.pipeline, "peek->yen<dijkstra>".parse().unwrap());
+    }
+
+    #[test]
+    fn test_query_errors() {
+        assert!(matches!("Amsterdam->Chicago#3".parse::<Query>(), Err(QueryParseError::MissingPipeline { .. })));
+        assert!(matches!("Amsterdam->Chicago@yen<dijkstra>".parse::<Query>(), Err(QueryParseError::MissingK { .. })));
+        assert!(matches!("Amsterdam#3@yen<dijkstra>".parse::<Query>(), Err(QueryParseError::MissingDestination { .. })));
+        assert!(matches!("->Chicago#3@yen<dijkstra>".parse::<Query>(), Err(QueryParseError::MissingDestination { .. })));
+        assert!(matches!("Amsterdam->Chicago#-1@yen<dijkstra>".parse::<Query>(), Err(QueryParseError::IllegalK { .. })));
+        assert!(matches!("Amsterdam->Chicago#3@dijkstra".parse::<Query>(), Err(QueryParseError::IllegalPipeline { .. })));
+        assert!(matches!("Amsterdam->Chicago#3@yen".parse::<Query>(), Err(QueryParseError::IllegalPipeline { .. })));
+
+        // The errors are clear about what's wrong
+        assert_eq!(
+            "Amsterdam->Chicago@yen<dijkstra>".parse::<Query>().unwrap_err().to_string(),
+            "Missing '#<K>' in query 'Amsterdam->Chicago@yen<dijkstra>'"
+        );
+        assert_eq!(
+            "Amsterdam->Chicago#3@dijkstra".parse::<Query>().unwrap_err().to_string(),
+            "Failed to parse 'dijkstra' as a pipeline in query 'Amsterdam->Chicago#3@dijkstra'"
+        );
+    }
+}
+
+
+
+
+
+/***** ERRORS *****/
+/// Failed to parse a [`Query`] from a string.
+#[derive(Debug)]
+pub enum QueryParseError {
+    /// The `@` separating the endpoints from the pipeline was missing.
+    MissingPipeline { raw: String },
+    /// The `#` separating the endpoints from the K was missing.
+    MissingK { raw: String },
+    /// The `->` separating the source from the destination was missing, or either of them was empty.
+    MissingDestination { raw: String },
+    /// Failed to parse the K as a number.
+    IllegalK { raw: String, k: String, err: ParseIntError },
+    /// Failed to parse the pipeline.
+    IllegalPipeline { raw: String, pipeline: String, err: PipelineParseError },
+}
+impl Display for QueryParseError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        use QueryParseError::*;
+        match self {
+            MissingPipeline { raw } => write!(f, "Missing '@<PIPELINE>' in query '{raw}'"),
+            MissingK { raw } => write!(f, "Missing '#<K>' in query '{raw}'"),
+            MissingDestination { raw } => write!(f, "Missing '<SRC>-><DST>' in query '{raw}'"),
+            IllegalK { raw, k, .. } => write!(f, "Failed to parse '{k}' as a number of paths in query '{raw}'"),
+            IllegalPipeline { raw, pipeline, .. } => write!(f, "Failed to parse '{pipeline}' as a pipeline in query '{raw}'"),
+        }
+    }
+}
+impl Error for QueryParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use QueryParseError::*;
+        match self {
+            MissingPipeline { .. } => None,
+            MissingK { .. } => None,
+            MissingDestination { .. } => None,
+            IllegalK { err, .. } => Some(err),
+            IllegalPipeline { err, .. } => Some(err),
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines a full query for the K shortest paths between two nodes.
+///
+/// It can be parsed from and serialized to a single string of the form `<SRC>-><DST>#<K>@<PIPELINE>`, e.g.,
+/// `Amsterdam->Chicago#3@yen<dijkstra>`. Note that this means that the source node cannot contain `->`, and neither node can contain `#` or
+/// `@`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Query {
+    /// The source node to find paths from.
+    pub src:      String,
+    /// The destination node to find paths to.
+    pub dst:      String,
+    /// The number of paths to find.
+    pub k:        usize,
+    /// The [`Pipeline`] to find them with.
+    pub pipeline: Pipeline,
+}
+impl Display for Query {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FResult {
+        // NOTE: We don't use the pipeline's `Display`, as that's meant for humans and doesn't parse back
+        write!(f, "{}->{}#{}@{}", self.src, self.dst, self.k, self.pipeline.id())
+    }
+}
+impl FromStr for Query {
+    type Err = QueryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Split off the pipeline first, as that one may contain '->' itself
+        let (query, pipeline): (&str, &str) = match s.split_once('@') {
+            Some(parts) => parts,
+            None => return Err(QueryParseError::MissingPipeline { raw: s.into() }),
+        };
+        let (endpoints, k): (&str, &str) = match query.rsplit_once('#') {
+            Some(parts) => parts,
+            None => return Err(QueryParseError::MissingK { raw: s.into() }),
+        };
+        let (src, dst): (&str, &str) = match endpoints.split_once("->") {
+            Some((src, dst)) if !src.is_empty() && !dst.is_empty() => (src, dst),
+            _ => return Err(QueryParseError::MissingDestination { raw: s.into() }),
+        };
+
+        // Parse the K and the pipeline
+        let k: usize = match k.parse() {
+            Ok(k) => k,
+            Err(err) => return Err(QueryParseError::IllegalK { raw: s.into(), k: k.into(), err }),
+        };
+        let pipeline: Pipeline = match pipeline.parse() {
+            Ok(pipeline) => pipeline,
+            Err(err) => return Err(QueryParseError::IllegalPipeline { raw: s.into(), pipeline: pipeline.into(), err }),
+        };
+        Ok(Self { src: src.into(), dst: dst.into(), k, pipeline })
+    }
+}
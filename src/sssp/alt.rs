@@ -0,0 +1,393 @@
+//  ALT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:19:39
+//  Last edited:
+//    16 Oct 2026, 19:33:53
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements A* search with landmarks (ALT), which speeds up repeated
+//!   queries on the same graph using precomputed distances to a few
+//!   landmark nodes.
+//!
+//!   \[4\] A. V. Goldberg and C. Harrelson, "Computing the shortest path: A* search meets graph
+//!       theory," in Proceedings of the Sixteenth Annual ACM-SIAM Symposium on Discrete Algorithms
+//!       (SODA '05), pp. 156–165, 2005.
+//
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use arrayvec::ArrayString;
+use ksp_graph::compact::CompactGraph;
+use ksp_graph::{Edge, Graph, Node};
+
+use super::dijkstra::tree_path;
+use super::{ShortestPathTree, ShortestTree, SingleShortestPath};
+use crate::cost::OrderedCost;
+use crate::path::Path;
+use crate::sssp::compact::CompactDijkstraSSSP;
+use crate::sssp::dijkstra::DijkstraSSSP;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use ksp_graph::GraphGuard;
+
+    use super::*;
+    use crate::ksp::yen::YenKSP;
+    use crate::ksp::KShortestPath as _;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+    use crate::utils::{load_bench, load_graph};
+
+    /// Checks that ALT finds paths as short as Dijkstra's between all pairs of nodes.
+    fn assert_matches_dijkstra(g: &Graph, landmarks: &Landmarks) {
+        for src in g.nodes.keys() {
+            for dst in g.nodes.keys() {
+                let path: Option<Path> = AltSSSP::new(landmarks, g).try_shortest_weighted(g, src, dst, |e: &Edge| e.cost);
+                let reference: Option<Path> = DijkstraSSSP::new().try_shortest_weighted(g, src, dst, |e: &Edge| e.cost);
+                match (path, reference) {
+                    (Some(path), Some(reference)) => {
+                        assert!(path.validate_against(g).is_ok());
+                        assert!((path.cost() - reference.cost()).abs() < 1e-9, "{src} -> {dst}: {} vs {}", path.cost(), reference.cost());
+                    },
+                    (path, reference) => assert_eq!(path, reference, "{src} -> {dst}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_alt_cities() {
+        let g: Graph = load_graph("cities");
+        let landmarks: Landmarks = Landmarks::select(&g, 2);
+        // The first is the smallest identifier, the second the node farthest away from it
+        assert_eq!(landmarks.landmarks(), vec!["Amsterdam", "Chicago"]);
+        assert_matches_dijkstra(&g, &landmarks);
+
+        // The bounds never overestimate
+        for src in g.nodes.keys() {
+            let tree: ShortestTree = DijkstraSSSP::new().shortest_tree(&g, src);
+            for (dst, (cost, _)) in tree {
+                assert!(landmarks.lower_bound(src, dst) <= cost + 1e-9);
+            }
+        }
+        assert_eq!(landmarks.lower_bound("Amsterdam", "Chicago"), 540.86 + 6249.15);
+    }
+
+    #[test]
+    fn test_alt_reuse() {
+        // The same landmarks keep working for subgraphs, e.g., as Yen's algorithm removes edges
+        let mut g: Graph = load_bench("india35");
+        let landmarks: Landmarks = Landmarks::select(&g, 4);
+        assert_matches_dijkstra(&g, &landmarks);
+        for (src, dst) in [("12", "33"), ("0", "34")] {
+            let paths: Vec<f64> =
+                YenKSP::new(AltSSSP::new(&landmarks, &g)).k_shortest_paths(&g, src, dst, 5).iter().map(Path::cost).collect();
+            let reference: Vec<f64> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, 5).iter().map(Path::cost).collect();
+            assert_eq!(paths, reference);
+        }
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        guard.remove_node("20");
+        assert!(landmarks.covers(&guard));
+        assert_matches_dijkstra(&guard, &landmarks);
+        drop(guard);
+
+        // But not for graphs with edges cheaper than when they were selected, or with new ones
+        let mut guard: GraphGuard = GraphGuard::new(&mut g);
+        let ids: Vec<ArrayString<64>> = guard.edges.keys().copied().collect();
+        for id in ids {
+            guard.set_cost(&id, 1.0);
+        }
+        assert!(!landmarks.covers(&guard));
+        assert_matches_dijkstra(&guard, &landmarks);
+        drop(guard);
+        let mut h: Graph = g.clone();
+        h.edges.insert(ArrayString::from("shortcut").unwrap(), Edge {
+            id:    ArrayString::from("shortcut").unwrap(),
+            left:  ArrayString::from("12").unwrap(),
+            right: ArrayString::from("33").unwrap(),
+            cost:  1.0,
+        });
+        assert!(landmarks.covers(&g));
+        assert!(!landmarks.covers(&h));
+        assert_matches_dijkstra(&h, &landmarks);
+    }
+
+    #[test]
+    fn test_alt_directed() {
+        let g: Graph = load_graph("directed");
+        for count in 0..=3 {
+            assert_matches_dijkstra(&g, &Landmarks::select(&g, count));
+        }
+    }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// A node in the A* queue.
+#[derive(Clone, Copy, Debug)]
+struct Entry<'g> {
+    /// The distance to the node when it was queued, plus the lower bound on the distance from it to the destination.
+    estimate: OrderedCost,
+    /// The node to visit.
+    node:     &'g str,
+}
+impl<'g> Eq for Entry<'g> {}
+impl<'g> Ord for Entry<'g> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // NOTE: Reversed, as the `BinaryHeap` is a max-heap
+        other.estimate.cmp(&self.estimate).then_with(|| other.node.cmp(self.node))
+    }
+}
+impl<'g> PartialEq for Entry<'g> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl<'g> PartialOrd for Entry<'g> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Precomputed distances from and to a few landmark nodes in a [`Graph`].
+///
+/// By the triangle inequality, the distance between any two nodes is at least the difference of their distances to a landmark. This gives a
+/// lower bound that [`AltSSSP`] uses to guide its search towards the destination.
+///
+/// Selecting the landmarks takes one run of Dijkstra per landmark (two if the graph is [directed](Graph::directed)), so this is only worth
+/// it if many queries are run on the same graph. The landmarks stay valid for any subgraph of the one they were selected on, e.g., after
+/// preprocessing removed some nodes or edges.
+#[derive(Clone, Debug)]
+pub struct Landmarks {
+    /// The landmark nodes, in the order they were selected.
+    landmarks: Vec<ArrayString<64>>,
+    /// For every node, the distance from and to every landmark (in the same order). Unreachable ones are [`f64::INFINITY`].
+    dists:     HashMap<ArrayString<64>, Vec<(f64, f64)>>,
+    /// For every pair of nodes linked in the graph the landmarks were selected in, the cheapest cost of going from the first to the second.
+    costs:     HashMap<(ArrayString<64>, ArrayString<64>), f64>,
+}
+impl Landmarks {
+    /// Selects landmarks in a graph with the farthest-point heuristic and computes the distances to them.
+    ///
+    /// The first landmark is the node with the smallest identifier. Every next one is the node farthest away from all landmarks selected so
+    /// far, which spreads them over the graph. This means that if the graph isn't connected, every component gets a landmark before any of
+    /// them gets a second one.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to select landmarks in.
+    /// - `count`: The number of landmarks to select. More landmarks give tighter bounds but take more time and memory. If the graph has
+    ///   fewer nodes, all of them are selected.
+    ///
+    /// # Returns
+    /// A new Landmarks with the distances from and to every selected landmark.
+    pub fn select(graph: &Graph, count: usize) -> Self {
+        let (compact, ids): (CompactGraph, Vec<&str>) = graph.compact();
+        // NOTE: The reversed graph has the same nodes, so they have the same indices in it
+        let reversed: Option<CompactGraph> = if graph.directed { Some(graph.reversed().compact().0) } else { None };
+
+        // Keep track of how far every node is from the nearest landmark to find the next one
+        let mut landmarks: Vec<ArrayString<64>> = Vec::with_capacity(count.min(ids.len()));
+        let mut dists: Vec<Vec<(f64, f64)>> = vec![Vec::with_capacity(count.min(ids.len())); ids.len()];
+        let mut nearest: Vec<f64> = vec![f64::INFINITY; ids.len()];
+        let mut next: Option<usize> = if ids.is_empty() { None } else { Some(0) };
+        while let Some(landmark) = next {
+            if landmarks.len() >= count {
+                break;
+            }
+            landmarks.push(ArrayString::from(ids[landmark]).unwrap());
+
+            // Compute the distances in both directions
+            let from: Vec<(f64, Option<usize>)> = CompactDijkstraSSSP.shortest_all(&compact, landmark);
+            let to: Option<Vec<(f64, Option<usize>)>> = reversed.as_ref().map(|reversed| CompactDijkstraSSSP.shortest_all(reversed, landmark));
+            for (i, dist) in dists.iter_mut().enumerate() {
+                let (from, to): (f64, f64) = (from[i].0, to.as_ref().map(|to| to[i].0).unwrap_or(from[i].0));
+                dist.push((from, to));
+                nearest[i] = nearest[i].min(from.min(to));
+            }
+
+            // Find the farthest node that isn't a landmark already
+            // NOTE: Unreachable nodes are the farthest of all, so other components are covered too
+            next = (0..ids.len()).filter(|i| nearest[*i] > 0.0).max_by(|i, j| nearest[*i].total_cmp(&nearest[*j]).then(j.cmp(i)));
+        }
+
+        // Remember what the edges cost, to tell in which graphs the distances are still lower bounds
+        let mut costs: HashMap<(ArrayString<64>, ArrayString<64>), f64> = HashMap::with_capacity(graph.edges.len());
+        for edge in graph.edges_iter().filter(|e| e.left != e.right) {
+            let links: &[(ArrayString<64>, ArrayString<64>)] =
+                if graph.directed { &[(edge.left, edge.right)] } else { &[(edge.left, edge.right), (edge.right, edge.left)] };
+            for link in links {
+                let cost: &mut f64 = costs.entry(*link).or_insert(f64::INFINITY);
+                *cost = cost.min(edge.cost);
+            }
+        }
+        Self { landmarks, dists: ids.into_iter().map(|id| ArrayString::from(id).unwrap()).zip(dists).collect(), costs }
+    }
+
+    /// Checks whether the bounds of these landmarks hold in the given graph.
+    ///
+    /// This is the case if it has no nodes the landmarks don't know about, and if going from one node to another costs at least as much as
+    /// it did in the graph the landmarks were selected in. Hence, this holds for any subgraph of that one, even if edges are more expensive
+    /// (or removed by making them [`f64::INFINITY`]), but not if edges became cheaper or new ones link nodes that weren't before.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to check.
+    ///
+    /// # Returns
+    /// True if [`Landmarks::lower_bound()`] never overestimates distances in `graph`, or false if it might.
+    pub fn covers(&self, graph: &Graph) -> bool {
+        let cost = |from: ArrayString<64>, to: ArrayString<64>| -> f64 { self.costs.get(&(from, to)).copied().unwrap_or(f64::INFINITY) };
+        graph.nodes.keys().all(|id| self.dists.contains_key(id))
+            && graph
+                .edges_iter()
+                .filter(|e| e.left != e.right)
+                .all(|e: &Edge| e.cost >= cost(e.left, e.right) && (graph.directed || e.cost >= cost(e.right, e.left)))
+    }
+
+    /// Returns the selected landmarks.
+    ///
+    /// # Returns
+    /// The identifiers of the landmark nodes, in the order they were selected.
+    #[inline]
+    pub fn landmarks(&self) -> Vec<&str> { self.landmarks.iter().map(|id| id.as_str()).collect() }
+
+    /// Computes a lower bound on the distance between two nodes.
+    ///
+    /// # Arguments
+    /// - `src`: The node to find the distance from.
+    /// - `dst`: The node to find the distance to.
+    ///
+    /// # Returns
+    /// A distance that is at most that of the shortest path from `src` to `dst` in the graph the landmarks were selected in (or any subgraph
+    /// of it). This is `0.0` if either node is unknown.
+    pub fn lower_bound(&self, src: &str, dst: &str) -> f64 {
+        let src: &[(f64, f64)] = match self.dists.get(src) {
+            Some(src) => src,
+            None => return 0.0,
+        };
+        let dst: &[(f64, f64)] = match self.dists.get(dst) {
+            Some(dst) => dst,
+            None => return 0.0,
+        };
+        let mut bound: f64 = 0.0;
+        for ((from_src, to_src), (from_dst, to_dst)) in src.iter().zip(dst) {
+            // NOTE: Bounds involving unreachable landmarks are skipped, as these may not be meaningful
+            // d(src, dst) >= d(L, dst) - d(L, src)
+            if from_src.is_finite() && from_dst.is_finite() {
+                bound = bound.max(from_dst - from_src);
+            }
+            // d(src, dst) >= d(src, L) - d(dst, L)
+            if to_src.is_finite() && to_dst.is_finite() {
+                bound = bound.max(to_src - to_dst);
+            }
+        }
+        bound
+    }
+}
+
+
+
+/// Implements A* search with [`Landmarks`] as heuristic (ALT) \[4\].
+///
+/// This finds the same shortest paths as [`DijkstraSSSP`](super::dijkstra::DijkstraSSSP), but visits fewer nodes by using
+/// [`Landmarks::lower_bound()`] to estimate how far the destination still is. The same landmarks can be used for any number of queries.
+///
+/// The bound is only valid if the costs are at least those of the graph the landmarks were selected in. Hence, the graph to search is
+/// checked with [`Landmarks::covers()`] once, when this AltSSSP is created, and searched with plain Dijkstra (i.e., a heuristic of `0.0`) if
+/// that fails. Searching other graphs than that one checks them on every search. Custom `weight`s may make edges more expensive than their
+/// [`Edge::cost`] (including removing them with [`f64::INFINITY`]), which is what algorithms like Yen's do, but not cheaper.
+#[derive(Clone, Copy, Debug)]
+pub struct AltSSSP<'l> {
+    /// The landmarks to compute the heuristic with.
+    landmarks: &'l Landmarks,
+    /// The graph that will be searched.
+    graph:     &'l Graph,
+    /// Whether the landmarks' bounds hold in `graph`.
+    informed:  bool,
+}
+impl<'l> AltSSSP<'l> {
+    /// Constructor for the AltSSSP.
+    ///
+    /// # Arguments
+    /// - `landmarks`: The [`Landmarks`] selected in the graph (or a supergraph of the graph) that will be searched.
+    /// - `graph`: The [`Graph`] that will be searched. It's borrowed, such that it can't change while the landmarks are known to hold in it.
+    ///
+    /// # Returns
+    /// A new AltSSSP instance.
+    #[inline]
+    pub fn new(landmarks: &'l Landmarks, graph: &'l Graph) -> Self { Self { landmarks, graph, informed: landmarks.covers(graph) } }
+}
+impl<'l> SingleShortestPath for AltSSSP<'l> {
+    #[track_caller]
+    fn try_shortest_node_weighted<'g>(
+        &mut self,
+        graph: &'g Graph,
+        src: &str,
+        dst: &str,
+        weight: impl Fn(&Edge) -> f64,
+        node_weight: impl Fn(&Node) -> f64,
+    ) -> Option<Path<'g>> {
+        let src: &'g str = match graph.nodes.get_key_value(src) {
+            Some((src, _)) => src.as_str(),
+            None => panic!("Unknown source node '{src}'"),
+        };
+        let dst: &'g str = match graph.nodes.get_key_value(dst) {
+            Some((dst, _)) => dst.as_str(),
+            None => panic!("Unknown destination node '{dst}'"),
+        };
+
+        // Only use the landmarks if their bounds hold for this graph
+        let informed: bool = if std::ptr::eq(graph, self.graph) { self.informed } else { self.landmarks.covers(graph) };
+        let heuristic = |node: &str| -> f64 { if informed { self.landmarks.lower_bound(node, dst) } else { 0.0 } };
+
+        // Then do A*, which is Dijkstra ordered by the estimated total distance
+        // NOTE: The destination is inserted first so that it's overwritten if it's also the source
+        let mut tree: ShortestTree<'g> = HashMap::from([(dst, (f64::INFINITY, None)), (src, (0.0, None))]);
+        let mut visited: HashSet<&'g str> = HashSet::new();
+        let mut todo: BinaryHeap<Entry<'g>> = BinaryHeap::from([Entry { estimate: OrderedCost(heuristic(src)), node: src }]);
+        while let Some(Entry { node, .. }) = todo.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+            if node == dst {
+                break;
+            }
+
+            // Update all distances, but only iff shorter
+            let cost: f64 = tree[node].0;
+            for (neigh, edge) in graph.successors(node) {
+                let new_dist: f64 = cost + weight(edge) + node_weight(&graph.nodes[neigh]);
+                debug_assert!(!new_dist.is_nan(), "Accumulated cost to node '{neigh}' is NaN");
+                let neigh_dist: &mut (f64, Option<&'g str>) = tree.entry(neigh).or_insert((f64::INFINITY, None));
+                if new_dist < neigh_dist.0 {
+                    *neigh_dist = (new_dist, Some(node));
+                    todo.push(Entry { estimate: OrderedCost(new_dist + heuristic(neigh)), node: neigh });
+                }
+            }
+        }
+        tree_path(&tree, dst)
+    }
+}
+impl<'l> ShortestPathTree for AltSSSP<'l> {
+    /// Computes the shortest paths from one node to all others, using a custom metric for edges.
+    ///
+    /// A tree has no destination for the landmarks to estimate the distance to, so this is plain Dijkstra. See
+    /// [`ShortestPathTree::shortest_tree_weighted()`] for more information.
+    #[inline]
+    #[track_caller]
+    fn shortest_tree_weighted<'g>(&mut self, graph: &'g Graph, src: &str, weight: impl Fn(&Edge) -> f64) -> ShortestTree<'g> {
+        DijkstraSSSP::new().shortest_tree_weighted(graph, src, weight)
+    }
+}
@@ -0,0 +1,169 @@
+//  COMPACT.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 15:35:23
+//  Last edited:
+//    16 Oct 2026, 19:00:47
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Implements Dijkstra's algorithm over a [`CompactGraph`], which is
+//!   much faster than over a [`Graph`] for larger graphs.
+//
+
+use ksp_graph::compact::CompactGraph;
+
+use super::queue::{BinaryHeapQueue, PriorityQueue};
+use super::ShortestTree;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use ksp_graph::Graph;
+
+    use super::*;
+    use crate::sssp::dijkstra::DijkstraSSSP;
+    use crate::sssp::queue::BucketQueue;
+    use crate::utils::{load_bench, load_graph};
+
+    #[test]
+    fn test_compact_sssp_reference() {
+        for g in [load_graph("cities"), load_bench("india35"), load_bench("abilene")] {
+            let (compact, ids): (CompactGraph, Vec<&str>) = g.compact();
+            for src in &ids {
+                let tree: ShortestTree = CompactDijkstraSSSP.shortest_tree(&compact, &ids, src);
+                let reference: ShortestTree = DijkstraSSSP::new().shortest_tree(&g, src);
+                assert_eq!(tree.len(), reference.len());
+                for (node, (cost, prev)) in &tree {
+                    assert!((cost - reference[node].0).abs() < 1e-9, "{src} -> {node}: {cost} vs {}", reference[node].0);
+                    // The predecessor may differ for equally short paths, but it must be on one
+                    if let Some(prev) = prev {
+                        assert!(g.neighbours(prev).any(|(n, e)| n == *node && (tree[prev].0 + e.cost - cost).abs() < 1e-9));
+                    }
+                }
+            }
+        }
+
+        // Unreachable nodes stay that way
+        let mut g: Graph = load_graph("cities");
+        g.edges.remove("Chicago-Dorchester");
+        let (compact, ids): (CompactGraph, Vec<&str>) = g.compact();
+        assert_eq!(CompactDijkstraSSSP.shortest_tree(&compact, &ids, "Berlin")["Chicago"], (f64::INFINITY, None));
+    }
+
+    #[test]
+    fn test_compact_sssp_queues() {
+        for mut g in [load_graph("cities"), load_bench("india35"), load_bench("abilene")] {
+            // NOTE: Round the costs for the bucket queue
+            for edge in g.edges.values_mut() {
+                edge.cost = edge.cost.round();
+            }
+            let (compact, _): (CompactGraph, Vec<&str>) = g.compact();
+            for src in 0..compact.node_count() {
+                let tree: Vec<(f64, Option<usize>)> = CompactDijkstraSSSP.shortest_all_with::<BinaryHeapQueue>(&compact, src);
+                assert_eq!(CompactDijkstraSSSP.shortest_all_with::<BucketQueue>(&compact, src), tree);
+            }
+        }
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Implements Dijkstra's SSSP algorithm \[2\] over a [`CompactGraph`].
+///
+/// This computes the same as [`DijkstraSSSP`](super::dijkstra::DijkstraSSSP), but works on the contiguous neighbours of the
+/// [`CompactGraph`] instead of looking up nodes and edges by identifier. Algorithms that compute many trees over the same graph can convert it
+/// once with [`Graph::compact()`](ksp_graph::Graph::compact()) to opt into this.
+///
+/// See the [`dijkstra`](super::dijkstra) module for the reference.
+#[derive(Clone, Copy, Debug)]
+pub struct CompactDijkstraSSSP;
+impl CompactDijkstraSSSP {
+    /// Computes the shortest paths from one node to all others.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`CompactGraph`] to find in.
+    /// - `src`: The index of the source node to find paths from.
+    ///
+    /// # Returns
+    /// For every node (by index), its distance from `src` and its predecessor on the shortest path to it. `src` itself has a distance of
+    /// `0.0` and no predecessor, whereas nodes that aren't reachable have a distance of [`f64::INFINITY`] and no predecessor.
+    ///
+    /// # Panics
+    /// This function panics if `src` is out-of-bounds for the given `graph`.
+    #[inline]
+    #[track_caller]
+    pub fn shortest_all(&self, graph: &CompactGraph, src: usize) -> Vec<(f64, Option<usize>)> {
+        self.shortest_all_with::<BinaryHeapQueue>(graph, src)
+    }
+
+    /// Computes the shortest paths from one node to all others, using a particular [`PriorityQueue`] to find the next node.
+    ///
+    /// All queues give the same result, so this is only useful to compare their performance, or to use one suited for a particular graph
+    /// (e.g., a [`BucketQueue`](super::queue::BucketQueue) if all costs are small integers).
+    ///
+    /// # Generics
+    /// - `Q`: The [`PriorityQueue`] to use.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`CompactGraph`] to find in.
+    /// - `src`: The index of the source node to find paths from.
+    ///
+    /// # Returns
+    /// For every node (by index), its distance from `src` and its predecessor on the shortest path to it. See
+    /// [`CompactDijkstraSSSP::shortest_all()`].
+    ///
+    /// # Panics
+    /// This function panics if `src` is out-of-bounds for the given `graph`, or if the queue does (e.g., because it doesn't support the
+    /// graph's costs).
+    #[track_caller]
+    pub fn shortest_all_with<Q: PriorityQueue>(&self, graph: &CompactGraph, src: usize) -> Vec<(f64, Option<usize>)> {
+        let mut tree: Vec<(f64, Option<usize>)> = vec![(f64::INFINITY, None); graph.node_count()];
+        let mut visited: Vec<bool> = vec![false; graph.node_count()];
+        tree[src] = (0.0, None);
+
+        let mut todo: Q = Q::default();
+        todo.push(src, 0.0);
+        while let Some((node, cost)) = todo.pop_min() {
+            visited[node] = true;
+
+            // Update all distances, but only iff shorter
+            for (neigh, weight) in graph.neighbours(node) {
+                let new_dist: f64 = cost + weight;
+                debug_assert!(!new_dist.is_nan(), "Accumulated cost to node {neigh} is NaN");
+                if !visited[neigh] && new_dist < tree[neigh].0 {
+                    tree[neigh] = (new_dist, Some(node));
+                    todo.decrease_key(neigh, new_dist);
+                }
+            }
+        }
+        tree
+    }
+
+    /// Computes the shortest paths from one node to all others as a [`ShortestTree`].
+    ///
+    /// # Arguments
+    /// - `graph`: The [`CompactGraph`] to find in.
+    /// - `ids`: The identifiers of the nodes in `graph`, as returned by [`Graph::compact()`](ksp_graph::Graph::compact()).
+    /// - `src`: The identifier of the source node to find paths from.
+    ///
+    /// # Returns
+    /// A [`ShortestTree`] rooted at `src`, which borrows the identifiers from `ids`.
+    ///
+    /// # Panics
+    /// This function panics if `src` is not in `ids`.
+    #[track_caller]
+    pub fn shortest_tree<'g>(&self, graph: &CompactGraph, ids: &[&'g str], src: &str) -> ShortestTree<'g> {
+        let src: usize = match ids.binary_search(&src) {
+            Ok(src) => src,
+            Err(_) => panic!("Unknown source node '{src}'"),
+        };
+        self.shortest_all(graph, src).into_iter().enumerate().map(|(i, (cost, prev))| (ids[i], (cost, prev.map(|p| ids[p])))).collect()
+    }
+}
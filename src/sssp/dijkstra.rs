@@ -4,7 +4,7 @@
 //  Created:
 //    24 Jul 2024, 00:43:39
 //  Last edited:
-//    24 Jul 2024, 20:41:16
+//    16 Oct 2026, 19:00:47
 //  Auto updated?
 //    Yes
 //
@@ -12,11 +12,13 @@
 //!   Implements Dijkstra's SSSP algorithm.
 //
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
 
-use ksp_graph::Graph;
+use ksp_graph::{Edge, Graph, Node};
 
-use super::SingleShortestPath;
+use super::queue::{BinaryHeapQueue, PriorityQueue};
+use super::{ShortestPathTree, SingleShortestPath};
 use crate::path::Path;
 
 
@@ -24,18 +26,190 @@ use crate::path::Path;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ksp::yen::YenKSP;
+    use crate::ksp::KShortestPath;
     use crate::path;
-    use crate::utils::load_graph;
+    use crate::sssp::queue::BucketQueue;
+    use crate::utils::{load_bench, load_graph};
 
     #[test]
     fn test_sssp() {
         // Run it quite some times to catch hashmap problems
         for _ in 0..10 {
             let g: Graph = load_graph("cities");
-            assert_eq!(DijkstraSSSP.shortest(&g, "Amsterdam", "Berlin"), path!(crate : g, "Amsterdam" -| "Berlin"));
-            assert_eq!(DijkstraSSSP.shortest(&g, "Amsterdam", "Dorchester"), path!(crate : g, "Amsterdam" -| "Dorchester"));
-            assert_eq!(DijkstraSSSP.shortest(&g, "Amsterdam", "Chicago"), path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago"));
-            assert_eq!(DijkstraSSSP.shortest(&g, "Berlin", "Chicago"), path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago"));
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "Amsterdam", "Berlin"), path!(crate : g, "Amsterdam" -| "Berlin"));
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "Amsterdam", "Dorchester"), path!(crate : g, "Amsterdam" -| "Dorchester"));
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "Amsterdam", "Chicago"), path!(crate : g, "Amsterdam" -> "Dorchester" -| "Chicago"));
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "Berlin", "Chicago"), path!(crate : g, "Berlin" -> "Amsterdam" -> "Dorchester" -| "Chicago"));
+        }
+    }
+
+    #[test]
+    fn test_sssp_directed() {
+        for _ in 0..10 {
+            // Edges only go one way, so going back means going round
+            let g: Graph = load_graph("directed");
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "A", "B"), path!(crate : g, "A" -| "B"));
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "B", "A"), path!(crate : g, "B" -> "C" -| "A"));
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "A", "C"), path!(crate : g, "A" -> "B" -| "C"));
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "C", "B"), path!(crate : g, "C" -| "B"));
+            assert_eq!(DijkstraSSSP::new().shortest_tree(&g, "B")["A"], (2.0, Some("C")));
+        }
+    }
+
+    #[test]
+    fn test_sssp_weighted() {
+        for _ in 0..10 {
+            let g: Graph = load_graph("cities");
+
+            // Routing on hop count finds the same route if there is only one, but reports hops instead of distance
+            let by_cost: Path = DijkstraSSSP::new().shortest_weighted(&g, "Berlin", "Chicago", |e| e.cost);
+            let by_hops: Path = DijkstraSSSP::new().shortest_weighted(&g, "Berlin", "Chicago", |_| 1.0);
+            assert_eq!(by_cost, by_hops);
+            assert_eq!(by_cost.cost(), 577.34 + 540.86 + 6249.15);
+            assert_eq!(by_hops.cost(), 3.0);
+
+            // If the cheapest route isn't the one with the fewest hops, the metrics pick different ones
+            let d: Graph = load_graph("detour");
+            let by_cost: Path = DijkstraSSSP::new().shortest_weighted(&d, "A", "E", |e| e.cost);
+            let by_hops: Path = DijkstraSSSP::new().shortest_weighted(&d, "A", "E", |_| 1.0);
+            assert_eq!(by_cost, path!(crate : d, "A" -> "B" -> "C" -| "E"));
+            assert_eq!(by_cost.cost(), 3.0);
+            assert_eq!(by_hops, path!(crate : d, "A" -> "D" -| "E"));
+            assert_eq!(by_hops.cost(), 2.0);
+
+            // Penalizing the direct link makes the route go around it
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "Amsterdam", "Dorchester"), path!(crate : g, "Amsterdam" -| "Dorchester"));
+            let detour = |e: &Edge| if e.id.as_str() == "Amsterdam-Dorchester" { 10000.0 } else { e.cost };
+            assert_eq!(
+                DijkstraSSSP::new().shortest_weighted(&g, "Amsterdam", "Dorchester", detour),
+                path!(crate : g, "Amsterdam" -> "Edinburgh" -| "Dorchester")
+            );
+        }
+    }
+
+    #[test]
+    fn test_sssp_node_weighted() {
+        for _ in 0..10 {
+            // The cheapest route from A to C is through B...
+            let g: Graph = load_graph("asymmetric");
+            assert_eq!(DijkstraSSSP::new().shortest(&g, "A", "C"), path!(crate : g, "A" -> "B" -| "C"));
+            assert_eq!(DijkstraSSSP::new().shortest_node_weighted(&g, "A", "C", |e| e.cost, |_| 0.0).cost(), 3.0);
+
+            // ...until passing through B becomes expensive
+            let congested = |n: &Node| if n.id.as_str() == "B" { 10.0 } else { 0.0 };
+            let path: Path = DijkstraSSSP::new().shortest_node_weighted(&g, "A", "C", |e| e.cost, congested);
+            assert_eq!(path, path!(crate : g, "A" -| "C"));
+            assert_eq!(path.cost(), 5.0);
+
+            // The source is free, but the destination is not
+            assert_eq!(DijkstraSSSP::new().shortest_node_weighted(&g, "B", "C", |e| e.cost, congested).cost(), 2.0);
+            assert_eq!(DijkstraSSSP::new().shortest_node_weighted(&g, "C", "B", |e| e.cost, congested).cost(), 12.0);
+            let closed = |n: &Node| if n.id.as_str() == "B" { f64::INFINITY } else { 0.0 };
+            assert_eq!(DijkstraSSSP::new().try_shortest_node_weighted(&g, "A", "B", |e| e.cost, closed), None);
+        }
+    }
+
+    #[test]
+    fn test_sssp_tree() {
+        let g: Graph = load_graph("cities");
+        for src in ["Amsterdam", "Chicago", "Edinburgh"] {
+            let tree: ShortestTree = DijkstraSSSP::new().shortest_tree(&g, src);
+            assert_eq!(tree.len(), g.nodes.len());
+            assert_eq!(tree[src], (0.0, None));
+            for dst in g.nodes.keys() {
+                assert_eq!(tree_path(&tree, dst), Some(DijkstraSSSP::new().shortest(&g, src, dst)));
+            }
+        }
+
+        // Unreachable nodes have no path
+        let tree: ShortestTree =
+            DijkstraSSSP::new().shortest_tree_weighted(&g, "Berlin", |e| if e.id.as_str() == "Chicago-Dorchester" { f64::INFINITY } else { e.cost });
+        assert_eq!(tree["Chicago"], (f64::INFINITY, None));
+        assert_eq!(tree_path(&tree, "Chicago"), None);
+        assert_eq!(tree_path(&tree, "Dorchester"), Some(path!(crate : g, "Berlin" -> "Amsterdam" -| "Dorchester")));
+    }
+
+    #[test]
+    fn test_sssp_metric_closure() {
+        // The closure's edges are the shortest distances
+        let g: Graph = load_graph("cities");
+        let closure: Graph = g.metric_closure();
+        assert_eq!(closure.edge_count(), 10);
+        for (src, dst) in [("Berlin", "Chicago"), ("Edinburgh", "Berlin"), ("Amsterdam", "Dorchester")] {
+            let tree: ShortestTree = DijkstraSSSP::new().shortest_tree(&g, src);
+            let cost: f64 = closure.neighbours(src).find(|(n, _)| *n == dst).map(|(_, e)| e.cost).unwrap();
+            assert!((cost - tree[dst].0).abs() < 1e-9, "{src} -> {dst}: {cost} vs {}", tree[dst].0);
+        }
+        // So the shortest path in it is always direct
+        assert_eq!(DijkstraSSSP::new().shortest(&closure, "Berlin", "Chicago").hops.len(), 2);
+    }
+
+    #[test]
+    fn test_sssp_some() {
+        for name in ["cities", "india35"] {
+            let g: Graph = if name == "cities" { load_graph(name) } else { load_bench(name) };
+            let mut nodes: Vec<&str> = g.nodes.keys().map(|id| id.as_str()).collect();
+            nodes.sort();
+            let src: &str = nodes[0];
+            let tree: ShortestTree = DijkstraSSSP::new().shortest_tree(&g, src);
+
+            // Try a few subsets, including the empty one
+            for targets in [&[][..], &nodes[..1], &nodes[1..3], &nodes[nodes.len() / 2..], &nodes[..]] {
+                let targets: HashSet<&str> = targets.iter().copied().collect();
+                let dists: HashMap<&str, f64> = DijkstraSSSP::new().shortest_some(&g, src, &targets);
+                assert_eq!(dists.len(), targets.len());
+                for (node, dist) in dists {
+                    assert!(targets.contains(node));
+                    assert_eq!(dist, tree[node].0, "Disagreement with tree for {src} -> {node} in {name}");
+                }
+            }
+        }
+
+        // Unreachable and unknown targets work too
+        let g: Graph = load_graph("cities");
+        let targets: HashSet<&str> = HashSet::from(["Chicago", "Dorchester", "Paris"]);
+        let blocked = |e: &Edge| if e.id.as_str() == "Chicago-Dorchester" { f64::INFINITY } else { e.cost };
+        let dists: HashMap<&str, f64> = DijkstraSSSP::new().shortest_some_weighted(&g, "Berlin", &targets, blocked);
+        assert_eq!(dists, HashMap::from([("Chicago", f64::INFINITY), ("Dorchester", 577.34 + 540.86)]));
+    }
+
+    #[test]
+    fn test_sssp_unreachable() {
+        let g: Graph = load_graph("cities");
+        assert_eq!(
+            DijkstraSSSP::new().try_shortest_weighted(&g, "Berlin", "Chicago", |e| e.cost),
+            Some(DijkstraSSSP::new().shortest(&g, "Berlin", "Chicago"))
+        );
+
+        // Edges with infinite weight are considered absent
+        let blocked = |e: &Edge| if e.id.as_str() == "Chicago-Dorchester" { f64::INFINITY } else { e.cost };
+        assert_eq!(DijkstraSSSP::new().try_shortest_weighted(&g, "Berlin", "Chicago", blocked), None);
+        let blocked = |e: &Edge| if e.id.as_str() == "Amsterdam-Dorchester" { f64::INFINITY } else { e.cost };
+        assert_eq!(
+            DijkstraSSSP::new().try_shortest_weighted(&g, "Amsterdam", "Dorchester", blocked),
+            Some(path!(crate : g, "Amsterdam" -> "Edinburgh" -| "Dorchester"))
+        );
+    }
+
+    #[test]
+    fn test_sssp_queues() {
+        for mut g in [load_graph("cities"), load_bench("india35"), load_bench("abilene")] {
+            // NOTE: Round the costs for the bucket queue
+            for edge in g.edges.values_mut() {
+                edge.cost = edge.cost.round();
+            }
+            let mut ids: Vec<&str> = g.nodes.keys().map(|id| id.as_str()).collect();
+            ids.sort();
+            for src in &ids {
+                let tree: ShortestTree = DijkstraSSSP::<BinaryHeapQueue>::default().shortest_tree(&g, src);
+                assert_eq!(DijkstraSSSP::<BucketQueue>::default().shortest_tree(&g, src), tree);
+            }
+
+            // The choice carries over to algorithms using it
+            let (src, dst): (&str, &str) = (ids[0], ids[ids.len() - 1]);
+            let paths: Vec<Path> = YenKSP::new(DijkstraSSSP::new()).k_shortest_paths(&g, src, dst, 5);
+            assert_eq!(YenKSP::new(DijkstraSSSP::<BucketQueue>::default()).k_shortest_paths(&g, src, dst, 5), paths);
         }
     }
 }
@@ -45,87 +219,242 @@ mod tests {
 
 
 /***** LIBRARY *****/
+/// A shortest-path tree, mapping every node to its distance from the root and its predecessor on the shortest path to it.
+///
+/// The root itself has a distance of `0.0` and no predecessor, whereas nodes that aren't reachable have a distance of [`f64::INFINITY`] and no predecessor.
+pub type ShortestTree<'g> = HashMap<&'g str, (f64, Option<&'g str>)>;
+
+/// Reconstructs the path to a particular node from a [`ShortestTree`].
+///
+/// # Arguments
+/// - `tree`: The [`ShortestTree`] to walk.
+/// - `dst`: The destination node to find the path to.
+///
+/// # Returns
+/// The shortest path from the tree's root to `dst`, or [`None`] if `dst` isn't reachable from it.
+///
+/// # Panics
+/// This function panics if `dst` is not in the tree.
+#[track_caller]
+pub fn tree_path<'g>(tree: &ShortestTree<'g>, dst: &str) -> Option<Path<'g>> {
+    let (dst, (cost, mut prev)): (&&'g str, &(f64, Option<&'g str>)) = match tree.get_key_value(dst) {
+        Some(entry) => entry,
+        None => panic!("Unknown destination node '{dst}'"),
+    };
+    if *cost == f64::INFINITY {
+        return None;
+    }
+
+    // Walk the predecessors back to the root
+    let mut hops: Vec<(&'g str, f64)> = vec![(dst, *cost)];
+    while let Some(node) = prev {
+        let (cost, next): (f64, Option<&'g str>) = tree[node];
+        hops.push((node, cost));
+        prev = next;
+    }
+    hops.reverse();
+    Some(Path { hops })
+}
+
+
+
 /// Defines the SSSP (Single-Source Shortest Path) used in Yen's algorithm.
 ///
-/// In particular, we implement Dijkstra's SSSP Algorithm \[2\], a.k.a., A*.
+/// In particular, we implement Dijkstra's SSSP Algorithm \[2\], a.k.a., A*. The next node to visit is found with a [`PriorityQueue`], which
+/// can be swapped to compare implementations. Nodes at the same distance are visited in order of identifier, so all of them give the same
+/// result.
+///
+/// # Generics
+/// - `Q`: The [`PriorityQueue`] to use. Defaults to a [`BinaryHeapQueue`].
 ///
 /// # References
 /// \[2\] Dijkstra, E.W. A note on two problems in connexion with graphs.
 /// _Numer. Math._ 1, 269–271 (1959). https://doi.org/10.1007/BF01386390.
-pub struct DijkstraSSSP;
-impl SingleShortestPath for DijkstraSSSP {
-    #[track_caller]
-    fn shortest<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str) -> Path<'g> {
-        // Do a depth-first search with the shortest path heuristic
-        let mut distances: HashMap<&'g str, (f64, bool)> =
-            graph.nodes.keys().map(|id| (id.as_str(), if id.as_str() == src { (0.0, false) } else { (f64::INFINITY, false) })).collect();
+pub struct DijkstraSSSP<Q = BinaryHeapQueue> {
+    /// The queue used, which is only created while running.
+    queue: PhantomData<fn() -> Q>,
+}
+impl DijkstraSSSP {
+    /// Constructor for the DijkstraSSSP.
+    ///
+    /// Use [`DijkstraSSSP::default()`] with another `Q` to use another queue.
+    ///
+    /// # Returns
+    /// A new DijkstraSSSP that uses a [`BinaryHeapQueue`] to find the next node.
+    #[inline]
+    pub const fn new() -> Self { Self { queue: PhantomData } }
+}
+impl<Q> Default for DijkstraSSSP<Q> {
+    #[inline]
+    fn default() -> Self { Self { queue: PhantomData } }
+}
+impl<Q: PriorityQueue> DijkstraSSSP<Q> {
+    /// Runs Dijkstra's algorithm from a node to build the tree of shortest paths to it.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find paths from.
+    /// - `done`: Called with every node as soon as the shortest path to it is known. If it returns true, the search is stopped and other nodes
+    ///   may have incomplete information.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`]. Edges for which it returns [`f64::INFINITY`] are considered to be absent.
+    /// - `node_weight`: A function computing the cost of entering a particular [`Node`]. Nodes for which it returns [`f64::INFINITY`] are considered to be absent.
+    ///
+    /// # Returns
+    /// A [`ShortestTree`] rooted at `src`.
+    #[inline]
+    fn run<'g>(
+        graph: &'g Graph,
+        src: &str,
+        mut done: impl FnMut(&str) -> bool,
+        weight: impl Fn(&Edge) -> f64,
+        node_weight: impl Fn(&Node) -> f64,
+    ) -> ShortestTree<'g> {
+        // Index the nodes, such that they can be queued
+        // NOTE: Sorted, such that ties are popped in the same order every run
+        let mut ids: Vec<&'g str> = graph.nodes.keys().map(|id| id.as_str()).collect();
+        ids.sort();
+        let index: HashMap<&'g str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut tree: Vec<(f64, Option<usize>)> = vec![(f64::INFINITY, None); ids.len()];
+        let mut visited: Vec<bool> = vec![false; ids.len()];
 
         // Loop to populate the distances
-        loop {
-            // Find the node to treat
-            let mut next: Option<(&'g str, f64)> = None;
-            for (node, (distance, visited)) in &distances {
-                if !visited && *distance < next.map(|(_, d)| d).unwrap_or(f64::INFINITY) {
-                    next = Some((node, *distance));
-                }
-            }
-            let (next, cost): (&'g str, f64) = match next {
-                Some(next) => next,
-                None => break,
-            };
-            if next == dst {
+        let mut todo: Q = Q::default();
+        if let Some(src) = index.get(src) {
+            tree[*src].0 = 0.0;
+            todo.push(*src, 0.0);
+        }
+        while let Some((next, cost)) = todo.pop_min() {
+            if done(ids[next]) {
                 break;
             }
+            visited[next] = true;
 
             // Update all distances
-            for edge in graph.edges.values() {
-                // Get the neighbour of this node
-                let neigh: &str = if edge.left.as_str() == next && edge.right.as_str() != next {
-                    edge.right.as_str()
-                } else if edge.left.as_str() != next && edge.right.as_str() == next {
-                    edge.left.as_str()
-                } else {
-                    continue;
-                };
-
+            for (neigh, edge) in graph.successors(ids[next]) {
                 // Update its value, but only iff shorter
-                let neigh_dist: &mut f64 = &mut distances.get_mut(neigh).unwrap().0;
-                if cost + edge.cost < *neigh_dist {
-                    *neigh_dist = cost + edge.cost;
+                let neigh_idx: usize = index[neigh];
+                let new_dist: f64 = cost + weight(edge) + node_weight(&graph.nodes[neigh]);
+                // NOTE: Infinite costs are fine (they mean "absent"), but NaN makes everything compare equal
+                debug_assert!(!new_dist.is_nan(), "Accumulated cost to node '{neigh}' is NaN");
+                if !visited[neigh_idx] && new_dist < tree[neigh_idx].0 {
+                    tree[neigh_idx] = (new_dist, Some(next));
+                    todo.decrease_key(neigh_idx, new_dist);
                 }
             }
+        }
+        ids.iter().zip(tree).map(|(id, (cost, prev))| (*id, (cost, prev.map(|p| ids[p])))).collect()
+    }
+
+    /// Computes the shortest paths from one node to all others.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find paths from.
+    ///
+    /// # Returns
+    /// A [`ShortestTree`] rooted at `src`. Use [`tree_path()`] to find the path to a particular node in it.
+    ///
+    /// # Panics
+    /// This function panics if `src` is not in the given `graph`.
+    #[inline]
+    #[track_caller]
+    pub fn shortest_tree<'g>(&self, graph: &'g Graph, src: &str) -> ShortestTree<'g> { self.shortest_tree_weighted(graph, src, |e: &Edge| e.cost) }
 
-            // Mark this node as visited
-            distances.get_mut(next).unwrap().1 = true;
+    /// Computes the shortest paths from one node to all others, using a custom metric for edges.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find paths from.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`]. Edges for which it returns [`f64::INFINITY`] are considered to be absent.
+    ///
+    /// # Returns
+    /// A [`ShortestTree`] rooted at `src`. Use [`tree_path()`] to find the path to a particular node in it.
+    ///
+    /// # Panics
+    /// This function panics if `src` is not in the given `graph`.
+    #[track_caller]
+    pub fn shortest_tree_weighted<'g>(&self, graph: &'g Graph, src: &str, weight: impl Fn(&Edge) -> f64) -> ShortestTree<'g> {
+        if !graph.nodes.contains_key(src) {
+            panic!("Unknown source node '{src}'");
         }
+        Self::run(graph, src, |_| false, weight, |_: &Node| 0.0)
+    }
 
-        // To find the path, now walk it backwards
-        let dst_dist: (&&'g str, &(f64, bool)) = distances.get_key_value(dst).unwrap();
-        let mut path: Path<'g> = Path { hops: vec![(dst_dist.0, dst_dist.1.0)] };
-        while path.hops[0].0 != src {
-            // Get the next edge leading to the smallest distance
-            let mut nearest: Option<(&'g str, f64)> = None;
-            for edge in graph.edges.values() {
-                // Get the neighbour of this node
-                let neigh: &str = if edge.left.as_str() == path.hops[0].0 && edge.right.as_str() != path.hops[0].0 {
-                    edge.right.as_str()
-                } else if edge.left.as_str() != path.hops[0].0 && edge.right.as_str() == path.hops[0].0 {
-                    edge.left.as_str()
-                } else {
-                    continue;
-                };
-
-                // Store it only if the smallest
-                let dist: f64 = distances.get(neigh).unwrap().0;
-                if dist < nearest.map(|(_, d)| d).unwrap_or(f64::INFINITY) {
-                    nearest = Some((neigh, dist));
-                }
-            }
-            match nearest {
-                Some((node, cost)) => path.hops.insert(0, (node, cost)),
-                None => panic!("Source '{src}' and destination '{dst}' nodes are not connected"),
-            }
+    /// Computes the distances from one node to a few others, stopping as soon as those are known.
+    ///
+    /// This is cheaper than [`DijkstraSSSP::shortest_tree()`] if the targets are close to `src` in a large graph.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find distances from.
+    /// - `targets`: The nodes to find the distances to. Any that are not in `graph` are ignored.
+    ///
+    /// # Returns
+    /// A map of every (known) target to its distance from `src`, which is [`f64::INFINITY`] if it isn't reachable.
+    ///
+    /// # Panics
+    /// This function panics if `src` is not in the given `graph`.
+    #[inline]
+    #[track_caller]
+    pub fn shortest_some<'g>(&self, graph: &'g Graph, src: &str, targets: &HashSet<&str>) -> HashMap<&'g str, f64> {
+        self.shortest_some_weighted(graph, src, targets, |e: &Edge| e.cost)
+    }
+
+    /// Computes the distances from one node to a few others, stopping as soon as those are known, using a custom metric for edges.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find distances from.
+    /// - `targets`: The nodes to find the distances to. Any that are not in `graph` are ignored.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`]. Edges for which it returns [`f64::INFINITY`] are considered to be absent.
+    ///
+    /// # Returns
+    /// A map of every (known) target to its distance from `src`, which is [`f64::INFINITY`] if it isn't reachable.
+    ///
+    /// # Panics
+    /// This function panics if `src` is not in the given `graph`.
+    #[track_caller]
+    pub fn shortest_some_weighted<'g>(&self, graph: &'g Graph, src: &str, targets: &HashSet<&str>, weight: impl Fn(&Edge) -> f64) -> HashMap<&'g str, f64> {
+        if !graph.nodes.contains_key(src) {
+            panic!("Unknown source node '{src}'");
         }
-        path
+
+        // Stop once the last target has been settled
+        let mut left: usize = targets.iter().filter(|target| graph.nodes.contains_key(**target)).count();
+        let tree: ShortestTree<'g> = Self::run(
+            graph,
+            src,
+            |node: &str| {
+                if targets.contains(node) {
+                    left -= 1;
+                }
+                left == 0
+            },
+            weight,
+            |_: &Node| 0.0,
+        );
+        targets.iter().filter_map(|target| tree.get_key_value(*target).map(|(node, (dist, _))| (*node, *dist))).collect()
+    }
+}
+impl<Q: PriorityQueue> SingleShortestPath for DijkstraSSSP<Q> {
+    #[track_caller]
+    fn try_shortest_node_weighted<'g>(
+        &mut self,
+        graph: &'g Graph,
+        src: &str,
+        dst: &str,
+        weight: impl Fn(&Edge) -> f64,
+        node_weight: impl Fn(&Node) -> f64,
+    ) -> Option<Path<'g>> {
+        // Run the algorithm until we found the destination, then walk back
+        let tree: ShortestTree<'g> = Self::run(graph, src, |node: &str| node == dst, weight, node_weight);
+        tree_path(&tree, dst)
+    }
+}
+impl<Q: PriorityQueue> ShortestPathTree for DijkstraSSSP<Q> {
+    #[inline]
+    #[track_caller]
+    fn shortest_tree_weighted<'g>(&mut self, graph: &'g Graph, src: &str, weight: impl Fn(&Edge) -> f64) -> ShortestTree<'g> {
+        Self::shortest_tree_weighted(self, graph, src, weight)
     }
 }
@@ -4,7 +4,7 @@
 //  Created:
 //    24 Jul 2024, 00:41:28
 //  Last edited:
-//    24 Jul 2024, 20:54:18
+//    16 Oct 2026, 16:34:52
 //  Auto updated?
 //    Yes
 //
@@ -13,17 +13,21 @@
 //
 
 // Declarations
+pub mod alt;
+pub mod compact;
 pub mod dijkstra;
 pub mod profiled;
+pub mod queue;
 
 // Imports
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FResult};
 use std::str::FromStr;
 
-use ksp_graph::Graph;
+use ksp_graph::{Edge, Graph, Node};
 
 use crate::path::Path;
+pub use dijkstra::ShortestTree;
 
 
 /***** ERRORS *****/
@@ -57,6 +61,17 @@ impl Sssp {
     /// A static list of the implemented SSSP algorithms.
     #[inline]
     pub const fn all() -> &'static [Self] { &[Self::Dijkstra] }
+
+    /// Returns the identifier by which this SSSP algorithm is parsed.
+    ///
+    /// # Returns
+    /// A static string that [`Sssp::from_str()`] parses back to this algorithm.
+    #[inline]
+    pub const fn id(&self) -> &'static str {
+        match self {
+            Self::Dijkstra => "dijkstra",
+        }
+    }
 }
 impl FromStr for Sssp {
     type Err = UnknownSsspError;
@@ -89,11 +104,149 @@ pub trait SingleShortestPath {
     ///
     /// # Panics
     /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph` or they are not connected.
-    fn shortest<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str) -> Path<'g>;
+    #[inline]
+    #[track_caller]
+    fn shortest<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str) -> Path<'g> { self.shortest_weighted(graph, src, dst, |e: &Edge| e.cost) }
+
+    /// Finds the shortest paths from one node to another, using a custom metric for edges.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`]. [`SingleShortestPath::shortest()`] uses `|e| e.cost`.
+    ///
+    /// # Returns
+    /// The shortest paths found. Its costs are those computed by `weight`.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph` or they are not connected.
+    #[inline]
+    #[track_caller]
+    fn shortest_weighted<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, weight: impl Fn(&Edge) -> f64) -> Path<'g> {
+        match self.try_shortest_weighted(graph, src, dst, weight) {
+            Some(path) => path,
+            None => panic!("Source '{src}' and destination '{dst}' nodes are not connected"),
+        }
+    }
+
+    /// Finds the shortest paths from one node to another, using a custom metric for edges, without panicking if there is none.
+    ///
+    /// Edges for which `weight` returns [`f64::INFINITY`] are considered to be absent.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`].
+    ///
+    /// # Returns
+    /// The shortest paths found, or [`None`] if `src` and `dst` are not connected.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
+    #[inline]
+    fn try_shortest_weighted<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str, weight: impl Fn(&Edge) -> f64) -> Option<Path<'g>> {
+        self.try_shortest_node_weighted(graph, src, dst, weight, |_: &Node| 0.0)
+    }
+
+    /// Finds the shortest paths from one node to another, using a custom metric for both edges and nodes.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`].
+    /// - `node_weight`: A function computing the cost of entering a particular [`Node`]. [`SingleShortestPath::shortest_weighted()`] uses
+    ///   `|_| 0.0`.
+    ///
+    /// # Returns
+    /// The shortest paths found. Its costs are those computed by `weight` and `node_weight`, where the latter is charged for every node but the
+    /// source.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph` or they are not connected.
+    #[inline]
+    #[track_caller]
+    fn shortest_node_weighted<'g>(
+        &mut self,
+        graph: &'g Graph,
+        src: &str,
+        dst: &str,
+        weight: impl Fn(&Edge) -> f64,
+        node_weight: impl Fn(&Node) -> f64,
+    ) -> Path<'g> {
+        match self.try_shortest_node_weighted(graph, src, dst, weight, node_weight) {
+            Some(path) => path,
+            None => panic!("Source '{src}' and destination '{dst}' nodes are not connected"),
+        }
+    }
+
+    /// Finds the shortest paths from one node to another, using a custom metric for both edges and nodes, without panicking if there is
+    /// none.
+    ///
+    /// Edges for which `weight` returns [`f64::INFINITY`] and nodes for which `node_weight` does are considered to be absent.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find a path from.
+    /// - `dst`: The destination node to find a path to.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`].
+    /// - `node_weight`: A function computing the cost of entering a particular [`Node`]. It is not charged for `src`.
+    ///
+    /// # Returns
+    /// The shortest paths found, or [`None`] if `src` and `dst` are not connected.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` or `dst` are not in the given `graph`.
+    fn try_shortest_node_weighted<'g>(
+        &mut self,
+        graph: &'g Graph,
+        src: &str,
+        dst: &str,
+        weight: impl Fn(&Edge) -> f64,
+        node_weight: impl Fn(&Node) -> f64,
+    ) -> Option<Path<'g>>;
 }
 
 // Pointer-like impls
-impl<'a, T: SingleShortestPath> SingleShortestPath for &'a mut T {
+impl<T: SingleShortestPath> SingleShortestPath for &mut T {
     #[inline]
-    fn shortest<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str) -> Path<'g> { <T as SingleShortestPath>::shortest(self, graph, src, dst) }
+    fn try_shortest_node_weighted<'g>(
+        &mut self,
+        graph: &'g Graph,
+        src: &str,
+        dst: &str,
+        weight: impl Fn(&Edge) -> f64,
+        node_weight: impl Fn(&Node) -> f64,
+    ) -> Option<Path<'g>> {
+        <T as SingleShortestPath>::try_shortest_node_weighted(self, graph, src, dst, weight, node_weight)
+    }
+}
+
+
+
+/// Defines SSSP algorithms that can compute the shortest paths from one node to all others at once.
+pub trait ShortestPathTree: SingleShortestPath {
+    /// Computes the shortest paths from one node to all others, using a custom metric for edges.
+    ///
+    /// # Arguments
+    /// - `graph`: The [`Graph`] to find in.
+    /// - `src`: The source node to find paths from.
+    /// - `weight`: A function computing the cost of traversing a particular [`Edge`]. Edges for which it returns [`f64::INFINITY`] are considered to be absent.
+    ///
+    /// # Returns
+    /// A [`ShortestTree`] rooted at `src`.
+    ///
+    /// # Panics
+    /// This function is allowed to panic if the given `src` is not in the given `graph`.
+    fn shortest_tree_weighted<'g>(&mut self, graph: &'g Graph, src: &str, weight: impl Fn(&Edge) -> f64) -> ShortestTree<'g>;
+}
+
+// Pointer-like impls
+impl<T: ShortestPathTree> ShortestPathTree for &mut T {
+    #[inline]
+    fn shortest_tree_weighted<'g>(&mut self, graph: &'g Graph, src: &str, weight: impl Fn(&Edge) -> f64) -> ShortestTree<'g> {
+        <T as ShortestPathTree>::shortest_tree_weighted(self, graph, src, weight)
+    }
 }
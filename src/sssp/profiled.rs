@@ -4,7 +4,7 @@
 //  Created:
 //    24 Jul 2024, 20:41:44
 //  Last edited:
-//    24 Jul 2024, 20:53:31
+//    16 Oct 2026, 17:48:28
 //  Auto updated?
 //    Yes
 //
@@ -15,9 +15,9 @@
 
 use std::time::{Duration, Instant};
 
-use ksp_graph::Graph;
+use ksp_graph::{Edge, Graph, Node};
 
-use super::SingleShortestPath;
+use super::{ShortestPathTree, ShortestTree, SingleShortestPath};
 use crate::path::Path;
 
 
@@ -42,11 +42,21 @@ impl<S> ProfilingSSSP<S> {
 }
 impl<S: SingleShortestPath> SingleShortestPath for ProfilingSSSP<S> {
     #[track_caller]
-    fn shortest<'g>(&mut self, graph: &'g Graph, src: &str, dst: &str) -> Path<'g> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self, graph, weight, node_weight), fields(found)))]
+    fn try_shortest_node_weighted<'g>(
+        &mut self,
+        graph: &'g Graph,
+        src: &str,
+        dst: &str,
+        weight: impl Fn(&Edge) -> f64,
+        node_weight: impl Fn(&Node) -> f64,
+    ) -> Option<Path<'g>> {
         // Record the run
         let start: Instant = Instant::now();
-        let path: Path<'g> = self.sssp.shortest(graph, src, dst);
+        let path: Option<Path<'g>> = self.sssp.try_shortest_node_weighted(graph, src, dst, weight, node_weight);
         let time: Duration = start.elapsed();
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("found", path.is_some());
 
         // Store it internally before completing
         if self.timings.len() >= self.timings.capacity() {
@@ -56,3 +66,20 @@ impl<S: SingleShortestPath> SingleShortestPath for ProfilingSSSP<S> {
         path
     }
 }
+impl<S: ShortestPathTree> ShortestPathTree for ProfilingSSSP<S> {
+    #[track_caller]
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self, graph, weight)))]
+    fn shortest_tree_weighted<'g>(&mut self, graph: &'g Graph, src: &str, weight: impl Fn(&Edge) -> f64) -> ShortestTree<'g> {
+        // Record the run
+        let start: Instant = Instant::now();
+        let tree: ShortestTree<'g> = self.sssp.shortest_tree_weighted(graph, src, weight);
+        let time: Duration = start.elapsed();
+
+        // Store it internally before completing
+        if self.timings.len() >= self.timings.capacity() {
+            self.timings.reserve(self.timings.len());
+        }
+        self.timings.push(time);
+        tree
+    }
+}
@@ -0,0 +1,297 @@
+//  QUEUE.rs
+//    by Lut99
+//
+//  Created:
+//    16 Oct 2026, 16:27:52
+//  Last edited:
+//    16 Oct 2026, 18:18:17
+//  Auto updated?
+//    Yes
+//
+//  Description:
+//!   Defines the [`PriorityQueue`] used as frontier by Dijkstra-like
+//!   algorithms, such that its implementation can be swapped.
+//
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::cost::OrderedCost;
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pushes a fixed set of nodes and decreases some keys, then returns the order in which they are popped.
+    fn pop_all<Q: PriorityQueue>() -> Vec<(usize, f64)> {
+        let mut queue: Q = Q::default();
+        assert!(queue.is_empty());
+        for (node, priority) in [(0, 5.0), (1, 3.0), (2, 8.0), (3, 3.0), (4, 0.0)] {
+            queue.push(node, priority);
+        }
+        queue.decrease_key(2, 1.0);
+        queue.decrease_key(0, 4.0);
+        // NOTE: Increasing is ignored
+        queue.decrease_key(1, 7.0);
+
+        let mut popped: Vec<(usize, f64)> = Vec::new();
+        while let Some(entry) = queue.pop_min() {
+            popped.push(entry);
+            // Popped nodes may be pushed again
+            if entry == (4, 0.0) {
+                queue.push(4, 6.0);
+            }
+        }
+        assert!(queue.is_empty());
+        popped
+    }
+
+    #[test]
+    fn test_priority_queues() {
+        let expected: Vec<(usize, f64)> = vec![(4, 0.0), (2, 1.0), (1, 3.0), (3, 3.0), (0, 4.0), (4, 6.0)];
+        assert_eq!(pop_all::<BinaryHeapQueue>(), expected);
+        assert_eq!(pop_all::<BucketQueue>(), expected);
+    }
+
+    #[test]
+    fn test_binary_heap_queue_nan() {
+        let mut queue: BinaryHeapQueue = BinaryHeapQueue::default();
+        queue.push(0, f64::NAN);
+        queue.push(1, 2.0);
+        // NOTE: NaN is never an improvement
+        queue.decrease_key(1, f64::NAN);
+        queue.decrease_key(0, 1.0);
+        queue.push(2, f64::NAN);
+        assert_eq!(queue.pop_min(), Some((0, 1.0)));
+        assert_eq!(queue.pop_min(), Some((1, 2.0)));
+        assert!(matches!(queue.pop_min(), Some((2, p)) if p.is_nan()));
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop_min(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bucket_queue_fractional() { BucketQueue::default().push(0, 0.5); }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// A node in the [`BinaryHeapQueue`].
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    /// The priority of the node when it was queued.
+    priority: OrderedCost,
+    /// The node to pop.
+    node:     usize,
+}
+impl Eq for Entry {}
+impl Ord for Entry {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        // NOTE: Reversed, as the `BinaryHeap` is a max-heap
+        other.priority.cmp(&self.priority).then_with(|| other.node.cmp(&self.node))
+    }
+}
+impl PartialEq for Entry {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl PartialOrd for Entry {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+
+
+/// Keeps track of the current priority of every queued node, such that queues can lazily skip outdated entries.
+#[derive(Clone, Debug, Default)]
+struct Queued(Vec<Option<f64>>);
+impl Queued {
+    /// Returns the current priority of a node.
+    ///
+    /// # Arguments
+    /// - `node`: The node to get the priority of.
+    ///
+    /// # Returns
+    /// Its priority, or [`None`] if it isn't queued.
+    #[inline]
+    fn get(&self, node: usize) -> Option<f64> { self.0.get(node).copied().flatten() }
+
+    /// Sets the current priority of a node.
+    ///
+    /// # Arguments
+    /// - `node`: The node to set the priority of.
+    /// - `priority`: Its new priority, or [`None`] if it isn't queued anymore.
+    #[inline]
+    fn set(&mut self, node: usize, priority: Option<f64>) {
+        if node >= self.0.len() {
+            self.0.resize(node + 1, None);
+        }
+        self.0[node] = priority;
+    }
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines the frontier of Dijkstra-like algorithms, which pops nodes (by index) in order of priority.
+///
+/// Every node is queued at most once. Implementations are free to break ties as they like, but must do so deterministically.
+pub trait PriorityQueue: Default {
+    /// Queues a node.
+    ///
+    /// If the node is already queued, this behaves like [`PriorityQueue::decrease_key()`].
+    ///
+    /// # Arguments
+    /// - `node`: The node to queue.
+    /// - `priority`: The priority of the node. Lower ones are popped first.
+    fn push(&mut self, node: usize, priority: f64);
+
+    /// Pops the node with the lowest priority.
+    ///
+    /// # Returns
+    /// The popped node and its priority, or [`None`] if the queue is empty.
+    fn pop_min(&mut self) -> Option<(usize, f64)>;
+
+    /// Lowers the priority of a queued node.
+    ///
+    /// # Arguments
+    /// - `node`: The node to update. If it isn't queued, it is queued.
+    /// - `priority`: The new priority of the node. Ignored if it's not lower than its current one.
+    fn decrease_key(&mut self, node: usize, priority: f64);
+
+    /// Returns whether there are any nodes left to pop.
+    ///
+    /// # Returns
+    /// True if [`PriorityQueue::pop_min()`] would return [`None`].
+    fn is_empty(&self) -> bool;
+}
+
+
+
+/// A [`PriorityQueue`] backed by the standard library's [`BinaryHeap`].
+///
+/// Decreasing a key pushes a new entry, and the outdated ones are skipped when popped. Ties are popped in order of node index.
+#[derive(Clone, Debug, Default)]
+pub struct BinaryHeapQueue {
+    /// The (possibly outdated) entries.
+    heap:   BinaryHeap<Entry>,
+    /// The current priority of every queued node.
+    queued: Queued,
+    /// The number of nodes queued.
+    len:    usize,
+}
+impl PriorityQueue for BinaryHeapQueue {
+    #[inline]
+    fn push(&mut self, node: usize, priority: f64) { self.decrease_key(node, priority) }
+
+    fn pop_min(&mut self) -> Option<(usize, f64)> {
+        while let Some(Entry { priority, node }) = self.heap.pop() {
+            // Skip it if it has been decreased since, or popped already
+            if self.queued.get(node).map(OrderedCost) != Some(priority) {
+                continue;
+            }
+            self.queued.set(node, None);
+            self.len -= 1;
+            return Some((node, priority.0));
+        }
+        None
+    }
+
+    fn decrease_key(&mut self, node: usize, priority: f64) {
+        match self.queued.get(node) {
+            Some(old) if OrderedCost(old) <= OrderedCost(priority) => return,
+            Some(_) => {},
+            None => self.len += 1,
+        }
+        self.queued.set(node, Some(priority));
+        self.heap.push(Entry { priority: OrderedCost(priority), node });
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool { self.len == 0 }
+}
+
+
+
+/// A [`PriorityQueue`] for small, non-negative integer priorities, which keeps a bucket of nodes for every priority.
+///
+/// Popping is `O(1)` amortized if priorities are popped in increasing order (as Dijkstra's algorithm does), and pushing only costs as much
+/// as keeping the nodes with the same priority ordered by index (which is how ties are popped). The memory used is linear in the largest
+/// priority, so this is only suitable for graphs with small integer costs, e.g., hop counts.
+#[derive(Clone, Debug, Default)]
+pub struct BucketQueue {
+    /// The (possibly outdated) nodes of every priority.
+    buckets: Vec<Vec<usize>>,
+    /// The lowest bucket that may be non-empty.
+    cursor:  usize,
+    /// The current priority of every queued node.
+    queued:  Queued,
+    /// The number of nodes queued.
+    len:     usize,
+}
+impl PriorityQueue for BucketQueue {
+    #[inline]
+    #[track_caller]
+    fn push(&mut self, node: usize, priority: f64) { self.decrease_key(node, priority) }
+
+    fn pop_min(&mut self) -> Option<(usize, f64)> {
+        while self.len > 0 {
+            let bucket: &mut Vec<usize> = &mut self.buckets[self.cursor];
+            if bucket.is_empty() {
+                self.cursor += 1;
+                continue;
+            }
+            // NOTE: Buckets are sorted in descending order, so the last one has the lowest index
+            let node: usize = bucket.pop().unwrap();
+            if self.queued.get(node) != Some(self.cursor as f64) {
+                continue;
+            }
+            self.queued.set(node, None);
+            self.len -= 1;
+            return Some((node, self.cursor as f64));
+        }
+        None
+    }
+
+    /// Lowers the priority of a queued node.
+    ///
+    /// # Arguments
+    /// - `node`: The node to update. If it isn't queued, it is queued.
+    /// - `priority`: The new priority of the node. Ignored if it's not lower than its current one.
+    ///
+    /// # Panics
+    /// This function panics if `priority` is not a non-negative integer.
+    #[track_caller]
+    fn decrease_key(&mut self, node: usize, priority: f64) {
+        if !(priority >= 0.0 && priority.fract() == 0.0 && priority <= u32::MAX as f64) {
+            panic!("Cannot queue node {node} with priority {priority} in a bucket queue (must be a non-negative integer)");
+        }
+        match self.queued.get(node) {
+            Some(old) if OrderedCost(old) <= OrderedCost(priority) => return,
+            Some(_) => {},
+            None => self.len += 1,
+        }
+        self.queued.set(node, Some(priority));
+
+        // Insert it in its bucket
+        let index: usize = priority as usize;
+        if index >= self.buckets.len() {
+            self.buckets.resize_with(index + 1, Vec::new);
+        }
+        let bucket: &mut Vec<usize> = &mut self.buckets[index];
+        let pos: usize = bucket.partition_point(|other| *other > node);
+        bucket.insert(pos, node);
+        self.cursor = self.cursor.min(index);
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool { self.len == 0 }
+}
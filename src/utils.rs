@@ -4,7 +4,7 @@
 //  Created:
 //    20 Jul 2024, 01:05:09
 //  Last edited:
-//    23 Jul 2024, 01:50:19
+//    16 Oct 2026, 17:48:28
 //  Auto updated?
 //    Yes
 //
@@ -12,13 +12,173 @@
 //!   Defines utilities for use in tests.
 //
 
+#[cfg(feature = "tracing")]
+use std::fmt::Debug;
 use std::path::PathBuf;
+#[cfg(feature = "tracing")]
+use std::sync::Arc;
+#[cfg(any(feature = "log", feature = "tracing"))]
+use std::sync::Mutex;
+#[cfg(any(feature = "log", feature = "tracing"))]
+use std::sync::MutexGuard;
+#[cfg(feature = "log")]
+use std::thread::{self, ThreadId};
 
 use error_trace::trace;
 use ksp_graph::Graph;
 
 
+/***** HELPERS *****/
+/// A [`log::Log`]ger that remembers all records, such that tests can inspect them.
+#[cfg(feature = "log")]
+struct CaptureLogger {
+    /// The records logged so far, together with the thread that logged them.
+    records: Mutex<Vec<(ThreadId, log::Level, String)>>,
+}
+#[cfg(feature = "log")]
+impl log::Log for CaptureLogger {
+    #[inline]
+    fn enabled(&self, _metadata: &log::Metadata) -> bool { true }
+
+    #[inline]
+    fn log(&self, record: &log::Record) {
+        self.records.lock().unwrap().push((thread::current().id(), record.level(), record.args().to_string()));
+    }
+
+    #[inline]
+    fn flush(&self) {}
+}
+
+/// A [`tracing::Subscriber`] that remembers all spans, such that tests can inspect them.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Default)]
+struct CaptureSubscriber {
+    /// The spans created so far. Their [`Id`](tracing::span::Id) is their index + 1.
+    spans:   Arc<Mutex<Vec<CapturedSpan>>>,
+    /// The spans currently entered, innermost last.
+    entered: Arc<Mutex<Vec<tracing::span::Id>>>,
+}
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for CaptureSubscriber {
+    #[inline]
+    fn enabled(&self, _metadata: &tracing::Metadata) -> bool { true }
+
+    fn new_span(&self, span: &tracing::span::Attributes) -> tracing::span::Id {
+        let mut fields: Vec<(&'static str, String)> = Vec::new();
+        span.record(&mut FieldCollector(&mut fields));
+        let mut spans: MutexGuard<Vec<CapturedSpan>> = self.spans.lock().unwrap();
+        spans.push(CapturedSpan { metadata: span.metadata(), fields });
+        tracing::span::Id::from_u64(spans.len() as u64)
+    }
+
+    #[inline]
+    fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record) {
+        values.record(&mut FieldCollector(&mut self.spans.lock().unwrap()[span.into_u64() as usize - 1].fields));
+    }
+
+    #[inline]
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    #[inline]
+    fn event(&self, _event: &tracing::Event) {}
+
+    #[inline]
+    fn enter(&self, span: &tracing::span::Id) { self.entered.lock().unwrap().push(span.clone()); }
+
+    #[inline]
+    fn exit(&self, _span: &tracing::span::Id) { self.entered.lock().unwrap().pop(); }
+
+    fn current_span(&self) -> tracing_core::span::Current {
+        match self.entered.lock().unwrap().last() {
+            Some(id) => tracing_core::span::Current::new(id.clone(), self.spans.lock().unwrap()[id.into_u64() as usize - 1].metadata),
+            None => tracing_core::span::Current::none(),
+        }
+    }
+}
+
+/// A [`tracing::field::Visit`]or that formats the fields of a span.
+#[cfg(feature = "tracing")]
+struct FieldCollector<'f>(&'f mut Vec<(&'static str, String)>);
+#[cfg(feature = "tracing")]
+impl tracing::field::Visit for FieldCollector<'_> {
+    #[inline]
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn Debug) { self.0.push((field.name(), format!("{value:?}"))); }
+}
+
+/// The logger installed by [`capture_logs()`].
+#[cfg(feature = "log")]
+static LOGGER: CaptureLogger = CaptureLogger { records: Mutex::new(Vec::new()) };
+
+
+
+
+
 /***** LIBRARY *****/
+/// A span captured by [`capture_spans()`].
+#[cfg(feature = "tracing")]
+#[derive(Clone, Debug)]
+pub struct CapturedSpan {
+    /// The metadata of the span, e.g., its name.
+    pub metadata: &'static tracing::Metadata<'static>,
+    /// The fields recorded for the span, formatted with [`Debug`]. Includes those recorded after it was created.
+    pub fields:   Vec<(&'static str, String)>,
+}
+#[cfg(feature = "tracing")]
+impl CapturedSpan {
+    /// Returns the value of a field.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the field.
+    ///
+    /// # Returns
+    /// The last value recorded for the field, formatted with [`Debug`], or [`None`] if it was never recorded.
+    #[inline]
+    pub fn field(&self, name: &str) -> Option<&str> { self.fields.iter().rev().find(|(n, _)| *n == name).map(|(_, v)| v.as_str()) }
+}
+
+/// Runs a closure while capturing the log records it emits.
+///
+/// Records are captured per thread, so this works while other tests are running in parallel.
+///
+/// # Arguments
+/// - `f`: The closure to run.
+///
+/// # Returns
+/// A pair of what `f` returned and the level and message of every record it logged, in order.
+#[cfg(feature = "log")]
+pub fn capture_logs<R>(f: impl FnOnce() -> R) -> (R, Vec<(log::Level, String)>) {
+    // Install the logger (if no other test did already)
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Trace);
+    }
+    let id: ThreadId = thread::current().id();
+    LOGGER.records.lock().unwrap().retain(|(tid, _, _)| *tid != id);
+
+    // Run the closure, then collect what it logged
+    let res: R = f();
+    let mut records: MutexGuard<Vec<(ThreadId, log::Level, String)>> = LOGGER.records.lock().unwrap();
+    let logs: Vec<(log::Level, String)> = records.iter().filter(|(tid, _, _)| *tid == id).map(|(_, level, msg)| (*level, msg.clone())).collect();
+    records.retain(|(tid, _, _)| *tid != id);
+    (res, logs)
+}
+
+/// Runs a closure while capturing the tracing spans it creates.
+///
+/// The subscriber is only installed for the current thread, so this works while other tests are running in parallel.
+///
+/// # Arguments
+/// - `f`: The closure to run.
+///
+/// # Returns
+/// A pair of what `f` returned and every span it created, in order.
+#[cfg(feature = "tracing")]
+pub fn capture_spans<R>(f: impl FnOnce() -> R) -> (R, Vec<CapturedSpan>) {
+    let subscriber: CaptureSubscriber = CaptureSubscriber::default();
+    let res: R = tracing::subscriber::with_default(subscriber.clone(), f);
+    let spans: Vec<CapturedSpan> = std::mem::take(&mut *subscriber.spans.lock().unwrap());
+    (res, spans)
+}
+
 /// Loads a test graph with a given name.
 ///
 /// # Arguments